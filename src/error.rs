@@ -5,4 +5,75 @@ use thiserror::Error;
 pub enum FhirError {
     #[error("serialization error occured: {0}")]
     SerdeError(#[from] serde_json::Error),
+
+    #[error("io error occured: {0}")]
+    IoError(#[from] std::io::Error),
+
+    #[error("terminology error: {0}")]
+    TerminologyError(String),
+
+    #[error("profile error: {0}")]
+    ProfileError(String),
+
+    #[error("invalid date/time: {0}")]
+    DateTimeParseError(String),
+
+    #[error("invalid UDI carrier: {0}")]
+    UdiParseError(String),
+
+    #[error("fhirpath error: {0}")]
+    FhirPathError(String),
+
+    #[error("invalid reference: {0}")]
+    ReferenceError(String),
+
+    #[error("resource store error: {0}")]
+    StoreError(String),
+
+    #[error("invalid attachment: {0}")]
+    AttachmentError(String),
+
+    #[error("invalid iCalendar data: {0}")]
+    CalendarError(String),
+
+    #[error("hierarchy error: {0}")]
+    HierarchyError(String),
+
+    #[error("geospatial error: {0}")]
+    GeoError(String),
+
+    #[error("invalid opening hours: {0}")]
+    OpeningHoursError(String),
+
+    #[error("invalid period: {0}")]
+    PeriodError(String),
+
+    #[error("{type_name} is missing mandatory elements: {}", elements.join(", "))]
+    MissingElements {
+        type_name: &'static str,
+        elements: Vec<&'static str>,
+    },
+}
+
+impl FhirError {
+    /// Returns `MissingElements` listing every element whose presence check
+    /// failed, used by the `try_build` methods of the builders.
+    pub(crate) fn require(
+        type_name: &'static str,
+        elements: &[(&'static str, bool)],
+    ) -> Result<(), FhirError> {
+        let missing: Vec<&'static str> = elements
+            .iter()
+            .filter(|(_, present)| !present)
+            .map(|(element, _)| *element)
+            .collect();
+        if missing.is_empty() {
+            Ok(())
+        } else {
+            Err(FhirError::MissingElements {
+                type_name,
+                elements: missing,
+            })
+        }
+    }
 }
@@ -39,6 +39,16 @@ pub mod r4 {
             pub mod contact_point;
             pub use contact_point::*;
 
+            pub mod element_definition {
+                pub mod builder;
+                pub mod element;
+            }
+            pub use element_definition::builder::*;
+            pub use element_definition::element::*;
+
+            pub mod extension;
+            pub use extension::*;
+
             pub mod human_name;
             pub use human_name::*;
 
@@ -68,10 +78,49 @@ pub mod r4 {
         pub mod element;
         pub use element::*;
 
+        pub mod primitive_types {
+            pub mod date_time;
+            pub use date_time::*;
+
+            pub mod time_zone;
+            pub use time_zone::*;
+        }
+        pub use primitive_types::*;
+
         pub mod reference;
         pub use reference::*;
     }
     pub mod resources {
+        pub mod code_system {
+            pub mod builder;
+            pub mod resource;
+        }
+        pub use code_system::builder::*;
+        pub use code_system::resource::*;
+
+        pub mod concept_map {
+            pub mod builder;
+            pub mod resource;
+        }
+        pub use concept_map::builder::*;
+        pub use concept_map::resource::*;
+
+        pub mod device {
+            pub mod builder;
+            pub mod resource;
+            pub mod udi;
+        }
+        pub use device::builder::*;
+        pub use device::resource::*;
+        pub use device::udi::*;
+
+        pub mod device_definition {
+            pub mod builder;
+            pub mod resource;
+        }
+        pub use device_definition::builder::*;
+        pub use device_definition::resource::*;
+
         pub mod healthcare_service {
             pub mod builder;
             pub mod resource;
@@ -86,6 +135,13 @@ pub mod r4 {
         pub use endpoint::builder::*;
         pub use endpoint::resource::*;
 
+        pub mod naming_system {
+            pub mod builder;
+            pub mod resource;
+        }
+        pub use naming_system::builder::*;
+        pub use naming_system::resource::*;
+
         pub mod organization {
             pub mod builder;
             pub mod resource;
@@ -123,6 +179,88 @@ pub mod r4 {
         }
         pub use practitioner_role::builder::*;
         pub use practitioner_role::resource::*;
+
+        pub mod structure_definition {
+            pub mod builder;
+            pub mod resource;
+        }
+        pub use structure_definition::builder::*;
+        pub use structure_definition::resource::*;
+
+        pub mod value_set {
+            pub mod builder;
+            pub mod resource;
+        }
+        pub use value_set::builder::*;
+        pub use value_set::resource::*;
+    }
+    pub mod directory {
+        pub mod availability;
+        pub use availability::*;
+
+        pub mod discovery;
+        pub use discovery::*;
+
+        #[cfg(test)]
+        mod fixtures;
+
+        pub mod geo;
+        pub use geo::*;
+
+        pub mod geojson;
+        pub use geojson::*;
+
+        pub mod hierarchy;
+        pub use hierarchy::*;
+
+        pub mod icalendar;
+        pub use icalendar::*;
+
+        pub mod opening_hours;
+        pub use opening_hours::*;
+
+        pub mod query;
+        pub use query::*;
+
+        pub mod rewrite;
+        pub use rewrite::*;
+
+        pub mod store;
+        pub use store::*;
+    }
+    pub mod fhirpath {
+        pub mod parser;
+        pub use parser::*;
+
+        pub mod regex;
+
+        pub mod value;
+        pub use value::*;
+
+        pub mod evaluator;
+        pub use evaluator::*;
+
+        mod functions;
+    }
+    pub mod profiling {
+        pub mod invariants;
+        pub use invariants::*;
+
+        pub mod snapshot;
+        pub use snapshot::*;
+
+        pub mod validation;
+        pub use validation::*;
+    }
+    pub mod terminology {
+        pub mod engine;
+        pub use engine::*;
+
+        pub mod identifier_system;
+        pub use identifier_system::*;
+
+        pub mod translate;
+        pub use translate::*;
     }
 }
 
@@ -0,0 +1,640 @@
+use std::{collections::BTreeMap, fmt};
+
+use crate::{
+    FhirError,
+    r4::{
+        elements::{
+            AvailableTime, DateTimePrecision, DaysOfWeek, FhirDateTime, FhirTime, MILLIS_PER_DAY,
+            NotAvailable, TimeZone,
+        },
+        resources::{HealthcareService, HoursOfOperation, Location, PractitionerRole},
+    },
+};
+
+/// How far ahead [`WeeklySchedule::next_opening`] looks.
+const SEARCH_DAYS: i64 = 366;
+
+const WEEK: i64 = 7 * MILLIS_PER_DAY;
+
+/// An opening on a day of the week in local time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OpeningSlot {
+    pub day: DaysOfWeek,
+    /// Milliseconds after midnight.
+    start: i64,
+    /// Milliseconds after midnight of `day`, beyond a day when the slot runs
+    /// overnight.
+    end: i64,
+}
+
+impl OpeningSlot {
+    /// A slot from `opens` to `closes`, running overnight when `closes` is not
+    /// after `opens`. `None` stands for midnight.
+    pub fn new(day: DaysOfWeek, opens: Option<&FhirTime>, closes: Option<&FhirTime>) -> Self {
+        let start = opens.map_or(0, FhirTime::millis_of_day);
+        let end = match closes.map(FhirTime::millis_of_day) {
+            Some(end) if end > start => end,
+            Some(end) => end + MILLIS_PER_DAY,
+            None => MILLIS_PER_DAY,
+        };
+        Self { day, start, end }
+    }
+
+    pub fn all_day(day: DaysOfWeek) -> Self {
+        Self::new(day, None, None)
+    }
+
+    pub fn opens(&self) -> FhirTime {
+        time_of_day(self.start)
+    }
+
+    pub fn closes(&self) -> FhirTime {
+        time_of_day(self.end % MILLIS_PER_DAY)
+    }
+
+    /// Whether the slot closes on the next day.
+    pub fn is_overnight(&self) -> bool {
+        self.end > MILLIS_PER_DAY
+    }
+
+    pub fn is_all_day(&self) -> bool {
+        self.start == 0 && self.end == MILLIS_PER_DAY
+    }
+
+    /// Milliseconds after midnight of `day` at which the slot opens and
+    /// closes.
+    pub(crate) fn bounds(&self) -> (i64, i64) {
+        (self.start, self.end)
+    }
+
+    /// Whether the local time `millis_of_day` on `day` falls in the slot,
+    /// including the overnight part of a slot from the day before.
+    fn covers(&self, day: DaysOfWeek, millis_of_day: i64) -> bool {
+        let offset = (day.index() + 7 - self.day.index()) % 7;
+        let since_start = millis_of_day + offset as i64 * MILLIS_PER_DAY;
+        (offset == 0 || offset == 1) && (self.start..self.end).contains(&since_start)
+    }
+}
+
+impl fmt::Display for OpeningSlot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_all_day() {
+            return f.write_str("all day");
+        }
+        write!(
+            f,
+            "{}-{}",
+            clock(self.start),
+            clock(self.end % MILLIS_PER_DAY)
+        )?;
+        if self.is_overnight() {
+            f.write_str(" (+1)")?;
+        }
+        Ok(())
+    }
+}
+
+/// A period in which the regular openings do not apply, taken from
+/// `NotAvailable.during`. Either end may be open.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Closure {
+    pub description: String,
+    pub start: Option<FhirDateTime>,
+    pub end: Option<FhirDateTime>,
+}
+
+impl Closure {
+    /// Whether the instant `utc_millis` falls in the closure. Dates and times
+    /// without an offset are read in `zone`.
+    pub fn covers(&self, utc_millis: i64, zone: &impl TimeZone) -> bool {
+        self.start
+            .is_none_or(|start| instant(&start.lower_bound(), zone) <= utc_millis)
+            && self
+                .end
+                .is_none_or(|end| utc_millis <= instant(&end.upper_bound(), zone))
+    }
+}
+
+/// The recurring weekly openings of a service, role or location with the
+/// periods it is not available.
+///
+/// Free text that cannot be evaluated, the `availabilityExceptions` and
+/// `NotAvailable` entries without a period, is kept in `notes`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct WeeklySchedule {
+    pub slots: Vec<OpeningSlot>,
+    pub closures: Vec<Closure>,
+    pub notes: Vec<String>,
+}
+
+impl WeeklySchedule {
+    /// Builds the schedule of a HealthcareService or PractitionerRole.
+    ///
+    /// An `AvailableTime` without days applies to every day, one without
+    /// times or flagged `allDay` lasts the whole day.
+    pub fn from_available_times(
+        available_times: &[AvailableTime],
+        not_available: &[NotAvailable],
+        availability_exceptions: Option<&str>,
+    ) -> Result<Self, FhirError> {
+        let mut schedule = Self::default();
+        for available in available_times {
+            schedule.add_slots(
+                available.days_of_week.as_deref(),
+                available.all_day,
+                available.available_start_time.as_deref(),
+                available.available_end_time.as_deref(),
+            )?;
+        }
+        for entry in not_available {
+            match &entry.during {
+                Some(during) => schedule.closures.push(Closure {
+                    description: entry.description.clone(),
+                    start: during
+                        .start
+                        .as_deref()
+                        .map(FhirDateTime::parse)
+                        .transpose()?,
+                    end: during.end.as_deref().map(FhirDateTime::parse).transpose()?,
+                }),
+                None => schedule.notes.push(entry.description.clone()),
+            }
+        }
+        schedule.finish(availability_exceptions);
+        Ok(schedule)
+    }
+
+    /// Builds the schedule of a Location.
+    pub fn from_hours_of_operation(
+        hours_of_operation: &[HoursOfOperation],
+        availability_exceptions: Option<&str>,
+    ) -> Result<Self, FhirError> {
+        let mut schedule = Self::default();
+        for hours in hours_of_operation {
+            schedule.add_slots(
+                hours.days_of_week.as_deref(),
+                hours.all_day,
+                hours.operation_time.as_deref(),
+                hours.closing_time.as_deref(),
+            )?;
+        }
+        schedule.finish(availability_exceptions);
+        Ok(schedule)
+    }
+
+    fn add_slots(
+        &mut self,
+        days: Option<&[DaysOfWeek]>,
+        all_day: Option<bool>,
+        opens: Option<&str>,
+        closes: Option<&str>,
+    ) -> Result<(), FhirError> {
+        let opens = opens.map(FhirTime::parse).transpose()?;
+        let closes = closes.map(FhirTime::parse).transpose()?;
+        for day in days.unwrap_or(&DaysOfWeek::ALL) {
+            self.slots.push(if all_day == Some(true) {
+                OpeningSlot::all_day(*day)
+            } else {
+                OpeningSlot::new(*day, opens.as_ref(), closes.as_ref())
+            });
+        }
+        Ok(())
+    }
+
+    fn finish(&mut self, availability_exceptions: Option<&str>) {
+        self.slots
+            .sort_by_key(|slot| (slot.day.index(), slot.start, slot.end));
+        self.slots.dedup();
+        self.notes.extend(availability_exceptions.map(String::from));
+    }
+
+    /// The slots opening on `day`, earliest first.
+    pub fn slots_on(&self, day: DaysOfWeek) -> impl Iterator<Item = &OpeningSlot> {
+        self.slots.iter().filter(move |slot| slot.day == day)
+    }
+
+    /// Whether any slot is open for part of the window from `from` to `to` on
+    /// `day`, such as a Tuesday morning. The window runs past midnight when
+    /// `to` is not after `from`. Closures are not considered, as the window
+    /// recurs every week.
+    pub fn is_open_during(&self, day: DaysOfWeek, from: &FhirTime, to: &FhirTime) -> bool {
+        let window = OpeningSlot::new(day, Some(from), Some(to));
+        let (start, end) = window.bounds();
+        let start = start + day.index() as i64 * MILLIS_PER_DAY;
+        let end = end + day.index() as i64 * MILLIS_PER_DAY;
+        self.slots.iter().any(|slot| {
+            let (opens, closes) = slot.bounds();
+            let base = slot.day.index() as i64 * MILLIS_PER_DAY;
+            [-WEEK, 0, WEEK]
+                .iter()
+                .any(|shift| base + shift + opens < end && start < base + shift + closes)
+        })
+    }
+
+    /// Whether the schedule is open at `at`. A value without an offset is a
+    /// local time in `zone`.
+    pub fn is_available_at(&self, at: &FhirDateTime, zone: &impl TimeZone) -> bool {
+        self.is_open(instant(&at.lower_bound(), zone), zone)
+    }
+
+    /// The first moment at or after `after` at which the schedule is open,
+    /// `after` itself when already open. Looks a year ahead.
+    pub fn next_opening(&self, after: &FhirDateTime, zone: &impl TimeZone) -> Option<FhirDateTime> {
+        let from = instant(&after.lower_bound(), zone);
+        if self.is_open(from, zone) {
+            return Some(*after);
+        }
+        let first_day = zone.to_local(from).div_euclid(MILLIS_PER_DAY) - 1;
+        let mut candidates: Vec<i64> = (first_day..=first_day + SEARCH_DAYS)
+            .flat_map(|day| {
+                self.slots_on(DaysOfWeek::from_epoch_days(day))
+                    .map(move |slot| zone.to_utc(day * MILLIS_PER_DAY + slot.start))
+            })
+            .chain(
+                self.closures
+                    .iter()
+                    .filter_map(|closure| Some(instant(&closure.end?.upper_bound(), zone) + 1)),
+            )
+            .filter(|candidate| *candidate > from)
+            .collect();
+        candidates.sort_unstable();
+        candidates
+            .into_iter()
+            .find(|candidate| self.is_open(*candidate, zone))
+            .map(|utc| {
+                FhirDateTime::from_epoch_millis(
+                    utc,
+                    Some(zone.offset_at(utc)),
+                    DateTimePrecision::Second,
+                )
+            })
+    }
+
+    fn is_open(&self, utc_millis: i64, zone: &impl TimeZone) -> bool {
+        let local = zone.to_local(utc_millis);
+        let day = DaysOfWeek::from_epoch_days(local.div_euclid(MILLIS_PER_DAY));
+        let millis_of_day = local.rem_euclid(MILLIS_PER_DAY);
+        self.slots
+            .iter()
+            .any(|slot| slot.covers(day, millis_of_day))
+            && !self
+                .closures
+                .iter()
+                .any(|closure| closure.covers(utc_millis, zone))
+    }
+}
+
+impl fmt::Display for WeeklySchedule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for day in DaysOfWeek::ALL {
+            let slots: Vec<String> = self.slots_on(day).map(ToString::to_string).collect();
+            let day_name = day_name(day);
+            if slots.is_empty() {
+                writeln!(f, "{day_name} closed")?;
+            } else {
+                writeln!(f, "{day_name} {}", slots.join(", "))?;
+            }
+        }
+        for closure in &self.closures {
+            let bound = |value: Option<FhirDateTime>| {
+                value.map_or_else(|| "…".to_string(), |value| value.to_string())
+            };
+            writeln!(
+                f,
+                "Not available: {} ({} to {})",
+                closure.description,
+                bound(closure.start),
+                bound(closure.end)
+            )?;
+        }
+        for note in &self.notes {
+            writeln!(f, "Note: {note}")?;
+        }
+        Ok(())
+    }
+}
+
+/// A resource with recurring availability.
+pub trait Availability {
+    fn weekly_schedule(&self) -> Result<WeeklySchedule, FhirError>;
+
+    /// Whether the resource is available at `at`, see
+    /// [`WeeklySchedule::is_available_at`].
+    fn is_available_at(&self, at: &FhirDateTime, zone: &impl TimeZone) -> Result<bool, FhirError> {
+        Ok(self.weekly_schedule()?.is_available_at(at, zone))
+    }
+
+    /// The first moment at or after `after` at which the resource is
+    /// available, see [`WeeklySchedule::next_opening`].
+    fn next_opening(
+        &self,
+        after: &FhirDateTime,
+        zone: &impl TimeZone,
+    ) -> Result<Option<FhirDateTime>, FhirError> {
+        Ok(self.weekly_schedule()?.next_opening(after, zone))
+    }
+}
+
+impl Availability for HealthcareService {
+    fn weekly_schedule(&self) -> Result<WeeklySchedule, FhirError> {
+        WeeklySchedule::from_available_times(
+            self.available_time.as_deref().unwrap_or_default(),
+            self.not_available.as_deref().unwrap_or_default(),
+            self.availability_exceptions.as_deref(),
+        )
+    }
+}
+
+impl Availability for PractitionerRole {
+    fn weekly_schedule(&self) -> Result<WeeklySchedule, FhirError> {
+        WeeklySchedule::from_available_times(
+            self.available_time.as_deref().unwrap_or_default(),
+            self.not_available.as_deref().unwrap_or_default(),
+            self.availability_exceptions.as_deref(),
+        )
+    }
+}
+
+impl Availability for Location {
+    fn weekly_schedule(&self) -> Result<WeeklySchedule, FhirError> {
+        WeeklySchedule::from_hours_of_operation(
+            self.hours_of_operation.as_deref().unwrap_or_default(),
+            self.availability_exceptions.as_deref(),
+        )
+    }
+}
+
+/// The bounds of `slots` with the days they open on, Monday first.
+pub(crate) fn group_slots(slots: &[OpeningSlot]) -> BTreeMap<(i64, i64), Vec<DaysOfWeek>> {
+    let mut groups: BTreeMap<(i64, i64), Vec<DaysOfWeek>> = BTreeMap::new();
+    for slot in slots {
+        let days = groups.entry(slot.bounds()).or_default();
+        if !days.contains(&slot.day) {
+            days.push(slot.day);
+        }
+    }
+    for days in groups.values_mut() {
+        days.sort_by_key(|day| day.index());
+    }
+    groups
+}
+
+/// Milliseconds since the epoch of `value`, read in `zone` when it has no
+/// offset.
+pub(crate) fn instant(value: &FhirDateTime, zone: &impl TimeZone) -> i64 {
+    match value.offset() {
+        Some(_) => value.epoch_millis(0),
+        None => zone.to_utc(value.epoch_millis(0)),
+    }
+}
+
+pub(crate) fn time_of_day(millis: i64) -> FhirTime {
+    let seconds = millis / 1_000;
+    FhirTime::from_hms(
+        (seconds / 3_600) as u8,
+        (seconds % 3_600 / 60) as u8,
+        (seconds % 60) as u8,
+    )
+    .unwrap_or_default()
+}
+
+/// `HH:MM`, with seconds when there are any.
+fn clock(millis: i64) -> String {
+    let seconds = millis / 1_000;
+    let (hours, minutes, seconds) = (seconds / 3_600, seconds % 3_600 / 60, seconds % 60);
+    if seconds == 0 {
+        format!("{hours:02}:{minutes:02}")
+    } else {
+        format!("{hours:02}:{minutes:02}:{seconds:02}")
+    }
+}
+
+fn day_name(day: DaysOfWeek) -> &'static str {
+    match day {
+        DaysOfWeek::Mon => "Mon",
+        DaysOfWeek::Tue => "Tue",
+        DaysOfWeek::Wed => "Wed",
+        DaysOfWeek::Thu => "Thu",
+        DaysOfWeek::Fri => "Fri",
+        DaysOfWeek::Sat => "Sat",
+        DaysOfWeek::Sun => "Sun",
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::r4::{
+        directory::fixtures::{self, amsterdam, at},
+        elements::{AvailableTimeBuilder, FixedOffset, NotAvailableBuilder},
+        resources::{HealthcareServiceBuilder, HoursOfOperationBuilder, LocationBuilder},
+    };
+
+    use super::*;
+
+    fn service() -> HealthcareService {
+        fixtures::service()
+            .add_not_available(
+                NotAvailableBuilder::default()
+                    .with_desscription("Closed on public holidays")
+                    .build(),
+            )
+            .with_availability_exceptions("Reduced hours in August")
+            .build()
+    }
+
+    #[test]
+    fn test_weekly_schedule_should_combine_available_times() {
+        let schedule = service().weekly_schedule().unwrap();
+
+        let expected = "\
+Mon 09:00-17:00
+Tue 09:00-17:00
+Wed 09:00-17:00
+Thu 09:00-17:00
+Fri 09:00-17:00
+Sat 22:00-06:00 (+1)
+Sun closed
+Not available: Christmas (2024-12-25 to 2024-12-26)
+Note: Closed on public holidays
+Note: Reduced hours in August
+";
+        assert_eq!(expected, schedule.to_string());
+        let saturday: Vec<&OpeningSlot> = schedule.slots_on(DaysOfWeek::Sat).collect();
+        assert!(saturday[0].is_overnight());
+        assert_eq!(FhirTime::parse("06:00:00").unwrap(), saturday[0].closes());
+    }
+
+    #[test]
+    fn test_is_open_during_should_overlap_weekly_windows() {
+        let schedule = service().weekly_schedule().unwrap();
+        let time = |value: &str| FhirTime::parse(value).unwrap();
+
+        assert!(schedule.is_open_during(DaysOfWeek::Tue, &time("08:00:00"), &time("12:00:00")));
+        assert!(!schedule.is_open_during(DaysOfWeek::Mon, &time("06:00:00"), &time("09:00:00")));
+        assert!(schedule.is_open_during(DaysOfWeek::Sun, &time("05:00:00"), &time("07:00:00")));
+        assert!(!schedule.is_open_during(DaysOfWeek::Sun, &time("07:00:00"), &time("12:00:00")));
+        assert!(schedule.is_open_during(DaysOfWeek::Fri, &time("23:00:00"), &time("23:00:00")));
+        assert!(!schedule.is_open_during(DaysOfWeek::Sun, &time("18:00:00"), &time("08:00:00")));
+    }
+
+    #[test]
+    fn test_is_available_at_should_read_local_and_offset_times() {
+        let service = service();
+        let zone = amsterdam();
+
+        // 2024-07-01 is a Monday, Amsterdam is on CEST.
+        assert!(
+            service
+                .is_available_at(&at("2024-07-01T09:00:00"), &zone)
+                .unwrap()
+        );
+        assert!(
+            service
+                .is_available_at(&at("2024-07-01T14:59:00Z"), &zone)
+                .unwrap()
+        );
+        assert!(
+            !service
+                .is_available_at(&at("2024-07-01T15:00:00Z"), &zone)
+                .unwrap()
+        );
+        assert!(
+            !service
+                .is_available_at(&at("2024-07-01T08:59:59"), &zone)
+                .unwrap()
+        );
+        // The Saturday evening opening runs into Sunday morning.
+        assert!(
+            service
+                .is_available_at(&at("2024-07-07T05:59:00"), &zone)
+                .unwrap()
+        );
+        assert!(
+            !service
+                .is_available_at(&at("2024-07-07T06:00:00"), &zone)
+                .unwrap()
+        );
+        assert!(
+            !service
+                .is_available_at(&at("2024-07-07T22:00:00"), &zone)
+                .unwrap()
+        );
+        // Christmas falls on a Wednesday.
+        assert!(
+            service
+                .is_available_at(&at("2024-12-24T10:00:00"), &zone)
+                .unwrap()
+        );
+        assert!(
+            !service
+                .is_available_at(&at("2024-12-25T10:00:00"), &zone)
+                .unwrap()
+        );
+        assert!(
+            !service
+                .is_available_at(&at("2024-12-26T16:59:00"), &zone)
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_is_available_at_should_follow_dst_transitions() {
+        let service = service();
+        let zone = amsterdam();
+
+        // On 2024-10-27 the clocks go back at 03:00, the overnight opening
+        // still closes at 06:00 local time, 05:00 UTC.
+        assert!(
+            service
+                .is_available_at(&at("2024-10-27T04:59:00Z"), &zone)
+                .unwrap()
+        );
+        assert!(
+            !service
+                .is_available_at(&at("2024-10-27T05:00:00Z"), &zone)
+                .unwrap()
+        );
+        // On 2024-03-31 the clocks go forward at 02:00, closing at 04:00 UTC.
+        assert!(
+            service
+                .is_available_at(&at("2024-03-31T03:59:00Z"), &zone)
+                .unwrap()
+        );
+        assert!(
+            !service
+                .is_available_at(&at("2024-03-31T04:00:00Z"), &zone)
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_next_opening_should_skip_closures() {
+        let service = service();
+        let zone = amsterdam();
+
+        let opening = |after: &str| {
+            service
+                .next_opening(&at(after), &zone)
+                .unwrap()
+                .map(|value| value.to_string())
+        };
+
+        assert_eq!(
+            Some("2024-07-06T22:00:00+02:00".to_string()),
+            opening("2024-07-05T17:00:00")
+        );
+        assert_eq!(
+            Some("2024-07-01T10:00:00Z".to_string()),
+            opening("2024-07-01T10:00:00Z")
+        );
+        assert_eq!(
+            Some("2024-12-27T09:00:00+01:00".to_string()),
+            opening("2024-12-24T17:30:00")
+        );
+
+        let closed = HealthcareServiceBuilder::new("hs-2").build();
+        assert_eq!(None, closed.next_opening(&at("2024-01-01"), &zone).unwrap());
+    }
+
+    #[test]
+    fn test_next_opening_should_land_after_a_skipped_local_time() {
+        let location = LocationBuilder::new("location-1")
+            .add_hours_of_operation(
+                HoursOfOperationBuilder::default()
+                    .add_day_of_week(DaysOfWeek::Sun)
+                    .operation_time("02:30:00")
+                    .closing_time("04:00:00")
+                    .build(),
+            )
+            .build();
+
+        let opening = location
+            .next_opening(&at("2024-03-31T00:00:00"), &amsterdam())
+            .unwrap();
+
+        assert_eq!(
+            Some("2024-03-31T03:30:00+02:00".to_string()),
+            opening.map(|value| value.to_string())
+        );
+        assert_eq!(
+            Some("2024-03-31T02:30:00Z".to_string()),
+            location
+                .next_opening(&at("2024-03-31T00:00:00"), &FixedOffset::UTC)
+                .unwrap()
+                .map(|value| value.to_string())
+        );
+    }
+
+    #[test]
+    fn test_weekly_schedule_should_reject_invalid_times() {
+        let service = HealthcareServiceBuilder::new("hs-3")
+            .add_available_time(
+                AvailableTimeBuilder::default()
+                    .with_available_start_time("9am")
+                    .build(),
+            )
+            .build();
+
+        assert!(service.weekly_schedule().is_err());
+    }
+}
@@ -0,0 +1,607 @@
+use std::fmt;
+
+use crate::r4::{
+    directory::{GeoPoint, ResourceStore, location_boundary, speaks},
+    elements::{Address, CodeableConcept},
+    resources::{HealthcareService, Location, ReferenceTarget},
+};
+
+/// A requirement a HealthcareService is checked against by a
+/// [`ServiceQuery`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum ServiceCriterion {
+    /// The service is not flagged inactive, always checked.
+    Active,
+    Category {
+        system: String,
+        code: String,
+    },
+    Type {
+        system: String,
+        code: String,
+    },
+    Program {
+        system: String,
+        code: String,
+    },
+    Characteristic {
+        system: String,
+        code: String,
+    },
+    Eligibility {
+        system: String,
+        code: String,
+    },
+    ReferralMethod {
+        system: String,
+        code: String,
+    },
+    /// A BCP 47 tag such as `nl` or a language name such as `Dutch`.
+    Communication(String),
+    /// `appointmentRequired` is stated and equals this value. A service that
+    /// does not state it fails for both `true` and `false`.
+    AppointmentRequired(bool),
+    /// A coverage area Location has a boundary containing the point.
+    CoversPosition(GeoPoint),
+    /// A coverage area Location has an address agreeing with this one.
+    ///
+    /// A coverage area without an address, such as a region known only by
+    /// name, matches when its name equals the city, district or state.
+    CoversAddress(Box<Address>),
+}
+
+impl fmt::Display for ServiceCriterion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Active => f.write_str("active"),
+            Self::Category { system, code } => write!(f, "category {system}|{code}"),
+            Self::Type { system, code } => write!(f, "type {system}|{code}"),
+            Self::Program { system, code } => write!(f, "program {system}|{code}"),
+            Self::Characteristic { system, code } => write!(f, "characteristic {system}|{code}"),
+            Self::Eligibility { system, code } => write!(f, "eligibility {system}|{code}"),
+            Self::ReferralMethod { system, code } => write!(f, "referral method {system}|{code}"),
+            Self::Communication(language) => write!(f, "communication {language}"),
+            Self::AppointmentRequired(required) => write!(f, "appointment required {required}"),
+            Self::CoversPosition(point) => {
+                write!(f, "coverage of {}, {}", point.latitude(), point.longitude())
+            }
+            Self::CoversAddress(address) => write!(f, "coverage of {}", describe(address)),
+        }
+    }
+}
+
+/// Whether a service met one criterion, and why.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CriterionOutcome {
+    pub criterion: ServiceCriterion,
+    pub matched: bool,
+    pub reason: String,
+}
+
+impl fmt::Display for CriterionOutcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let verdict = if self.matched { "matched" } else { "failed" };
+        write!(f, "{}: {verdict}, {}", self.criterion, self.reason)
+    }
+}
+
+/// A HealthcareService with the outcome of every criterion of the query.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ServiceEvaluation<'a> {
+    pub service: &'a HealthcareService,
+    pub outcomes: Vec<CriterionOutcome>,
+}
+
+impl ServiceEvaluation<'_> {
+    /// Whether the service met every criterion.
+    pub fn is_match(&self) -> bool {
+        self.outcomes.iter().all(|outcome| outcome.matched)
+    }
+
+    pub fn failures(&self) -> impl Iterator<Item = &CriterionOutcome> {
+        self.outcomes.iter().filter(|outcome| !outcome.matched)
+    }
+}
+
+impl fmt::Display for ServiceEvaluation<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = self
+            .service
+            .name
+            .as_deref()
+            .or(self.service.resource_id())
+            .unwrap_or("unnamed service");
+        let verdict = if self.is_match() {
+            "matches"
+        } else {
+            "does not match"
+        };
+        writeln!(f, "{name} {verdict}")?;
+        for outcome in &self.outcomes {
+            writeln!(f, "  {outcome}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Finds the HealthcareServices of a [`ResourceStore`] meeting every
+/// criterion, explaining for each service which criteria it met or failed.
+///
+/// Coded criteria compare the system and code of the codings. Coverage
+/// criteria look at the `coverageArea` Locations, resolved in the store.
+pub struct ServiceQuery<'a> {
+    store: &'a ResourceStore,
+    criteria: Vec<ServiceCriterion>,
+}
+
+impl<'a> ServiceQuery<'a> {
+    pub fn new(store: &'a ResourceStore) -> Self {
+        Self {
+            store,
+            criteria: vec![ServiceCriterion::Active],
+        }
+    }
+
+    pub fn with_criterion(mut self, criterion: ServiceCriterion) -> Self {
+        self.criteria.push(criterion);
+        self
+    }
+
+    pub fn with_category(self, system: impl Into<String>, code: impl Into<String>) -> Self {
+        self.with_criterion(ServiceCriterion::Category {
+            system: system.into(),
+            code: code.into(),
+        })
+    }
+
+    pub fn with_type(self, system: impl Into<String>, code: impl Into<String>) -> Self {
+        self.with_criterion(ServiceCriterion::Type {
+            system: system.into(),
+            code: code.into(),
+        })
+    }
+
+    pub fn with_program(self, system: impl Into<String>, code: impl Into<String>) -> Self {
+        self.with_criterion(ServiceCriterion::Program {
+            system: system.into(),
+            code: code.into(),
+        })
+    }
+
+    pub fn with_characteristic(self, system: impl Into<String>, code: impl Into<String>) -> Self {
+        self.with_criterion(ServiceCriterion::Characteristic {
+            system: system.into(),
+            code: code.into(),
+        })
+    }
+
+    pub fn with_eligibility(self, system: impl Into<String>, code: impl Into<String>) -> Self {
+        self.with_criterion(ServiceCriterion::Eligibility {
+            system: system.into(),
+            code: code.into(),
+        })
+    }
+
+    pub fn with_referral_method(self, system: impl Into<String>, code: impl Into<String>) -> Self {
+        self.with_criterion(ServiceCriterion::ReferralMethod {
+            system: system.into(),
+            code: code.into(),
+        })
+    }
+
+    pub fn speaking(self, language: impl Into<String>) -> Self {
+        self.with_criterion(ServiceCriterion::Communication(language.into()))
+    }
+
+    pub fn appointment_required(self, required: bool) -> Self {
+        self.with_criterion(ServiceCriterion::AppointmentRequired(required))
+    }
+
+    pub fn covering_position(self, point: GeoPoint) -> Self {
+        self.with_criterion(ServiceCriterion::CoversPosition(point))
+    }
+
+    pub fn covering_address(self, address: Address) -> Self {
+        self.with_criterion(ServiceCriterion::CoversAddress(Box::new(address)))
+    }
+
+    /// Every service in the store with its outcomes, in store order.
+    pub fn evaluate(&self) -> Vec<ServiceEvaluation<'a>> {
+        self.store
+            .resources::<HealthcareService>()
+            .map(|service| ServiceEvaluation {
+                service,
+                outcomes: self
+                    .criteria
+                    .iter()
+                    .map(|criterion| self.check(service, criterion))
+                    .collect(),
+            })
+            .collect()
+    }
+
+    /// The services meeting every criterion.
+    pub fn run(&self) -> Vec<ServiceEvaluation<'a>> {
+        self.evaluate()
+            .into_iter()
+            .filter(ServiceEvaluation::is_match)
+            .collect()
+    }
+
+    fn check(&self, service: &HealthcareService, criterion: &ServiceCriterion) -> CriterionOutcome {
+        let (matched, reason) = match criterion {
+            ServiceCriterion::Active => match service.active {
+                Some(false) => (false, "the service is inactive".to_string()),
+                _ => (true, "the service is not flagged inactive".to_string()),
+            },
+            ServiceCriterion::Category { system, code } => {
+                coded(service.category.as_deref(), system, code)
+            }
+            ServiceCriterion::Type { system, code } => {
+                coded(service.r#type.as_deref(), system, code)
+            }
+            ServiceCriterion::Program { system, code } => {
+                coded(service.program.as_deref(), system, code)
+            }
+            ServiceCriterion::Characteristic { system, code } => {
+                coded(service.characteristic.as_deref(), system, code)
+            }
+            ServiceCriterion::Eligibility { system, code } => {
+                let codes: Vec<CodeableConcept> = service
+                    .eligibility
+                    .iter()
+                    .flatten()
+                    .filter_map(|eligibility| eligibility.code.clone())
+                    .collect();
+                coded(Some(&codes), system, code)
+            }
+            ServiceCriterion::ReferralMethod { system, code } => {
+                coded(service.referral_method.as_deref(), system, code)
+            }
+            ServiceCriterion::Communication(language) => match service
+                .communication
+                .iter()
+                .flatten()
+                .find(|concept| speaks(concept, language))
+            {
+                Some(concept) => (true, format!("offered in {}", label(concept))),
+                None => (false, "not offered in this language".to_string()),
+            },
+            ServiceCriterion::AppointmentRequired(required) => match service.appointment_required {
+                Some(value) => (
+                    value == *required,
+                    format!("appointmentRequired is {value}"),
+                ),
+                None => (false, "appointmentRequired is not stated".to_string()),
+            },
+            ServiceCriterion::CoversPosition(point) => self.covers(service, |area| {
+                location_boundary(area)
+                    .ok()
+                    .flatten()
+                    .is_some_and(|boundary| boundary.contains(point))
+            }),
+            ServiceCriterion::CoversAddress(address) => {
+                self.covers(service, |area| area_includes(area, address))
+            }
+        };
+        CriterionOutcome {
+            criterion: criterion.clone(),
+            matched,
+            reason,
+        }
+    }
+
+    /// Looks for a coverage area of `service` that `includes` the patient.
+    fn covers(
+        &self,
+        service: &HealthcareService,
+        includes: impl Fn(&Location) -> bool,
+    ) -> (bool, String) {
+        let references = service.coverage_area.as_deref().unwrap_or_default();
+        if references.is_empty() {
+            return (false, "the service lists no coverage area".to_string());
+        }
+        let areas: Vec<&Location> = references
+            .iter()
+            .filter_map(|reference| self.store.resolve_in(service, reference))
+            .collect();
+        if areas.is_empty() {
+            return (false, "no coverage area resolves".to_string());
+        }
+        match areas.iter().find(|area| includes(area)) {
+            Some(area) => (true, format!("inside {}", location_name(area))),
+            None => {
+                let names: Vec<&str> = areas.iter().map(|area| location_name(area)).collect();
+                (false, format!("outside {}", names.join(", ")))
+            }
+        }
+    }
+}
+
+/// Looks for a concept coded `code` in `system` among `concepts`.
+fn coded(concepts: Option<&[CodeableConcept]>, system: &str, code: &str) -> (bool, String) {
+    let found = concepts.unwrap_or_default().iter().find(|concept| {
+        concept.coding.iter().flatten().any(|coding| {
+            coding.system.as_deref() == Some(system) && coding.code.as_deref() == Some(code)
+        })
+    });
+    match found {
+        Some(concept) => (true, format!("found {}", label(concept))),
+        None => (false, "not listed".to_string()),
+    }
+}
+
+/// Whether `address` lies in the coverage area `area`.
+///
+/// Every country, state, district, city and postal code on the address of
+/// `area` must agree with `address`, ignoring case and spaces. An area
+/// without an address is matched by its name against the city, district
+/// and state.
+fn area_includes(area: &Location, address: &Address) -> bool {
+    let same = |left: &str, right: &str| normalize(left) == normalize(right);
+    let Some(bounds) = &area.address else {
+        return area.name.as_deref().is_some_and(|name| {
+            [&address.city, &address.district, &address.state]
+                .into_iter()
+                .flatten()
+                .any(|part| same(name, part))
+        });
+    };
+    let fields = [
+        (&bounds.country, &address.country),
+        (&bounds.state, &address.state),
+        (&bounds.district, &address.district),
+        (&bounds.city, &address.city),
+        (&bounds.postal_code, &address.postal_code),
+    ];
+    let mut compared = false;
+    for (wanted, given) in fields {
+        if let Some(wanted) = wanted {
+            if !given.as_deref().is_some_and(|given| same(wanted, given)) {
+                return false;
+            }
+            compared = true;
+        }
+    }
+    compared
+}
+
+fn normalize(value: &str) -> String {
+    value
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+fn label(concept: &CodeableConcept) -> String {
+    let coding = concept.coding.iter().flatten();
+    coding
+        .clone()
+        .find_map(|coding| coding.display.clone())
+        .or_else(|| concept.text.clone())
+        .or_else(|| coding.clone().find_map(|coding| coding.code.clone()))
+        .unwrap_or_else(|| "an uncoded concept".to_string())
+}
+
+fn location_name(location: &Location) -> &str {
+    location
+        .name
+        .as_deref()
+        .or(location.resource_id())
+        .unwrap_or("an unnamed location")
+}
+
+/// The text of `address`, or its city, postal code and country.
+fn describe(address: &Address) -> String {
+    if let Some(text) = &address.text {
+        return text.clone();
+    }
+    let parts: Vec<&str> = [&address.city, &address.postal_code, &address.country]
+        .into_iter()
+        .filter_map(|part| part.as_deref())
+        .collect();
+    parts.join(", ")
+}
+
+#[cfg(test)]
+mod test {
+    use crate::r4::{
+        directory::{Geometry, Polygon, boundary_extension},
+        elements::{AddressBuilder, CodeableConceptBuilder, CodingBuilder, Reference},
+        resources::{EligibilityBuilder, HealthcareServiceBuilder, LocationBuilder},
+    };
+
+    use super::*;
+
+    const SNOMED: &str = "http://snomed.info/sct";
+    const CATEGORY: &str = "http://terminology.hl7.org/CodeSystem/service-category";
+    const REFERRAL: &str = "http://terminology.hl7.org/CodeSystem/service-referral-method";
+
+    fn concept(system: &str, code: &str, display: &str) -> CodeableConcept {
+        CodeableConceptBuilder::default()
+            .add_coding(
+                CodingBuilder::default()
+                    .with_system(system)
+                    .with_code(code)
+                    .with_display(display)
+                    .build(),
+            )
+            .build()
+    }
+
+    fn point(latitude: f64, longitude: f64) -> GeoPoint {
+        GeoPoint::new(latitude, longitude).unwrap()
+    }
+
+    /// Amsterdam with a rough boundary, Utrecht by address and a region
+    /// known only by name.
+    fn store() -> ResourceStore {
+        let amsterdam = LocationBuilder::new("amsterdam")
+            .name("Amsterdam")
+            .add_extension(
+                boundary_extension(&Geometry::Polygon(Polygon::new(vec![
+                    point(52.28, 4.73),
+                    point(52.28, 5.07),
+                    point(52.43, 5.07),
+                    point(52.43, 4.73),
+                ])))
+                .unwrap(),
+            )
+            .build();
+        let utrecht = LocationBuilder::new("utrecht")
+            .name("Utrecht")
+            .address(
+                AddressBuilder::default()
+                    .city("Utrecht")
+                    .country("NL")
+                    .build(),
+            )
+            .build();
+        let flevoland = LocationBuilder::new("flevoland").name("Flevoland").build();
+
+        let counselling = HealthcareServiceBuilder::new("counselling")
+            .with_name("Youth counselling")
+            .add_category(concept(CATEGORY, "8", "Counselling"))
+            .add_type(concept(SNOMED, "310030004", "Youth counselling"))
+            .add_eligibility(
+                EligibilityBuilder::default()
+                    .with_code(concept(SNOMED, "133937008", "Adolescent"))
+                    .build(),
+            )
+            .add_communication(concept("urn:ietf:bcp:47", "nl", "Dutch"))
+            .add_referral_method(concept(REFERRAL, "phone", "Phone"))
+            .with_appointment_required(true)
+            .add_coverage_area(Reference::to(&amsterdam))
+            .add_coverage_area(Reference::to(&utrecht))
+            .build();
+        let physio = HealthcareServiceBuilder::new("physio")
+            .with_name("Physiotherapy")
+            .add_category(concept(CATEGORY, "28", "Physiotherapy"))
+            .add_communication(concept("urn:ietf:bcp:47", "en", "English"))
+            .add_coverage_area(Reference::to(&flevoland))
+            .build();
+        let closed = HealthcareServiceBuilder::new("closed")
+            .with_name("Closed clinic")
+            .with_active(false)
+            .add_category(concept(CATEGORY, "8", "Counselling"))
+            .build();
+
+        let mut store = ResourceStore::new();
+        for location in [amsterdam, utrecht, flevoland] {
+            store.insert(location).unwrap();
+        }
+        for service in [counselling, physio, closed] {
+            store.insert(service).unwrap();
+        }
+        store
+    }
+
+    fn ids(evaluations: &[ServiceEvaluation<'_>]) -> Vec<String> {
+        evaluations
+            .iter()
+            .filter_map(|evaluation| evaluation.service.resource_id().map(String::from))
+            .collect()
+    }
+
+    #[test]
+    fn test_run_should_match_coded_and_coverage_criteria() {
+        let store = store();
+
+        let matches = ServiceQuery::new(&store)
+            .with_category(CATEGORY, "8")
+            .with_type(SNOMED, "310030004")
+            .with_eligibility(SNOMED, "133937008")
+            .with_referral_method(REFERRAL, "phone")
+            .speaking("nl-NL")
+            .appointment_required(true)
+            .covering_position(point(52.37, 4.89))
+            .run();
+
+        assert_eq!(vec!["counselling"], ids(&matches));
+        let by_address = ServiceQuery::new(&store)
+            .covering_address(
+                AddressBuilder::default()
+                    .city("utrecht ")
+                    .postal_code("3511 AB")
+                    .country("nl")
+                    .build(),
+            )
+            .run();
+        assert_eq!(vec!["counselling"], ids(&by_address));
+        let by_name = ServiceQuery::new(&store)
+            .covering_address(AddressBuilder::default().state("Flevoland").build())
+            .run();
+        assert_eq!(vec!["physio"], ids(&by_name));
+        let by_postal_code = ServiceQuery::new(&store)
+            .covering_address(AddressBuilder::default().postal_code("Flevoland").build())
+            .run();
+        assert!(by_postal_code.is_empty());
+    }
+
+    #[test]
+    fn test_run_should_not_match_unstated_appointment_required() {
+        let store = store();
+
+        let required = ServiceQuery::new(&store).appointment_required(true).run();
+        let not_required = ServiceQuery::new(&store).appointment_required(false).run();
+
+        assert_eq!(vec!["counselling"], ids(&required));
+        assert!(not_required.is_empty());
+        let physio = &ServiceQuery::new(&store)
+            .appointment_required(false)
+            .evaluate()[1];
+        assert_eq!(
+            vec!["appointment required false: failed, appointmentRequired is not stated"],
+            physio
+                .failures()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_evaluate_should_explain_each_outcome() {
+        let store = store();
+
+        let evaluations = ServiceQuery::new(&store)
+            .with_category(CATEGORY, "8")
+            .speaking("Dutch")
+            .appointment_required(true)
+            .covering_position(point(52.09, 5.12))
+            .evaluate();
+
+        let expected = "\
+Youth counselling does not match
+  active: matched, the service is not flagged inactive
+  category http://terminology.hl7.org/CodeSystem/service-category|8: matched, found Counselling
+  communication Dutch: matched, offered in Dutch
+  appointment required true: matched, appointmentRequired is true
+  coverage of 52.09, 5.12: failed, outside Amsterdam, Utrecht
+";
+        assert_eq!(expected, evaluations[0].to_string());
+        let physio: Vec<String> = evaluations[1]
+            .failures()
+            .map(|f| f.reason.clone())
+            .collect();
+        assert_eq!(
+            vec![
+                "not listed",
+                "not offered in this language",
+                "appointmentRequired is not stated",
+                "outside Flevoland",
+            ],
+            physio
+        );
+        let closed: Vec<String> = evaluations[2].failures().map(ToString::to_string).collect();
+        assert_eq!(
+            vec![
+                "active: failed, the service is inactive",
+                "communication Dutch: failed, not offered in this language",
+                "appointment required true: failed, appointmentRequired is not stated",
+                "coverage of 52.09, 5.12: failed, the service lists no coverage area",
+            ],
+            closed
+        );
+    }
+}
@@ -0,0 +1,54 @@
+//! Fixtures shared by the availability and iCalendar tests.
+
+use crate::r4::{
+    elements::{
+        AvailableTimeBuilder, DaysOfWeek, FhirDateTime, NotAvailableBuilder, PeriodBuilder,
+        PosixTimeZone,
+    },
+    resources::HealthcareServiceBuilder,
+};
+
+pub(crate) fn amsterdam() -> PosixTimeZone {
+    PosixTimeZone::parse("CET-1CEST,M3.5.0,M10.5.0/3").unwrap()
+}
+
+pub(crate) fn at(value: &str) -> FhirDateTime {
+    FhirDateTime::parse(value).unwrap()
+}
+
+/// A service open 09:00-17:00 on weekdays and overnight from Saturday
+/// 22:00 to Sunday 06:00, closed for Christmas 2024.
+pub(crate) fn service() -> HealthcareServiceBuilder {
+    HealthcareServiceBuilder::new("hs-1")
+        .add_available_time(
+            AvailableTimeBuilder::default()
+                .with_days_of_week(vec![
+                    DaysOfWeek::Mon,
+                    DaysOfWeek::Tue,
+                    DaysOfWeek::Wed,
+                    DaysOfWeek::Thu,
+                    DaysOfWeek::Fri,
+                ])
+                .with_available_start_time("09:00:00")
+                .with_available_end_time("17:00:00")
+                .build(),
+        )
+        .add_available_time(
+            AvailableTimeBuilder::default()
+                .add_day_of_week(DaysOfWeek::Sat)
+                .with_available_start_time("22:00:00")
+                .with_available_end_time("06:00:00")
+                .build(),
+        )
+        .add_not_available(
+            NotAvailableBuilder::default()
+                .with_desscription("Christmas")
+                .with_during(
+                    PeriodBuilder::default()
+                        .with_start("2024-12-25")
+                        .with_end("2024-12-26")
+                        .build(),
+                )
+                .build(),
+        )
+}
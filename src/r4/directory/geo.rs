@@ -0,0 +1,485 @@
+use crate::{
+    FhirError,
+    r4::resources::{Location, LocationPosition},
+};
+
+/// The mean radius of the earth in meters, as recommended by the IUGG.
+pub const EARTH_RADIUS_METERS: f64 = 6_371_008.8;
+
+/// A WGS84 coordinate in decimal degrees.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GeoPoint {
+    latitude: f64,
+    longitude: f64,
+}
+
+impl GeoPoint {
+    /// Fails unless the latitude lies within [-90, 90] and the longitude
+    /// within [-180, 180].
+    pub fn new(latitude: f64, longitude: f64) -> Result<Self, FhirError> {
+        if !(-90.0..=90.0).contains(&latitude) {
+            return Err(FhirError::GeoError(format!(
+                "latitude {latitude} is outside [-90, 90]"
+            )));
+        }
+        if !(-180.0..=180.0).contains(&longitude) {
+            return Err(FhirError::GeoError(format!(
+                "longitude {longitude} is outside [-180, 180]"
+            )));
+        }
+        Ok(Self {
+            latitude,
+            longitude,
+        })
+    }
+
+    /// The position of `location`, `None` when it has none or when the
+    /// position is outside the WGS84 ranges.
+    pub fn of(location: &Location) -> Option<Self> {
+        location
+            .position
+            .as_ref()
+            .and_then(|position| Self::try_from(position).ok())
+    }
+
+    pub fn latitude(&self) -> f64 {
+        self.latitude
+    }
+
+    pub fn longitude(&self) -> f64 {
+        self.longitude
+    }
+
+    /// The great-circle distance to `other` in meters, using the haversine
+    /// formula on a spherical earth.
+    pub fn distance_to(&self, other: &GeoPoint) -> f64 {
+        let latitude = (other.latitude - self.latitude).to_radians();
+        let longitude = (other.longitude - self.longitude).to_radians();
+        let a = (latitude / 2.0).sin().powi(2)
+            + self.latitude.to_radians().cos()
+                * other.latitude.to_radians().cos()
+                * (longitude / 2.0).sin().powi(2);
+        2.0 * EARTH_RADIUS_METERS * a.sqrt().min(1.0).asin()
+    }
+
+    /// The point on the unit sphere, used by the [`SpatialIndex`] as the
+    /// straight line distance between such points orders them the same way as
+    /// the great-circle distance.
+    fn unit_vector(&self) -> [f64; 3] {
+        let (latitude, longitude) = (self.latitude.to_radians(), self.longitude.to_radians());
+        [
+            latitude.cos() * longitude.cos(),
+            latitude.cos() * longitude.sin(),
+            latitude.sin(),
+        ]
+    }
+}
+
+impl TryFrom<&LocationPosition> for GeoPoint {
+    type Error = FhirError;
+
+    fn try_from(position: &LocationPosition) -> Result<Self, Self::Error> {
+        Self::new(position.latitude.into(), position.longitude.into())
+    }
+}
+
+/// The great-circle distance in meters between two Locations, `None` when
+/// either lacks a valid position.
+pub fn distance_between(left: &Location, right: &Location) -> Option<f64> {
+    Some(GeoPoint::of(left)?.distance_to(&GeoPoint::of(right)?))
+}
+
+/// An area bounded by two parallels and two meridians.
+///
+/// When `west` is greater than `east` the box crosses the antimeridian.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BoundingBox {
+    pub south: f64,
+    pub west: f64,
+    pub north: f64,
+    pub east: f64,
+}
+
+impl BoundingBox {
+    pub fn new(south_west: GeoPoint, north_east: GeoPoint) -> Self {
+        Self {
+            south: south_west.latitude,
+            west: south_west.longitude,
+            north: north_east.latitude,
+            east: north_east.longitude,
+        }
+    }
+
+    /// The smallest box holding every point within `radius` meters of
+    /// `center`, spanning all longitudes when it reaches a pole.
+    pub fn around(center: GeoPoint, radius: f64) -> Self {
+        let angle = (radius / EARTH_RADIUS_METERS).to_degrees();
+        let south = center.latitude - angle;
+        let north = center.latitude + angle;
+        if south <= -90.0 || north >= 90.0 {
+            return Self {
+                south: south.max(-90.0),
+                west: -180.0,
+                north: north.min(90.0),
+                east: 180.0,
+            };
+        }
+        let spread = ((radius / EARTH_RADIUS_METERS).sin() / center.latitude.to_radians().cos())
+            .min(1.0)
+            .asin()
+            .to_degrees();
+        let wrap = |longitude: f64| (longitude + 540.0).rem_euclid(360.0) - 180.0;
+        Self {
+            south,
+            west: wrap(center.longitude - spread),
+            north,
+            east: wrap(center.longitude + spread),
+        }
+    }
+
+    pub fn contains(&self, point: &GeoPoint) -> bool {
+        let latitude = (self.south..=self.north).contains(&point.latitude);
+        let longitude = if self.west <= self.east {
+            (self.west..=self.east).contains(&point.longitude)
+        } else {
+            point.longitude >= self.west || point.longitude <= self.east
+        };
+        latitude && longitude
+    }
+}
+
+struct IndexEntry<T> {
+    point: GeoPoint,
+    vector: [f64; 3],
+    value: T,
+}
+
+/// A k-d tree over points on the sphere answering nearest neighbour, radius
+/// and bounding box queries.
+///
+/// Distances are great-circle distances in meters. Results are sorted
+/// nearest first.
+pub struct SpatialIndex<T> {
+    // A balanced tree laid out in place, the median of every slice is the
+    // node splitting it.
+    entries: Vec<IndexEntry<T>>,
+}
+
+impl<T> SpatialIndex<T> {
+    pub fn new(values: impl IntoIterator<Item = (GeoPoint, T)>) -> Self {
+        let mut entries: Vec<IndexEntry<T>> = values
+            .into_iter()
+            .map(|(point, value)| IndexEntry {
+                point,
+                vector: point.unit_vector(),
+                value,
+            })
+            .collect();
+        build(&mut entries, 0);
+        Self { entries }
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&GeoPoint, &T)> {
+        self.entries
+            .iter()
+            .map(|entry| (&entry.point, &entry.value))
+    }
+
+    /// The `count` values nearest to `point` with their distance.
+    pub fn nearest(&self, point: &GeoPoint, count: usize) -> Vec<(f64, &T)> {
+        let mut search = Search {
+            target: point.unit_vector(),
+            limit: f64::INFINITY,
+            capacity: count,
+            found: Vec::new(),
+        };
+        if count > 0 {
+            self.search(0, self.entries.len(), 0, &mut search);
+        }
+        self.distances(point, search.found)
+    }
+
+    /// The values within `radius` meters of `point` with their distance.
+    pub fn within_radius(&self, point: &GeoPoint, radius: f64) -> Vec<(f64, &T)> {
+        let angle = (radius / EARTH_RADIUS_METERS).min(std::f64::consts::PI);
+        let chord = 2.0 * (angle / 2.0).sin();
+        let mut search = Search {
+            target: point.unit_vector(),
+            limit: chord * chord,
+            capacity: usize::MAX,
+            found: Vec::new(),
+        };
+        self.search(0, self.entries.len(), 0, &mut search);
+        let mut found = self.distances(point, search.found);
+        found.retain(|(distance, _)| *distance <= radius);
+        found
+    }
+
+    /// The values inside `bounds`, in no particular order.
+    pub fn within_bounds(&self, bounds: &BoundingBox) -> Vec<&T> {
+        self.entries
+            .iter()
+            .filter(|entry| bounds.contains(&entry.point))
+            .map(|entry| &entry.value)
+            .collect()
+    }
+
+    fn distances(&self, point: &GeoPoint, found: Vec<(f64, usize)>) -> Vec<(f64, &T)> {
+        found
+            .into_iter()
+            .map(|(_, index)| {
+                let entry = &self.entries[index];
+                (point.distance_to(&entry.point), &entry.value)
+            })
+            .collect()
+    }
+
+    fn search(&self, start: usize, end: usize, depth: usize, search: &mut Search) {
+        if start >= end {
+            return;
+        }
+        let middle = start + (end - start) / 2;
+        let entry = &self.entries[middle];
+        search.offer(squared_distance(&entry.vector, &search.target), middle);
+
+        let axis = depth % 3;
+        let offset = search.target[axis] - entry.vector[axis];
+        let (near, far) = if offset < 0.0 {
+            ((start, middle), (middle + 1, end))
+        } else {
+            ((middle + 1, end), (start, middle))
+        };
+        self.search(near.0, near.1, depth + 1, search);
+        if offset * offset <= search.bound() {
+            self.search(far.0, far.1, depth + 1, search);
+        }
+    }
+}
+
+/// The state of a [`SpatialIndex`] query, distances are squared chord
+/// distances between unit vectors.
+struct Search {
+    target: [f64; 3],
+    limit: f64,
+    capacity: usize,
+    // Sorted nearest first, at most `capacity` long.
+    found: Vec<(f64, usize)>,
+}
+
+impl Search {
+    /// The largest distance an entry may have to be kept.
+    fn bound(&self) -> f64 {
+        match self.found.last() {
+            Some((distance, _)) if self.found.len() >= self.capacity => distance.min(self.limit),
+            _ => self.limit,
+        }
+    }
+
+    fn offer(&mut self, distance: f64, index: usize) {
+        if distance <= self.bound() {
+            let position = self.found.partition_point(|(other, _)| *other <= distance);
+            self.found.insert(position, (distance, index));
+            self.found.truncate(self.capacity);
+        }
+    }
+}
+
+impl<'a> SpatialIndex<&'a Location> {
+    /// Indexes the Locations that have a valid position, skipping the others.
+    pub fn from_locations(locations: impl IntoIterator<Item = &'a Location>) -> Self {
+        Self::new(
+            locations
+                .into_iter()
+                .filter_map(|location| Some((GeoPoint::of(location)?, location))),
+        )
+    }
+}
+
+fn build<T>(entries: &mut [IndexEntry<T>], depth: usize) {
+    if entries.len() <= 1 {
+        return;
+    }
+    let axis = depth % 3;
+    let middle = entries.len() / 2;
+    entries.select_nth_unstable_by(middle, |left, right| {
+        left.vector[axis].total_cmp(&right.vector[axis])
+    });
+    let (left, right) = entries.split_at_mut(middle);
+    build(left, depth + 1);
+    build(&mut right[1..], depth + 1);
+}
+
+fn squared_distance(left: &[f64; 3], right: &[f64; 3]) -> f64 {
+    left.iter()
+        .zip(right)
+        .map(|(left, right)| (left - right).powi(2))
+        .sum()
+}
+
+#[cfg(test)]
+mod test {
+    use crate::r4::resources::{LocationBuilder, LocationPositionBuilder};
+
+    use super::*;
+
+    fn point(latitude: f64, longitude: f64) -> GeoPoint {
+        GeoPoint::new(latitude, longitude).unwrap()
+    }
+
+    fn location(id: &str, latitude: f32, longitude: f32) -> Location {
+        LocationBuilder::new(id)
+            .position(
+                LocationPositionBuilder::default()
+                    .latitude(latitude)
+                    .longitude(longitude)
+                    .build(),
+            )
+            .build()
+    }
+
+    /// Deterministic points spread over the globe.
+    fn scattered(count: usize) -> Vec<GeoPoint> {
+        let mut state: u64 = 0x2545_f491_4f6c_dd1d;
+        let mut next = move || {
+            state = state
+                .wrapping_mul(6_364_136_223_846_793_005)
+                .wrapping_add(1_442_695_040_888_963_407);
+            (state >> 11) as f64 / (1u64 << 53) as f64
+        };
+        (0..count)
+            .map(|_| point(next() * 180.0 - 90.0, next() * 360.0 - 180.0))
+            .collect()
+    }
+
+    #[test]
+    fn test_geo_point_should_validate_wgs84_ranges() {
+        assert!(GeoPoint::new(90.0, -180.0).is_ok());
+        assert_eq!(
+            "geospatial error: latitude 90.5 is outside [-90, 90]",
+            GeoPoint::new(90.5, 0.0).unwrap_err().to_string()
+        );
+        assert_eq!(
+            "geospatial error: longitude -181 is outside [-180, 180]",
+            GeoPoint::new(0.0, -181.0).unwrap_err().to_string()
+        );
+        assert!(GeoPoint::new(f64::NAN, 0.0).is_err());
+        assert_eq!(None, GeoPoint::of(&location("bad", 91.0, 0.0)));
+        assert_eq!(None, GeoPoint::of(&LocationBuilder::new("none").build()));
+    }
+
+    #[test]
+    fn test_distance_should_use_great_circle() {
+        let london = location("london", 51.5074, -0.1278);
+        let paris = location("paris", 48.8566, 2.3522);
+
+        let distance = distance_between(&london, &paris).unwrap();
+
+        assert!((distance - 343_550.0).abs() < 500.0, "{distance}");
+        assert_eq!(0.0, point(10.0, 20.0).distance_to(&point(10.0, 20.0)));
+        let across = point(0.0, 179.5).distance_to(&point(0.0, -179.5));
+        assert!((across - 111_195.0).abs() < 10.0, "{across}");
+        let antipode = point(0.0, 0.0).distance_to(&point(0.0, 180.0));
+        assert!((antipode - std::f64::consts::PI * EARTH_RADIUS_METERS).abs() < 1.0);
+    }
+
+    #[test]
+    fn test_bounding_box_should_handle_antimeridian() {
+        let fiji = BoundingBox::around(point(-17.7, 179.9), 50_000.0);
+        assert!(fiji.west > fiji.east);
+        assert!(fiji.contains(&point(-17.7, -179.9)));
+        assert!(fiji.contains(&point(-17.9, 179.5)));
+        assert!(!fiji.contains(&point(-17.7, 178.0)));
+
+        let polar = BoundingBox::around(point(89.9, 10.0), 50_000.0);
+        assert_eq!((-180.0, 180.0, 90.0), (polar.west, polar.east, polar.north));
+
+        let netherlands = BoundingBox::new(point(50.75, 3.2), point(53.7, 7.22));
+        assert!(netherlands.contains(&point(52.37, 4.89)));
+        assert!(!netherlands.contains(&point(51.5, -0.12)));
+    }
+
+    #[test]
+    fn test_spatial_index_should_match_brute_force() {
+        let points = scattered(500);
+        let index = SpatialIndex::new(points.iter().copied().enumerate().map(|(i, p)| (p, i)));
+        assert_eq!(500, index.len());
+
+        for target in scattered(520).into_iter().skip(500) {
+            let mut expected: Vec<(f64, usize)> = points
+                .iter()
+                .enumerate()
+                .map(|(i, p)| (target.distance_to(p), i))
+                .collect();
+            expected.sort_by(|left, right| left.0.total_cmp(&right.0));
+
+            let nearest: Vec<usize> = index
+                .nearest(&target, 5)
+                .into_iter()
+                .map(|(_, i)| *i)
+                .collect();
+            let expected_nearest: Vec<usize> = expected.iter().take(5).map(|(_, i)| *i).collect();
+            assert_eq!(expected_nearest, nearest);
+
+            let radius = 1_500_000.0;
+            let within: Vec<usize> = index
+                .within_radius(&target, radius)
+                .into_iter()
+                .map(|(_, i)| *i)
+                .collect();
+            let expected_within: Vec<usize> = expected
+                .iter()
+                .take_while(|(distance, _)| *distance <= radius)
+                .map(|(_, i)| *i)
+                .collect();
+            assert_eq!(expected_within, within);
+        }
+        assert!(index.nearest(&point(0.0, 0.0), 0).is_empty());
+        assert_eq!(500, index.nearest(&point(0.0, 0.0), 1000).len());
+    }
+
+    #[test]
+    fn test_spatial_index_should_index_locations() {
+        let locations = [
+            location("amsterdam", 52.3676, 4.9041),
+            location("utrecht", 52.0907, 5.1214),
+            location("rotterdam", 51.9244, 4.4777),
+            location("groningen", 53.2194, 6.5665),
+            LocationBuilder::new("unplaced").build(),
+        ];
+        let index = SpatialIndex::from_locations(&locations);
+        let ids = |found: Vec<&&Location>| -> Vec<String> {
+            found
+                .into_iter()
+                .filter_map(|location| location.domain_resource.resource.id.clone())
+                .collect()
+        };
+
+        assert_eq!(4, index.len());
+        let nearest = index.nearest(&point(52.09, 5.12), 2);
+        assert!(nearest[0].0 < 1_000.0);
+        assert_eq!(
+            vec!["utrecht", "amsterdam"],
+            ids(nearest.into_iter().map(|(_, l)| l).collect())
+        );
+        assert_eq!(
+            vec!["amsterdam", "utrecht"],
+            ids(index
+                .within_radius(&point(52.3676, 4.9041), 40_000.0)
+                .into_iter()
+                .map(|(_, l)| l)
+                .collect())
+        );
+        let mut north =
+            ids(index.within_bounds(&BoundingBox::new(point(52.0, 4.0), point(54.0, 7.0))));
+        north.sort();
+        assert_eq!(vec!["amsterdam", "groningen", "utrecht"], north);
+    }
+}
@@ -0,0 +1,676 @@
+use serde_json::{Map, Value, json};
+
+use crate::{
+    FhirError,
+    r4::{
+        directory::{GeoPoint, LocationKind},
+        elements::{AttachmentBuilder, Extension, ExtensionBuilder},
+        resources::{Location, LocationBuilder, LocationPositionBuilder, LocationStatus},
+    },
+};
+
+/// The extension holding the boundary of a Location as a GeoJSON attachment.
+pub const LOCATION_BOUNDARY_GEOJSON: &str =
+    "http://hl7.org/fhir/StructureDefinition/location-boundary-geojson";
+
+pub const GEOJSON_CONTENT_TYPE: &str = "application/geo+json";
+
+/// A polygon of WGS84 points, the first ring is the outer boundary and the
+/// others are holes.
+///
+/// Containment is computed on the plane of longitudes and latitudes, rings
+/// crossing the antimeridian are not supported.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Polygon {
+    pub exterior: Vec<GeoPoint>,
+    pub holes: Vec<Vec<GeoPoint>>,
+}
+
+impl Polygon {
+    pub fn new(exterior: Vec<GeoPoint>) -> Self {
+        Self {
+            exterior,
+            holes: Vec::new(),
+        }
+    }
+
+    /// Whether `point` lies inside the outer ring and outside every hole.
+    pub fn contains(&self, point: &GeoPoint) -> bool {
+        ring_contains(&self.exterior, point)
+            && !self.holes.iter().any(|hole| ring_contains(hole, point))
+    }
+
+    fn from_coordinates(coordinates: &Value) -> Result<Self, FhirError> {
+        let mut rings = array(coordinates, "polygon")?
+            .iter()
+            .map(|ring| {
+                let mut ring = array(ring, "linear ring")?
+                    .iter()
+                    .map(|position| Ok(parse_position(position)?.0))
+                    .collect::<Result<Vec<_>, FhirError>>()?;
+                // The closing position is implied by the ring.
+                if ring.len() > 1 && ring.first() == ring.last() {
+                    ring.pop();
+                }
+                Ok(ring)
+            })
+            .collect::<Result<Vec<_>, FhirError>>()?
+            .into_iter();
+        let Some(exterior) = rings.next() else {
+            return Err(geo_error("polygon without rings"));
+        };
+        Ok(Self {
+            exterior,
+            holes: rings.collect(),
+        })
+    }
+
+    fn coordinates(&self) -> Value {
+        let ring = |ring: &Vec<GeoPoint>| -> Value {
+            let mut positions: Vec<Value> = ring.iter().map(position).collect();
+            // GeoJSON rings are closed, the first position is repeated.
+            if let (Some(first), Some(last)) = (ring.first(), ring.last())
+                && first != last
+            {
+                positions.push(position(first));
+            }
+            Value::Array(positions)
+        };
+        Value::Array(
+            std::iter::once(&self.exterior)
+                .chain(&self.holes)
+                .map(ring)
+                .collect(),
+        )
+    }
+}
+
+/// The GeoJSON geometries a Location can be described with.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Geometry {
+    Point(GeoPoint),
+    Polygon(Polygon),
+    MultiPolygon(Vec<Polygon>),
+}
+
+impl Geometry {
+    /// Reads a GeoJSON geometry, or the geometry of a GeoJSON feature.
+    pub fn from_geojson(value: &Value) -> Result<Self, FhirError> {
+        let coordinates = || {
+            value
+                .get("coordinates")
+                .ok_or_else(|| geo_error("geometry without coordinates"))
+        };
+        match value.get("type").and_then(Value::as_str) {
+            Some("Feature") => match value.get("geometry") {
+                Some(geometry) if !geometry.is_null() => Self::from_geojson(geometry),
+                _ => Err(geo_error("feature without geometry")),
+            },
+            Some("Point") => Ok(Self::Point(parse_position(coordinates()?)?.0)),
+            Some("Polygon") => Ok(Self::Polygon(Polygon::from_coordinates(coordinates()?)?)),
+            Some("MultiPolygon") => Ok(Self::MultiPolygon(
+                array(coordinates()?, "multi polygon")?
+                    .iter()
+                    .map(Polygon::from_coordinates)
+                    .collect::<Result<_, _>>()?,
+            )),
+            Some(other) => Err(geo_error(format!("unsupported geometry type '{other}'"))),
+            None => Err(geo_error("geometry without a type")),
+        }
+    }
+
+    pub fn to_geojson(&self) -> Value {
+        match self {
+            Self::Point(point) => json!({"type": "Point", "coordinates": position(point)}),
+            Self::Polygon(polygon) => {
+                json!({"type": "Polygon", "coordinates": polygon.coordinates()})
+            }
+            Self::MultiPolygon(polygons) => json!({
+                "type": "MultiPolygon",
+                "coordinates": polygons.iter().map(Polygon::coordinates).collect::<Vec<_>>()
+            }),
+        }
+    }
+
+    /// Whether `point` lies within the area of the geometry, always false for
+    /// a point.
+    pub fn contains(&self, point: &GeoPoint) -> bool {
+        match self {
+            Self::Point(_) => false,
+            Self::Polygon(polygon) => polygon.contains(point),
+            Self::MultiPolygon(polygons) => polygons.iter().any(|polygon| polygon.contains(point)),
+        }
+    }
+}
+
+/// Reads the `location-boundary-geojson` extension of `location`, returns
+/// `Ok(None)` when there is none.
+pub fn location_boundary(location: &Location) -> Result<Option<Geometry>, FhirError> {
+    let Some(extension) = location
+        .domain_resource
+        .extensions(LOCATION_BOUNDARY_GEOJSON)
+        .next()
+    else {
+        return Ok(None);
+    };
+    let Some(data) = extension
+        .value_attachment
+        .as_ref()
+        .map(|attachment| attachment.decode_data())
+        .transpose()?
+        .flatten()
+    else {
+        return Err(geo_error("boundary extension without attachment data"));
+    };
+    let geojson: Value = serde_json::from_slice(&data)?;
+    Geometry::from_geojson(&geojson).map(Some)
+}
+
+/// Creates a `location-boundary-geojson` extension holding `geometry`.
+pub fn boundary_extension(geometry: &Geometry) -> Result<Extension, FhirError> {
+    let data = serde_json::to_vec(&geometry.to_geojson())?;
+    Ok(ExtensionBuilder::default()
+        .with_url(LOCATION_BOUNDARY_GEOJSON)
+        .with_value_attachment(
+            AttachmentBuilder::default()
+                .content_type(GEOJSON_CONTENT_TYPE)
+                .data_bytes(&data)
+                .build(),
+        )
+        .build())
+}
+
+/// The jurisdictions among `locations` whose boundary contains `point`.
+pub fn jurisdictions_containing<'a>(
+    locations: impl IntoIterator<Item = &'a Location>,
+    point: &GeoPoint,
+) -> Vec<&'a Location> {
+    locations
+        .into_iter()
+        .filter(|location| LocationKind::of(location) == LocationKind::Jurisdiction)
+        .filter(|location| {
+            location_boundary(location)
+                .ok()
+                .flatten()
+                .is_some_and(|boundary| boundary.contains(point))
+        })
+        .collect()
+}
+
+/// Describes `location` as a GeoJSON feature.
+///
+/// The geometry is the position, the boundary, or a `GeometryCollection` of
+/// both. The id, name, status, mode, description, `partOf` and
+/// `managingOrganization` references are kept in the properties.
+pub fn location_to_feature(location: &Location) -> Result<Value, FhirError> {
+    let mut geometries = Vec::new();
+    if let Some(point) = GeoPoint::of(location) {
+        let mut point = Geometry::Point(point).to_geojson();
+        if let Some(altitude) = location
+            .position
+            .as_ref()
+            .and_then(|position| position.altitude)
+            && let Some(coordinates) = point["coordinates"].as_array_mut()
+        {
+            coordinates.push(json!(altitude));
+        }
+        geometries.push(point);
+    }
+    if let Some(boundary) = location_boundary(location)? {
+        geometries.push(boundary.to_geojson());
+    }
+    let geometry = match geometries.len() {
+        0 => Value::Null,
+        1 => geometries.remove(0),
+        _ => json!({"type": "GeometryCollection", "geometries": geometries}),
+    };
+
+    let mut properties = Map::new();
+    properties.insert("resourceType".into(), json!(location.resource_type));
+    let mut property = |name: &str, value: Option<Value>| {
+        if let Some(value) = value {
+            properties.insert(name.into(), value);
+        }
+    };
+    property("name", location.name.as_ref().map(|name| json!(name)));
+    property(
+        "status",
+        location
+            .status
+            .as_ref()
+            .map(serde_json::to_value)
+            .transpose()?,
+    );
+    property("mode", location.mode.as_ref().map(|mode| json!(mode)));
+    property(
+        "description",
+        location.description.as_ref().map(|text| json!(text)),
+    );
+    property(
+        "partOf",
+        location
+            .part_of
+            .as_ref()
+            .and_then(|part_of| part_of.reference.as_ref())
+            .map(|reference| json!(reference)),
+    );
+    property(
+        "managingOrganization",
+        location
+            .managing_organization
+            .as_ref()
+            .and_then(|organization| organization.reference.as_ref())
+            .map(|reference| json!(reference)),
+    );
+
+    let mut feature = json!({
+        "type": "Feature",
+        "geometry": geometry,
+        "properties": properties,
+    });
+    if let Some(id) = &location.domain_resource.resource.id {
+        feature["id"] = json!(id);
+    }
+    Ok(feature)
+}
+
+/// Describes `locations` as a GeoJSON feature collection, see
+/// [`location_to_feature`].
+pub fn locations_to_geojson<'a>(
+    locations: impl IntoIterator<Item = &'a Location>,
+) -> Result<Value, FhirError> {
+    let features = locations
+        .into_iter()
+        .map(location_to_feature)
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(json!({"type": "FeatureCollection", "features": features}))
+}
+
+/// Creates a Location builder from a GeoJSON feature, the reverse of
+/// [`location_to_feature`].
+///
+/// A point becomes the position, polygons become the boundary extension.
+pub fn location_from_feature(feature: &Value) -> Result<LocationBuilder, FhirError> {
+    if feature.get("type").and_then(Value::as_str) != Some("Feature") {
+        return Err(geo_error("expected a GeoJSON Feature"));
+    }
+    let properties = feature.get("properties").unwrap_or(&Value::Null);
+    let text = |name: &str| properties.get(name).and_then(Value::as_str);
+
+    let id = match feature.get("id") {
+        Some(Value::String(id)) => Some(id.clone()),
+        Some(Value::Number(id)) => Some(id.to_string()),
+        _ => text("id").map(String::from),
+    };
+    let mut builder = match id {
+        Some(id) => LocationBuilder::new(id),
+        None => LocationBuilder::default(),
+    };
+    if let Some(name) = text("name") {
+        builder = builder.name(name);
+    }
+    if let Some(status) = properties.get("status") {
+        builder = builder.status(serde_json::from_value::<LocationStatus>(status.clone())?);
+    }
+    if let Some(mode) = text("mode") {
+        builder = builder.mode(mode);
+    }
+    if let Some(description) = text("description") {
+        builder = builder.description(description);
+    }
+
+    let geometry = feature.get("geometry").unwrap_or(&Value::Null);
+    let geometries = match geometry.get("type").and_then(Value::as_str) {
+        None if geometry.is_null() => Vec::new(),
+        Some("GeometryCollection") => array(
+            geometry.get("geometries").unwrap_or(&Value::Null),
+            "geometry collection",
+        )?
+        .iter()
+        .collect(),
+        _ => vec![geometry],
+    };
+    let mut polygons = Vec::new();
+    for geometry in geometries {
+        if geometry.get("type").and_then(Value::as_str) == Some("Point") {
+            let (point, altitude) =
+                parse_position(geometry.get("coordinates").unwrap_or(&Value::Null))?;
+            let mut position = LocationPositionBuilder::default()
+                .latitude(point.latitude() as f32)
+                .longitude(point.longitude() as f32);
+            if let Some(altitude) = altitude {
+                position = position.altitude(altitude as f32);
+            }
+            builder = builder.position(position.build());
+            continue;
+        }
+        match Geometry::from_geojson(geometry)? {
+            Geometry::Polygon(polygon) => polygons.push(polygon),
+            Geometry::MultiPolygon(multi) => polygons.extend(multi),
+            Geometry::Point(_) => {}
+        }
+    }
+    let boundary = match polygons.len() {
+        0 => None,
+        1 => polygons.pop().map(Geometry::Polygon),
+        _ => Some(Geometry::MultiPolygon(polygons)),
+    };
+    if let Some(boundary) = boundary {
+        builder = builder.add_extension(boundary_extension(&boundary)?);
+    }
+    Ok(builder)
+}
+
+/// Creates a Location builder for every feature of a GeoJSON feature
+/// collection, or for a single feature.
+pub fn locations_from_geojson(geojson: &Value) -> Result<Vec<LocationBuilder>, FhirError> {
+    match geojson.get("type").and_then(Value::as_str) {
+        Some("FeatureCollection") => array(
+            geojson.get("features").unwrap_or(&Value::Null),
+            "feature collection",
+        )?
+        .iter()
+        .map(location_from_feature)
+        .collect(),
+        _ => Ok(vec![location_from_feature(geojson)?]),
+    }
+}
+
+fn geo_error(message: impl Into<String>) -> FhirError {
+    FhirError::GeoError(message.into())
+}
+
+fn array<'a>(value: &'a Value, what: &str) -> Result<&'a Vec<Value>, FhirError> {
+    value
+        .as_array()
+        .ok_or_else(|| geo_error(format!("{what} is not an array")))
+}
+
+/// Reads a `[longitude, latitude, altitude?]` position.
+fn parse_position(value: &Value) -> Result<(GeoPoint, Option<f64>), FhirError> {
+    let numbers = array(value, "position")?
+        .iter()
+        .map(|number| {
+            number
+                .as_f64()
+                .ok_or_else(|| geo_error(format!("position value {number} is not a number")))
+        })
+        .collect::<Result<Vec<f64>, FhirError>>()?;
+    match numbers[..] {
+        [longitude, latitude] => Ok((GeoPoint::new(latitude, longitude)?, None)),
+        [longitude, latitude, altitude, ..] => {
+            Ok((GeoPoint::new(latitude, longitude)?, Some(altitude)))
+        }
+        _ => Err(geo_error("position needs a longitude and a latitude")),
+    }
+}
+
+fn position(point: &GeoPoint) -> Value {
+    json!([point.longitude(), point.latitude()])
+}
+
+/// Even-odd ray casting, points on an edge may fall either way.
+fn ring_contains(ring: &[GeoPoint], point: &GeoPoint) -> bool {
+    let (x, y) = (point.longitude(), point.latitude());
+    let mut inside = false;
+    let mut previous = match ring.last() {
+        Some(last) => last,
+        None => return false,
+    };
+    for current in ring {
+        let (x1, y1) = (current.longitude(), current.latitude());
+        let (x2, y2) = (previous.longitude(), previous.latitude());
+        if (y1 > y) != (y2 > y) && x < (x2 - x1) * (y - y1) / (y2 - y1) + x1 {
+            inside = !inside;
+        }
+        previous = current;
+    }
+    inside
+}
+
+#[cfg(test)]
+mod test {
+    use crate::r4::{
+        directory::MCSD_LOCATION_TYPE_SYSTEM,
+        elements::{CodeableConceptBuilder, CodingBuilder, ReferenceBuilder},
+    };
+
+    use super::*;
+
+    fn point(latitude: f64, longitude: f64) -> GeoPoint {
+        GeoPoint::new(latitude, longitude).unwrap()
+    }
+
+    fn square(south: f64, west: f64, north: f64, east: f64) -> Vec<GeoPoint> {
+        vec![
+            point(south, west),
+            point(south, east),
+            point(north, east),
+            point(north, west),
+        ]
+    }
+
+    fn jurisdiction(id: &str, boundary: Geometry) -> Location {
+        LocationBuilder::new(id)
+            .name(id)
+            .add_type(
+                CodeableConceptBuilder::default()
+                    .add_coding(
+                        CodingBuilder::default()
+                            .with_system(MCSD_LOCATION_TYPE_SYSTEM)
+                            .with_code("jurisdiction")
+                            .build(),
+                    )
+                    .build(),
+            )
+            .add_extension(boundary_extension(&boundary).unwrap())
+            .build()
+    }
+
+    #[test]
+    fn test_polygon_should_exclude_holes() {
+        let polygon = Polygon {
+            exterior: square(0.0, 0.0, 10.0, 10.0),
+            holes: vec![square(4.0, 4.0, 6.0, 6.0)],
+        };
+
+        assert!(polygon.contains(&point(2.0, 2.0)));
+        assert!(!polygon.contains(&point(5.0, 5.0)));
+        assert!(!polygon.contains(&point(11.0, 5.0)));
+        assert!(!polygon.contains(&point(5.0, -0.5)));
+
+        let multi =
+            Geometry::MultiPolygon(vec![polygon, Polygon::new(square(20.0, 20.0, 21.0, 21.0))]);
+        assert!(multi.contains(&point(20.5, 20.5)));
+        assert!(!Geometry::Point(point(1.0, 1.0)).contains(&point(1.0, 1.0)));
+    }
+
+    #[test]
+    fn test_geometry_should_round_trip_geojson() {
+        let geojson = json!({
+            "type": "Polygon",
+            "coordinates": [
+                [[0.0, 0.0], [10.0, 0.0], [10.0, 10.0], [0.0, 10.0], [0.0, 0.0]],
+                [[4.0, 4.0], [6.0, 4.0], [6.0, 6.0], [4.0, 4.0]]
+            ]
+        });
+
+        let geometry = Geometry::from_geojson(&geojson).unwrap();
+
+        assert_eq!(geojson, geometry.to_geojson());
+        let Geometry::Polygon(polygon) = &geometry else {
+            panic!("expected a polygon");
+        };
+        assert_eq!(point(10.0, 0.0), polygon.exterior[3]);
+
+        let feature = json!({"type": "Feature", "geometry": geojson, "properties": {}});
+        assert_eq!(geometry, Geometry::from_geojson(&feature).unwrap());
+        assert_eq!(
+            "geospatial error: latitude 95 is outside [-90, 90]",
+            Geometry::from_geojson(&json!({"type": "Point", "coordinates": [5.0, 95.0]}))
+                .unwrap_err()
+                .to_string()
+        );
+        assert!(Geometry::from_geojson(&json!({"type": "LineString", "coordinates": []})).is_err());
+    }
+
+    #[test]
+    fn test_location_boundary_should_read_extension() {
+        let boundary = Geometry::Polygon(Polygon::new(square(50.0, 3.0, 54.0, 8.0)));
+        let location = jurisdiction("nl", boundary.clone());
+
+        let extension = &location.domain_resource.extension.as_ref().unwrap()[0];
+        assert_eq!(LOCATION_BOUNDARY_GEOJSON, extension.url);
+        assert_eq!(
+            Some(GEOJSON_CONTENT_TYPE),
+            extension
+                .value_attachment
+                .as_ref()
+                .unwrap()
+                .content_type
+                .as_deref()
+        );
+        assert_eq!(Some(boundary), location_boundary(&location).unwrap());
+        assert_eq!(
+            None,
+            location_boundary(&LocationBuilder::new("x").build()).unwrap()
+        );
+
+        let broken = LocationBuilder::new("broken")
+            .add_extension(
+                ExtensionBuilder::default()
+                    .with_url(LOCATION_BOUNDARY_GEOJSON)
+                    .build(),
+            )
+            .build();
+        assert!(location_boundary(&broken).is_err());
+    }
+
+    #[test]
+    fn test_locations_should_round_trip_feature_collection() {
+        let hospital = LocationBuilder::new("hospital")
+            .name("Hospital")
+            .status(LocationStatus::Active)
+            .mode("instance")
+            .position(
+                LocationPositionBuilder::default()
+                    .latitude(52.5)
+                    .longitude(5.25)
+                    .altitude(3.0)
+                    .build(),
+            )
+            .part_of(
+                ReferenceBuilder::default()
+                    .with_reference("Location/nl")
+                    .build(),
+            )
+            .build();
+        let country = jurisdiction(
+            "nl",
+            Geometry::Polygon(Polygon::new(square(50.0, 3.0, 54.0, 8.0))),
+        );
+        let unplaced = LocationBuilder::new("unplaced").build();
+
+        let geojson = locations_to_geojson([&hospital, &country, &unplaced]).unwrap();
+
+        assert_eq!(
+            json!({
+                "type": "Feature",
+                "id": "hospital",
+                "geometry": {"type": "Point", "coordinates": [5.25, 52.5, 3.0]},
+                "properties": {
+                    "resourceType": "Location",
+                    "name": "Hospital",
+                    "status": "active",
+                    "mode": "instance",
+                    "partOf": "Location/nl"
+                }
+            }),
+            geojson["features"][0]
+        );
+        assert_eq!("Polygon", geojson["features"][1]["geometry"]["type"]);
+        assert_eq!(Value::Null, geojson["features"][2]["geometry"]);
+
+        let imported: Vec<Location> = locations_from_geojson(&geojson)
+            .unwrap()
+            .into_iter()
+            .map(LocationBuilder::build)
+            .collect();
+        assert_eq!(3, imported.len());
+        assert_eq!(hospital.position, imported[0].position);
+        assert_eq!(hospital.name, imported[0].name);
+        assert_eq!(hospital.status, imported[0].status);
+        assert_eq!(
+            location_boundary(&country).unwrap(),
+            location_boundary(&imported[1]).unwrap()
+        );
+        assert_eq!(
+            Some("unplaced"),
+            imported[2].domain_resource.resource.id.as_deref()
+        );
+    }
+
+    #[test]
+    fn test_feature_should_combine_position_and_boundary() {
+        let feature = json!({
+            "type": "Feature",
+            "id": 7,
+            "geometry": {
+                "type": "GeometryCollection",
+                "geometries": [
+                    {"type": "Point", "coordinates": [1.0, 1.0]},
+                    {"type": "Polygon", "coordinates": [[[0.0, 0.0], [2.0, 0.0], [2.0, 2.0], [0.0, 0.0]]]},
+                    {"type": "Polygon", "coordinates": [[[5.0, 5.0], [6.0, 5.0], [6.0, 6.0], [5.0, 5.0]]]}
+                ]
+            },
+            "properties": null
+        });
+
+        let location = location_from_feature(&feature).unwrap().build();
+
+        assert_eq!(Some("7"), location.domain_resource.resource.id.as_deref());
+        assert_eq!(Some(point(1.0, 1.0)), GeoPoint::of(&location));
+        assert!(matches!(
+            location_boundary(&location).unwrap(),
+            Some(Geometry::MultiPolygon(polygons)) if polygons.len() == 2
+        ));
+        assert_eq!(
+            "GeometryCollection",
+            location_to_feature(&location).unwrap()["geometry"]["type"]
+        );
+        assert!(location_from_feature(&json!({"type": "Point"})).is_err());
+    }
+
+    #[test]
+    fn test_jurisdictions_containing_should_test_boundaries() {
+        let country = jurisdiction(
+            "country",
+            Geometry::Polygon(Polygon::new(square(50.0, 3.0, 54.0, 8.0))),
+        );
+        let north = jurisdiction(
+            "north",
+            Geometry::Polygon(Polygon::new(square(52.5, 3.0, 54.0, 8.0))),
+        );
+        let south = jurisdiction(
+            "south",
+            Geometry::MultiPolygon(vec![Polygon::new(square(50.0, 3.0, 52.5, 8.0))]),
+        );
+        let facility = LocationBuilder::new("facility")
+            .position(
+                LocationPositionBuilder::default()
+                    .latitude(53.2)
+                    .longitude(6.56)
+                    .build(),
+            )
+            .build();
+        let facility_point = GeoPoint::of(&facility).unwrap();
+
+        let containing: Vec<&str> =
+            jurisdictions_containing([&country, &north, &south, &facility], &facility_point)
+                .into_iter()
+                .filter_map(|location| location.name.as_deref())
+                .collect();
+
+        assert_eq!(vec!["country", "north"], containing);
+    }
+}
@@ -0,0 +1,838 @@
+use std::{collections::HashMap, fmt};
+
+use crate::{
+    FhirError,
+    r4::{
+        elements::{CodeableConcept, ParsedReference, Reference},
+        resources::{Location, Organization, ReferenceTarget},
+    },
+};
+
+/// A resource that nests within another resource of the same type.
+pub trait PartOf: ReferenceTarget + Sized {
+    fn part_of(&self) -> Option<&Reference<Self>>;
+}
+
+impl PartOf for Organization {
+    fn part_of(&self) -> Option<&Reference<Self>> {
+        self.part_of.as_ref()
+    }
+}
+
+impl PartOf for Location {
+    fn part_of(&self) -> Option<&Reference<Self>> {
+        self.part_of.as_ref()
+    }
+}
+
+/// The tree of Organizations formed by their `partOf` references.
+pub type OrganizationHierarchy = Hierarchy<Organization>;
+
+/// The tree of Locations formed by their `partOf` references.
+pub type LocationHierarchy = Hierarchy<Location>;
+
+/// A resource whose `partOf` reference could not be followed within the
+/// hierarchy.
+#[derive(Debug, PartialEq)]
+pub struct Orphan<'a, T: PartOf> {
+    pub resource: &'a T,
+    pub part_of: &'a Reference<T>,
+}
+
+/// A tree, or forest, of resources linked through their `partOf` references.
+///
+/// Only relative and RESTful absolute references are followed, the base of an
+/// absolute reference is not compared. A resource whose `partOf` names a
+/// resource outside the hierarchy is an orphan, resources whose `partOf`
+/// chain loops are reported by [`Hierarchy::cycles`]. Neither is a root.
+#[derive(Debug)]
+pub struct Hierarchy<T: PartOf> {
+    resources: Vec<T>,
+    by_id: HashMap<String, usize>,
+    parents: Vec<Option<usize>>,
+    children: Vec<Vec<usize>>,
+    /// Whether the resource at an index has a `partOf` that is not followed.
+    orphaned: Vec<bool>,
+    cycles: Vec<Vec<usize>>,
+}
+
+impl<T: PartOf> Hierarchy<T> {
+    /// Builds the hierarchy, failing when a resource has no id or when two
+    /// resources share an id.
+    pub fn new(resources: impl IntoIterator<Item = T>) -> Result<Self, FhirError> {
+        let resources: Vec<T> = resources.into_iter().collect();
+        let mut by_id = HashMap::new();
+        for (index, resource) in resources.iter().enumerate() {
+            let Some(id) = resource.resource_id() else {
+                return Err(FhirError::HierarchyError(format!(
+                    "{} without an id",
+                    T::TYPE
+                )));
+            };
+            if by_id.insert(id.to_string(), index).is_some() {
+                return Err(FhirError::HierarchyError(format!(
+                    "duplicate {}/{id}",
+                    T::TYPE
+                )));
+            }
+        }
+
+        let mut parents = vec![None; resources.len()];
+        let mut children = vec![Vec::new(); resources.len()];
+        let mut orphaned = vec![false; resources.len()];
+        for (index, resource) in resources.iter().enumerate() {
+            let Some(part_of) = resource.part_of() else {
+                continue;
+            };
+            let parent = match part_of.target() {
+                Ok(Some(
+                    ParsedReference::Relative { id, .. } | ParsedReference::Absolute { id, .. },
+                )) => by_id.get(id).copied(),
+                _ => None,
+            };
+            match parent {
+                Some(parent) => {
+                    parents[index] = Some(parent);
+                    children[parent].push(index);
+                }
+                None => orphaned[index] = true,
+            }
+        }
+
+        let mut hierarchy = Self {
+            resources,
+            by_id,
+            parents,
+            children,
+            orphaned,
+            cycles: Vec::new(),
+        };
+        hierarchy.cycles = hierarchy.find_cycles();
+        Ok(hierarchy)
+    }
+
+    fn find_cycles(&self) -> Vec<Vec<usize>> {
+        #[derive(Clone, Copy, PartialEq)]
+        enum State {
+            Unvisited,
+            OnPath,
+            Done,
+        }
+
+        let mut states = vec![State::Unvisited; self.resources.len()];
+        let mut cycles = Vec::new();
+        for start in 0..self.resources.len() {
+            let mut path = Vec::new();
+            let mut current = Some(start);
+            while let Some(index) = current
+                && states[index] == State::Unvisited
+            {
+                states[index] = State::OnPath;
+                path.push(index);
+                current = self.parents[index];
+            }
+            if let Some(index) = current
+                && states[index] == State::OnPath
+                && let Some(position) = path.iter().position(|&on_path| on_path == index)
+            {
+                cycles.push(path[position..].to_vec());
+            }
+            for index in path {
+                states[index] = State::Done;
+            }
+        }
+        cycles
+    }
+
+    fn is_root(&self, index: usize) -> bool {
+        self.parents[index].is_none() && !self.orphaned[index]
+    }
+
+    fn orphan_indices(&self) -> impl Iterator<Item = usize> + '_ {
+        (0..self.resources.len()).filter(|&index| self.orphaned[index])
+    }
+
+    fn index(&self, id: &str) -> Option<usize> {
+        self.by_id.get(id).copied()
+    }
+
+    fn resolve(&self, indices: impl IntoIterator<Item = usize>) -> Vec<&T> {
+        indices
+            .into_iter()
+            .map(|index| &self.resources[index])
+            .collect()
+    }
+
+    /// The parent indices of `index`, nearest first, stopping before an index
+    /// repeats. The second value tells whether the chain loops.
+    fn ancestor_indices(&self, index: usize) -> (Vec<usize>, bool) {
+        let mut ancestors = Vec::new();
+        let mut current = self.parents[index];
+        while let Some(parent) = current {
+            if parent == index || ancestors.contains(&parent) {
+                return (ancestors, true);
+            }
+            ancestors.push(parent);
+            current = self.parents[parent];
+        }
+        (ancestors, false)
+    }
+
+    pub fn len(&self) -> usize {
+        self.resources.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.resources.is_empty()
+    }
+
+    pub fn get(&self, id: &str) -> Option<&T> {
+        self.index(id).map(|index| &self.resources[index])
+    }
+
+    pub fn resources(&self) -> impl Iterator<Item = &T> {
+        self.resources.iter()
+    }
+
+    pub fn parent(&self, id: &str) -> Option<&T> {
+        let parent = self.parents[self.index(id)?]?;
+        Some(&self.resources[parent])
+    }
+
+    /// The direct children of `id`, in the order they were given.
+    pub fn children(&self, id: &str) -> Vec<&T> {
+        match self.index(id) {
+            Some(index) => self.resolve(self.children[index].iter().copied()),
+            None => Vec::new(),
+        }
+    }
+
+    /// The ancestors of `id`, nearest first. Within a cycle every other member
+    /// of the cycle is listed once.
+    pub fn ancestors(&self, id: &str) -> Vec<&T> {
+        match self.index(id) {
+            Some(index) => self.resolve(self.ancestor_indices(index).0),
+            None => Vec::new(),
+        }
+    }
+
+    /// All resources below `id`, depth first.
+    pub fn descendants(&self, id: &str) -> Vec<&T> {
+        self.subtree(id)
+            .skip(1)
+            .map(|(_, resource)| resource)
+            .collect()
+    }
+
+    /// The number of ancestors of `id`, zero for a root or an orphan. Returns
+    /// `None` for an unknown id or when the `partOf` chain loops.
+    pub fn depth(&self, id: &str) -> Option<usize> {
+        let (ancestors, cyclic) = self.ancestor_indices(self.index(id)?);
+        (!cyclic).then_some(ancestors.len())
+    }
+
+    /// The resources without a `partOf` reference.
+    pub fn roots(&self) -> Vec<&T> {
+        self.resolve((0..self.resources.len()).filter(|&index| self.is_root(index)))
+    }
+
+    /// The resources whose `partOf` does not point to a resource in the
+    /// hierarchy, including logical and contained references.
+    pub fn orphans(&self) -> Vec<Orphan<'_, T>> {
+        self.orphan_indices()
+            .filter_map(|index| {
+                let resource = &self.resources[index];
+                Some(Orphan {
+                    resource,
+                    part_of: resource.part_of()?,
+                })
+            })
+            .collect()
+    }
+
+    /// Every loop in the `partOf` chains, each starting at the resource where
+    /// the walk entered it and following `partOf` from there.
+    pub fn cycles(&self) -> Vec<Vec<&T>> {
+        self.cycles
+            .iter()
+            .map(|cycle| self.resolve(cycle.iter().copied()))
+            .collect()
+    }
+
+    /// Iterates over `id` and everything below it depth first, yielding each
+    /// resource with its depth relative to `id`.
+    pub fn subtree(&self, id: &str) -> Subtree<'_, T> {
+        Subtree {
+            hierarchy: self,
+            stack: self.index(id).map(|index| (0, index)).into_iter().collect(),
+            visited: vec![false; self.resources.len()],
+        }
+    }
+
+    fn label(&self, index: usize) -> String {
+        let resource = &self.resources[index];
+        let id = resource.resource_id().unwrap_or_default();
+        match resource.reference_display() {
+            Some(display) => format!("{display} ({id})"),
+            None => id.to_string(),
+        }
+    }
+
+    fn render_children(&self, out: &mut String, index: usize, prefix: &str, visited: &mut [bool]) {
+        let children: Vec<usize> = self.children[index]
+            .iter()
+            .copied()
+            .filter(|&child| !visited[child])
+            .collect();
+        for (position, &child) in children.iter().enumerate() {
+            visited[child] = true;
+            let last = position + 1 == children.len();
+            out.push_str(prefix);
+            out.push_str(if last { "└── " } else { "├── " });
+            out.push_str(&self.label(child));
+            out.push('\n');
+            let prefix = format!("{prefix}{}", if last { "    " } else { "│   " });
+            self.render_children(out, child, &prefix, visited);
+        }
+    }
+
+    /// Renders the hierarchy as an indented tree, roots first, then the
+    /// orphans with their unresolved `partOf` and finally the cycles, each
+    /// followed by the branches hanging off its members. Every resource is
+    /// listed once.
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+        let mut visited = vec![false; self.resources.len()];
+        for index in (0..self.resources.len()).filter(|&index| self.is_root(index)) {
+            visited[index] = true;
+            out.push_str(&self.label(index));
+            out.push('\n');
+            self.render_children(&mut out, index, "", &mut visited);
+        }
+        for index in self.orphan_indices() {
+            visited[index] = true;
+            let part_of = self.resources[index]
+                .part_of()
+                .map(|part_of| {
+                    let view = part_of.view();
+                    view.reference
+                        .or(view.display)
+                        .unwrap_or("a logical reference")
+                })
+                .unwrap_or_default();
+            out.push_str(&format!(
+                "{} [orphan, partOf {part_of}]\n",
+                self.label(index)
+            ));
+            self.render_children(&mut out, index, "", &mut visited);
+        }
+        for cycle in &self.cycles {
+            let labels: Vec<String> = cycle
+                .iter()
+                .chain(cycle.first())
+                .map(|&index| self.label(index))
+                .collect();
+            out.push_str(&format!("cycle: {}\n", labels.join(" -> ")));
+            for &index in cycle {
+                visited[index] = true;
+            }
+            for &index in cycle {
+                if self.children[index].iter().all(|&child| visited[child]) {
+                    continue;
+                }
+                out.push_str(&format!("{} [in cycle]\n", self.label(index)));
+                self.render_children(&mut out, index, "", &mut visited);
+            }
+        }
+        out
+    }
+}
+
+impl<T: PartOf> fmt::Display for Hierarchy<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.render())
+    }
+}
+
+/// Depth first iterator over a part of a [`Hierarchy`], see
+/// [`Hierarchy::subtree`].
+pub struct Subtree<'a, T: PartOf> {
+    hierarchy: &'a Hierarchy<T>,
+    stack: Vec<(usize, usize)>,
+    visited: Vec<bool>,
+}
+
+impl<'a, T: PartOf> Iterator for Subtree<'a, T> {
+    type Item = (usize, &'a T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (depth, index) = loop {
+            let (depth, index) = self.stack.pop()?;
+            if !self.visited[index] {
+                break (depth, index);
+            }
+        };
+        self.visited[index] = true;
+        self.stack.extend(
+            self.hierarchy.children[index]
+                .iter()
+                .rev()
+                .filter(|&&child| !self.visited[child])
+                .map(|&child| (depth + 1, child)),
+        );
+        Some((depth, &self.hierarchy.resources[index]))
+    }
+}
+
+/// The code system mCSD uses in `Location.type` to tell jurisdictions from
+/// facilities.
+pub const MCSD_LOCATION_TYPE_SYSTEM: &str =
+    "https://profiles.ihe.net/ITI/mCSD/CodeSystem/IHE.mCSD.Organization.Location.Types";
+
+pub const LOCATION_PHYSICAL_TYPE_SYSTEM: &str =
+    "http://terminology.hl7.org/CodeSystem/location-physical-type";
+
+/// The role of a Location in an mCSD directory.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LocationKind {
+    /// A political or administrative area, typed `jurisdiction` or with the
+    /// `jdn` physical type.
+    Jurisdiction,
+    /// A place where services are delivered, typed `facility`.
+    Facility,
+    Other,
+}
+
+impl LocationKind {
+    pub fn of(location: &Location) -> Self {
+        let types = location.r#type.iter().flatten();
+        if types
+            .clone()
+            .any(|r#type| has_code(r#type, MCSD_LOCATION_TYPE_SYSTEM, "jurisdiction"))
+            || location
+                .physical_type
+                .as_ref()
+                .is_some_and(|r#type| has_code(r#type, LOCATION_PHYSICAL_TYPE_SYSTEM, "jdn"))
+        {
+            Self::Jurisdiction
+        } else if types
+            .into_iter()
+            .any(|r#type| has_code(r#type, MCSD_LOCATION_TYPE_SYSTEM, "facility"))
+        {
+            Self::Facility
+        } else {
+            Self::Other
+        }
+    }
+}
+
+fn has_code(concept: &CodeableConcept, system: &str, code: &str) -> bool {
+    concept.coding.iter().flatten().any(|coding| {
+        coding.system.as_deref() == Some(system) && coding.code.as_deref() == Some(code)
+    })
+}
+
+fn managed_by(location: &Location, organization_id: &str) -> bool {
+    location
+        .managing_organization
+        .as_ref()
+        .and_then(|organization| organization.target_id())
+        == Some(organization_id)
+}
+
+impl LocationHierarchy {
+    pub fn kind(&self, id: &str) -> Option<LocationKind> {
+        self.get(id).map(LocationKind::of)
+    }
+
+    /// The facilities anywhere below `jurisdiction`, depth first.
+    pub fn facilities_within(&self, jurisdiction: &str) -> Vec<&Location> {
+        self.descendants(jurisdiction)
+            .into_iter()
+            .filter(|location| LocationKind::of(location) == LocationKind::Facility)
+            .collect()
+    }
+
+    /// The jurisdictions above `id`, nearest first.
+    pub fn jurisdictions_of(&self, id: &str) -> Vec<&Location> {
+        self.ancestors(id)
+            .into_iter()
+            .filter(|location| LocationKind::of(location) == LocationKind::Jurisdiction)
+            .collect()
+    }
+
+    /// The chain from the top of the hierarchy down to and including `id`.
+    pub fn administrative_path(&self, id: &str) -> Vec<&Location> {
+        let Some(location) = self.get(id) else {
+            return Vec::new();
+        };
+        let mut path = self.ancestors(id);
+        path.reverse();
+        path.push(location);
+        path
+    }
+
+    /// The Locations whose `managingOrganization` is `organization`.
+    pub fn managed_by(&self, organization: &str) -> Vec<&Location> {
+        self.resources()
+            .filter(|location| managed_by(location, organization))
+            .collect()
+    }
+
+    /// The Locations managed by `organization` or by any Organization below
+    /// it in `organizations`.
+    pub fn managed_within(
+        &self,
+        organizations: &OrganizationHierarchy,
+        organization: &str,
+    ) -> Vec<&Location> {
+        let managers: Vec<&str> = organizations
+            .subtree(organization)
+            .filter_map(|(_, organization)| organization.resource_id())
+            .collect();
+        self.resources()
+            .filter(|location| {
+                managers
+                    .iter()
+                    .any(|organization| managed_by(location, organization))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::r4::{
+        elements::{CodeableConceptBuilder, CodingBuilder, ReferenceBuilder},
+        resources::{LocationBuilder, OrganizationBuilder, ResourceType},
+    };
+
+    use super::*;
+
+    fn organization(id: &str, part_of: Option<&str>) -> Organization {
+        let builder = OrganizationBuilder::new(id).name(id.to_uppercase());
+        match part_of {
+            Some(part_of) => builder
+                .part_of(ReferenceBuilder::default().with_reference(part_of).build())
+                .build(),
+            None => builder.build(),
+        }
+    }
+
+    fn ids<T: PartOf>(resources: Vec<&T>) -> Vec<&str> {
+        resources
+            .into_iter()
+            .filter_map(|resource| resource.resource_id())
+            .collect()
+    }
+
+    fn national() -> OrganizationHierarchy {
+        OrganizationHierarchy::new([
+            organization("ministry", None),
+            organization("north", Some("Organization/ministry")),
+            organization(
+                "south",
+                Some("https://directory.example/fhir/Organization/ministry"),
+            ),
+            organization("north-hospital", Some("Organization/north")),
+            organization("north-clinic", Some("Organization/north/_history/2")),
+            organization("ward", Some("Organization/north-hospital")),
+            organization("private", None),
+        ])
+        .unwrap()
+    }
+
+    #[test]
+    fn test_hierarchy_should_link_part_of() {
+        let hierarchy = national();
+
+        assert_eq!(7, hierarchy.len());
+        assert_eq!(vec!["ministry", "private"], ids(hierarchy.roots()));
+        assert_eq!(vec!["north", "south"], ids(hierarchy.children("ministry")));
+        assert_eq!(
+            Some("north"),
+            hierarchy
+                .parent("north-clinic")
+                .and_then(|o| o.resource_id())
+        );
+        assert_eq!(
+            vec!["north-hospital", "north", "ministry"],
+            ids(hierarchy.ancestors("ward"))
+        );
+        assert_eq!(
+            vec!["north", "north-hospital", "ward", "north-clinic", "south"],
+            ids(hierarchy.descendants("ministry"))
+        );
+        assert_eq!(Some(3), hierarchy.depth("ward"));
+        assert_eq!(Some(0), hierarchy.depth("private"));
+        assert_eq!(None, hierarchy.depth("unknown"));
+        assert!(hierarchy.orphans().is_empty());
+        assert!(hierarchy.cycles().is_empty());
+
+        let subtree: Vec<(usize, &str)> = hierarchy
+            .subtree("north")
+            .filter_map(|(depth, o)| Some((depth, o.resource_id()?)))
+            .collect();
+        assert_eq!(
+            vec![
+                (0, "north"),
+                (1, "north-hospital"),
+                (2, "ward"),
+                (1, "north-clinic")
+            ],
+            subtree
+        );
+    }
+
+    #[test]
+    fn test_hierarchy_should_detect_cycles_and_orphans() {
+        let hierarchy = OrganizationHierarchy::new([
+            organization("a", Some("Organization/c")),
+            organization("b", Some("Organization/a")),
+            organization("c", Some("Organization/b")),
+            organization("hanging", Some("Organization/a")),
+            organization("self", Some("Organization/self")),
+            organization("lost", Some("Organization/missing")),
+            organization("lost-child", Some("Organization/lost")),
+            organization("contained", Some("#parent")),
+        ])
+        .unwrap();
+
+        assert!(hierarchy.roots().is_empty());
+        let cycles: Vec<Vec<&str>> = hierarchy.cycles().into_iter().map(ids).collect();
+        assert_eq!(vec![vec!["a", "c", "b"], vec!["self"]], cycles);
+        assert_eq!(None, hierarchy.depth("hanging"));
+        assert_eq!(vec!["a", "c", "b"], ids(hierarchy.ancestors("hanging")));
+        assert_eq!(vec!["c", "b"], ids(hierarchy.ancestors("a")));
+        assert_eq!(vec!["b", "c", "hanging"], ids(hierarchy.descendants("a")));
+
+        let orphans: Vec<(&str, Option<&str>)> = hierarchy
+            .orphans()
+            .into_iter()
+            .map(|orphan| {
+                (
+                    orphan.resource.resource_id().unwrap(),
+                    orphan.part_of.reference.as_deref(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            vec![
+                ("lost", Some("Organization/missing")),
+                ("contained", Some("#parent"))
+            ],
+            orphans
+        );
+        assert_eq!(Some(1), hierarchy.depth("lost-child"));
+    }
+
+    #[test]
+    fn test_hierarchy_should_reject_duplicate_and_missing_ids() {
+        let duplicate =
+            OrganizationHierarchy::new([organization("a", None), organization("a", None)]);
+        assert_eq!(
+            "hierarchy error: duplicate Organization/a",
+            duplicate.unwrap_err().to_string()
+        );
+
+        let missing = OrganizationHierarchy::new([OrganizationBuilder::default().build()]);
+        assert_eq!(
+            format!("hierarchy error: {} without an id", Organization::TYPE),
+            missing.unwrap_err().to_string()
+        );
+    }
+
+    #[test]
+    fn test_render_should_draw_the_tree() {
+        let hierarchy = OrganizationHierarchy::new([
+            organization("ministry", None),
+            organization("north", Some("Organization/ministry")),
+            organization("south", Some("Organization/ministry")),
+            organization("north-hospital", Some("Organization/north")),
+            organization("north-clinic", Some("Organization/north")),
+            organization("ward", Some("Organization/north-hospital")),
+            organization("lost", Some("Organization/missing")),
+            organization("a", Some("Organization/b")),
+            organization("b", Some("Organization/a")),
+        ])
+        .unwrap();
+
+        let expected = "\
+MINISTRY (ministry)
+├── NORTH (north)
+│   ├── NORTH-HOSPITAL (north-hospital)
+│   │   └── WARD (ward)
+│   └── NORTH-CLINIC (north-clinic)
+└── SOUTH (south)
+LOST (lost) [orphan, partOf Organization/missing]
+cycle: A (a) -> B (b) -> A (a)
+";
+        assert_eq!(expected, hierarchy.render());
+        assert_eq!(expected, hierarchy.to_string());
+    }
+
+    #[test]
+    fn test_render_should_draw_branches_below_cycles() {
+        let hierarchy = OrganizationHierarchy::new([
+            organization("a", Some("Organization/b")),
+            organization("b", Some("Organization/a")),
+            organization("c", Some("Organization/a")),
+            organization("d", Some("Organization/c")),
+            organization("e", Some("Organization/b")),
+        ])
+        .unwrap();
+
+        let expected = "\
+cycle: A (a) -> B (b) -> A (a)
+A (a) [in cycle]
+└── C (c)
+    └── D (d)
+B (b) [in cycle]
+└── E (e)
+";
+        assert_eq!(expected, hierarchy.render());
+        assert_eq!(vec!["b", "e", "c", "d"], ids(hierarchy.descendants("a")));
+        for resource in hierarchy.resources() {
+            let id = resource.resource_id().unwrap();
+            assert!(expected.contains(&format!("({id})")), "{id}");
+        }
+    }
+
+    fn location(
+        id: &str,
+        kind: Option<&str>,
+        part_of: Option<&str>,
+        manager: Option<&str>,
+    ) -> Location {
+        let mut builder = LocationBuilder::new(id).name(id.to_uppercase());
+        if let Some(kind) = kind {
+            builder = builder.add_type(
+                CodeableConceptBuilder::default()
+                    .add_coding(
+                        CodingBuilder::default()
+                            .with_system(MCSD_LOCATION_TYPE_SYSTEM)
+                            .with_code(kind)
+                            .build(),
+                    )
+                    .build(),
+            );
+        }
+        if let Some(part_of) = part_of {
+            builder = builder.part_of(ReferenceBuilder::default().with_reference(part_of).build());
+        }
+        if let Some(manager) = manager {
+            builder = builder
+                .managing_organization(ReferenceBuilder::default().with_reference(manager).build());
+        }
+        builder.build()
+    }
+
+    fn locations() -> LocationHierarchy {
+        LocationHierarchy::new([
+            location("country", Some("jurisdiction"), None, None),
+            location(
+                "province",
+                Some("jurisdiction"),
+                Some("Location/country"),
+                None,
+            ),
+            LocationBuilder::new("district")
+                .physical_type(
+                    CodeableConceptBuilder::default()
+                        .add_coding(
+                            CodingBuilder::default()
+                                .with_system(LOCATION_PHYSICAL_TYPE_SYSTEM)
+                                .with_code("jdn")
+                                .build(),
+                        )
+                        .build(),
+                )
+                .part_of(
+                    ReferenceBuilder::default()
+                        .with_reference("Location/province")
+                        .build(),
+                )
+                .build(),
+            location(
+                "hospital",
+                Some("facility"),
+                Some("Location/district"),
+                Some("Organization/north-hospital"),
+            ),
+            location(
+                "hospital-wing",
+                None,
+                Some("Location/hospital"),
+                Some("Organization/north-hospital"),
+            ),
+            location(
+                "clinic",
+                Some("facility"),
+                Some("Location/province"),
+                Some("Organization/north-clinic"),
+            ),
+            location(
+                "other-clinic",
+                Some("facility"),
+                None,
+                Some("Organization/private"),
+            ),
+        ])
+        .unwrap()
+    }
+
+    #[test]
+    fn test_location_kind_should_follow_mcsd_types() {
+        let locations = locations();
+
+        assert_eq!(Some(LocationKind::Jurisdiction), locations.kind("country"));
+        assert_eq!(Some(LocationKind::Jurisdiction), locations.kind("district"));
+        assert_eq!(Some(LocationKind::Facility), locations.kind("hospital"));
+        assert_eq!(Some(LocationKind::Other), locations.kind("hospital-wing"));
+        assert_eq!(None, locations.kind("unknown"));
+    }
+
+    #[test]
+    fn test_location_hierarchy_should_answer_jurisdiction_queries() {
+        let locations = locations();
+
+        assert_eq!(
+            vec!["hospital", "clinic"],
+            ids(locations.facilities_within("province"))
+        );
+        assert_eq!(
+            vec!["hospital"],
+            ids(locations.facilities_within("district"))
+        );
+        assert_eq!(
+            vec![
+                "country",
+                "province",
+                "district",
+                "hospital",
+                "hospital-wing"
+            ],
+            ids(locations.administrative_path("hospital-wing"))
+        );
+        assert_eq!(
+            vec!["district", "province", "country"],
+            ids(locations.jurisdictions_of("hospital-wing"))
+        );
+        assert!(locations.administrative_path("unknown").is_empty());
+    }
+
+    #[test]
+    fn test_location_hierarchy_should_find_managed_locations() {
+        let locations = locations();
+
+        assert_eq!(
+            vec!["hospital", "hospital-wing"],
+            ids(locations.managed_by("north-hospital"))
+        );
+        assert_eq!(
+            vec!["hospital", "hospital-wing", "clinic"],
+            ids(locations.managed_within(&national(), "north"))
+        );
+        assert!(locations.managed_within(&national(), "south").is_empty());
+    }
+}
@@ -0,0 +1,803 @@
+use std::collections::BTreeMap;
+
+use crate::{
+    FhirError,
+    r4::{
+        directory::{Availability, Closure, WeeklySchedule, group_slots, instant, time_of_day},
+        elements::{
+            AvailableTime, AvailableTimeBuilder, DaysOfWeek, FhirDateTime, FhirTime, FixedOffset,
+            MILLIS_PER_DAY, PosixTimeZone, TimeZone, TransitionRule, civil_from_days,
+            days_from_civil,
+        },
+        resources::ReferenceTarget,
+    },
+};
+
+const PRODUCT_ID: &str = "-//libfhir//Availability//EN";
+
+/// How far ahead occurrences are checked against closures for `EXDATE`s.
+const EXCLUSION_DAYS: i64 = 3_660;
+
+/// Exports a [`WeeklySchedule`] as an RFC 5545 calendar.
+///
+/// The slots sharing their times become one `VEVENT` repeating weekly from
+/// the first matching day on or after the start date. Occurrences starting
+/// in a closure are removed with `EXDATE`, a closure without an end stops
+/// the recurrence with `UNTIL`, and every closure is listed as an event of
+/// its own.
+///
+/// Without a time zone the times are floating, shown in whatever zone the
+/// calendar app is in.
+pub struct CalendarExport {
+    /// Days since the epoch of the first day events may start on.
+    starting: i64,
+    name: Option<String>,
+    time_zone: Option<(String, PosixTimeZone)>,
+    timestamp: FhirDateTime,
+}
+
+impl CalendarExport {
+    /// An export whose events start on the day of `starting`.
+    pub fn new(starting: &FhirDateTime) -> Self {
+        Self {
+            starting: days_from_civil(
+                starting.year(),
+                starting.month().unwrap_or(1),
+                starting.day().unwrap_or(1),
+            ),
+            name: None,
+            time_zone: None,
+            timestamp: FhirDateTime::now(),
+        }
+    }
+
+    /// The calendar name shown by calendar apps, `X-WR-CALNAME`.
+    pub fn with_name(mut self, name: impl Into<String>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    /// Anchors the times in the zone `tzid`, described to the calendar app by
+    /// a `VTIMEZONE` built from the rules of `zone`.
+    pub fn with_time_zone(mut self, tzid: impl Into<String>, zone: PosixTimeZone) -> Self {
+        self.time_zone = Some((tzid.into(), zone));
+        self
+    }
+
+    /// The `DTSTAMP` of the events, the current time by default.
+    pub fn with_timestamp(mut self, timestamp: FhirDateTime) -> Self {
+        self.timestamp = timestamp;
+        self
+    }
+
+    /// Exports the schedule of `resource`, named after the resource unless a
+    /// name was set.
+    pub fn export_resource<R>(&self, resource: &R) -> Result<String, FhirError>
+    where
+        R: Availability + ReferenceTarget,
+    {
+        let uid = format!(
+            "{}-{}",
+            R::TYPE,
+            resource.resource_id().unwrap_or("unknown")
+        );
+        let name = self.name.clone().or_else(|| resource.reference_display());
+        Ok(self.render(&uid, &resource.weekly_schedule()?, name.as_deref()))
+    }
+
+    /// Exports `schedule`, the UIDs of the events start with `uid`.
+    pub fn export(&self, uid: &str, schedule: &WeeklySchedule) -> String {
+        self.render(uid, schedule, self.name.as_deref())
+    }
+
+    fn render(&self, uid: &str, schedule: &WeeklySchedule, name: Option<&str>) -> String {
+        let mut out = Lines::default();
+        out.line("BEGIN:VCALENDAR");
+        out.line("VERSION:2.0");
+        out.line(&format!("PRODID:{PRODUCT_ID}"));
+        out.line("CALSCALE:GREGORIAN");
+        if let Some(name) = name {
+            out.line(&format!("X-WR-CALNAME:{}", escape(name)));
+        }
+        if let Some((tzid, zone)) = &self.time_zone {
+            write_time_zone(&mut out, tzid, zone);
+        }
+
+        let stamp = format_utc(self.timestamp.epoch_millis(0));
+        let notes: Vec<String> = schedule.notes.iter().map(|note| escape(note)).collect();
+        for (index, ((start, end), days)) in group_slots(&schedule.slots).into_iter().enumerate() {
+            let Some(first) = (self.starting..self.starting + 7)
+                .find(|day| days.contains(&DaysOfWeek::from_epoch_days(*day)))
+            else {
+                continue;
+            };
+            let date = start == 0 && end == MILLIS_PER_DAY;
+            let parameters = self.parameters(date);
+            let first = first * MILLIS_PER_DAY;
+            out.line("BEGIN:VEVENT");
+            out.line(&format!("UID:{uid}-available-{}", index + 1));
+            out.line(&format!("DTSTAMP:{stamp}"));
+            out.line("SUMMARY:Available");
+            if !notes.is_empty() {
+                out.line(&format!("DESCRIPTION:{}", notes.join("\\n")));
+            }
+            out.line(&format!(
+                "DTSTART{parameters}:{}",
+                format_value(first + start, date)
+            ));
+            out.line(&format!(
+                "DTEND{parameters}:{}",
+                format_value(first + end, date)
+            ));
+            let by_day: Vec<&str> = days.iter().map(|day| weekday_code(*day)).collect();
+            let mut rule = format!("RRULE:FREQ=WEEKLY;BYDAY={}", by_day.join(","));
+            if let Some(until) = self.until(schedule, date) {
+                rule.push_str(&format!(";UNTIL={until}"));
+            }
+            out.line(&rule);
+            let excluded: Vec<String> = self
+                .excluded(schedule, first + start, &days)
+                .into_iter()
+                .map(|local| format_value(local, date))
+                .collect();
+            if !excluded.is_empty() {
+                out.line(&format!("EXDATE{parameters}:{}", excluded.join(",")));
+            }
+            out.line("END:VEVENT");
+        }
+
+        for (index, closure) in schedule.closures.iter().enumerate() {
+            let Some(start) = closure.start else {
+                continue;
+            };
+            out.line("BEGIN:VEVENT");
+            out.line(&format!("UID:{uid}-not-available-{}", index + 1));
+            out.line(&format!("DTSTAMP:{stamp}"));
+            out.line(&format!(
+                "SUMMARY:Not available: {}",
+                escape(&closure.description)
+            ));
+            out.line("TRANSP:TRANSPARENT");
+            out.line(&format!("DTSTART{}", self.closure_value(&start, false)));
+            if let Some(end) = closure.end {
+                out.line(&format!("DTEND{}", self.closure_value(&end, true)));
+            }
+            out.line("END:VEVENT");
+        }
+        out.line("END:VCALENDAR");
+        out.0
+    }
+
+    fn zone(&self) -> &dyn TimeZone {
+        match &self.time_zone {
+            Some((_, zone)) => zone,
+            None => &FixedOffset::UTC,
+        }
+    }
+
+    /// The parameters of a local date or time value.
+    fn parameters(&self, date: bool) -> String {
+        match (&self.time_zone, date) {
+            (_, true) => ";VALUE=DATE".to_string(),
+            (Some((tzid, _)), false) => format!(";TZID={tzid}"),
+            (None, false) => String::new(),
+        }
+    }
+
+    fn closure_value(&self, value: &FhirDateTime, end: bool) -> String {
+        if !value.has_time() {
+            let day = days_from_civil(
+                value.year(),
+                value.month().unwrap_or(1),
+                value.day().unwrap_or(1),
+            );
+            // The end of a date event is exclusive.
+            let day = if end { day + 1 } else { day };
+            return format!(";VALUE=DATE:{}", format_date(day * MILLIS_PER_DAY));
+        }
+        match value.offset() {
+            Some(_) => format!(":{}", format_utc(value.epoch_millis(0))),
+            None => format!(
+                "{}:{}",
+                self.parameters(false),
+                format_local(value.epoch_millis(0))
+            ),
+        }
+    }
+
+    /// The `UNTIL` ending the recurrence just before the earliest closure
+    /// without an end.
+    fn until(&self, schedule: &WeeklySchedule, date: bool) -> Option<String> {
+        let zone = self.zone();
+        let utc = schedule
+            .closures
+            .iter()
+            .filter(|closure| closure.end.is_none())
+            .filter_map(|closure| closure.start)
+            .map(|start| instant(&start.lower_bound(), &zone) - 1_000)
+            .min()?;
+        Some(match (&self.time_zone, date) {
+            (_, true) => format_date(zone.to_local(utc)),
+            // UNTIL is in UTC when DTSTART has a time zone.
+            (Some(_), false) => format_utc(utc),
+            (None, false) => format_local(utc),
+        })
+    }
+
+    /// The local starts of the weekly occurrences from `first` on that fall
+    /// in a closure with an end.
+    fn excluded(&self, schedule: &WeeklySchedule, first: i64, days: &[DaysOfWeek]) -> Vec<i64> {
+        let zone = self.zone();
+        let closures: Vec<&Closure> = schedule
+            .closures
+            .iter()
+            .filter(|closure| closure.end.is_some())
+            .collect();
+        let Some(last) = closures
+            .iter()
+            .filter_map(|closure| closure.end)
+            .map(|end| zone.to_local(instant(&end.upper_bound(), &zone)))
+            .max()
+        else {
+            return Vec::new();
+        };
+        let last = last.min(first + EXCLUSION_DAYS * MILLIS_PER_DAY);
+        (0..)
+            .map(|day| first + day * MILLIS_PER_DAY)
+            .take_while(|local| *local <= last)
+            .filter(|local| {
+                days.contains(&DaysOfWeek::from_epoch_days(
+                    local.div_euclid(MILLIS_PER_DAY),
+                ))
+            })
+            .filter(|local| {
+                let utc = zone.to_utc(*local);
+                closures.iter().any(|closure| closure.covers(utc, &zone))
+            })
+            .collect()
+    }
+}
+
+fn write_time_zone(out: &mut Lines, tzid: &str, zone: &PosixTimeZone) {
+    out.line("BEGIN:VTIMEZONE");
+    out.line(&format!("TZID:{tzid}"));
+    match &zone.rules {
+        Some((start, end)) => {
+            let daylight_name = zone.daylight_name.as_deref().unwrap_or(&zone.standard_name);
+            write_observance(
+                out,
+                "DAYLIGHT",
+                (zone.standard_offset, zone.daylight_offset),
+                daylight_name,
+                start,
+            );
+            write_observance(
+                out,
+                "STANDARD",
+                (zone.daylight_offset, zone.standard_offset),
+                &zone.standard_name,
+                end,
+            );
+        }
+        None => {
+            let offset = format_offset(zone.standard_offset);
+            out.line("BEGIN:STANDARD");
+            out.line("DTSTART:19700101T000000");
+            out.line(&format!("TZOFFSETFROM:{offset}"));
+            out.line(&format!("TZOFFSETTO:{offset}"));
+            out.line(&format!("TZNAME:{}", escape(&zone.standard_name)));
+            out.line("END:STANDARD");
+        }
+    }
+    out.line("END:VTIMEZONE");
+}
+
+/// A `DAYLIGHT` or `STANDARD` observance, switching between the `offsets`
+/// every year by `rule`.
+fn write_observance(
+    out: &mut Lines,
+    kind: &str,
+    (from, to): (i32, i32),
+    name: &str,
+    rule: &TransitionRule,
+) {
+    const WEEKDAYS: [&str; 7] = ["SU", "MO", "TU", "WE", "TH", "FR", "SA"];
+    let week = match rule.week {
+        5 => "-1".to_string(),
+        week => week.to_string(),
+    };
+    let weekday = WEEKDAYS.get(usize::from(rule.weekday)).unwrap_or(&"SU");
+    out.line(&format!("BEGIN:{kind}"));
+    out.line(&format!(
+        "DTSTART:{}",
+        format_local(rule.local_millis(1970))
+    ));
+    out.line(&format!("TZOFFSETFROM:{}", format_offset(from)));
+    out.line(&format!("TZOFFSETTO:{}", format_offset(to)));
+    out.line(&format!("TZNAME:{}", escape(name)));
+    out.line(&format!(
+        "RRULE:FREQ=YEARLY;BYMONTH={};BYDAY={week}{weekday}",
+        rule.month
+    ));
+    out.line(&format!("END:{kind}"));
+}
+
+/// Content lines ended by CRLF and folded at 75 octets.
+#[derive(Default)]
+struct Lines(String);
+
+impl Lines {
+    fn line(&mut self, line: &str) {
+        let mut width = 0;
+        for character in line.chars() {
+            if width + character.len_utf8() > 75 {
+                self.0.push_str("\r\n ");
+                width = 1;
+            }
+            width += character.len_utf8();
+            self.0.push(character);
+        }
+        self.0.push_str("\r\n");
+    }
+}
+
+/// Escapes a TEXT value.
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+}
+
+fn weekday_code(day: DaysOfWeek) -> &'static str {
+    match day {
+        DaysOfWeek::Mon => "MO",
+        DaysOfWeek::Tue => "TU",
+        DaysOfWeek::Wed => "WE",
+        DaysOfWeek::Thu => "TH",
+        DaysOfWeek::Fri => "FR",
+        DaysOfWeek::Sat => "SA",
+        DaysOfWeek::Sun => "SU",
+    }
+}
+
+fn format_value(local_millis: i64, date: bool) -> String {
+    if date {
+        format_date(local_millis)
+    } else {
+        format_local(local_millis)
+    }
+}
+
+fn format_date(local_millis: i64) -> String {
+    let (year, month, day) = civil_from_days(local_millis.div_euclid(MILLIS_PER_DAY));
+    format!("{year:04}{month:02}{day:02}")
+}
+
+fn format_local(local_millis: i64) -> String {
+    let seconds = local_millis.rem_euclid(MILLIS_PER_DAY) / 1_000;
+    format!(
+        "{}T{:02}{:02}{:02}",
+        format_date(local_millis),
+        seconds / 3_600,
+        seconds % 3_600 / 60,
+        seconds % 60
+    )
+}
+
+fn format_utc(utc_millis: i64) -> String {
+    format!("{}Z", format_local(utc_millis))
+}
+
+fn format_offset(minutes: i32) -> String {
+    let sign = if minutes < 0 { '-' } else { '+' };
+    format!("{sign}{:02}{:02}", minutes.abs() / 60, minutes.abs() % 60)
+}
+
+/// Reads the recurring events of an iCalendar document as `AvailableTime`s,
+/// the reverse of [`CalendarExport`].
+///
+/// Events repeating every week on their `BYDAY` days, or on the day of
+/// `DTSTART`, and events repeating every day are read. One-off events,
+/// other frequencies, intervals and events lasting a day or more are
+/// skipped as an `AvailableTime` cannot express them, and so are the
+/// `UNTIL`, `COUNT` and `EXDATE` limiting a recurrence. Times in UTC are
+/// moved to `zone`, times with a `TZID` and floating times are read as they
+/// are.
+pub fn import_available_times(
+    calendar: &str,
+    zone: &impl TimeZone,
+) -> Result<Vec<AvailableTime>, FhirError> {
+    // A line starting with white space continues the one before it.
+    let mut lines: Vec<String> = Vec::new();
+    for line in calendar.split('\n').map(|line| line.trim_end_matches('\r')) {
+        match (line.strip_prefix([' ', '\t']), lines.last_mut()) {
+            (Some(continuation), Some(last)) => last.push_str(continuation),
+            _ if line.trim().is_empty() => {}
+            _ => lines.push(line.to_string()),
+        }
+    }
+
+    let mut available_times = Vec::new();
+    let mut event: Option<Vec<Property>> = None;
+    for line in &lines {
+        let property = Property::parse(line)
+            .ok_or_else(|| FhirError::CalendarError(format!("invalid content line '{line}'")))?;
+        match (property.name.as_str(), property.value) {
+            ("BEGIN", "VEVENT") => event = Some(Vec::new()),
+            ("END", "VEVENT") => {
+                if let Some(properties) = event.take()
+                    && let Some(available_time) = read_event(&properties, zone)?
+                {
+                    available_times.push(available_time);
+                }
+            }
+            _ => {
+                if let Some(properties) = &mut event {
+                    properties.push(property);
+                }
+            }
+        }
+    }
+    if event.is_some() {
+        return Err(FhirError::CalendarError(
+            "VEVENT without END:VEVENT".to_string(),
+        ));
+    }
+    Ok(available_times)
+}
+
+/// A content line, `NAME;PARAM=value:value`. The parameters are not needed.
+struct Property<'a> {
+    name: String,
+    value: &'a str,
+}
+
+impl<'a> Property<'a> {
+    fn parse(line: &'a str) -> Option<Self> {
+        let (head, value) = line.split_once(':')?;
+        let name = head.split(';').next()?.trim().to_ascii_uppercase();
+        (!name.is_empty()).then_some(Self { name, value })
+    }
+}
+
+/// A `DATE` or `DATE-TIME` value in local milliseconds since the epoch.
+struct CalendarTime {
+    local: i64,
+    date: bool,
+}
+
+impl CalendarTime {
+    fn parse(property: &Property, zone: &impl TimeZone) -> Result<Self, FhirError> {
+        let value = property.value.trim();
+        let invalid = || FhirError::CalendarError(format!("invalid {} '{value}'", property.name));
+        let digits = |from: usize, to: usize| {
+            value
+                .get(from..to)
+                .filter(|digits| digits.bytes().all(|byte| byte.is_ascii_digit()))
+                .ok_or_else(invalid)
+        };
+        let number = |from: usize, to: usize| -> Result<u8, FhirError> {
+            digits(from, to)?.parse().map_err(|_| invalid())
+        };
+        let year: i32 = digits(0, 4)?.parse().map_err(|_| invalid())?;
+        let (month, day) = (number(4, 6)?, number(6, 8)?);
+        FhirDateTime::from_ymd(year, month, day).map_err(|_| invalid())?;
+        let day = days_from_civil(year, month, day);
+        if value.len() == 8 {
+            return Ok(Self {
+                local: day * MILLIS_PER_DAY,
+                date: true,
+            });
+        }
+        if value.get(8..9) != Some("T") {
+            return Err(invalid());
+        }
+        let time = FhirTime::from_hms(number(9, 11)?, number(11, 13)?, number(13, 15)?)
+            .map_err(|_| invalid())?;
+        let millis = day * MILLIS_PER_DAY + time.millis_of_day();
+        let local = match value.get(15..) {
+            Some("") => millis,
+            Some("Z") => zone.to_local(millis),
+            _ => return Err(invalid()),
+        };
+        Ok(Self { local, date: false })
+    }
+}
+
+/// Milliseconds in an RFC 5545 `DURATION` such as `PT8H30M`.
+fn parse_duration(value: &str) -> Option<i64> {
+    let value = value.trim();
+    let value = value.strip_prefix('+').unwrap_or(value).strip_prefix('P')?;
+    let (mut total, mut number, mut time) = (0, String::new(), false);
+    for character in value.chars() {
+        let unit = match character {
+            '0'..='9' => {
+                number.push(character);
+                continue;
+            }
+            'T' if !time => {
+                time = true;
+                continue;
+            }
+            'W' if !time => 7 * MILLIS_PER_DAY,
+            'D' if !time => MILLIS_PER_DAY,
+            'H' if time => 3_600_000,
+            'M' if time => 60_000,
+            'S' if time => 1_000,
+            _ => return None,
+        };
+        total += number.parse::<i64>().ok()? * unit;
+        number.clear();
+    }
+    number.is_empty().then_some(total)
+}
+
+fn read_event(
+    properties: &[Property],
+    zone: &impl TimeZone,
+) -> Result<Option<AvailableTime>, FhirError> {
+    let find = |name: &str| properties.iter().find(|property| property.name == name);
+    let (Some(rule), Some(start)) = (find("RRULE"), find("DTSTART")) else {
+        return Ok(None);
+    };
+    let rule: BTreeMap<String, &str> = rule
+        .value
+        .split(';')
+        .filter_map(|part| part.split_once('='))
+        .map(|(key, value)| (key.trim().to_ascii_uppercase(), value.trim()))
+        .collect();
+    if rule
+        .get("INTERVAL")
+        .is_some_and(|interval| *interval != "1")
+    {
+        return Ok(None);
+    }
+
+    let start = CalendarTime::parse(start, zone)?;
+    let end = match (find("DTEND"), find("DURATION")) {
+        (Some(end), _) => CalendarTime::parse(end, zone)?.local,
+        (None, Some(duration)) => {
+            let millis = parse_duration(duration.value).ok_or_else(|| {
+                FhirError::CalendarError(format!("invalid DURATION '{}'", duration.value))
+            })?;
+            start.local + millis
+        }
+        (None, None) if start.date => start.local + MILLIS_PER_DAY,
+        (None, None) => start.local,
+    };
+    let length = end - start.local;
+    let all_day = start.date && length == MILLIS_PER_DAY;
+    if !all_day && (start.date || length <= 0 || length >= MILLIS_PER_DAY) {
+        return Ok(None);
+    }
+
+    let days = match (rule.get("FREQ").copied(), rule.get("BYDAY")) {
+        (Some("WEEKLY" | "DAILY"), Some(by_day)) => {
+            // Days with an ordinal such as `1MO` belong to monthly rules.
+            let days: Option<Vec<DaysOfWeek>> = by_day
+                .split(',')
+                .map(|code| {
+                    DaysOfWeek::ALL
+                        .into_iter()
+                        .find(|day| weekday_code(*day).eq_ignore_ascii_case(code.trim()))
+                })
+                .collect();
+            match days {
+                Some(days) => days,
+                None => return Ok(None),
+            }
+        }
+        (Some("WEEKLY"), None) => vec![DaysOfWeek::from_epoch_days(
+            start.local.div_euclid(MILLIS_PER_DAY),
+        )],
+        (Some("DAILY"), None) => DaysOfWeek::ALL.to_vec(),
+        _ => return Ok(None),
+    };
+
+    let builder = AvailableTimeBuilder::default().with_days_of_week(days);
+    let builder = if all_day {
+        builder.with_all_day(true)
+    } else {
+        builder
+            .with_available_start_time(
+                time_of_day(start.local.rem_euclid(MILLIS_PER_DAY)).to_string(),
+            )
+            .with_available_end_time(time_of_day(end.rem_euclid(MILLIS_PER_DAY)).to_string())
+    };
+    Ok(Some(builder.build()))
+}
+
+#[cfg(test)]
+mod test {
+    use crate::r4::{
+        directory::fixtures::{self, amsterdam, at},
+        resources::HealthcareService,
+    };
+
+    use super::*;
+
+    fn service() -> HealthcareService {
+        fixtures::service()
+            .with_name("Outpatient clinic")
+            .add_available_time(
+                AvailableTimeBuilder::default()
+                    .add_day_of_week(DaysOfWeek::Sun)
+                    .with_all_day(true)
+                    .build(),
+            )
+            .with_availability_exceptions("Reduced hours in August; call ahead")
+            .build()
+    }
+
+    fn export() -> String {
+        CalendarExport::new(&at("2024-12-01"))
+            .with_time_zone("Europe/Amsterdam", amsterdam())
+            .with_timestamp(at("2024-11-30T12:00:00Z"))
+            .export_resource(&service())
+            .unwrap()
+    }
+
+    fn unfold(calendar: &str) -> Vec<String> {
+        calendar
+            .replace("\r\n ", "")
+            .split("\r\n")
+            .map(String::from)
+            .collect()
+    }
+
+    #[test]
+    fn test_export_should_write_weekly_events() {
+        let calendar = export();
+        let lines = unfold(&calendar);
+        let has = |line: &str| lines.iter().any(|actual| actual == line);
+
+        assert!(calendar.starts_with("BEGIN:VCALENDAR\r\nVERSION:2.0\r\n"));
+        assert!(calendar.ends_with("END:VCALENDAR\r\n"));
+        assert!(calendar.split("\r\n").all(|line| line.len() <= 75));
+        assert!(has("X-WR-CALNAME:Outpatient clinic"));
+        assert!(has("DESCRIPTION:Reduced hours in August\\; call ahead"));
+
+        assert!(has("TZID:Europe/Amsterdam"));
+        assert!(has("DTSTART:19700329T020000"));
+        assert!(has("RRULE:FREQ=YEARLY;BYMONTH=3;BYDAY=-1SU"));
+        assert!(has("DTSTART:19701025T030000"));
+        assert!(has("TZOFFSETFROM:+0200"));
+        assert!(has("TZNAME:CEST"));
+
+        assert!(has("DTSTART;TZID=Europe/Amsterdam:20241202T090000"));
+        assert!(has("DTEND;TZID=Europe/Amsterdam:20241202T170000"));
+        assert!(has("RRULE:FREQ=WEEKLY;BYDAY=MO,TU,WE,TH,FR"));
+        assert!(has(
+            "EXDATE;TZID=Europe/Amsterdam:20241225T090000,20241226T090000"
+        ));
+
+        assert!(has("DTSTART;TZID=Europe/Amsterdam:20241207T220000"));
+        assert!(has("DTEND;TZID=Europe/Amsterdam:20241208T060000"));
+        assert!(has("RRULE:FREQ=WEEKLY;BYDAY=SA"));
+
+        assert!(has("DTSTART;VALUE=DATE:20241201"));
+        assert!(has("DTEND;VALUE=DATE:20241202"));
+        assert!(has("RRULE:FREQ=WEEKLY;BYDAY=SU"));
+
+        assert!(has("UID:HealthcareService-hs-1-not-available-1"));
+        assert!(has("SUMMARY:Not available: Christmas"));
+        assert!(has("DTSTART;VALUE=DATE:20241225"));
+        assert!(has("DTEND;VALUE=DATE:20241227"));
+    }
+
+    #[test]
+    fn test_export_should_end_recurrence_at_open_closure() {
+        let mut schedule = service().weekly_schedule().unwrap();
+        schedule.closures = vec![Closure {
+            description: "Moved to the new wing".to_string(),
+            start: Some(at("2025-03-01")),
+            end: None,
+        }];
+
+        let lines = unfold(&CalendarExport::new(&at("2024-12-01")).export("clinic", &schedule));
+        let has = |line: &str| lines.iter().any(|actual| actual == line);
+
+        assert!(has("DTSTART:20241202T090000"));
+        assert!(has(
+            "RRULE:FREQ=WEEKLY;BYDAY=MO,TU,WE,TH,FR;UNTIL=20250228T235959"
+        ));
+        assert!(has("RRULE:FREQ=WEEKLY;BYDAY=SU;UNTIL=20250228"));
+        assert!(has("SUMMARY:Not available: Moved to the new wing"));
+        assert!(!has("BEGIN:VTIMEZONE"));
+        assert!(!lines.iter().any(|line| line.starts_with("EXDATE")));
+    }
+
+    #[test]
+    fn test_lines_should_fold_long_lines() {
+        let line = format!("DESCRIPTION:{}", "é".repeat(40));
+        let mut lines = Lines::default();
+        lines.line(&line);
+
+        let folded: Vec<&str> = lines.0.trim_end().split("\r\n").collect();
+
+        assert_eq!(2, folded.len());
+        assert!(folded.iter().all(|line| line.len() <= 75));
+        assert!(folded[1].starts_with(' '));
+        assert_eq!(line, unfold(&lines.0)[0]);
+    }
+
+    #[test]
+    fn test_import_should_round_trip_export() {
+        let expected = service().weekly_schedule().unwrap().slots;
+
+        let available_times = import_available_times(&export(), &amsterdam()).unwrap();
+        let actual = WeeklySchedule::from_available_times(&available_times, &[], None)
+            .unwrap()
+            .slots;
+
+        assert_eq!(3, available_times.len());
+        assert_eq!(expected.len(), actual.len());
+        for slot in &expected {
+            assert!(actual.contains(slot), "{slot:?} missing");
+        }
+    }
+
+    #[test]
+    fn test_import_should_read_utc_times_and_durations() {
+        let calendar = "BEGIN:VCALENDAR\r\n\
+            BEGIN:VEVENT\r\n\
+            DTSTART:20240701T070000Z\r\n\
+            DURATION:PT4H30M\r\n\
+            RRULE:FREQ=WEEKLY\r\n\
+            END:VEVENT\r\n\
+            BEGIN:VEVENT\r\n\
+            DTSTART:20240701T080000\r\n\
+            DTEND:20240701T090000\r\n\
+            RRULE:FREQ=DAILY\r\n\
+            END:VEVENT\r\n\
+            BEGIN:VEVENT\r\n\
+            DTSTART:20240701T080000\r\n\
+            DTEND:20240701T090000\r\n\
+            RRULE:FREQ=WEEKLY;INTERVAL=2;BYDAY=MO\r\n\
+            END:VEVENT\r\n\
+            BEGIN:VEVENT\r\n\
+            DTSTART:20240701T080000\r\n\
+            DTEND:20240701T090000\r\n\
+            END:VEVENT\r\n\
+            END:VCALENDAR\r\n";
+
+        let actual = import_available_times(calendar, &amsterdam()).unwrap();
+
+        let expected = vec![
+            AvailableTimeBuilder::default()
+                .with_days_of_week(vec![DaysOfWeek::Mon])
+                .with_available_start_time("09:00:00")
+                .with_available_end_time("13:30:00")
+                .build(),
+            AvailableTimeBuilder::default()
+                .with_days_of_week(DaysOfWeek::ALL.to_vec())
+                .with_available_start_time("08:00:00")
+                .with_available_end_time("09:00:00")
+                .build(),
+        ];
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn test_import_should_reject_invalid_values() {
+        let event = |start: &str| {
+            format!("BEGIN:VEVENT\r\nDTSTART:{start}\r\nRRULE:FREQ=WEEKLY\r\nEND:VEVENT\r\n")
+        };
+
+        for start in [
+            "20241332T090000",
+            "20240101T250000",
+            "2024-01-01",
+            "20240101T0900",
+        ] {
+            let error = import_available_times(&event(start), &FixedOffset::UTC).unwrap_err();
+            assert_eq!(
+                format!("invalid iCalendar data: invalid DTSTART '{start}'"),
+                error.to_string()
+            );
+        }
+        assert!(import_available_times("BEGIN:VEVENT\r\nno colon\r\n", &FixedOffset::UTC).is_err());
+        assert!(import_available_times("BEGIN:VEVENT\r\n", &FixedOffset::UTC).is_err());
+    }
+}
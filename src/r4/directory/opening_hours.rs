@@ -0,0 +1,971 @@
+use std::fmt;
+
+use crate::{
+    FhirError,
+    r4::{
+        directory::{Closure, OpeningSlot, WeeklySchedule, group_slots, time_of_day},
+        elements::{
+            AvailableTime, AvailableTimeBuilder, DateTimePrecision, DaysOfWeek, FhirDateTime,
+            MILLIS_PER_DAY, NotAvailable, NotAvailableBuilder, PeriodBuilder,
+        },
+        resources::{HoursOfOperation, HoursOfOperationBuilder},
+    },
+};
+
+/// The `NotAvailable` description standing for `PH off`.
+pub const CLOSED_ON_PUBLIC_HOLIDAYS: &str = "Closed on public holidays";
+
+/// The `NotAvailable` description of a closed date without a comment.
+const CLOSED: &str = "Closed";
+
+const WEEKDAYS: [&str; 7] = ["Mo", "Tu", "We", "Th", "Fr", "Sa", "Su"];
+
+const MONTHS: [&str; 12] = [
+    "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+];
+
+/// The opening and closing times of a day in milliseconds after midnight,
+/// closing beyond a day when overnight.
+type Spans = Vec<(i64, i64)>;
+
+/// A part of an opening hours value with no equivalent on the other side,
+/// left out of the conversion.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnsupportedRule {
+    /// The rule, or the FHIR value, that was left out.
+    pub rule: String,
+    pub reason: &'static str,
+}
+
+impl fmt::Display for UnsupportedRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.rule, self.reason)
+    }
+}
+
+/// Opening hours in the OpenStreetMap `opening_hours` syntax, such as
+/// `Mo-Fr 08:00-17:00; Sa 09:00-12:00; PH off`.
+///
+/// Weekday and time selectors, `24/7`, `off`, additional rules after a `,`,
+/// `PH off` and closed dates with a year, `2024 Dec 24-26 off "Christmas"`,
+/// are understood. Rules using other selectors, such as recurring dates,
+/// week numbers, sunrise and sunset or school holidays, cannot be expressed
+/// in FHIR and are listed in `unsupported`. As in OpenStreetMap, a later
+/// rule replaces the hours of the days it selects. A range ending before it
+/// starts closes the next day; one ending when it starts, `08:00-08:00`, is
+/// rejected, a day from 08:00 is written `08:00-32:00`.
+///
+/// The value is rendered back by `Display`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct OpeningHours {
+    pub slots: Vec<OpeningSlot>,
+    /// Closed dates, both ends without a time.
+    pub closures: Vec<Closure>,
+    pub closed_on_public_holidays: bool,
+    pub unsupported: Vec<UnsupportedRule>,
+}
+
+impl OpeningHours {
+    /// Parses an `opening_hours` value. Rules that are valid but cannot be
+    /// represented are skipped and listed in `unsupported`, invalid syntax
+    /// and an empty value are errors.
+    pub fn parse(value: &str) -> Result<Self, FhirError> {
+        let mut opening_hours = Self::default();
+        let mut week: [Spans; 7] = Default::default();
+        for (text, fallback) in split_rules(value)? {
+            if fallback {
+                opening_hours.unsupport(text, "fallback rules");
+                continue;
+            }
+            let mut rest = Some(text);
+            let mut additional = false;
+            while let Some(text) = rest.take() {
+                match parse_rule(text)? {
+                    Ok((rule, next)) => {
+                        opening_hours.apply(&mut week, rule, additional);
+                        rest = next;
+                        additional = true;
+                    }
+                    Err(reason) => opening_hours.unsupport(text, reason),
+                }
+            }
+        }
+        for (day, spans) in DaysOfWeek::ALL.into_iter().zip(week) {
+            for (start, end) in spans {
+                let closes = (end != MILLIS_PER_DAY).then(|| time_of_day(end % MILLIS_PER_DAY));
+                opening_hours.slots.push(OpeningSlot::new(
+                    day,
+                    Some(&time_of_day(start)),
+                    closes.as_ref(),
+                ));
+            }
+        }
+        Ok(opening_hours)
+    }
+
+    /// The opening hours of a HealthcareService or PractitionerRole.
+    pub fn from_available_times(
+        available_times: &[AvailableTime],
+        not_available: &[NotAvailable],
+    ) -> Result<Self, FhirError> {
+        Ok(Self::from_schedule(WeeklySchedule::from_available_times(
+            available_times,
+            not_available,
+            None,
+        )?))
+    }
+
+    /// The opening hours of a Location.
+    pub fn from_hours_of_operation(
+        hours_of_operation: &[HoursOfOperation],
+    ) -> Result<Self, FhirError> {
+        Ok(Self::from_schedule(
+            WeeklySchedule::from_hours_of_operation(hours_of_operation, None)?,
+        ))
+    }
+
+    /// The opening hours of `schedule`. Times with seconds, closures that are
+    /// open ended or have times, and notes other than
+    /// [`CLOSED_ON_PUBLIC_HOLIDAYS`] are listed in `unsupported`.
+    pub fn from_schedule(schedule: WeeklySchedule) -> Self {
+        let mut opening_hours = Self::default();
+        for slot in schedule.slots {
+            let (start, end) = slot.bounds();
+            if start % 60_000 != 0 || end % 60_000 != 0 {
+                opening_hours.unsupport(&format!("{:?} {slot}", slot.day), "times with seconds");
+            } else {
+                opening_hours.slots.push(slot);
+            }
+        }
+        for closure in schedule.closures {
+            let dates = [closure.start, closure.end];
+            if dates.iter().any(Option::is_none) {
+                opening_hours.unsupport(&closure.description, "open ended closures");
+            } else if dates
+                .iter()
+                .flatten()
+                .any(|date| date.precision() != DateTimePrecision::Day)
+            {
+                opening_hours.unsupport(&closure.description, "closures not on whole days");
+            } else {
+                opening_hours.closures.push(closure);
+            }
+        }
+        for note in schedule.notes {
+            if note == CLOSED_ON_PUBLIC_HOLIDAYS {
+                opening_hours.closed_on_public_holidays = true;
+            } else {
+                opening_hours.unsupport(&note, "free text notes");
+            }
+        }
+        opening_hours
+    }
+
+    pub fn available_times(&self) -> Vec<AvailableTime> {
+        group_slots(&self.slots)
+            .into_iter()
+            .map(|((start, end), days)| {
+                let builder = AvailableTimeBuilder::default().with_days_of_week(days);
+                if start == 0 && end == MILLIS_PER_DAY {
+                    builder.with_all_day(true).build()
+                } else {
+                    builder
+                        .with_available_start_time(time_of_day(start).to_string())
+                        .with_available_end_time(time_of_day(end % MILLIS_PER_DAY).to_string())
+                        .build()
+                }
+            })
+            .collect()
+    }
+
+    pub fn hours_of_operation(&self) -> Vec<HoursOfOperation> {
+        group_slots(&self.slots)
+            .into_iter()
+            .map(|((start, end), days)| {
+                let builder = HoursOfOperationBuilder::default().days_of_week(days);
+                if start == 0 && end == MILLIS_PER_DAY {
+                    builder.all_day(true).build()
+                } else {
+                    builder
+                        .operation_time(time_of_day(start).to_string())
+                        .closing_time(time_of_day(end % MILLIS_PER_DAY).to_string())
+                        .build()
+                }
+            })
+            .collect()
+    }
+
+    /// The closed dates, and public holidays without a period.
+    pub fn not_available(&self) -> Vec<NotAvailable> {
+        let mut not_available: Vec<NotAvailable> = self
+            .closures
+            .iter()
+            .map(|closure| {
+                let mut during = PeriodBuilder::default();
+                if let Some(start) = closure.start {
+                    during = during.with_start(start.to_string());
+                }
+                if let Some(end) = closure.end {
+                    during = during.with_end(end.to_string());
+                }
+                NotAvailableBuilder::default()
+                    .with_desscription(&closure.description)
+                    .with_during(during.build())
+                    .build()
+            })
+            .collect();
+        if self.closed_on_public_holidays {
+            not_available.push(
+                NotAvailableBuilder::default()
+                    .with_desscription(CLOSED_ON_PUBLIC_HOLIDAYS)
+                    .build(),
+            );
+        }
+        not_available
+    }
+
+    fn unsupport(&mut self, rule: &str, reason: &'static str) {
+        self.unsupported.push(UnsupportedRule {
+            rule: rule.trim().to_string(),
+            reason,
+        });
+    }
+
+    fn apply(&mut self, week: &mut [Spans; 7], rule: Rule, additional: bool) {
+        let Rule {
+            text,
+            dates,
+            days,
+            public_holidays,
+            spans,
+            state,
+            comment,
+        } = rule;
+        let selects_days = !days.is_empty() || !spans.is_empty();
+        match (state, dates, public_holidays) {
+            (State::Unknown, _, _) => self.unsupport(text, "unknown state"),
+            (State::Closed, Some((start, end)), false) if !selects_days => {
+                self.closures.push(Closure {
+                    description: comment.unwrap_or_else(|| CLOSED.to_string()),
+                    start: Some(start),
+                    end: Some(end),
+                })
+            }
+            (_, Some(_), _) => self.unsupport(text, "opening hours on specific dates"),
+            (State::Closed, None, true) if !selects_days => self.closed_on_public_holidays = true,
+            (_, None, true) => self.unsupport(text, "opening hours on public holidays"),
+            (State::Open, None, false) if !selects_days && comment.is_some() => {
+                self.unsupport(text, "comments without opening hours")
+            }
+            (state, None, false) => {
+                let days = if days.is_empty() {
+                    DaysOfWeek::ALL.to_vec()
+                } else {
+                    days
+                };
+                if state == State::Closed && !spans.is_empty() {
+                    return self.unsupport(text, "closing part of a day");
+                }
+                let spans = match state {
+                    State::Open if spans.is_empty() => vec![(0, MILLIS_PER_DAY)],
+                    _ => spans,
+                };
+                for day in days {
+                    let hours = &mut week[day.index()];
+                    if !additional {
+                        hours.clear();
+                    }
+                    hours.extend(&spans);
+                    hours.sort_unstable();
+                    hours.dedup();
+                }
+                if comment.is_some() {
+                    self.unsupport(text, "comments on opening hours, the hours were kept");
+                }
+            }
+        }
+    }
+}
+
+impl fmt::Display for OpeningHours {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut week: [Spans; 7] = Default::default();
+        for slot in &self.slots {
+            week[slot.day.index()].push(slot.bounds());
+        }
+        for hours in &mut week {
+            hours.sort_unstable();
+            hours.dedup();
+        }
+
+        let mut rules = Vec::new();
+        if week.iter().all(|hours| hours == &[(0, MILLIS_PER_DAY)]) {
+            rules.push("24/7".to_string());
+        } else {
+            // Days with the same hours share a rule, in the order of their
+            // first day.
+            let mut groups: Vec<(&Spans, Vec<usize>)> = Vec::new();
+            for (day, hours) in week
+                .iter()
+                .enumerate()
+                .filter(|(_, hours)| !hours.is_empty())
+            {
+                match groups.iter_mut().find(|(other, _)| *other == hours) {
+                    Some((_, days)) => days.push(day),
+                    None => groups.push((hours, vec![day])),
+                }
+            }
+            for (hours, days) in groups {
+                let spans: Vec<String> = hours
+                    .iter()
+                    .map(|(start, end)| format!("{}-{}", clock(*start), clock(*end)))
+                    .collect();
+                rules.push(format!("{} {}", weekday_ranges(&days), spans.join(",")));
+            }
+        }
+        for closure in &self.closures {
+            let (Some(start), Some(end)) = (closure.start, closure.end) else {
+                continue;
+            };
+            let mut rule = format!("{} off", date_range(&start, &end));
+            if closure.description != CLOSED {
+                rule.push_str(&format!(" \"{}\"", closure.description.replace('"', "'")));
+            }
+            rules.push(rule);
+        }
+        if self.closed_on_public_holidays {
+            rules.push("PH off".to_string());
+        }
+        f.write_str(&rules.join("; "))
+    }
+}
+
+/// `HH:MM`, `24:00` at the end of the day and beyond it for overnight hours.
+fn clock(millis: i64) -> String {
+    let minutes = millis / 60_000;
+    format!("{:02}:{:02}", minutes / 60, minutes % 60)
+}
+
+/// `Mo-We,Fr` for the day indices `days`, Monday first.
+fn weekday_ranges(days: &[usize]) -> String {
+    let mut ranges: Vec<String> = Vec::new();
+    let mut index = 0;
+    while let Some(first) = days.get(index) {
+        let mut last = index;
+        while days.get(last + 1) == Some(&(days[last] + 1)) {
+            last += 1;
+        }
+        let name = |day: usize| WEEKDAYS.get(day).copied().unwrap_or_default();
+        match last - index {
+            0 => ranges.push(name(*first).to_string()),
+            1 => ranges.push(format!("{},{}", name(*first), name(days[last]))),
+            _ => ranges.push(format!("{}-{}", name(*first), name(days[last]))),
+        }
+        index = last + 1;
+    }
+    ranges.join(",")
+}
+
+/// `2024 Dec 24-26`, or with the month and year of the end where they
+/// differ.
+fn date_range(start: &FhirDateTime, end: &FhirDateTime) -> String {
+    let month = |date: &FhirDateTime| {
+        MONTHS
+            .get(usize::from(date.month().unwrap_or(1)) - 1)
+            .copied()
+            .unwrap_or_default()
+    };
+    let day = |date: &FhirDateTime| date.day().unwrap_or(1);
+    let first = format!("{} {} {:02}", start.year(), month(start), day(start));
+    match (start.year() == end.year(), start.month() == end.month()) {
+        _ if start == end => first,
+        (true, true) => format!("{first}-{:02}", day(end)),
+        (true, false) => format!("{first}-{} {:02}", month(end), day(end)),
+        (false, _) => format!("{first}-{} {} {:02}", end.year(), month(end), day(end)),
+    }
+}
+
+/// The rules of `value` separated by `;`, flagged when they follow `||`. A
+/// separator without a rule after it is an error rather than an empty rule,
+/// which would open every day.
+fn split_rules(value: &str) -> Result<Vec<(&str, bool)>, FhirError> {
+    if value.trim().is_empty() {
+        return Err(FhirError::OpeningHoursError(
+            "the value is empty".to_string(),
+        ));
+    }
+    let mut rules = Vec::new();
+    let (mut start, mut fallback, mut quoted) = (0, false, false);
+    let bytes = value.as_bytes();
+    let mut index = 0;
+    while index < bytes.len() {
+        match bytes[index] {
+            b'"' => quoted = !quoted,
+            b';' if !quoted => {
+                rules.push((&value[start..index], fallback));
+                (start, fallback) = (index + 1, false);
+            }
+            b'|' if !quoted && bytes.get(index + 1) == Some(&b'|') => {
+                rules.push((&value[start..index], fallback));
+                (start, fallback) = (index + 2, true);
+                index += 1;
+            }
+            _ => {}
+        }
+        index += 1;
+    }
+    rules.push((&value[start..], fallback));
+    if rules.len() > 1 && rules.iter().any(|(rule, _)| rule.trim().is_empty()) {
+        return Err(FhirError::OpeningHoursError(format!(
+            "empty rule in '{}'",
+            value.trim()
+        )));
+    }
+    Ok(rules
+        .into_iter()
+        .filter(|(rule, _)| !rule.trim().is_empty())
+        .collect())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum State {
+    Open,
+    Closed,
+    Unknown,
+}
+
+struct Rule<'a> {
+    text: &'a str,
+    dates: Option<(FhirDateTime, FhirDateTime)>,
+    days: Vec<DaysOfWeek>,
+    public_holidays: bool,
+    spans: Spans,
+    state: State,
+    comment: Option<String>,
+}
+
+/// A parsed value, or the reason a valid rule cannot be represented.
+type Outcome<T> = Result<T, &'static str>;
+
+/// Parses a rule up to an additional rule after `,`, which is returned as
+/// well.
+fn parse_rule(text: &str) -> Result<Outcome<(Rule<'_>, Option<&str>)>, FhirError> {
+    let mut cursor = Cursor { text, position: 0 };
+    let mut rule = Rule {
+        text,
+        dates: None,
+        days: Vec::new(),
+        public_holidays: false,
+        spans: Vec::new(),
+        state: State::Open,
+        comment: None,
+    };
+    let mut additional = None;
+
+    if cursor.eat("24/7") {
+        rule.spans.push((0, MILLIS_PER_DAY));
+    } else {
+        // A year starts a date selector, four digits of a time do not.
+        let year = cursor.digits(4).is_some() && !cursor.rest().starts_with(':');
+        cursor.position = 0;
+        if year {
+            match cursor.dates()? {
+                Ok(dates) => rule.dates = Some(dates),
+                Err(reason) => return Ok(Err(reason)),
+            }
+        }
+        let word = cursor.peek_word();
+        if MONTHS.iter().any(|month| month.eq_ignore_ascii_case(word)) {
+            return Ok(Err("dates repeating every year"));
+        }
+        if word.eq_ignore_ascii_case("week") {
+            return Ok(Err("week numbers"));
+        }
+
+        loop {
+            if cursor.eat("PH") {
+                rule.public_holidays = true;
+            } else if cursor.eat("SH") {
+                return Ok(Err("school holidays"));
+            } else if let Some(first) = cursor.weekday() {
+                let last = if cursor.eat("-") {
+                    cursor.weekday().ok_or_else(|| cursor.error())?
+                } else {
+                    first
+                };
+                if cursor.rest().starts_with('[') {
+                    return Ok(Err("n-th weekdays of the month"));
+                }
+                let mut day = first;
+                loop {
+                    if !rule.days.contains(&DaysOfWeek::ALL[day]) {
+                        rule.days.push(DaysOfWeek::ALL[day]);
+                    }
+                    if day == last {
+                        break;
+                    }
+                    day = (day + 1) % 7;
+                }
+            } else if rule.public_holidays || !rule.days.is_empty() {
+                // A `,` after a selector must be followed by another one.
+                return Err(cursor.error());
+            } else {
+                break;
+            }
+            if !cursor.eat(",") {
+                break;
+            }
+        }
+        cursor.eat(":");
+
+        let word = cursor.peek_word();
+        if ["sunrise", "sunset", "dawn", "dusk"].contains(&word) || cursor.rest().starts_with('(') {
+            return Ok(Err("times relative to the sun"));
+        }
+        if cursor.rest().starts_with(|c: char| c.is_ascii_digit()) {
+            loop {
+                let start = cursor.time()?;
+                if cursor.eat("+") {
+                    return Ok(Err("open ended times"));
+                }
+                if !cursor.eat("-") {
+                    return Ok(Err("points in time"));
+                }
+                let word = cursor.peek_word();
+                if ["sunrise", "sunset", "dawn", "dusk"].contains(&word) {
+                    return Ok(Err("times relative to the sun"));
+                }
+                let mut end = cursor.time()?;
+                if cursor.eat("+") {
+                    return Ok(Err("open ended times"));
+                }
+                if cursor.rest().starts_with('/') {
+                    return Ok(Err("repeating times"));
+                }
+                if start >= MILLIS_PER_DAY {
+                    return Err(cursor.error());
+                }
+                // A range closing when it opens could mean closed or open
+                // for 24 hours, it is rejected rather than guessed.
+                if end == start {
+                    return Err(FhirError::OpeningHoursError(format!(
+                        "zero-length range {}-{} in '{}'",
+                        clock(start),
+                        clock(end),
+                        text.trim()
+                    )));
+                }
+                if end < start {
+                    end += MILLIS_PER_DAY;
+                }
+                if end - start > MILLIS_PER_DAY {
+                    return Ok(Err("hours longer than a day"));
+                }
+                rule.spans.push((start, end));
+                if !cursor.eat(",") {
+                    break;
+                }
+                if cursor.rest().is_empty() {
+                    return Err(cursor.error());
+                }
+                if !cursor.rest().starts_with(|c: char| c.is_ascii_digit()) {
+                    additional = Some(cursor.rest());
+                    break;
+                }
+            }
+        }
+    }
+
+    if additional.is_none() {
+        let word = cursor.peek_word().to_ascii_lowercase();
+        rule.state = match word.as_str() {
+            "off" | "closed" => State::Closed,
+            "unknown" => State::Unknown,
+            _ => State::Open,
+        };
+        if ["open", "off", "closed", "unknown"].contains(&word.as_str()) {
+            cursor.eat(&word);
+        }
+        if cursor.eat("\"") {
+            let (comment, _) = cursor
+                .rest()
+                .split_once('"')
+                .ok_or_else(|| cursor.error())?;
+            rule.comment = Some(comment.to_string());
+            cursor.position += comment.len() + 1;
+        }
+        if !cursor.rest().is_empty() {
+            return Err(cursor.error());
+        }
+    }
+    rule.text = match additional {
+        Some(rest) => text[..text.len() - rest.len()].trim_end_matches([',', ' ']),
+        None => text,
+    };
+    Ok(Ok((rule, additional)))
+}
+
+/// A position in a rule, past the spaces before it.
+struct Cursor<'a> {
+    text: &'a str,
+    position: usize,
+}
+
+impl<'a> Cursor<'a> {
+    fn rest(&self) -> &'a str {
+        self.text
+            .get(self.position..)
+            .unwrap_or_default()
+            .trim_start()
+    }
+
+    fn skip_spaces(&mut self) {
+        self.position = self.text.len() - self.rest().len();
+    }
+
+    fn error(&self) -> FhirError {
+        FhirError::OpeningHoursError(match self.rest() {
+            "" => format!("unexpected end of '{}'", self.text.trim()),
+            rest => format!("unexpected '{rest}' in '{}'", self.text.trim()),
+        })
+    }
+
+    /// Consumes `token` when the rest starts with it, ignoring case.
+    fn eat(&mut self, token: &str) -> bool {
+        self.skip_spaces();
+        let matches = self
+            .rest()
+            .get(..token.len())
+            .is_some_and(|start| start.eq_ignore_ascii_case(token));
+        if matches {
+            self.position += token.len();
+        }
+        matches
+    }
+
+    fn peek_word(&self) -> &'a str {
+        let rest = self.rest();
+        let end = rest
+            .find(|c: char| !c.is_ascii_alphabetic())
+            .unwrap_or(rest.len());
+        &rest[..end]
+    }
+
+    /// Consumes exactly `count` digits.
+    fn digits(&mut self, count: usize) -> Option<u32> {
+        self.skip_spaces();
+        let digits = self.rest().get(..count)?;
+        let value = digits
+            .bytes()
+            .all(|byte| byte.is_ascii_digit())
+            .then(|| digits.parse().ok())??;
+        if self.rest()[count..].starts_with(|c: char| c.is_ascii_digit()) {
+            return None;
+        }
+        self.position += count;
+        Some(value)
+    }
+
+    /// A day of the month of one or two digits.
+    fn day(&mut self) -> Option<u8> {
+        let value = self.digits(2).or_else(|| self.digits(1))?;
+        u8::try_from(value).ok()
+    }
+
+    /// Consumes a name in `names`, returning its index.
+    fn name(&mut self, names: &[&str]) -> Option<usize> {
+        let word = self.peek_word();
+        let (index, length) = (
+            names
+                .iter()
+                .position(|name| name.eq_ignore_ascii_case(word))?,
+            word.len(),
+        );
+        self.skip_spaces();
+        self.position += length;
+        Some(index)
+    }
+
+    fn weekday(&mut self) -> Option<usize> {
+        self.name(&WEEKDAYS)
+    }
+
+    fn month(&mut self) -> Option<u8> {
+        u8::try_from(self.name(&MONTHS)? + 1).ok()
+    }
+
+    /// `HH:MM` in milliseconds after midnight, up to `48:00`.
+    fn time(&mut self) -> Result<i64, FhirError> {
+        let hours = self.digits(2).or_else(|| self.digits(1));
+        let (Some(hours), true) = (hours, self.text[self.position..].starts_with(':')) else {
+            return Err(self.error());
+        };
+        self.position += 1;
+        match self.digits(2) {
+            Some(minutes) if minutes < 60 && hours * 60 + minutes <= 48 * 60 => {
+                Ok(i64::from(hours * 60 + minutes) * 60_000)
+            }
+            _ => Err(self.error()),
+        }
+    }
+
+    fn date(&mut self, year: i32) -> Result<FhirDateTime, FhirError> {
+        let month = self.month().ok_or_else(|| self.error())?;
+        let day = self.day().ok_or_else(|| self.error())?;
+        FhirDateTime::from_ymd(year, month, day).map_err(|_| self.error())
+    }
+
+    /// `2024 Dec 24`, `2024 Dec 24-26`, `2024 Dec 24-Jan 02` with the year
+    /// of the start, or `2024 Dec 24-2025 Jan 02`.
+    fn dates(&mut self) -> Result<Outcome<(FhirDateTime, FhirDateTime)>, FhirError> {
+        let year = self.digits(4).ok_or_else(|| self.error())? as i32;
+        if self.peek_word().is_empty() {
+            return Ok(Err("whole years"));
+        }
+        let start = self.date(year)?;
+        if !self.eat("-") {
+            return Ok(Ok((start, start)));
+        }
+        let end = if let Some(year) = self.digits(4) {
+            self.date(year as i32)?
+        } else if !self.peek_word().is_empty() {
+            self.date(year)?
+        } else {
+            let day = self.day().ok_or_else(|| self.error())?;
+            FhirDateTime::from_ymd(year, start.month().unwrap_or(1), day)
+                .map_err(|_| self.error())?
+        };
+        if end.epoch_millis(0) < start.epoch_millis(0) {
+            return Err(self.error());
+        }
+        Ok(Ok((start, end)))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn slots(opening_hours: &OpeningHours, day: DaysOfWeek) -> Vec<String> {
+        opening_hours
+            .slots
+            .iter()
+            .filter(|slot| slot.day == day)
+            .map(ToString::to_string)
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_should_read_weekdays_and_times() {
+        let opening_hours =
+            OpeningHours::parse("Mo-Fr 08:00-12:00,13:00-17:00; Sa 09:00-12:00; We off; PH off")
+                .unwrap();
+
+        assert_eq!(
+            vec!["08:00-12:00", "13:00-17:00"],
+            slots(&opening_hours, DaysOfWeek::Mon)
+        );
+        assert!(slots(&opening_hours, DaysOfWeek::Wed).is_empty());
+        assert_eq!(vec!["09:00-12:00"], slots(&opening_hours, DaysOfWeek::Sat));
+        assert!(slots(&opening_hours, DaysOfWeek::Sun).is_empty());
+        assert!(opening_hours.closed_on_public_holidays);
+        assert!(opening_hours.unsupported.is_empty());
+        assert_eq!(
+            "Mo,Tu,Th,Fr 08:00-12:00,13:00-17:00; Sa 09:00-12:00; PH off",
+            opening_hours.to_string()
+        );
+    }
+
+    #[test]
+    fn test_parse_should_read_overnight_times() {
+        let opening_hours = OpeningHours::parse("Fr 22:00-06:00; Sa 08:00-32:00").unwrap();
+
+        assert_eq!("Fr 22:00-30:00; Sa 08:00-32:00", opening_hours.to_string());
+        assert_eq!(
+            "invalid opening hours: zero-length range 08:00-08:00 in 'Sa 08:00-08:00'",
+            OpeningHours::parse("Sa 08:00-08:00")
+                .unwrap_err()
+                .to_string()
+        );
+    }
+
+    #[test]
+    fn test_parse_should_reject_empty_value() {
+        for value in ["", "  \t"] {
+            assert_eq!(
+                "invalid opening hours: the value is empty",
+                OpeningHours::parse(value).unwrap_err().to_string(),
+                "{value:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_should_convert_to_fhir() {
+        let opening_hours = OpeningHours::parse(
+            "Mo-Fr 09:00-17:00, Sa 10:00-14:00; Fr-Su 22:00-02:00; \
+             2024 Dec 24-26 off \"Christmas\"; PH off",
+        )
+        .unwrap();
+
+        let available_times = opening_hours.available_times();
+        let expected = vec![
+            AvailableTimeBuilder::default()
+                .with_days_of_week(vec![
+                    DaysOfWeek::Mon,
+                    DaysOfWeek::Tue,
+                    DaysOfWeek::Wed,
+                    DaysOfWeek::Thu,
+                ])
+                .with_available_start_time("09:00:00")
+                .with_available_end_time("17:00:00")
+                .build(),
+            AvailableTimeBuilder::default()
+                .with_days_of_week(vec![DaysOfWeek::Fri, DaysOfWeek::Sat, DaysOfWeek::Sun])
+                .with_available_start_time("22:00:00")
+                .with_available_end_time("02:00:00")
+                .build(),
+        ];
+        assert_eq!(expected, available_times);
+
+        let hours = opening_hours.hours_of_operation();
+        assert_eq!(2, hours.len());
+        assert_eq!(Some("22:00:00"), hours[1].operation_time.as_deref());
+
+        let not_available = opening_hours.not_available();
+        let expected = vec![
+            NotAvailableBuilder::default()
+                .with_desscription("Christmas")
+                .with_during(
+                    PeriodBuilder::default()
+                        .with_start("2024-12-24")
+                        .with_end("2024-12-26")
+                        .build(),
+                )
+                .build(),
+            NotAvailableBuilder::default()
+                .with_desscription(CLOSED_ON_PUBLIC_HOLIDAYS)
+                .build(),
+        ];
+        assert_eq!(expected, not_available);
+    }
+
+    #[test]
+    fn test_format_should_round_trip_fhir_values() {
+        let available_times = vec![
+            AvailableTimeBuilder::default()
+                .with_days_of_week(vec![DaysOfWeek::Sat, DaysOfWeek::Sun])
+                .with_all_day(true)
+                .build(),
+            AvailableTimeBuilder::default()
+                .with_days_of_week(vec![DaysOfWeek::Mon, DaysOfWeek::Tue, DaysOfWeek::Wed])
+                .with_available_start_time("08:30:00")
+                .with_available_end_time("17:00:00")
+                .build(),
+        ];
+        let not_available = vec![
+            NotAvailableBuilder::default()
+                .with_desscription("Maintenance")
+                .with_during(
+                    PeriodBuilder::default()
+                        .with_start("2024-12-30")
+                        .with_end("2025-01-02")
+                        .build(),
+                )
+                .build(),
+            NotAvailableBuilder::default()
+                .with_desscription("Renovation")
+                .with_during(
+                    PeriodBuilder::default()
+                        .with_start("2025-03-01T12:00:00")
+                        .build(),
+                )
+                .build(),
+            NotAvailableBuilder::default()
+                .with_desscription(CLOSED_ON_PUBLIC_HOLIDAYS)
+                .build(),
+        ];
+
+        let opening_hours =
+            OpeningHours::from_available_times(&available_times, &not_available).unwrap();
+        let formatted = opening_hours.to_string();
+
+        assert_eq!(
+            "Mo-We 08:30-17:00; Sa,Su 00:00-24:00; \
+             2024 Dec 30-2025 Jan 02 off \"Maintenance\"; PH off",
+            formatted
+        );
+        assert_eq!(
+            vec![UnsupportedRule {
+                rule: "Renovation".to_string(),
+                reason: "open ended closures",
+            }],
+            opening_hours.unsupported
+        );
+
+        let parsed = OpeningHours::parse(&formatted).unwrap();
+        assert_eq!(available_times, parsed.available_times());
+        assert_eq!(formatted, parsed.to_string());
+        assert_eq!("24/7", OpeningHours::parse("Mo-Su").unwrap().to_string());
+    }
+
+    #[test]
+    fn test_parse_should_report_unsupported_rules() {
+        let opening_hours = OpeningHours::parse(
+            "Mo-Fr 08:00-17:00; Dec 25 off; SH off; Su[1] 10:00-12:00; \
+             Sa sunrise-sunset; Mo 12:00-13:00 off; PH 10:00-12:00; week 01-10 Tu off; \
+             Th 18:00+; Fr 10:00-12:00 \"by appointment\" || \"call us\"",
+        )
+        .unwrap();
+
+        let reasons: Vec<String> = opening_hours
+            .unsupported
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        assert_eq!(
+            vec![
+                "Dec 25 off: dates repeating every year",
+                "SH off: school holidays",
+                "Su[1] 10:00-12:00: n-th weekdays of the month",
+                "Sa sunrise-sunset: times relative to the sun",
+                "Mo 12:00-13:00 off: closing part of a day",
+                "PH 10:00-12:00: opening hours on public holidays",
+                "week 01-10 Tu off: week numbers",
+                "Th 18:00+: open ended times",
+                "Fr 10:00-12:00 \"by appointment\": comments on opening hours, the hours were kept",
+                "\"call us\": fallback rules",
+            ],
+            reasons
+        );
+        assert_eq!(
+            "Mo-Th 08:00-17:00; Fr 10:00-12:00",
+            opening_hours.to_string()
+        );
+    }
+
+    #[test]
+    fn test_parse_should_reject_invalid_syntax() {
+        for value in [
+            "Mo-Fr 8-17",
+            "Mo-Fr 08:00-25:61",
+            "Xy 08:00-12:00",
+            "2024 Feb 30 off",
+            "Mo 08:00-12:00 \"open",
+            "Mo-Fr 08:00-17:00,",
+            "Mo-Fr 08:00-17:00;",
+            "Mo-Fr 08:00-17:00; ; Sa 10:00-12:00",
+            "Mo-Fr 08:00-17:00 ||",
+            "Mo, 08:00-17:00",
+            "Mo 08:00-08:00",
+        ] {
+            assert!(
+                matches!(
+                    OpeningHours::parse(value),
+                    Err(FhirError::OpeningHoursError(_))
+                ),
+                "{value}"
+            );
+        }
+    }
+}
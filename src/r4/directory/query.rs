@@ -0,0 +1,539 @@
+use std::cmp::Ordering;
+
+use crate::{
+    FhirError,
+    r4::{
+        directory::{Availability, GeoPoint, ResourceStore},
+        elements::{CodeableConcept, DaysOfWeek, Effective, FhirDateTime, FhirTime, TimeZone},
+        resources::{Location, Practitioner, PractitionerRole},
+    },
+};
+
+/// A search for practitioners across the PractitionerRoles of a
+/// [`ResourceStore`], such as a cardiologist within 10 km, available on
+/// Tuesday morning, who speaks Dutch.
+///
+/// Every criterion narrows the result. Roles not currently in effect and
+/// practitioners flagged inactive are left out. Matches are ranked nearest
+/// first, then by the number of requested languages spoken, then by role id.
+pub struct ProviderQuery<'a> {
+    store: &'a ResourceStore,
+    specialties: Vec<(String, String)>,
+    near: Option<(GeoPoint, f64)>,
+    window: Option<(DaysOfWeek, FhirTime, FhirTime)>,
+    available_at: Option<(FhirDateTime, &'a dyn TimeZone)>,
+    languages: Vec<String>,
+    effective_at: Option<FhirDateTime>,
+    limit: Option<usize>,
+}
+
+/// The outcome of a [`ProviderQuery`].
+#[derive(Debug)]
+pub struct ProviderResults<'a> {
+    pub matches: Vec<ProviderMatch<'a>>,
+    /// The roles left out of `matches` because they could not be checked,
+    /// such as a role with invalid available times or a period whose dates
+    /// do not parse, with the reason.
+    pub errors: Vec<(&'a PractitionerRole, FhirError)>,
+}
+
+/// A PractitionerRole found by a [`ProviderQuery`] with the resources that
+/// made it match.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProviderMatch<'a> {
+    pub role: &'a PractitionerRole,
+    pub practitioner: Option<&'a Practitioner>,
+    /// The nearest location of the role with a position when searching by
+    /// distance, otherwise the first location that resolves.
+    pub location: Option<&'a Location>,
+    /// Meters from the searched point to `location`.
+    pub distance: Option<f64>,
+    /// The entries of `Practitioner.communication` matching a requested
+    /// language.
+    pub languages: Vec<&'a CodeableConcept>,
+}
+
+impl<'a> ProviderQuery<'a> {
+    pub fn new(store: &'a ResourceStore) -> Self {
+        Self {
+            store,
+            specialties: Vec::new(),
+            near: None,
+            window: None,
+            available_at: None,
+            languages: Vec::new(),
+            effective_at: None,
+            limit: None,
+        }
+    }
+
+    /// Requires a specialty coded `code` in `system`. Repeated calls accept
+    /// any of the specialties given.
+    pub fn with_specialty(mut self, system: impl Into<String>, code: impl Into<String>) -> Self {
+        self.specialties.push((system.into(), code.into()));
+        self
+    }
+
+    /// Requires a location of the role within `radius` meters of `center`.
+    pub fn within(mut self, center: GeoPoint, radius: f64) -> Self {
+        self.near = Some((center, radius));
+        self
+    }
+
+    /// Requires the role to be available for part of the weekly window from
+    /// `from` to `to` on `day`, see [`WeeklySchedule::is_open_during`].
+    ///
+    /// [`WeeklySchedule::is_open_during`]: crate::r4::directory::WeeklySchedule::is_open_during
+    pub fn available_during(mut self, day: DaysOfWeek, from: FhirTime, to: FhirTime) -> Self {
+        self.window = Some((day, from, to));
+        self
+    }
+
+    /// Requires the role to be available at `at`, taking its
+    /// `notAvailable` periods into account. A value without an offset is a
+    /// local time in `zone`.
+    pub fn available_at(mut self, at: FhirDateTime, zone: &'a dyn TimeZone) -> Self {
+        self.available_at = Some((at, zone));
+        self
+    }
+
+    /// Requires the practitioner to speak `language`, a BCP 47 tag such as
+    /// `nl` or a name such as `Dutch`. Repeated calls accept any of the
+    /// languages given and rank practitioners speaking more of them first.
+    pub fn speaking(mut self, language: impl Into<String>) -> Self {
+        self.languages.push(language.into());
+        self
+    }
+
+    /// Requires the role to be in effect at `at` rather than now.
+    pub fn effective_at(mut self, at: FhirDateTime) -> Self {
+        self.effective_at = Some(at);
+        self
+    }
+
+    /// Keeps the first `limit` matches.
+    pub fn limit(mut self, limit: usize) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// Runs the query. A role that cannot be checked does not match and is
+    /// reported in [`ProviderResults::errors`], so one bad record does not
+    /// fail the whole search.
+    pub fn run(&self) -> ProviderResults<'a> {
+        let mut matches = Vec::new();
+        let mut errors = Vec::new();
+        for role in self.store.resources::<PractitionerRole>() {
+            match self.evaluate(role) {
+                Ok(Some(found)) => matches.push(found),
+                Ok(None) => {}
+                Err(error) => errors.push((role, error)),
+            }
+        }
+        matches.sort_by(rank);
+        if let Some(limit) = self.limit {
+            matches.truncate(limit);
+        }
+        ProviderResults { matches, errors }
+    }
+
+    fn evaluate(&self, role: &'a PractitionerRole) -> Result<Option<ProviderMatch<'a>>, FhirError> {
+        let effective = match &self.effective_at {
+            Some(at) => role.is_effective_at(at)?,
+            None => role.currently_effective()?,
+        };
+        if !effective || !self.has_specialty(role) {
+            return Ok(None);
+        }
+
+        let practitioner = role
+            .practitioner
+            .as_ref()
+            .and_then(|reference| self.store.resolve_in(role, reference));
+        if practitioner.is_some_and(|practitioner| practitioner.active == Some(false)) {
+            return Ok(None);
+        }
+        let languages = self.spoken(practitioner);
+        if !self.languages.is_empty() && languages.is_empty() {
+            return Ok(None);
+        }
+
+        let (location, distance) = match self.locate(role) {
+            Some(found) => found,
+            None => return Ok(None),
+        };
+
+        if self.window.is_some() || self.available_at.is_some() {
+            let schedule = role.weekly_schedule()?;
+            if let Some((day, from, to)) = &self.window
+                && !schedule.is_open_during(*day, from, to)
+            {
+                return Ok(None);
+            }
+            if let Some((at, zone)) = &self.available_at
+                && !schedule.is_available_at(at, zone)
+            {
+                return Ok(None);
+            }
+        }
+
+        Ok(Some(ProviderMatch {
+            role,
+            practitioner,
+            location,
+            distance,
+            languages,
+        }))
+    }
+
+    fn has_specialty(&self, role: &PractitionerRole) -> bool {
+        self.specialties.is_empty()
+            || role
+                .speciality
+                .iter()
+                .flatten()
+                .flat_map(|concept| concept.coding.iter().flatten())
+                .any(|coding| {
+                    self.specialties.iter().any(|(system, code)| {
+                        coding.system.as_deref() == Some(system.as_str())
+                            && coding.code.as_deref() == Some(code.as_str())
+                    })
+                })
+    }
+
+    fn spoken(&self, practitioner: Option<&'a Practitioner>) -> Vec<&'a CodeableConcept> {
+        if self.languages.is_empty() {
+            return Vec::new();
+        }
+        practitioner
+            .and_then(|practitioner| practitioner.communication.as_ref())
+            .into_iter()
+            .flatten()
+            .filter(|concept| {
+                self.languages
+                    .iter()
+                    .any(|language| speaks(concept, language))
+            })
+            .collect()
+    }
+
+    /// The location to report for `role`, `None` when searching by distance
+    /// and no location of the role is close enough.
+    fn locate(&self, role: &'a PractitionerRole) -> Option<(Option<&'a Location>, Option<f64>)> {
+        let mut locations = role
+            .location
+            .iter()
+            .flatten()
+            .filter_map(|reference| self.store.resolve_in(role, reference));
+        let Some((center, radius)) = self.near else {
+            return Some((locations.next(), None));
+        };
+        locations
+            .filter_map(|location| {
+                let distance = center.distance_to(&GeoPoint::of(location)?);
+                (distance <= radius).then_some((location, distance))
+            })
+            .min_by(|left, right| left.1.total_cmp(&right.1))
+            .map(|(location, distance)| (Some(location), Some(distance)))
+    }
+}
+
+/// Whether `concept` is the language `language`, comparing codes by their
+/// primary subtag so that `nl` matches `nl-BE`, and names with the display
+/// and text, ignoring case.
+pub(crate) fn speaks(concept: &CodeableConcept, language: &str) -> bool {
+    let primary = |tag: &str| tag.split(['-', '_']).next().unwrap_or(tag).to_lowercase();
+    let wanted = primary(language);
+    concept.coding.iter().flatten().any(|coding| {
+        coding
+            .code
+            .as_deref()
+            .is_some_and(|code| primary(code) == wanted)
+            || coding
+                .display
+                .as_deref()
+                .is_some_and(|display| display.eq_ignore_ascii_case(language))
+    }) || concept
+        .text
+        .as_deref()
+        .is_some_and(|text| text.eq_ignore_ascii_case(language))
+}
+
+fn rank(left: &ProviderMatch<'_>, right: &ProviderMatch<'_>) -> Ordering {
+    let distance = match (left.distance, right.distance) {
+        (Some(left), Some(right)) => left.total_cmp(&right),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => Ordering::Equal,
+    };
+    let id = |found: &ProviderMatch<'_>| found.role.domain_resource.resource.id.clone();
+    distance
+        .then_with(|| right.languages.len().cmp(&left.languages.len()))
+        .then_with(|| id(left).cmp(&id(right)))
+}
+
+#[cfg(test)]
+mod test {
+    use crate::r4::{
+        elements::{
+            AvailableTime, AvailableTimeBuilder, CodeableConceptBuilder, CodingBuilder,
+            FixedOffset, NotAvailableBuilder, PeriodBuilder, Reference,
+        },
+        resources::{
+            LocationBuilder, LocationPositionBuilder, PractitionerBuilder, PractitionerRoleBuilder,
+            ReferenceTarget,
+        },
+    };
+
+    use super::*;
+
+    const SNOMED: &str = "http://snomed.info/sct";
+    const CARDIOLOGY: &str = "394579002";
+    const DERMATOLOGY: &str = "394582007";
+
+    fn language(code: &str, display: &str) -> CodeableConcept {
+        CodeableConceptBuilder::default()
+            .add_coding(
+                CodingBuilder::default()
+                    .with_system("urn:ietf:bcp:47")
+                    .with_code(code)
+                    .with_display(display)
+                    .build(),
+            )
+            .build()
+    }
+
+    fn specialty(code: &str) -> CodeableConcept {
+        CodeableConceptBuilder::default()
+            .add_coding(
+                CodingBuilder::default()
+                    .with_system(SNOMED)
+                    .with_code(code)
+                    .build(),
+            )
+            .build()
+    }
+
+    fn location(id: &str, latitude: f32, longitude: f32) -> Location {
+        LocationBuilder::new(id)
+            .position(
+                LocationPositionBuilder::default()
+                    .latitude(latitude)
+                    .longitude(longitude)
+                    .build(),
+            )
+            .build()
+    }
+
+    fn weekdays(start: &str, end: &str) -> AvailableTime {
+        AvailableTimeBuilder::default()
+            .with_days_of_week(vec![DaysOfWeek::Mon, DaysOfWeek::Tue, DaysOfWeek::Wed])
+            .with_available_start_time(start)
+            .with_available_end_time(end)
+            .build()
+    }
+
+    fn role(
+        id: &str,
+        practitioner: &Practitioner,
+        code: &str,
+        location: &Location,
+    ) -> PractitionerRoleBuilder {
+        PractitionerRoleBuilder::new(id)
+            .practitioner(Reference::to(practitioner))
+            .add_speciality(specialty(code))
+            .add_location(Reference::to(location))
+    }
+
+    /// Cardiologists in Amsterdam and Utrecht and a dermatologist in
+    /// Amsterdam, speaking Dutch, English or both.
+    fn store() -> ResourceStore {
+        let amsterdam = location("amsterdam", 52.3676, 4.9041);
+        let amstelveen = location("amstelveen", 52.3114, 4.8701);
+        let utrecht = location("utrecht", 52.0907, 5.1214);
+        let de_vries = PractitionerBuilder::new("de-vries")
+            .add_communication(language("nl", "Dutch"))
+            .add_communication(language("en", "English"))
+            .build();
+        let jansen = PractitionerBuilder::new("jansen")
+            .add_communication(language("nl-NL", "Nederlands"))
+            .build();
+        let smith = PractitionerBuilder::new("smith")
+            .add_communication(language("en", "English"))
+            .build();
+        let retired = PractitionerBuilder::new("retired")
+            .active(false)
+            .add_communication(language("nl", "Dutch"))
+            .build();
+
+        let roles = [
+            role("role-1", &jansen, CARDIOLOGY, &amsterdam)
+                .add_available_time(weekdays("08:00:00", "12:00:00"))
+                .build(),
+            role("role-2", &de_vries, CARDIOLOGY, &utrecht)
+                .add_location(Reference::to(&amstelveen))
+                .add_available_time(weekdays("09:00:00", "17:00:00"))
+                .add_not_available_time(
+                    NotAvailableBuilder::default()
+                        .with_desscription("Leave")
+                        .with_during(
+                            PeriodBuilder::default()
+                                .with_start("2024-06-04")
+                                .with_end("2024-06-05")
+                                .build(),
+                        )
+                        .build(),
+                )
+                .build(),
+            role("role-3", &smith, CARDIOLOGY, &amsterdam)
+                .add_available_time(weekdays("13:00:00", "17:00:00"))
+                .build(),
+            role("role-4", &de_vries, DERMATOLOGY, &amsterdam)
+                .add_available_time(weekdays("08:00:00", "12:00:00"))
+                .build(),
+            role("role-5", &retired, CARDIOLOGY, &amsterdam)
+                .add_available_time(weekdays("08:00:00", "12:00:00"))
+                .build(),
+            role("role-6", &jansen, CARDIOLOGY, &utrecht)
+                .active(false)
+                .build(),
+            role("role-7", &jansen, CARDIOLOGY, &amsterdam)
+                .period(
+                    PeriodBuilder::default()
+                        .with_start("2020-01-01")
+                        .with_end("2022-12-31")
+                        .build(),
+                )
+                .build(),
+        ];
+
+        let mut store = ResourceStore::new();
+        for location in [amsterdam, amstelveen, utrecht] {
+            store.insert(location).unwrap();
+        }
+        for practitioner in [de_vries, jansen, smith, retired] {
+            store.insert(practitioner).unwrap();
+        }
+        for role in roles {
+            store.insert(role).unwrap();
+        }
+        store
+    }
+
+    fn ids(matches: &[ProviderMatch<'_>]) -> Vec<String> {
+        matches
+            .iter()
+            .filter_map(|found| found.role.domain_resource.resource.id.clone())
+            .collect()
+    }
+
+    fn time(value: &str) -> FhirTime {
+        FhirTime::parse(value).unwrap()
+    }
+
+    #[test]
+    fn test_run_should_combine_specialty_distance_availability_and_language() {
+        let store = store();
+        let dam = GeoPoint::new(52.3731, 4.8926).unwrap();
+
+        let matches = ProviderQuery::new(&store)
+            .with_specialty(SNOMED, CARDIOLOGY)
+            .within(dam, 10_000.0)
+            .available_during(DaysOfWeek::Tue, time("08:00:00"), time("12:00:00"))
+            .speaking("nl")
+            .run()
+            .matches;
+
+        assert_eq!(vec!["role-1", "role-2"], ids(&matches));
+        assert_eq!(
+            "jansen",
+            matches[0].practitioner.unwrap().resource_id().unwrap()
+        );
+        assert!(matches[0].distance.unwrap() < 1_000.0);
+        assert_eq!(
+            Some("amstelveen"),
+            matches[1].location.unwrap().resource_id()
+        );
+        assert!(matches[1].distance.unwrap() < 10_000.0);
+        assert_eq!(1, matches[1].languages.len());
+    }
+
+    #[test]
+    fn test_run_should_rank_by_languages_without_distance() {
+        let store = store();
+
+        let matches = ProviderQuery::new(&store)
+            .speaking("nl")
+            .speaking("English")
+            .run()
+            .matches;
+
+        assert_eq!(vec!["role-2", "role-4", "role-1", "role-3"], ids(&matches));
+        assert_eq!(2, matches[0].languages.len());
+        assert_eq!(Some("utrecht"), matches[0].location.unwrap().resource_id());
+        assert_eq!(None, matches[0].distance);
+    }
+
+    #[test]
+    fn test_run_should_honour_not_available_and_effective_periods() {
+        let store = store();
+        let zone = FixedOffset(120);
+        let query = |at: &str| {
+            ProviderQuery::new(&store)
+                .with_specialty(SNOMED, CARDIOLOGY)
+                .available_at(FhirDateTime::parse(at).unwrap(), &zone)
+                .run()
+                .matches
+        };
+
+        assert_eq!(vec!["role-1", "role-2"], ids(&query("2024-06-11T10:00:00")));
+        assert_eq!(vec!["role-1"], ids(&query("2024-06-04T10:00:00")));
+
+        let effective = ProviderQuery::new(&store)
+            .with_specialty(SNOMED, CARDIOLOGY)
+            .effective_at(FhirDateTime::parse("2021-06-01").unwrap())
+            .limit(2)
+            .run()
+            .matches;
+        assert_eq!(vec!["role-1", "role-2"], ids(&effective));
+        let past = ProviderQuery::new(&store)
+            .effective_at(FhirDateTime::parse("2023-06-01").unwrap())
+            .run()
+            .matches;
+        assert!(!ids(&past).contains(&"role-7".to_string()));
+    }
+
+    #[test]
+    fn test_run_should_report_roles_that_cannot_be_checked() {
+        let mut store = store();
+        let jansen = store.get::<Practitioner>("jansen").unwrap().clone();
+        let amsterdam = store.get::<Location>("amsterdam").unwrap().clone();
+        store
+            .insert(
+                role("role-8", &jansen, CARDIOLOGY, &amsterdam)
+                    .add_available_time(weekdays("8 o'clock", "12:00:00"))
+                    .build(),
+            )
+            .unwrap();
+        store
+            .insert(
+                role("role-9", &jansen, CARDIOLOGY, &amsterdam)
+                    .period(PeriodBuilder::default().with_end("20-10-2025").build())
+                    .build(),
+            )
+            .unwrap();
+
+        let results = ProviderQuery::new(&store)
+            .with_specialty(SNOMED, CARDIOLOGY)
+            .available_during(DaysOfWeek::Tue, time("08:00:00"), time("12:00:00"))
+            .run();
+
+        assert_eq!(vec!["role-1", "role-2"], ids(&results.matches));
+        let failed: Vec<&str> = results
+            .errors
+            .iter()
+            .filter_map(|(role, _)| role.domain_resource.resource.id.as_deref())
+            .collect();
+        assert_eq!(vec!["role-8", "role-9"], failed);
+    }
+}
@@ -0,0 +1,297 @@
+use std::collections::HashMap;
+
+use crate::r4::{
+    elements::{GetResourceReferencesMut, ParsedReference},
+    resources::ReferenceTarget,
+};
+
+/// Maps the ids used by another server onto local ids, per resource type.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct IdMap {
+    ids: HashMap<String, HashMap<String, String>>,
+}
+
+impl IdMap {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a mapping, returning the id `old_id` was previously mapped to.
+    pub fn insert(
+        &mut self,
+        resource_type: impl Into<String>,
+        old_id: impl Into<String>,
+        new_id: impl Into<String>,
+    ) -> Option<String> {
+        self.ids
+            .entry(resource_type.into())
+            .or_default()
+            .insert(old_id.into(), new_id.into())
+    }
+
+    pub fn get(&self, resource_type: &str, old_id: &str) -> Option<&str> {
+        self.ids.get(resource_type)?.get(old_id).map(String::as_str)
+    }
+
+    pub fn len(&self) -> usize {
+        self.ids.values().map(HashMap::len).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// Rewrites the id of `resource` and every relative reference whose target is
+/// in `ids`, returning the number of values changed.
+///
+/// A `_history` version is dropped from rewritten references as it belongs to
+/// the other server. Absolute references are left alone, use [`rebase`] first
+/// to make the references to the other server relative.
+pub fn remap_ids<R>(resource: &mut R, ids: &IdMap) -> usize
+where
+    R: GetResourceReferencesMut + ReferenceTarget,
+{
+    let mut changed = 0;
+    if let Some(id) = resource.resource_id_mut()
+        && let Some(new_id) = ids.get(R::TYPE, id)
+    {
+        *id = new_id.to_string();
+        changed += 1;
+    }
+    for mut reference in resource.get_references_mut() {
+        let Some(value) = reference.reference_mut() else {
+            continue;
+        };
+        let remapped = match ParsedReference::parse(value) {
+            Ok(ParsedReference::Relative {
+                resource_type, id, ..
+            }) => ids
+                .get(resource_type, id)
+                .map(|new_id| format!("{resource_type}/{new_id}")),
+            _ => None,
+        };
+        if let Some(remapped) = remapped {
+            *value = remapped;
+            changed += 1;
+        }
+    }
+    changed
+}
+
+/// Turns the RESTful absolute references into `base` into relative
+/// `Type/id` references, returning the number of references changed.
+///
+/// The scheme and host of the base are compared case-insensitively and a
+/// trailing `/` is ignored.
+pub fn rebase<R>(resource: &mut R, base: &str) -> usize
+where
+    R: GetResourceReferencesMut + ?Sized,
+{
+    let mut changed = 0;
+    for mut reference in resource.get_references_mut() {
+        let Some(value) = reference.reference_mut() else {
+            continue;
+        };
+        let relative = match ParsedReference::parse(value) {
+            Ok(ParsedReference::Absolute {
+                base: reference_base,
+                resource_type,
+                id,
+                version,
+            }) if same_base(reference_base, base) => Some(match version {
+                Some(version) => format!("{resource_type}/{id}/_history/{version}"),
+                None => format!("{resource_type}/{id}"),
+            }),
+            _ => None,
+        };
+        if let Some(relative) = relative {
+            *value = relative;
+            changed += 1;
+        }
+    }
+    changed
+}
+
+pub(crate) fn same_base(left: &str, right: &str) -> bool {
+    let split = |base: &'_ str| -> (String, String) {
+        let base = base.trim_end_matches('/');
+        let path_start = base
+            .find("://")
+            .and_then(|scheme_end| {
+                base[scheme_end + 3..]
+                    .find('/')
+                    .map(|index| scheme_end + 3 + index)
+            })
+            .unwrap_or(base.len());
+        (
+            base[..path_start].to_ascii_lowercase(),
+            base[path_start..].to_string(),
+        )
+    };
+    split(left) == split(right)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::r4::{
+        elements::{
+            GetResourceReferences, IdentifierBuilder, Reference, ReferenceBuilder, ReferenceView,
+        },
+        resources::{
+            Endpoint, HealthcareService, HealthcareServiceBuilder, Location, Organization,
+            OrganizationAffiliation, Practitioner, PractitionerRole, PractitionerRoleBuilder,
+            ResourceType,
+        },
+    };
+
+    use super::*;
+
+    fn reference<T: ResourceType>(value: &str) -> Reference<T> {
+        ReferenceBuilder::default().with_reference(value).build()
+    }
+
+    #[test]
+    fn test_remap_ids_should_rewrite_ids_and_references() {
+        let mut role = PractitionerRoleBuilder::new("p-role-1")
+            .practitioner(reference("Practitioner/p-7"))
+            .organization(reference("Organization/p-1/_history/4"))
+            .add_location(reference("Location/unmapped"))
+            .add_endpoint(reference("#contained-endpoint"))
+            .add_identifier(
+                IdentifierBuilder::default()
+                    .with_value("123")
+                    .with_assigner(reference::<Organization>("Organization/p-1"))
+                    .build(),
+            )
+            .build();
+        let mut ids = IdMap::new();
+        ids.insert("PractitionerRole", "p-role-1", "role-1");
+        ids.insert("Practitioner", "p-7", "practitioner-7");
+        ids.insert("Organization", "p-1", "org-1");
+
+        let changed = remap_ids(&mut role, &ids);
+
+        assert_eq!(4, changed);
+        assert_eq!(Some("role-1"), role.domain_resource.resource.id.as_deref());
+        assert_eq!(
+            Some("Practitioner/practitioner-7"),
+            role.practitioner.unwrap().reference.as_deref()
+        );
+        assert_eq!(
+            Some("Organization/org-1"),
+            role.organization.unwrap().reference.as_deref()
+        );
+        assert_eq!(
+            Some("Location/unmapped"),
+            role.location.unwrap()[0].reference.as_deref()
+        );
+        assert_eq!(
+            Some("#contained-endpoint"),
+            role.endpoint.unwrap()[0].reference.as_deref()
+        );
+        assert_eq!(
+            Some("Organization/org-1"),
+            role.identifier.unwrap()[0]
+                .assigner
+                .as_ref()
+                .unwrap()
+                .reference
+                .as_deref()
+        );
+    }
+
+    #[test]
+    fn test_rebase_should_make_matching_absolute_references_relative() {
+        let mut service = HealthcareServiceBuilder::new("hs-1")
+            .with_provided_by(reference("HTTPS://Partner.example/fhir/Organization/1"))
+            .add_location(reference(
+                "https://partner.example/fhir/Location/2/_history/3",
+            ))
+            .add_coverage_area(reference("https://other.example/fhir/Location/9"))
+            .add_endpoint(reference("Endpoint/5"))
+            .build();
+
+        let changed = rebase(&mut service, "https://partner.example/fhir/");
+
+        assert_eq!(2, changed);
+        assert_eq!(
+            Some("Organization/1"),
+            service.provided_by.unwrap().reference.as_deref()
+        );
+        assert_eq!(
+            Some("Location/2/_history/3"),
+            service.location.unwrap()[0].reference.as_deref()
+        );
+        assert_eq!(
+            Some("https://other.example/fhir/Location/9"),
+            service.coverage_area.unwrap()[0].reference.as_deref()
+        );
+    }
+
+    fn assert_same_references<R>(mut resource: R)
+    where
+        R: GetResourceReferences + GetResourceReferencesMut,
+    {
+        let expected: Vec<(&str, Option<String>)> = resource
+            .get_references()
+            .iter()
+            .map(|reference| reference.view())
+            .map(|view: ReferenceView| (view.target_type, view.reference.map(String::from)))
+            .collect();
+
+        let actual: Vec<(&str, Option<String>)> = resource
+            .get_references_mut()
+            .iter()
+            .map(|reference| {
+                let view = reference.view();
+                (view.target_type, view.reference.map(String::from))
+            })
+            .collect();
+
+        assert!(!actual.is_empty());
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn test_get_references_mut_should_visit_every_reference() {
+        assert_same_references(
+            Organization::from_json(include_str!(
+                "../../../fixtures/r4/resources/organization.json"
+            ))
+            .unwrap(),
+        );
+        assert_same_references(
+            Location::from_json(include_str!("../../../fixtures/r4/resources/location.json"))
+                .unwrap(),
+        );
+        assert_same_references(
+            Endpoint::from_json(include_str!("../../../fixtures/r4/resources/endpoint.json"))
+                .unwrap(),
+        );
+        assert_same_references(
+            HealthcareService::from_json(include_str!(
+                "../../../fixtures/r4/resources/healthcare_service.json"
+            ))
+            .unwrap(),
+        );
+        assert_same_references(
+            Practitioner::from_json(include_str!(
+                "../../../fixtures/r4/resources/practitioner.json"
+            ))
+            .unwrap(),
+        );
+        assert_same_references(
+            PractitionerRole::from_json(include_str!(
+                "../../../fixtures/r4/resources/practitioner_role.json"
+            ))
+            .unwrap(),
+        );
+        assert_same_references(
+            OrganizationAffiliation::from_json(include_str!(
+                "../../../fixtures/r4/resources/organization_affiliation.json"
+            ))
+            .unwrap(),
+        );
+    }
+}
@@ -0,0 +1,744 @@
+use std::{any::Any, collections::HashMap};
+
+use serde::{Serialize, de::DeserializeOwned};
+use serde_json::Value;
+
+use crate::{
+    FhirError,
+    r4::{
+        directory::same_base,
+        elements::{
+            GetIdentifiers, GetResourceReferences, Identifier, ParsedReference, Reference,
+            ReferenceTypes, ReferenceView,
+        },
+        fhirpath::ReferenceResolver,
+        resources::{
+            CodeSystem, ConceptMap, Device, DeviceDefinition, Endpoint, HealthcareService,
+            Location, NamingSystem, Organization, OrganizationAffiliation, Practitioner,
+            PractitionerRole, ReferenceTarget, ResourceType, StructureDefinition, ValueSet,
+        },
+        terminology::normalize_system,
+    },
+};
+
+/// A resource that can be held by a [`ResourceStore`], implemented for every
+/// libfhir resource.
+pub trait StoredResource:
+    ReferenceTarget + GetResourceReferences + GetIdentifiers + Serialize + DeserializeOwned + Any
+{
+}
+
+impl<T> StoredResource for T where
+    T: ReferenceTarget
+        + GetResourceReferences
+        + GetIdentifiers
+        + Serialize
+        + DeserializeOwned
+        + Any
+{
+}
+
+/// The object safe part of [`StoredResource`].
+trait Entry {
+    fn as_any(&self) -> &dyn Any;
+    fn resource_type(&self) -> &'static str;
+    fn id(&self) -> Option<&str>;
+    fn identifiers(&self) -> Vec<&Identifier>;
+    fn references(&self) -> Vec<ReferenceTypes<'_>>;
+    fn to_json(&self) -> Result<Value, FhirError>;
+}
+
+impl<T: StoredResource> Entry for T {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn resource_type(&self) -> &'static str {
+        T::TYPE
+    }
+
+    fn id(&self) -> Option<&str> {
+        self.resource_id()
+    }
+
+    fn identifiers(&self) -> Vec<&Identifier> {
+        self.get_identifiers()
+    }
+
+    fn references(&self) -> Vec<ReferenceTypes<'_>> {
+        self.get_references()
+    }
+
+    fn to_json(&self) -> Result<Value, FhirError> {
+        Ok(serde_json::to_value(self)?)
+    }
+}
+
+fn entry_from_json(json: Value) -> Result<Box<dyn Entry>, FhirError> {
+    fn parse<T: StoredResource>(json: Value) -> Result<Box<dyn Entry>, FhirError> {
+        Ok(Box::new(serde_json::from_value::<T>(json)?))
+    }
+
+    let resource_type = json
+        .get("resourceType")
+        .and_then(Value::as_str)
+        .unwrap_or_default();
+    match resource_type {
+        Organization::TYPE => parse::<Organization>(json),
+        Location::TYPE => parse::<Location>(json),
+        Endpoint::TYPE => parse::<Endpoint>(json),
+        Practitioner::TYPE => parse::<Practitioner>(json),
+        PractitionerRole::TYPE => parse::<PractitionerRole>(json),
+        HealthcareService::TYPE => parse::<HealthcareService>(json),
+        OrganizationAffiliation::TYPE => parse::<OrganizationAffiliation>(json),
+        Device::TYPE => parse::<Device>(json),
+        DeviceDefinition::TYPE => parse::<DeviceDefinition>(json),
+        CodeSystem::TYPE => parse::<CodeSystem>(json),
+        ConceptMap::TYPE => parse::<ConceptMap>(json),
+        ValueSet::TYPE => parse::<ValueSet>(json),
+        NamingSystem::TYPE => parse::<NamingSystem>(json),
+        StructureDefinition::TYPE => parse::<StructureDefinition>(json),
+        _ => Err(store_error(format!(
+            "unsupported resource type '{resource_type}'"
+        ))),
+    }
+}
+
+fn store_error(message: impl Into<String>) -> FhirError {
+    FhirError::StoreError(message.into())
+}
+
+struct StoredEntry {
+    resource: Box<dyn Entry>,
+    json: Value,
+    full_url: Option<String>,
+    /// The index of the container of a contained resource.
+    container: Option<usize>,
+}
+
+impl StoredEntry {
+    /// `Type/id`, `Type/id#contained` or the full url, used in reports.
+    fn label(&self, entries: &[StoredEntry]) -> String {
+        let resource_type = self.resource.resource_type();
+        match (self.container, self.resource.id(), &self.full_url) {
+            (Some(container), id, _) => {
+                format!(
+                    "{}#{}",
+                    entries[container].label(entries),
+                    id.unwrap_or_default()
+                )
+            }
+            (None, Some(id), _) => format!("{resource_type}/{id}"),
+            (None, None, Some(full_url)) => full_url.clone(),
+            (None, None, None) => resource_type.to_string(),
+        }
+    }
+}
+
+/// Why a reference could not be resolved.
+#[derive(Debug, PartialEq, Clone)]
+pub enum DanglingReason {
+    /// The reference cannot be parsed or names another resource type.
+    Malformed(String),
+    /// Nothing in the store matches the reference or its identifiers.
+    NotFound,
+}
+
+/// A reference in the store that does not resolve to a resource in it.
+#[derive(Debug, PartialEq, Clone)]
+pub struct DanglingReference {
+    /// The resource holding the reference, as `Type/id`.
+    pub source: String,
+    /// The declared target type of the reference.
+    pub target_type: &'static str,
+    pub reference: Option<String>,
+    pub identifier: Vec<Identifier>,
+    pub reason: DanglingReason,
+}
+
+/// An in-memory store of resources of any type, indexed by type and id, by
+/// full url and by identifier, that resolves references between them.
+///
+/// Contained resources are indexed under their container and are only found
+/// through `#id` references from that container.
+#[derive(Default)]
+pub struct ResourceStore {
+    base: Option<String>,
+    entries: Vec<StoredEntry>,
+    by_id: HashMap<&'static str, HashMap<String, usize>>,
+    by_full_url: HashMap<String, usize>,
+    by_identifier: HashMap<(String, String), Vec<usize>>,
+    contained: HashMap<(usize, String), usize>,
+}
+
+impl ResourceStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the base url of the server the resources belong to, absolute
+    /// references into it are resolved like relative ones.
+    pub fn with_base(mut self, base: impl Into<String>) -> Self {
+        self.base = Some(base.into());
+        self
+    }
+
+    /// Adds a resource, failing when a resource of the same type and id is
+    /// already stored.
+    pub fn insert<T: StoredResource>(&mut self, resource: T) -> Result<(), FhirError> {
+        self.insert_root(Box::new(resource), None)
+    }
+
+    /// Adds a resource under the url it is known by, such as the `fullUrl` of
+    /// a Bundle entry, which may be a `urn:uuid:`.
+    pub fn insert_with_full_url<T: StoredResource>(
+        &mut self,
+        full_url: impl Into<String>,
+        resource: T,
+    ) -> Result<(), FhirError> {
+        self.insert_root(Box::new(resource), Some(full_url.into()))
+    }
+
+    /// Adds a resource from its JSON form, dispatching on `resourceType`.
+    pub fn insert_json(&mut self, json: Value) -> Result<(), FhirError> {
+        self.insert_root(entry_from_json(json)?, None)
+    }
+
+    /// Inserts a resource with its contained resources, leaving the store as
+    /// it was when any of them fails.
+    fn insert_root(
+        &mut self,
+        resource: Box<dyn Entry>,
+        full_url: Option<String>,
+    ) -> Result<(), FhirError> {
+        let start = self.entries.len();
+        self.insert_entry(resource, full_url, None)
+            .map(|_| ())
+            .inspect_err(|_| self.truncate(start))
+    }
+
+    /// Drops the entries from `start` on and every index entry pointing at
+    /// them.
+    fn truncate(&mut self, start: usize) {
+        self.entries.truncate(start);
+        for ids in self.by_id.values_mut() {
+            ids.retain(|_, index| *index < start);
+        }
+        self.by_id.retain(|_, ids| !ids.is_empty());
+        self.by_full_url.retain(|_, index| *index < start);
+        for indices in self.by_identifier.values_mut() {
+            indices.retain(|index| *index < start);
+        }
+        self.by_identifier.retain(|_, indices| !indices.is_empty());
+        self.contained
+            .retain(|(container, _), index| *container < start && *index < start);
+    }
+
+    fn insert_entry(
+        &mut self,
+        resource: Box<dyn Entry>,
+        full_url: Option<String>,
+        container: Option<usize>,
+    ) -> Result<usize, FhirError> {
+        let json = resource.to_json()?;
+        let contained = json
+            .get("contained")
+            .and_then(Value::as_array)
+            .cloned()
+            .unwrap_or_default()
+            .into_iter()
+            .map(entry_from_json)
+            .collect::<Result<Vec<_>, _>>()?;
+        let resource_type = resource.resource_type();
+        let id = resource.id().map(String::from);
+        let index = self.entries.len();
+        match (container, &id) {
+            (Some(container), Some(id)) => {
+                if self.contained.contains_key(&(container, id.clone())) {
+                    return Err(store_error(format!(
+                        "duplicate contained resource #{id} in {}",
+                        self.entries[container].label(&self.entries)
+                    )));
+                }
+                self.contained.insert((container, id.clone()), index);
+            }
+            (Some(container), None) => {
+                return Err(store_error(format!(
+                    "contained {resource_type} in {} has no id",
+                    self.entries[container].label(&self.entries)
+                )));
+            }
+            (None, id) => {
+                if let Some(id) = id {
+                    if self
+                        .by_id
+                        .get(resource_type)
+                        .is_some_and(|ids| ids.contains_key(id))
+                    {
+                        return Err(store_error(format!("duplicate {resource_type}/{id}")));
+                    }
+                    self.by_id
+                        .entry(resource_type)
+                        .or_default()
+                        .insert(id.clone(), index);
+                }
+                if let Some(full_url) = &full_url {
+                    if self.by_full_url.contains_key(full_url) {
+                        return Err(store_error(format!("duplicate full url {full_url}")));
+                    }
+                    self.by_full_url.insert(full_url.clone(), index);
+                }
+                for identifier in resource.identifiers() {
+                    if let Some(key) = identifier_key(identifier) {
+                        self.by_identifier.entry(key).or_default().push(index);
+                    }
+                }
+            }
+        }
+
+        self.entries.push(StoredEntry {
+            resource,
+            json,
+            full_url,
+            container,
+        });
+        for contained in contained {
+            self.insert_entry(contained, None, Some(index))?;
+        }
+        Ok(index)
+    }
+
+    /// The number of resources in the store, contained resources excluded.
+    pub fn len(&self) -> usize {
+        self.entries
+            .iter()
+            .filter(|entry| entry.container.is_none())
+            .count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn downcast<T: StoredResource>(&self, index: usize) -> Option<&T> {
+        self.entries[index].resource.as_any().downcast_ref::<T>()
+    }
+
+    pub fn get<T: StoredResource>(&self, id: &str) -> Option<&T> {
+        let index = *self.by_id.get(T::TYPE)?.get(id)?;
+        self.downcast(index)
+    }
+
+    /// All stored resources of type `T`, in insertion order.
+    pub fn resources<T: StoredResource>(&self) -> impl Iterator<Item = &T> {
+        self.entries
+            .iter()
+            .filter(|entry| entry.container.is_none())
+            .filter_map(|entry| entry.resource.as_any().downcast_ref::<T>())
+    }
+
+    /// The resources of type `T` with the identifier, the system is matched
+    /// in its normalized form so an OID and its `urn:oid:` are the same.
+    pub fn find_by_identifier<T: StoredResource>(&self, system: &str, value: &str) -> Vec<&T> {
+        self.by_identifier
+            .get(&(normalize_system(system), value.to_string()))
+            .into_iter()
+            .flatten()
+            .filter_map(|index| self.downcast(*index))
+            .collect()
+    }
+
+    /// Resolves a reference by relative id, absolute url or full url, falling
+    /// back to its logical identifiers. Contained references need the
+    /// container, see [`ResourceStore::resolve_in`].
+    pub fn resolve<T: StoredResource>(&self, reference: &Reference<T>) -> Option<&T> {
+        let index = self
+            .locate(reference.view(), reference.target(), None)
+            .ok()??;
+        self.downcast(index)
+    }
+
+    /// Resolves a reference found in `container`, which must be in the store.
+    pub fn resolve_in<T: StoredResource, C: ReferenceTarget>(
+        &self,
+        container: &C,
+        reference: &Reference<T>,
+    ) -> Option<&T> {
+        let container = *self.by_id.get(C::TYPE)?.get(container.resource_id()?)?;
+        let index = self
+            .locate(reference.view(), reference.target(), Some(container))
+            .ok()??;
+        self.downcast(index)
+    }
+
+    fn locate(
+        &self,
+        view: ReferenceView,
+        target: Result<Option<ParsedReference>, FhirError>,
+        container: Option<usize>,
+    ) -> Result<Option<usize>, FhirError> {
+        let parsed = target?;
+        let index = match (parsed, view.reference) {
+            (Some(parsed), Some(raw)) => self.locate_parsed(parsed, raw, container),
+            _ => None,
+        };
+        let index = index.or_else(|| {
+            view.identifier.iter().find_map(|identifier| {
+                self.by_identifier
+                    .get(&identifier_key(identifier)?)?
+                    .iter()
+                    .copied()
+                    .find(|index| self.entries[*index].resource.resource_type() == view.target_type)
+            })
+        });
+        Ok(index.filter(|index| self.entries[*index].resource.resource_type() == view.target_type))
+    }
+
+    fn locate_parsed(
+        &self,
+        parsed: ParsedReference,
+        raw: &str,
+        container: Option<usize>,
+    ) -> Option<usize> {
+        let by_id = |resource_type: &str, id: &str| self.by_id.get(resource_type)?.get(id).copied();
+        match parsed {
+            ParsedReference::Relative {
+                resource_type, id, ..
+            } => by_id(resource_type, id),
+            ParsedReference::Absolute {
+                base,
+                resource_type,
+                id,
+                ..
+            } => self.by_full_url.get(raw).copied().or_else(|| {
+                self.base
+                    .as_deref()
+                    .filter(|own_base| same_base(own_base, base))
+                    .and_then(|_| by_id(resource_type, id))
+            }),
+            ParsedReference::Url(_) | ParsedReference::Uuid(_) | ParsedReference::Oid(_) => {
+                self.by_full_url.get(raw).copied()
+            }
+            ParsedReference::Contained("") => container,
+            ParsedReference::Contained(id) => {
+                self.contained.get(&(container?, id.to_string())).copied()
+            }
+        }
+    }
+
+    /// Every reference held by a stored resource, contained ones included,
+    /// that does not resolve within the store. References that only carry a
+    /// display are not reported.
+    pub fn dangling_references(&self) -> Vec<DanglingReference> {
+        let mut dangling = Vec::new();
+        for (index, entry) in self.entries.iter().enumerate() {
+            let container = entry.container.or(Some(index));
+            for reference in entry.resource.references() {
+                let view = reference.view();
+                if view.reference.is_none() && view.identifier.is_empty() {
+                    continue;
+                }
+                let reason = match self.locate(view, reference.target(), container) {
+                    Ok(Some(_)) => continue,
+                    Ok(None) => DanglingReason::NotFound,
+                    Err(FhirError::ReferenceError(message)) => DanglingReason::Malformed(message),
+                    Err(error) => DanglingReason::Malformed(error.to_string()),
+                };
+                dangling.push(DanglingReference {
+                    source: entry.label(&self.entries),
+                    target_type: view.target_type,
+                    reference: view.reference.map(String::from),
+                    identifier: view.identifier.to_vec(),
+                    reason,
+                });
+            }
+        }
+        dangling
+    }
+}
+
+fn identifier_key(identifier: &Identifier) -> Option<(String, String)> {
+    let value = identifier.value.clone()?;
+    let system = identifier
+        .system
+        .as_deref()
+        .map(normalize_system)
+        .unwrap_or_default();
+    Some((system, value))
+}
+
+/// Resolves FHIRPath `resolve()` against the stored resources.
+impl ReferenceResolver for ResourceStore {
+    fn resolve(&self, reference: &str) -> Option<&Value> {
+        let parsed = ParsedReference::parse(reference).ok()?;
+        let index = self.locate_parsed(parsed, reference, None)?;
+        Some(&self.entries[index].json)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::json;
+
+    use crate::r4::{
+        elements::{IdentifierBuilder, ReferenceBuilder},
+        fhirpath::{EvaluationContext, FhirPath, FhirPathValue},
+        resources::{EndpointBuilder, OrganizationBuilder},
+    };
+
+    use super::*;
+
+    fn fixture_store() -> ResourceStore {
+        let mut store = ResourceStore::new();
+        for fixture in [
+            include_str!("../../../fixtures/r4/resources/organization.json"),
+            include_str!("../../../fixtures/r4/resources/location.json"),
+            include_str!("../../../fixtures/r4/resources/endpoint.json"),
+            include_str!("../../../fixtures/r4/resources/practitioner.json"),
+            include_str!("../../../fixtures/r4/resources/practitioner_role.json"),
+            include_str!("../../../fixtures/r4/resources/healthcare_service.json"),
+            include_str!("../../../fixtures/r4/resources/organization_affiliation.json"),
+        ] {
+            store
+                .insert_json(serde_json::from_str(fixture).unwrap())
+                .unwrap();
+        }
+        store
+    }
+
+    #[test]
+    fn test_insert_fixtures_should_index_by_type_and_id() {
+        let store = fixture_store();
+
+        assert_eq!(7, store.len());
+        assert!(store.get::<Organization>("org-1").is_some());
+        assert!(store.get::<Location>("org-1").is_none());
+        assert_eq!(1, store.resources::<PractitionerRole>().count());
+
+        let role = store
+            .get::<PractitionerRole>("practitioner-role-1")
+            .unwrap();
+        let organization = store.resolve(role.organization.as_ref().unwrap()).unwrap();
+        assert_eq!(Some("org-1"), organization.resource_id());
+    }
+
+    #[test]
+    fn test_dangling_references_should_report_unresolved_targets() {
+        let store = fixture_store();
+
+        let dangling: Vec<(String, Option<String>)> = store
+            .dangling_references()
+            .into_iter()
+            .map(|dangling| (dangling.source, dangling.reference))
+            .collect();
+
+        let expected: Vec<(String, Option<String>)> = [
+            ("Organization/org-1", "Organization/parent-org"),
+            ("Organization/org-1", "Endpoint/endpoint-2"),
+            ("Location/location-1", "Organization/1"),
+            ("Location/location-1", "Location/parent"),
+            ("Location/location-1", "Endpoint/1"),
+            (
+                "Practitioner/practitioner-1",
+                "Organization/org-licensing-board",
+            ),
+            (
+                "HealthcareService/healthcare-service-1",
+                "Location/region-1",
+            ),
+            (
+                "OrganizationAffiliation/org-affiliation-1",
+                "Organization/org-2",
+            ),
+            (
+                "OrganizationAffiliation/org-affiliation-1",
+                "Organization/org-network-1",
+            ),
+        ]
+        .into_iter()
+        .map(|(source, reference)| (source.to_string(), Some(reference.to_string())))
+        .collect();
+        let mut dangling_sorted = dangling.clone();
+        dangling_sorted.sort();
+        let mut expected_sorted = expected.clone();
+        expected_sorted.sort();
+        assert_eq!(expected_sorted, dangling_sorted);
+    }
+
+    #[test]
+    fn test_resolve_should_support_every_reference_form() {
+        let mut store = ResourceStore::new().with_base("https://directory.example/fhir");
+        store
+            .insert(
+                OrganizationBuilder::new("org-1")
+                    .name("Acme")
+                    .add_identifier(
+                        IdentifierBuilder::default()
+                            .with_system("2.16.528.1.1007.3.3")
+                            .with_value("01234567")
+                            .build(),
+                    )
+                    .build(),
+            )
+            .unwrap();
+        store
+            .insert_with_full_url(
+                "urn:uuid:9d3e5b2a-3c1f-4a8e-9b6d-0f1e2d3c4b5a",
+                OrganizationBuilder::default().name("No id").build(),
+            )
+            .unwrap();
+        store
+            .insert_json(json!({
+                "resourceType": "Location",
+                "id": "loc-1",
+                "contained": [{
+                    "resourceType": "Endpoint",
+                    "id": "ep",
+                    "status": "active",
+                    "connectionType": {"code": "hl7-fhir-rest"},
+                    "payloadType": [{"text": "any"}],
+                    "address": "https://ep.example"
+                }],
+                "endpoint": {"reference": "#ep"}
+            }))
+            .unwrap();
+        let reference = |value: &str| {
+            ReferenceBuilder::default()
+                .with_reference(value)
+                .build::<Organization>()
+        };
+
+        let name = |organization: Option<&Organization>| organization.and_then(|o| o.name.clone());
+        assert_eq!(
+            Some("Acme".to_string()),
+            name(store.resolve(&reference("Organization/org-1/_history/2")))
+        );
+        assert_eq!(
+            Some("Acme".to_string()),
+            name(store.resolve(&reference(
+                "https://directory.example/fhir/Organization/org-1"
+            )))
+        );
+        assert_eq!(
+            None,
+            name(store.resolve(&reference("https://elsewhere.example/Organization/org-1")))
+        );
+        assert_eq!(
+            Some("No id".to_string()),
+            name(store.resolve(&reference("urn:uuid:9d3e5b2a-3c1f-4a8e-9b6d-0f1e2d3c4b5a")))
+        );
+        let logical = ReferenceBuilder::default()
+            .add_identfier(
+                IdentifierBuilder::default()
+                    .with_system("urn:oid:2.16.528.1.1007.3.3")
+                    .with_value("01234567")
+                    .build(),
+            )
+            .build::<Organization>();
+        assert_eq!(Some("Acme".to_string()), name(store.resolve(&logical)));
+        assert_eq!(
+            1,
+            store
+                .find_by_identifier::<Organization>("2.16.528.1.1007.3.3", "01234567")
+                .len()
+        );
+
+        let location = store.get::<Location>("loc-1").unwrap();
+        let endpoint_reference = location.endpoint.as_ref().unwrap();
+        assert!(store.resolve(endpoint_reference).is_none());
+        assert_eq!(
+            "https://ep.example",
+            store
+                .resolve_in(location, endpoint_reference)
+                .unwrap()
+                .address
+        );
+        assert!(store.dangling_references().is_empty());
+    }
+
+    #[test]
+    fn test_insert_duplicate_should_fail() {
+        let mut store = ResourceStore::new();
+        store.insert(EndpointBuilder::new("ep-1").build()).unwrap();
+
+        let error = store
+            .insert(EndpointBuilder::new("ep-1").build())
+            .unwrap_err();
+
+        assert_eq!(
+            "resource store error: duplicate Endpoint/ep-1",
+            error.to_string()
+        );
+        assert!(
+            store
+                .insert_json(json!({"resourceType": "Patient", "id": "p"}))
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_failed_insert_should_leave_store_unchanged() {
+        let mut store = ResourceStore::new();
+        store
+            .insert_with_full_url("urn:uuid:1", EndpointBuilder::new("ep-1").build())
+            .unwrap();
+        let organization = |contained: Value| {
+            json!({
+                "resourceType": "Organization",
+                "id": "org-1",
+                "identifier": [{"system": "http://example.org/orgs", "value": "1"}],
+                "contained": contained
+            })
+        };
+        let duplicate = organization(json!([
+            {"resourceType": "Location", "id": "loc-1"},
+            {"resourceType": "Location", "id": "loc-1"}
+        ]));
+        let without_id = organization(json!([
+            {"resourceType": "Location", "id": "loc-1"},
+            {"resourceType": "Location"}
+        ]));
+
+        assert!(store.insert_json(duplicate).is_err());
+        assert!(store.insert_json(without_id).is_err());
+        assert!(
+            store
+                .insert_with_full_url("urn:uuid:1", EndpointBuilder::new("ep-2").build())
+                .is_err()
+        );
+
+        assert_eq!(1, store.len());
+        assert_eq!(1, store.entries.len());
+        assert!(store.get::<Organization>("org-1").is_none());
+        assert!(store.get::<Endpoint>("ep-2").is_none());
+        assert!(
+            store
+                .find_by_identifier::<Organization>("http://example.org/orgs", "1")
+                .is_empty()
+        );
+        assert_eq!(1, store.by_full_url.len());
+        assert!(store.contained.is_empty());
+        store
+            .insert_json(organization(json!([
+                {"resourceType": "Location", "id": "loc-1"}
+            ])))
+            .unwrap();
+        store
+            .insert_with_full_url("urn:uuid:2", EndpointBuilder::new("ep-2").build())
+            .unwrap();
+        assert_eq!(3, store.len());
+    }
+
+    #[test]
+    fn test_store_should_resolve_fhirpath_references() {
+        let store = fixture_store();
+        let role =
+            serde_json::to_value(store.get::<PractitionerRole>("practitioner-role-1")).unwrap();
+        let path = FhirPath::parse("PractitionerRole.organization.resolve().id").unwrap();
+        let context = EvaluationContext::new(&role).with_resolver(&store);
+
+        let result = path.evaluate_in(&context, &role).unwrap();
+
+        assert_eq!(vec![FhirPathValue::String("org-1".into())], result);
+    }
+}
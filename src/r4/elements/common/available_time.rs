@@ -2,13 +2,10 @@ use serde::{Deserialize, Serialize};
 
 use crate::{
     FhirError,
-    r4::{
-        elements::BackboneElement,
-        resources::ResourceType,
-    },
+    r4::{elements::BackboneElement, resources::ResourceType},
 };
 
-#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Hash, Clone, Copy)]
 #[serde(rename_all(deserialize = "kebab-case", serialize = "lowercase"))]
 pub enum DaysOfWeek {
     Mon,
@@ -20,15 +17,59 @@ pub enum DaysOfWeek {
     Sun,
 }
 
+impl DaysOfWeek {
+    /// Every day, starting on Monday.
+    pub const ALL: [DaysOfWeek; 7] = [
+        DaysOfWeek::Mon,
+        DaysOfWeek::Tue,
+        DaysOfWeek::Wed,
+        DaysOfWeek::Thu,
+        DaysOfWeek::Fri,
+        DaysOfWeek::Sat,
+        DaysOfWeek::Sun,
+    ];
+
+    /// 0 for Monday to 6 for Sunday.
+    pub fn index(self) -> usize {
+        self as usize
+    }
+
+    /// The day `days` since 1970-01-01, which was a Thursday.
+    pub fn from_epoch_days(days: i64) -> Self {
+        Self::ALL[(days + 3).rem_euclid(7) as usize]
+    }
+
+    /// The FHIR code, e.g. `mon`.
+    pub fn code(self) -> &'static str {
+        match self {
+            DaysOfWeek::Mon => "mon",
+            DaysOfWeek::Tue => "tue",
+            DaysOfWeek::Wed => "wed",
+            DaysOfWeek::Thu => "thu",
+            DaysOfWeek::Fri => "fri",
+            DaysOfWeek::Sat => "sat",
+            DaysOfWeek::Sun => "sun",
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Default)]
-#[serde(rename_all(serialize = "snake_case", deserialize = "camelCase"))]
+#[serde(rename_all(serialize = "camelCase", deserialize = "camelCase"))]
 pub struct AvailableTime {
     #[serde(flatten)]
     pub backbone_element: BackboneElement,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub days_of_week: Option<Vec<DaysOfWeek>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub all_day: Option<bool>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub available_start_time: Option<String>, // to be addressed later
-    pub available_end_time: Option<String>,   // to be addressed later
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub available_end_time: Option<String>, // to be addressed later
 }
 
 impl ResourceType for AvailableTime {
@@ -94,6 +135,10 @@ impl AvailableTimeBuilder {
             available_end_time: self.available_end_time,
         }
     }
+
+    pub fn try_build(self) -> Result<AvailableTime, FhirError> {
+        Ok(self.build())
+    }
 }
 
 #[cfg(test)]
@@ -13,6 +13,8 @@ pub struct NotAvailable {
     #[serde(flatten)]
     pub backbone_element: BackboneElement,
     pub description: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub during: Option<Period>,
 }
 
@@ -57,6 +59,14 @@ impl NotAvailableBuilder {
             during: self.during,
         }
     }
+
+    pub fn try_build(self) -> Result<NotAvailable, FhirError> {
+        FhirError::require(
+            "NotAvailable",
+            &[("description", !self.description.trim().is_empty())],
+        )?;
+        Ok(self.build())
+    }
 }
 
 #[cfg(test)]
@@ -132,4 +142,14 @@ mod test {
 
         assert_eq!(expected, actual)
     }
+
+    #[test]
+    fn test_try_build_should_require_description() {
+        let error = NotAvailableBuilder::new("nv-1").try_build().unwrap_err();
+
+        assert_eq!(
+            "NotAvailable is missing mandatory elements: description",
+            error.to_string()
+        );
+    }
 }
@@ -1,4 +1,7 @@
-use crate::r4::elements::{Address, CodeableConcept, Element, Period};
+use crate::{
+    FhirError,
+    r4::elements::{Address, CodeableConcept, Element, Period},
+};
 
 #[derive(Default)]
 pub struct AddressBuilder {
@@ -100,6 +103,10 @@ impl AddressBuilder {
             period: self.period,
         }
     }
+
+    pub fn try_build(self) -> Result<Address, FhirError> {
+        Ok(self.build())
+    }
 }
 
 #[cfg(test)]
@@ -3,13 +3,13 @@ use serde::{Deserialize, Serialize};
 use crate::{
     FhirError,
     r4::{
-        elements::{CodeableConcept, Element, Period},
+        elements::{CodeableConcept, Element, GetCodeableConceptsMut, Period},
         resources::ResourceType,
     },
 };
 
 #[derive(Debug, PartialEq, Serialize, Deserialize, Clone, Default)]
-#[serde(rename_all(serialize = "snake_case", deserialize = "camelCase"))]
+#[serde(rename_all(serialize = "camelCase", deserialize = "camelCase"))]
 pub struct Address {
     #[serde(flatten)]
     pub element: Element,
@@ -49,6 +49,12 @@ impl ResourceType for Address {
     const TYPE: &'static str = "Address";
 }
 
+impl GetCodeableConceptsMut for Address {
+    fn get_codeable_concepts_mut(&mut self) -> Vec<&mut CodeableConcept> {
+        self.r#type.iter_mut().collect()
+    }
+}
+
 impl Address {
     pub fn from_json(data: &str) -> Result<Self, FhirError> {
         Ok(serde_json::from_str(data)?)
@@ -1,4 +1,7 @@
-use crate::r4::elements::{Attachment, Element};
+use crate::{
+    FhirError,
+    r4::elements::{Attachment, Element, encode_base64},
+};
 
 #[derive(Default)]
 pub struct AttachmentBuilder {
@@ -40,6 +43,11 @@ impl AttachmentBuilder {
         self
     }
 
+    /// Sets `data` to `bytes` encoded as base64.
+    pub fn data_bytes(self, bytes: &[u8]) -> Self {
+        self.data(encode_base64(bytes))
+    }
+
     pub fn url(mut self, url: impl Into<String>) -> Self {
         self.url = Some(url.into());
         self
@@ -78,6 +86,10 @@ impl AttachmentBuilder {
             creation: self.creation,
         }
     }
+
+    pub fn try_build(self) -> Result<Attachment, FhirError> {
+        Ok(self.build())
+    }
 }
 
 #[cfg(test)]
@@ -51,6 +51,68 @@ impl Attachment {
     pub fn to_json_string(&self) -> Result<String, FhirError> {
         Ok(serde_json::to_string_pretty(self)?)
     }
+
+    /// Decodes the base64 `data`, returns `Ok(None)` when there is none.
+    pub fn decode_data(&self) -> Result<Option<Vec<u8>>, FhirError> {
+        self.data.as_deref().map(decode_base64).transpose()
+    }
+}
+
+const BASE64_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+pub(crate) fn encode_base64(bytes: &[u8]) -> String {
+    let mut encoded = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let group = chunk.iter().enumerate().fold(0u32, |group, (index, byte)| {
+            group | u32::from(*byte) << (16 - 8 * index)
+        });
+        for index in 0..4 {
+            if index <= chunk.len() {
+                let sextet = (group >> (18 - 6 * index)) & 0x3f;
+                encoded.push(char::from(BASE64_ALPHABET[sextet as usize]));
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
+
+/// Decodes padded or unpadded base64, ignoring whitespace.
+fn decode_base64(data: &str) -> Result<Vec<u8>, FhirError> {
+    let mut decoded = Vec::with_capacity(data.len() / 4 * 3);
+    let (mut group, mut bits) = (0u32, 0);
+    let mut padding = false;
+    for character in data.bytes().filter(|byte| !byte.is_ascii_whitespace()) {
+        if character == b'=' {
+            padding = true;
+            continue;
+        }
+        let Some(sextet) = BASE64_ALPHABET
+            .iter()
+            .position(|candidate| *candidate == character)
+            .filter(|_| !padding)
+        else {
+            return Err(FhirError::AttachmentError(format!(
+                "data is not valid base64 at '{}'",
+                char::from(character)
+            )));
+        };
+        group = group << 6 | sextet as u32;
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            decoded.push((group >> bits) as u8);
+            group &= (1 << bits) - 1;
+        }
+    }
+    if bits >= 6 {
+        return Err(FhirError::AttachmentError(
+            "data is truncated base64".to_string(),
+        ));
+    }
+    Ok(decoded)
 }
 
 #[cfg(test)]
@@ -59,6 +121,50 @@ mod test {
     use crate::r4::elements::AttachmentBuilder;
     use serde_json::json;
 
+    #[test]
+    fn test_decode_data_should_reverse_encoding() {
+        for text in ["", "f", "fo", "foo", "foob", "fooba", "foobar"] {
+            let encoded = encode_base64(text.as_bytes());
+            let attachment = AttachmentBuilder::default().data(encoded).build();
+
+            assert_eq!(
+                Some(text.as_bytes().to_vec()),
+                attachment.decode_data().unwrap()
+            );
+        }
+        assert_eq!("Zm9vYmE=", encode_base64(b"fooba"));
+
+        let wrapped = AttachmentBuilder::default().data("Zm9v\nYmFy").build();
+        assert_eq!(Some(b"foobar".to_vec()), wrapped.decode_data().unwrap());
+        let invalid = AttachmentBuilder::default().data("Zm9v!").build();
+        assert!(invalid.decode_data().is_err());
+        assert_eq!(None, Attachment::default().decode_data().unwrap());
+    }
+
+    #[test]
+    fn test_decode_base64_should_accept_padded_and_unpadded_data() {
+        assert_eq!(b"f".to_vec(), decode_base64("Zg==").unwrap());
+        assert_eq!(b"f".to_vec(), decode_base64("Zg").unwrap());
+        assert_eq!(b"fo".to_vec(), decode_base64("Zm8=").unwrap());
+        assert_eq!(b"fo".to_vec(), decode_base64("Zm8").unwrap());
+        assert_eq!(b"foo".to_vec(), decode_base64("Zm9v").unwrap());
+        assert_eq!(b"foo".to_vec(), decode_base64(" Zm\r\n9v\t").unwrap());
+        assert_eq!(Vec::<u8>::new(), decode_base64("").unwrap());
+
+        let bytes: Vec<u8> = (0..=255).collect();
+        assert_eq!(bytes, decode_base64(&encode_base64(&bytes)).unwrap());
+    }
+
+    #[test]
+    fn test_decode_base64_should_fail_on_invalid_data() {
+        for data in ["Zm9v!", "Zm9v-_", "Zg=a", "Z=g=", "Zm9v\u{e9}"] {
+            assert!(decode_base64(data).is_err(), "{data}");
+        }
+        for data in ["Z", "Zm9vY", "Zm9vY==="] {
+            assert!(decode_base64(data).is_err(), "{data}");
+        }
+    }
+
     #[test]
     fn test_from_json_should_succeed() {
         let data = json!({
@@ -1,4 +1,7 @@
-use crate::r4::elements::{CodeableConcept, Coding, Element};
+use crate::{
+    FhirError,
+    r4::elements::{CodeableConcept, Coding, Element},
+};
 
 #[derive(Default)]
 pub struct CodeableConceptBuilder {
@@ -44,4 +47,8 @@ impl CodeableConceptBuilder {
             text: self.text,
         }
     }
+
+    pub fn try_build(self) -> Result<CodeableConcept, FhirError> {
+        Ok(self.build())
+    }
 }
@@ -38,6 +38,13 @@ impl CodeableConcept {
         Ok(serde_json::to_string_pretty(self)?)
     }
 }
+
+/// Mutable access to every `CodeableConcept` of a resource or element,
+/// nested elements and extensions included.
+pub trait GetCodeableConceptsMut {
+    fn get_codeable_concepts_mut(&mut self) -> Vec<&mut CodeableConcept>;
+}
+
 #[cfg(test)]
 mod test {
     use serde_json::json;
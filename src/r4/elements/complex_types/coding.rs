@@ -89,6 +89,10 @@ impl CodingBuilder {
             user_selected: self.user_selected,
         }
     }
+
+    pub fn try_build(self) -> Result<Coding, FhirError> {
+        Ok(self.build())
+    }
 }
 
 #[cfg(test)]
@@ -3,7 +3,7 @@ use serde::{Deserialize, Serialize};
 use crate::{
     FhirError,
     r4::{
-        elements::{Element, Period},
+        elements::{Effective, Element, Period},
         resources::ResourceType,
     },
 };
@@ -40,6 +40,16 @@ impl ContactPoint {
     }
 }
 
+impl Effective for ContactPoint {
+    fn period(&self) -> Option<&Period> {
+        self.period.as_ref()
+    }
+
+    fn is_active(&self) -> bool {
+        self.r#use.as_deref() != Some("old")
+    }
+}
+
 #[derive(Default)]
 pub struct ContactPointBuilder {
     element: Element,
@@ -96,6 +106,10 @@ impl ContactPointBuilder {
             period: self.period,
         }
     }
+
+    pub fn try_build(self) -> Result<ContactPoint, FhirError> {
+        Ok(self.build())
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,240 @@
+use std::collections::BTreeMap;
+
+use crate::{
+    FhirError,
+    r4::elements::{
+        BackboneElement, ElementDefinition, ElementDefinitionBase, ElementDefinitionBinding,
+        ElementDefinitionConstraint, ElementDefinitionSlicing, ElementDefinitionType,
+    },
+};
+
+#[derive(Default)]
+pub struct ElementDefinitionBuilder {
+    backbone_element: BackboneElement,
+    path: String,
+    slice_name: Option<String>,
+    slicing: Option<ElementDefinitionSlicing>,
+    short: Option<String>,
+    definition: Option<String>,
+    comment: Option<String>,
+    min: Option<u32>,
+    max: Option<String>,
+    base: Option<ElementDefinitionBase>,
+    r#type: Option<Vec<ElementDefinitionType>>,
+    max_length: Option<i64>,
+    constraint: Option<Vec<ElementDefinitionConstraint>>,
+    must_support: Option<bool>,
+    is_modifier: Option<bool>,
+    is_summary: Option<bool>,
+    binding: Option<ElementDefinitionBinding>,
+    choice_values: BTreeMap<String, serde_json::Value>,
+}
+
+impl ElementDefinitionBuilder {
+    pub fn new(id: impl Into<String>) -> Self {
+        let mut builder = Self::default();
+        builder.backbone_element.element.id = Some(id.into());
+        builder
+    }
+
+    pub fn with_id(mut self, id: impl Into<String>) -> Self {
+        self.backbone_element.element.id = Some(id.into());
+        self
+    }
+
+    pub fn with_path(mut self, path: impl Into<String>) -> Self {
+        self.path = path.into();
+        self
+    }
+
+    pub fn with_slice_name(mut self, slice_name: impl Into<String>) -> Self {
+        self.slice_name = Some(slice_name.into());
+        self
+    }
+
+    pub fn with_slicing(mut self, slicing: ElementDefinitionSlicing) -> Self {
+        self.slicing = Some(slicing);
+        self
+    }
+
+    pub fn with_short(mut self, short: impl Into<String>) -> Self {
+        self.short = Some(short.into());
+        self
+    }
+
+    pub fn with_definition(mut self, definition: impl Into<String>) -> Self {
+        self.definition = Some(definition.into());
+        self
+    }
+
+    pub fn with_comment(mut self, comment: impl Into<String>) -> Self {
+        self.comment = Some(comment.into());
+        self
+    }
+
+    pub fn with_min(mut self, min: u32) -> Self {
+        self.min = Some(min);
+        self
+    }
+
+    pub fn with_max(mut self, max: impl Into<String>) -> Self {
+        self.max = Some(max.into());
+        self
+    }
+
+    pub fn with_cardinality(self, min: u32, max: impl Into<String>) -> Self {
+        self.with_min(min).with_max(max)
+    }
+
+    pub fn with_base(mut self, base: ElementDefinitionBase) -> Self {
+        self.base = Some(base);
+        self
+    }
+
+    pub fn with_type(mut self, r#type: Vec<ElementDefinitionType>) -> Self {
+        self.r#type = Some(r#type);
+        self
+    }
+
+    pub fn add_type(self, code: impl Into<String>) -> Self {
+        self.add_element_type(ElementDefinitionType {
+            code: code.into(),
+            ..Default::default()
+        })
+    }
+
+    /// Adds a `Reference` type targeting the base definition of `resource_type`.
+    pub fn add_reference_type(self, resource_type: &str) -> Self {
+        self.add_element_type(ElementDefinitionType {
+            code: "Reference".to_string(),
+            target_profile: Some(vec![format!(
+                "http://hl7.org/fhir/StructureDefinition/{resource_type}"
+            )]),
+            ..Default::default()
+        })
+    }
+
+    pub fn add_element_type(mut self, r#type: ElementDefinitionType) -> Self {
+        match &mut self.r#type {
+            Some(t) => t.push(r#type),
+            None => self.r#type = Some(vec![r#type]),
+        }
+        self
+    }
+
+    pub fn with_max_length(mut self, max_length: i64) -> Self {
+        self.max_length = Some(max_length);
+        self
+    }
+
+    pub fn with_constraint(mut self, constraint: Vec<ElementDefinitionConstraint>) -> Self {
+        self.constraint = Some(constraint);
+        self
+    }
+
+    pub fn add_constraint(mut self, constraint: ElementDefinitionConstraint) -> Self {
+        match &mut self.constraint {
+            Some(c) => c.push(constraint),
+            None => self.constraint = Some(vec![constraint]),
+        }
+        self
+    }
+
+    pub fn with_must_support(mut self, must_support: bool) -> Self {
+        self.must_support = Some(must_support);
+        self
+    }
+
+    pub fn with_is_modifier(mut self, is_modifier: bool) -> Self {
+        self.is_modifier = Some(is_modifier);
+        self
+    }
+
+    pub fn with_is_summary(mut self, is_summary: bool) -> Self {
+        self.is_summary = Some(is_summary);
+        self
+    }
+
+    pub fn with_binding(mut self, binding: ElementDefinitionBinding) -> Self {
+        self.binding = Some(binding);
+        self
+    }
+
+    /// Sets a choice-typed value, e.g. `("fixed", "Uri", json!("http://..."))`.
+    pub fn with_choice_value(
+        mut self,
+        prefix: &str,
+        type_name: &str,
+        value: serde_json::Value,
+    ) -> Self {
+        self.choice_values
+            .insert(format!("{prefix}{type_name}"), value);
+        self
+    }
+
+    pub fn build(self) -> ElementDefinition {
+        ElementDefinition {
+            backbone_element: self.backbone_element,
+            path: self.path,
+            slice_name: self.slice_name,
+            slicing: self.slicing,
+            short: self.short,
+            definition: self.definition,
+            comment: self.comment,
+            min: self.min,
+            max: self.max,
+            base: self.base,
+            r#type: self.r#type,
+            max_length: self.max_length,
+            constraint: self.constraint,
+            must_support: self.must_support,
+            is_modifier: self.is_modifier,
+            is_summary: self.is_summary,
+            binding: self.binding,
+            choice_values: self.choice_values,
+            ..Default::default()
+        }
+    }
+
+    pub fn try_build(self) -> Result<ElementDefinition, FhirError> {
+        FhirError::require("ElementDefinition", &[("path", !self.path.is_empty())])?;
+        Ok(self.build())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_build_should_succeed() {
+        let expected = ElementDefinition {
+            backbone_element: BackboneElement {
+                element: crate::r4::elements::Element {
+                    id: Some("Organization.partOf".to_string()),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            path: "Organization.partOf".to_string(),
+            min: Some(0),
+            max: Some("1".to_string()),
+            r#type: Some(vec![ElementDefinitionType {
+                code: "Reference".to_string(),
+                target_profile: Some(vec![
+                    "http://hl7.org/fhir/StructureDefinition/Organization".to_string(),
+                ]),
+                ..Default::default()
+            }]),
+            ..Default::default()
+        };
+
+        let actual = ElementDefinitionBuilder::new("Organization.partOf")
+            .with_path("Organization.partOf")
+            .with_cardinality(0, "1")
+            .add_reference_type("Organization")
+            .build();
+
+        assert_eq!(expected, actual)
+    }
+}
@@ -0,0 +1,545 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Deserializer, Serialize};
+
+use crate::{
+    FhirError,
+    r4::{
+        elements::{BackboneElement, Coding, Element},
+        resources::ResourceType,
+    },
+};
+
+/// Prefixes of the choice-typed (`[x]`) properties of an `ElementDefinition`.
+pub const CHOICE_VALUE_PREFIXES: [&str; 5] =
+    ["defaultValue", "fixed", "pattern", "minValue", "maxValue"];
+
+/// Keys of the flattened `Element`/`BackboneElement` that serde also hands to
+/// a flattened map.
+const ELEMENT_KEYS: [&str; 3] = ["id", "extension", "modifierExtension"];
+
+/// Collects the properties left over by the modelled fields, without the
+/// `Element` keys that were already consumed.
+fn deserialize_remaining<'de, D>(
+    deserializer: D,
+) -> Result<BTreeMap<String, serde_json::Value>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let mut remaining = BTreeMap::<String, serde_json::Value>::deserialize(deserializer)?;
+    remaining.retain(|key, _| !ELEMENT_KEYS.contains(&key.as_str()));
+    Ok(remaining)
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum DiscriminatorType {
+    #[default]
+    Value,
+    Exists,
+    Pattern,
+    Type,
+    Profile,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Default)]
+#[serde(rename_all(serialize = "camelCase", deserialize = "camelCase"))]
+pub enum SlicingRules {
+    Closed,
+    #[default]
+    Open,
+    OpenAtEnd,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum ConstraintSeverity {
+    #[default]
+    Error,
+    Warning,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum BindingStrength {
+    Required,
+    Extensible,
+    Preferred,
+    #[default]
+    Example,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Default)]
+pub struct ElementDefinitionDiscriminator {
+    #[serde(flatten)]
+    pub element: Element,
+
+    pub r#type: DiscriminatorType,
+
+    pub path: String,
+}
+
+impl ResourceType for ElementDefinitionDiscriminator {
+    const TYPE: &'static str = "ElementDefinitionDiscriminator";
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Default)]
+pub struct ElementDefinitionSlicing {
+    #[serde(flatten)]
+    pub element: Element,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub discriminator: Option<Vec<ElementDefinitionDiscriminator>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ordered: Option<bool>,
+
+    pub rules: SlicingRules,
+}
+
+impl ResourceType for ElementDefinitionSlicing {
+    const TYPE: &'static str = "ElementDefinitionSlicing";
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Default)]
+pub struct ElementDefinitionBase {
+    #[serde(flatten)]
+    pub element: Element,
+
+    pub path: String,
+
+    pub min: u32,
+
+    pub max: String,
+}
+
+impl ResourceType for ElementDefinitionBase {
+    const TYPE: &'static str = "ElementDefinitionBase";
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Default)]
+#[serde(rename_all(serialize = "camelCase", deserialize = "camelCase"))]
+pub struct ElementDefinitionType {
+    #[serde(flatten)]
+    pub element: Element,
+
+    pub code: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub profile: Option<Vec<String>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub target_profile: Option<Vec<String>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub aggregation: Option<Vec<String>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub versioning: Option<String>,
+}
+
+impl ResourceType for ElementDefinitionType {
+    const TYPE: &'static str = "ElementDefinitionType";
+}
+
+impl ElementDefinitionType {
+    /// The resource type names allowed by `targetProfile`, for `Reference` types.
+    pub fn target_types(&self) -> Vec<&str> {
+        self.target_profile
+            .iter()
+            .flatten()
+            .filter_map(|p| p.rsplit('/').next())
+            .collect()
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Default)]
+pub struct ElementDefinitionExample {
+    #[serde(flatten)]
+    pub element: Element,
+
+    pub label: String,
+
+    /// The `value[x]` of the example keyed by its JSON property name.
+    #[serde(flatten, deserialize_with = "deserialize_remaining")]
+    pub value: BTreeMap<String, serde_json::Value>,
+}
+
+impl ResourceType for ElementDefinitionExample {
+    const TYPE: &'static str = "ElementDefinitionExample";
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Default)]
+#[serde(rename_all(serialize = "camelCase", deserialize = "camelCase"))]
+pub struct ElementDefinitionConstraint {
+    #[serde(flatten)]
+    pub element: Element,
+
+    pub key: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub requirements: Option<String>,
+
+    pub severity: ConstraintSeverity,
+
+    pub human: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expression: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub xpath: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+}
+
+impl ResourceType for ElementDefinitionConstraint {
+    const TYPE: &'static str = "ElementDefinitionConstraint";
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Default)]
+#[serde(rename_all(serialize = "camelCase", deserialize = "camelCase"))]
+pub struct ElementDefinitionBinding {
+    #[serde(flatten)]
+    pub element: Element,
+
+    pub strength: BindingStrength,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value_set: Option<String>,
+}
+
+impl ResourceType for ElementDefinitionBinding {
+    const TYPE: &'static str = "ElementDefinitionBinding";
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Default)]
+pub struct ElementDefinitionMapping {
+    #[serde(flatten)]
+    pub element: Element,
+
+    pub identity: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+
+    pub map: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub comment: Option<String>,
+}
+
+impl ResourceType for ElementDefinitionMapping {
+    const TYPE: &'static str = "ElementDefinitionMapping";
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Default)]
+#[serde(rename_all(serialize = "camelCase", deserialize = "camelCase"))]
+pub struct ElementDefinition {
+    #[serde(flatten)]
+    pub backbone_element: BackboneElement,
+
+    pub path: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub representation: Option<Vec<String>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub slice_name: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub slice_is_constraining: Option<bool>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub code: Option<Vec<Coding>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub slicing: Option<ElementDefinitionSlicing>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub short: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub definition: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub comment: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub requirements: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub alias: Option<Vec<String>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min: Option<u32>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub base: Option<ElementDefinitionBase>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content_reference: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub r#type: Option<Vec<ElementDefinitionType>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub meaning_when_missing: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub order_meaning: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub example: Option<Vec<ElementDefinitionExample>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_length: Option<i64>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub condition: Option<Vec<String>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub constraint: Option<Vec<ElementDefinitionConstraint>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub must_support: Option<bool>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub is_modifier: Option<bool>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub is_modifier_reason: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub is_summary: Option<bool>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub binding: Option<ElementDefinitionBinding>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mapping: Option<Vec<ElementDefinitionMapping>>,
+
+    /// The choice-typed properties (`fixed[x]`, `pattern[x]`, `defaultValue[x]`,
+    /// `minValue[x]`, `maxValue[x]`) keyed by their JSON name, e.g. `fixedUri`.
+    /// Properties that are not modelled above are kept here too, so that
+    /// definitions round-trip.
+    #[serde(flatten, deserialize_with = "deserialize_remaining")]
+    pub choice_values: BTreeMap<String, serde_json::Value>,
+}
+
+impl ResourceType for ElementDefinition {
+    const TYPE: &'static str = "ElementDefinition";
+}
+
+impl ElementDefinition {
+    pub fn from_json(data: &str) -> Result<Self, FhirError> {
+        Ok(serde_json::from_str(data)?)
+    }
+
+    pub fn to_json_value(&self) -> Result<serde_json::Value, FhirError> {
+        Ok(serde_json::to_value(self)?)
+    }
+
+    /// The element id, falling back to the path for definitions without ids.
+    pub fn element_id(&self) -> &str {
+        self.backbone_element
+            .element
+            .id
+            .as_deref()
+            .unwrap_or(&self.path)
+    }
+
+    /// The last segment of the path, e.g. `identifier` for `Organization.identifier`.
+    pub fn name(&self) -> &str {
+        self.path.rsplit('.').next().unwrap_or(&self.path)
+    }
+
+    pub fn fixed(&self) -> Option<(&str, &serde_json::Value)> {
+        self.choice_value("fixed")
+    }
+
+    pub fn pattern(&self) -> Option<(&str, &serde_json::Value)> {
+        self.choice_value("pattern")
+    }
+
+    pub fn default_value(&self) -> Option<(&str, &serde_json::Value)> {
+        self.choice_value("defaultValue")
+    }
+
+    /// Returns the type suffix and value of a choice property, e.g.
+    /// `("Uri", "http://...")` for `fixedUri`.
+    pub fn choice_value(&self, prefix: &str) -> Option<(&str, &serde_json::Value)> {
+        self.choice_values.iter().find_map(|(key, value)| {
+            key.strip_prefix(prefix)
+                .filter(|suffix| suffix.starts_with(|c: char| c.is_ascii_uppercase()))
+                .map(|suffix| (suffix, value))
+        })
+    }
+
+    /// Sets a choice property, replacing any other type for the same prefix.
+    pub fn set_choice_value(&mut self, prefix: &str, type_name: &str, value: serde_json::Value) {
+        self.choice_values.retain(|key, _| {
+            key.strip_prefix(prefix)
+                .is_none_or(|suffix| !suffix.starts_with(|c: char| c.is_ascii_uppercase()))
+        });
+        self.choice_values
+            .insert(format!("{prefix}{type_name}"), value);
+    }
+
+    /// The type codes allowed for this element.
+    pub fn type_codes(&self) -> Vec<&str> {
+        self.r#type
+            .iter()
+            .flatten()
+            .map(|t| t.code.as_str())
+            .collect()
+    }
+
+    /// `true` when `max` is `*` or greater than one.
+    pub fn is_repeating(&self) -> bool {
+        self.max
+            .as_deref()
+            .is_some_and(|max| max == "*" || max.parse::<u32>().is_ok_and(|m| m > 1))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::json;
+
+    use crate::r4::elements::ElementDefinitionBuilder;
+
+    use super::*;
+
+    #[test]
+    fn test_from_json_should_succeed() {
+        let data = r#"
+            {
+                "id": "Organization.identifier",
+                "path": "Organization.identifier",
+                "slicing": {
+                    "discriminator": [{"type": "value", "path": "system"}],
+                    "rules": "open"
+                },
+                "min": 1,
+                "max": "*",
+                "type": [{"code": "Identifier"}],
+                "patternIdentifier": {"system": "http://example.org/ids"},
+                "constraint": [
+                    {
+                        "key": "org-1",
+                        "severity": "error",
+                        "human": "The organization SHALL at least have a name or an identifier",
+                        "expression": "(identifier.count() + name.count()) > 0"
+                    }
+                ],
+                "binding": {
+                    "strength": "required",
+                    "valueSet": "http://example.org/vs"
+                }
+            }
+        "#;
+        let expected = ElementDefinitionBuilder::new("Organization.identifier")
+            .with_path("Organization.identifier")
+            .with_slicing(ElementDefinitionSlicing {
+                discriminator: Some(vec![ElementDefinitionDiscriminator {
+                    r#type: DiscriminatorType::Value,
+                    path: "system".to_string(),
+                    ..Default::default()
+                }]),
+                rules: SlicingRules::Open,
+                ..Default::default()
+            })
+            .with_cardinality(1, "*")
+            .add_type("Identifier")
+            .with_choice_value(
+                "pattern",
+                "Identifier",
+                json!({"system": "http://example.org/ids"}),
+            )
+            .add_constraint(ElementDefinitionConstraint {
+                key: "org-1".to_string(),
+                severity: ConstraintSeverity::Error,
+                human: "The organization SHALL at least have a name or an identifier".to_string(),
+                expression: Some("(identifier.count() + name.count()) > 0".to_string()),
+                ..Default::default()
+            })
+            .with_binding(ElementDefinitionBinding {
+                strength: BindingStrength::Required,
+                value_set: Some("http://example.org/vs".to_string()),
+                ..Default::default()
+            })
+            .build();
+
+        let actual = ElementDefinition::from_json(data).unwrap();
+
+        assert_eq!(expected, actual)
+    }
+
+    #[test]
+    fn test_to_json_value_should_succeed() {
+        let expected = json!({
+            "id": "Organization.type",
+            "path": "Organization.type",
+            "min": 0,
+            "max": "1",
+            "type": [{"code": "CodeableConcept"}],
+            "fixedCodeableConcept": {"text": "fixed"},
+            "mustSupport": true
+        });
+
+        let actual = ElementDefinitionBuilder::new("Organization.type")
+            .with_path("Organization.type")
+            .with_cardinality(0, "1")
+            .add_type("CodeableConcept")
+            .with_choice_value("fixed", "CodeableConcept", json!({"text": "fixed"}))
+            .with_must_support(true)
+            .build()
+            .to_json_value()
+            .unwrap();
+
+        assert_eq!(expected, actual)
+    }
+
+    #[test]
+    fn test_choice_value_should_succeed() {
+        let mut element = ElementDefinitionBuilder::new("Endpoint.status")
+            .with_path("Endpoint.status")
+            .with_choice_value("fixed", "Code", json!("active"))
+            .build();
+
+        assert_eq!(Some(("Code", &json!("active"))), element.fixed());
+        assert_eq!(None, element.pattern());
+
+        element.set_choice_value("fixed", "String", json!("test"));
+
+        assert_eq!(Some(("String", &json!("test"))), element.fixed());
+        assert_eq!(1, element.choice_values.len())
+    }
+
+    #[test]
+    fn test_target_types_should_succeed() {
+        let element_type = ElementDefinitionType {
+            code: "Reference".to_string(),
+            target_profile: Some(vec![
+                "http://hl7.org/fhir/StructureDefinition/Organization".to_string(),
+            ]),
+            ..Default::default()
+        };
+
+        assert_eq!(vec!["Organization"], element_type.target_types())
+    }
+}
@@ -0,0 +1,234 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    FhirError,
+    r4::{
+        elements::{Attachment, CodeableConcept, Coding, GetCodeableConceptsMut},
+        resources::ResourceType,
+    },
+};
+
+/// An extension with the commonly used `value[x]` types, other value types
+/// are not kept.
+#[derive(Debug, PartialEq, Serialize, Deserialize, Default, Clone)]
+#[serde(rename_all(serialize = "camelCase", deserialize = "camelCase"))]
+pub struct Extension {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub extension: Option<Vec<Extension>>,
+
+    pub url: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value_boolean: Option<bool>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value_integer: Option<i64>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value_decimal: Option<f64>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value_string: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value_code: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value_uri: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value_coding: Option<Coding>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value_codeable_concept: Option<CodeableConcept>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value_attachment: Option<Attachment>,
+}
+
+impl ResourceType for Extension {
+    const TYPE: &'static str = "Extension";
+}
+
+impl Extension {
+    pub fn from_json(data: &str) -> Result<Self, FhirError> {
+        Ok(serde_json::from_str(data)?)
+    }
+
+    /// The nested extensions with `url`.
+    pub fn extensions<'a>(&'a self, url: &'a str) -> impl Iterator<Item = &'a Extension> {
+        self.extension
+            .iter()
+            .flatten()
+            .filter(move |extension| extension.url == url)
+    }
+}
+
+impl GetCodeableConceptsMut for Extension {
+    fn get_codeable_concepts_mut(&mut self) -> Vec<&mut CodeableConcept> {
+        let mut concepts: Vec<&mut CodeableConcept> =
+            self.value_codeable_concept.iter_mut().collect();
+
+        for extension in self.extension.iter_mut().flatten() {
+            concepts.extend(extension.get_codeable_concepts_mut());
+        }
+
+        concepts
+    }
+}
+
+#[derive(Default)]
+pub struct ExtensionBuilder {
+    id: Option<String>,
+    extension: Option<Vec<Extension>>,
+    url: Option<String>,
+    value_boolean: Option<bool>,
+    value_integer: Option<i64>,
+    value_decimal: Option<f64>,
+    value_string: Option<String>,
+    value_code: Option<String>,
+    value_uri: Option<String>,
+    value_coding: Option<Coding>,
+    value_codeable_concept: Option<CodeableConcept>,
+    value_attachment: Option<Attachment>,
+}
+
+impl ExtensionBuilder {
+    pub fn new(id: impl Into<String>) -> Self {
+        ExtensionBuilder {
+            id: Some(id.into()),
+            ..Default::default()
+        }
+    }
+
+    pub fn with_url(mut self, url: impl Into<String>) -> Self {
+        self.url = Some(url.into());
+        self
+    }
+
+    pub fn add_extension(mut self, extension: Extension) -> Self {
+        self.extension.get_or_insert_with(Vec::new).push(extension);
+        self
+    }
+
+    pub fn with_value_boolean(mut self, value: bool) -> Self {
+        self.value_boolean = Some(value);
+        self
+    }
+
+    pub fn with_value_integer(mut self, value: i64) -> Self {
+        self.value_integer = Some(value);
+        self
+    }
+
+    pub fn with_value_decimal(mut self, value: f64) -> Self {
+        self.value_decimal = Some(value);
+        self
+    }
+
+    pub fn with_value_string(mut self, value: impl Into<String>) -> Self {
+        self.value_string = Some(value.into());
+        self
+    }
+
+    pub fn with_value_code(mut self, value: impl Into<String>) -> Self {
+        self.value_code = Some(value.into());
+        self
+    }
+
+    pub fn with_value_uri(mut self, value: impl Into<String>) -> Self {
+        self.value_uri = Some(value.into());
+        self
+    }
+
+    pub fn with_value_coding(mut self, value: Coding) -> Self {
+        self.value_coding = Some(value);
+        self
+    }
+
+    pub fn with_value_codeable_concept(mut self, value: CodeableConcept) -> Self {
+        self.value_codeable_concept = Some(value);
+        self
+    }
+
+    pub fn with_value_attachment(mut self, value: Attachment) -> Self {
+        self.value_attachment = Some(value);
+        self
+    }
+
+    pub fn build(self) -> Extension {
+        Extension {
+            id: self.id,
+            extension: self.extension,
+            url: self.url.unwrap_or_default(),
+            value_boolean: self.value_boolean,
+            value_integer: self.value_integer,
+            value_decimal: self.value_decimal,
+            value_string: self.value_string,
+            value_code: self.value_code,
+            value_uri: self.value_uri,
+            value_coding: self.value_coding,
+            value_codeable_concept: self.value_codeable_concept,
+            value_attachment: self.value_attachment,
+        }
+    }
+
+    pub fn try_build(self) -> Result<Extension, FhirError> {
+        FhirError::require(Extension::TYPE, &[("url", self.url.is_some())])?;
+        Ok(self.build())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::json;
+
+    use super::*;
+    use crate::r4::elements::AttachmentBuilder;
+
+    #[test]
+    fn test_from_json_should_succeed() {
+        let data = json!({
+            "url": "http://hl7.org/fhir/StructureDefinition/location-boundary-geojson",
+            "valueAttachment": {
+                "contentType": "application/geo+json",
+                "data": "e30="
+            }
+        })
+        .to_string();
+
+        let expected = ExtensionBuilder::default()
+            .with_url("http://hl7.org/fhir/StructureDefinition/location-boundary-geojson")
+            .with_value_attachment(
+                AttachmentBuilder::default()
+                    .content_type("application/geo+json")
+                    .data("e30=")
+                    .build(),
+            )
+            .build();
+
+        let actual = Extension::from_json(&data).unwrap();
+
+        assert_eq!(expected, actual);
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&data).unwrap(),
+            serde_json::to_value(&actual).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_try_build_should_require_url() {
+        let error = ExtensionBuilder::default()
+            .with_value_string("x")
+            .try_build()
+            .unwrap_err();
+
+        assert_eq!(
+            "Extension is missing mandatory elements: url",
+            error.to_string()
+        );
+    }
+}
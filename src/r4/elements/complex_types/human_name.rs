@@ -3,7 +3,7 @@ use serde::{Deserialize, Serialize};
 use crate::{
     FhirError,
     r4::{
-        elements::{Element, Period},
+        elements::{Effective, Element, Period},
         resources::ResourceType,
     },
 };
@@ -12,12 +12,26 @@ use crate::{
 pub struct HumanName {
     #[serde(flatten)]
     pub element: Element,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub r#use: Option<String>, // to be resolved
+
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub text: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub family: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub given: Option<Vec<String>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub prefix: Option<Vec<String>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub suffix: Option<Vec<String>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub period: Option<Period>,
 }
 
@@ -29,6 +43,33 @@ impl HumanName {
     pub fn from_json(data: &str) -> Result<Self, FhirError> {
         Ok(serde_json::from_str(data)?)
     }
+
+    /// Returns `text`, or the parts joined as prefix, given, family and suffix.
+    pub fn to_text(&self) -> Option<String> {
+        if let Some(text) = &self.text {
+            return Some(text.clone());
+        }
+        let parts: Vec<&str> = self
+            .prefix
+            .iter()
+            .flatten()
+            .chain(self.given.iter().flatten())
+            .chain(self.family.iter())
+            .chain(self.suffix.iter().flatten())
+            .map(String::as_str)
+            .collect();
+        (!parts.is_empty()).then(|| parts.join(" "))
+    }
+}
+
+impl Effective for HumanName {
+    fn period(&self) -> Option<&Period> {
+        self.period.as_ref()
+    }
+
+    fn is_active(&self) -> bool {
+        self.r#use.as_deref() != Some("old")
+    }
 }
 
 #[derive(Default)]
@@ -125,6 +166,10 @@ impl HumanNameBuilder {
             period: self.period,
         }
     }
+
+    pub fn try_build(self) -> Result<HumanName, FhirError> {
+        Ok(self.build())
+    }
 }
 
 #[cfg(test)]
@@ -3,7 +3,9 @@ use serde::{Deserialize, Serialize};
 use crate::{
     FhirError,
     r4::{
-        elements::{CodeableConcept, Element, Period, Reference},
+        elements::{
+            CodeableConcept, Effective, Element, GetCodeableConceptsMut, Period, Reference,
+        },
         resources::{Organization, ResourceType},
     },
 };
@@ -42,6 +44,26 @@ impl Identifier {
     }
 }
 
+impl Effective for Identifier {
+    fn period(&self) -> Option<&Period> {
+        self.period.as_ref()
+    }
+
+    fn is_active(&self) -> bool {
+        self.r#use.as_deref() != Some("old")
+    }
+}
+
+impl GetCodeableConceptsMut for Identifier {
+    fn get_codeable_concepts_mut(&mut self) -> Vec<&mut CodeableConcept> {
+        self.r#type.iter_mut().collect()
+    }
+}
+
+pub trait GetIdentifiers {
+    fn get_identifiers(&self) -> Vec<&Identifier>;
+}
+
 #[derive(Default)]
 pub struct IdentifierBuilder {
     element: Element,
@@ -107,4 +129,8 @@ impl IdentifierBuilder {
             assigner: self.assigner,
         }
     }
+
+    pub fn try_build(self) -> Result<Identifier, FhirError> {
+        Ok(self.build())
+    }
 }
@@ -3,7 +3,7 @@ use serde::{Deserialize, Serialize};
 use crate::{
     FhirError,
     r4::{
-        elements::Element,
+        elements::{Element, serialize_optional_decimal},
         resources::ResourceType,
     },
 };
@@ -13,7 +13,12 @@ use crate::{
 pub struct Money {
     #[serde(flatten)]
     pub element: Element,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(serialize_with = "serialize_optional_decimal")]
     pub value: Option<f32>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub currency: Option<String>, // to be resolved later
 }
 
@@ -63,6 +68,10 @@ impl MoneyBuilder {
             currency: self.currency,
         }
     }
+
+    pub fn try_build(self) -> Result<Money, FhirError> {
+        Ok(self.build())
+    }
 }
 
 #[cfg(test)]
@@ -1,9 +1,11 @@
+use std::{cmp::Ordering, time::Duration};
+
 use serde::{Deserialize, Serialize};
 
 use crate::{
     FhirError,
     r4::{
-        elements::Element,
+        elements::{Element, FhirDateTime, MAX_OFFSET_MILLIS},
         resources::ResourceType,
     },
 };
@@ -12,8 +14,12 @@ use crate::{
 pub struct Period {
     #[serde(flatten)]
     pub element: Element,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub start: Option<String>, // to br resolved later,
-    pub end: Option<String>,   // to be resolved later
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub end: Option<String>, // to be resolved later
 }
 
 impl ResourceType for Period {
@@ -24,6 +30,318 @@ impl Period {
     pub fn from_json(data: &str) -> Result<Self, FhirError> {
         Ok(serde_json::from_str(data)?)
     }
+
+    /// Whether `instant` falls in the period. A missing start or end leaves
+    /// the period open on that side.
+    ///
+    /// Partial dates cover their whole precision: a period ending in `2024`
+    /// lasts until the end of that year, and an `instant` of `2024-06`
+    /// counts when any part of that month is in the period. As in
+    /// [`FhirDateTime::compare_ranges`], a time without an offset may be in
+    /// any zone when compared with one that has an offset, and the answer is
+    /// `None` when that leaves it open.
+    pub fn contains(&self, instant: &FhirDateTime) -> Result<Option<bool>, FhirError> {
+        let instant = (
+            Some(Bound::new(instant, false)),
+            Some(Bound::new(instant, true)),
+        );
+        Ok(overlap(self.bounds()?, instant))
+    }
+
+    /// Whether `other` lies wholly within the period, `None` when that
+    /// cannot be told, see [`contains`](Self::contains).
+    pub fn contains_period(&self, other: &Period) -> Result<Option<bool>, FhirError> {
+        let ((start, end), (other_start, other_end)) = (self.bounds()?, other.bounds()?);
+        let within =
+            |outer: Option<Bound>, inner: Option<Bound>, ordering: Ordering| match (outer, inner) {
+                (None, _) => Some(true),
+                (Some(_), None) => Some(false),
+                (Some(outer), Some(inner)) => outer.order(&inner).map(|o| o != ordering),
+            };
+        Ok(all([
+            within(start, other_start, Ordering::Greater),
+            within(end, other_end, Ordering::Less),
+        ]))
+    }
+
+    /// Whether the periods share at least one instant, `None` when that
+    /// cannot be told, see [`contains`](Self::contains).
+    pub fn overlaps(&self, other: &Period) -> Result<Option<bool>, FhirError> {
+        Ok(overlap(self.bounds()?, other.bounds()?))
+    }
+
+    /// The part the periods share, `None` when they do not overlap. The
+    /// bounds are taken over as they are written, keeping their precision.
+    /// It is an error when the overlap depends on the zone of a time without
+    /// an offset.
+    pub fn intersection(&self, other: &Period) -> Result<Option<Period>, FhirError> {
+        if !self.determinate(self.overlaps(other)?, other)? {
+            return Ok(None);
+        }
+        let ((start, end), (other_start, other_end)) = (self.bounds()?, other.bounds()?);
+        let start = match (start, other_start) {
+            (Some(start), Some(other_start)) => match self.order(&start, &other_start, other)? {
+                Ordering::Less => &other.start,
+                _ => &self.start,
+            },
+            (Some(_), None) => &self.start,
+            _ => &other.start,
+        };
+        let end = match (end, other_end) {
+            (Some(end), Some(other_end)) => match self.order(&end, &other_end, other)? {
+                Ordering::Greater => &other.end,
+                _ => &self.end,
+            },
+            (Some(_), None) => &self.end,
+            _ => &other.end,
+        };
+        Ok(Some(Period {
+            element: Element::default(),
+            start: start.clone(),
+            end: end.clone(),
+        }))
+    }
+
+    /// The period covering both, `None` when a gap separates them. Periods
+    /// that meet, such as `2023` and `2024`, are joined. It is an error when
+    /// that depends on the zone of a time without an offset.
+    pub fn union(&self, other: &Period) -> Result<Option<Period>, FhirError> {
+        if self.gap(other)?.is_some_and(|gap| !gap.is_zero()) {
+            return Ok(None);
+        }
+        let ((start, end), (other_start, other_end)) = (self.bounds()?, other.bounds()?);
+        let start = match (start, other_start) {
+            (Some(start), Some(other_start)) => match self.order(&start, &other_start, other)? {
+                Ordering::Greater => other.start.clone(),
+                _ => self.start.clone(),
+            },
+            _ => None,
+        };
+        let end = match (end, other_end) {
+            (Some(end), Some(other_end)) => match self.order(&end, &other_end, other)? {
+                Ordering::Less => other.end.clone(),
+                _ => self.end.clone(),
+            },
+            _ => None,
+        };
+        Ok(Some(Period {
+            element: Element::default(),
+            start,
+            end,
+        }))
+    }
+
+    /// The time between the periods, zero when one starts right after the
+    /// other ends and `None` when they overlap. It is an error when the gap
+    /// depends on the zone of a time without an offset.
+    pub fn gap(&self, other: &Period) -> Result<Option<Duration>, FhirError> {
+        if self.determinate(self.overlaps(other)?, other)? {
+            return Ok(None);
+        }
+        let ((start, end), (other_start, other_end)) = (self.bounds()?, other.bounds()?);
+        let between = |end: Option<Bound>, start: Option<Bound>| {
+            let (end, start) = (end?, start?);
+            (end.order(&start) == Some(Ordering::Less)).then_some((end, start))
+        };
+        let Some((end, start)) = between(end, other_start).or_else(|| between(other_end, start))
+        else {
+            return Ok(None);
+        };
+        if end.floats_against(&start) || start.floats_against(&end) {
+            return Err(self.indeterminate(other));
+        }
+        Ok(u64::try_from(start.millis - end.millis - 1)
+            .ok()
+            .map(Duration::from_millis))
+    }
+
+    /// The time the period covers, `None` when it is open ended. The period
+    /// `2024` lasts 366 days. It is an error when only one of the bounds has
+    /// a time without an offset and the other an offset.
+    pub fn duration(&self) -> Result<Option<Duration>, FhirError> {
+        let (Some(start), Some(end)) = self.bounds()? else {
+            return Ok(None);
+        };
+        if start.floats_against(&end) || end.floats_against(&start) {
+            return Err(FhirError::PeriodError(format!(
+                "the duration of {} depends on the zone of its times",
+                self.describe()
+            )));
+        }
+        match u64::try_from(end.millis + 1 - start.millis) {
+            Ok(millis) => Ok(Some(Duration::from_millis(millis))),
+            Err(_) => Err(FhirError::PeriodError(format!(
+                "end {} is before start {}",
+                self.end.as_deref().unwrap_or_default(),
+                self.start.as_deref().unwrap_or_default()
+            ))),
+        }
+    }
+
+    /// The first and last instant the period covers, `None` on an open side.
+    fn bounds(&self) -> Result<(Option<Bound>, Option<Bound>), FhirError> {
+        let bound = |value: &Option<String>, last: bool| -> Result<Option<Bound>, FhirError> {
+            value
+                .as_deref()
+                .map(|value| Ok(Bound::new(&FhirDateTime::parse(value)?, last)))
+                .transpose()
+        };
+        Ok((bound(&self.start, false)?, bound(&self.end, true)?))
+    }
+
+    fn order(&self, a: &Bound, b: &Bound, other: &Period) -> Result<Ordering, FhirError> {
+        a.order(b).ok_or_else(|| self.indeterminate(other))
+    }
+
+    fn determinate(&self, answer: Option<bool>, other: &Period) -> Result<bool, FhirError> {
+        answer.ok_or_else(|| self.indeterminate(other))
+    }
+
+    fn indeterminate(&self, other: &Period) -> FhirError {
+        FhirError::PeriodError(format!(
+            "{} and {} can not be ordered without the zone of their times",
+            self.describe(),
+            other.describe()
+        ))
+    }
+
+    fn describe(&self) -> String {
+        format!(
+            "{}..{}",
+            self.start.as_deref().unwrap_or_default(),
+            self.end.as_deref().unwrap_or_default()
+        )
+    }
+}
+
+/// The first or last millisecond a start or end covers.
+#[derive(Debug, Clone, Copy)]
+struct Bound {
+    millis: i64,
+    /// A date or time without an offset, which may be in any zone.
+    floating: bool,
+    has_offset: bool,
+}
+
+impl Bound {
+    fn new(value: &FhirDateTime, last: bool) -> Self {
+        let bound = if last {
+            value.upper_bound()
+        } else {
+            value.lower_bound()
+        };
+        Bound {
+            millis: bound.epoch_millis(0),
+            floating: value.offset().is_none(),
+            has_offset: value.offset().is_some(),
+        }
+    }
+
+    /// Whether the zone of the bound is unknown next to `other`, which has
+    /// one.
+    fn floats_against(&self, other: &Bound) -> bool {
+        self.floating && other.has_offset
+    }
+
+    /// Orders the bounds by the rule of [`FhirDateTime::compare_ranges`],
+    /// `None` when the zone of a time without an offset leaves it open.
+    fn order(&self, other: &Bound) -> Option<Ordering> {
+        let range = |bound: &Bound, other: &Bound| {
+            let widen = if bound.floats_against(other) {
+                MAX_OFFSET_MILLIS
+            } else {
+                0
+            };
+            (bound.millis - widen, bound.millis + widen)
+        };
+        let (a, b) = (range(self, other), range(other, self));
+        if a.1 < b.0 {
+            Some(Ordering::Less)
+        } else if a.0 > b.1 {
+            Some(Ordering::Greater)
+        } else {
+            (a == b).then_some(Ordering::Equal)
+        }
+    }
+}
+
+/// Whether two spans share an instant: each starts at or before the end of
+/// the other, open sides reaching without limit.
+fn overlap(
+    (start, end): (Option<Bound>, Option<Bound>),
+    (other_start, other_end): (Option<Bound>, Option<Bound>),
+) -> Option<bool> {
+    let not_after = |start: Option<Bound>, end: Option<Bound>| match (start, end) {
+        (Some(start), Some(end)) => start.order(&end).map(|o| o != Ordering::Greater),
+        _ => Some(true),
+    };
+    all([not_after(start, other_end), not_after(other_start, end)])
+}
+
+/// Three valued `and`: false when any is, unknown when any is unknown.
+fn all(answers: [Option<bool>; 2]) -> Option<bool> {
+    if answers.contains(&Some(false)) {
+        Some(false)
+    } else if answers.contains(&None) {
+        None
+    } else {
+        Some(true)
+    }
+}
+
+/// A type that is only in force during its `period`, and while it is active
+/// where it has a flag or status for that.
+pub trait Effective {
+    fn period(&self) -> Option<&Period>;
+
+    /// Whether the flag or status of the type marks it as in use, `true` for
+    /// types that have neither.
+    ///
+    /// An absent `active` flag counts as active, and a value whose `use` is
+    /// `old` is out of use whatever its period.
+    fn is_active(&self) -> bool {
+        true
+    }
+
+    /// Whether the value is active and `instant` falls in its period, see
+    /// [`Period::contains`]. An instant that may or may not fall in the
+    /// period, for want of a zone, does not count.
+    fn is_effective_at(&self, instant: &FhirDateTime) -> Result<bool, FhirError> {
+        if !self.is_active() {
+            return Ok(false);
+        }
+        match self.period() {
+            Some(period) => Ok(period.contains(instant)? == Some(true)),
+            None => Ok(true),
+        }
+    }
+
+    fn currently_effective(&self) -> Result<bool, FhirError> {
+        self.is_effective_at(&FhirDateTime::now())
+    }
+}
+
+/// The values of `values` effective at `instant`, such as the identifiers in
+/// use on a date.
+pub fn effective_at<'a, T: Effective + 'a>(
+    values: impl IntoIterator<Item = &'a T>,
+    instant: &FhirDateTime,
+) -> Result<Vec<&'a T>, FhirError> {
+    let mut effective = Vec::new();
+    for value in values {
+        if value.is_effective_at(instant)? {
+            effective.push(value);
+        }
+    }
+    Ok(effective)
+}
+
+/// The values of `values` effective now, such as the active telecoms of a
+/// role.
+pub fn currently_effective<'a, T: Effective + 'a>(
+    values: impl IntoIterator<Item = &'a T>,
+) -> Result<Vec<&'a T>, FhirError> {
+    effective_at(values, &FhirDateTime::now())
 }
 
 #[derive(Default)]
@@ -62,6 +380,10 @@ impl PeriodBuilder {
             end: self.end,
         }
     }
+
+    pub fn try_build(self) -> Result<Period, FhirError> {
+        Ok(self.build())
+    }
 }
 
 #[cfg(test)]
@@ -106,4 +428,298 @@ mod test {
 
         assert_eq!(expected, actual)
     }
+
+    fn at(value: &str) -> FhirDateTime {
+        FhirDateTime::parse(value).unwrap()
+    }
+
+    #[test]
+    fn test_contains_should_cover_partial_dates() {
+        let year = PeriodBuilder::default()
+            .with_start("2024")
+            .with_end("2024")
+            .build();
+        let from = PeriodBuilder::default()
+            .with_start("2024-06-15T08:00:00+02:00")
+            .build();
+
+        assert_eq!(
+            Some(true),
+            year.contains(&at("2024-01-02T00:00:00Z")).unwrap()
+        );
+        assert_eq!(
+            None,
+            year.contains(&at("2024-12-31T23:59:59.999Z")).unwrap()
+        );
+        assert_eq!(
+            Some(false),
+            year.contains(&at("2025-01-02T00:00:00Z")).unwrap()
+        );
+        assert_eq!(Some(false), year.contains(&at("2023-12-31")).unwrap());
+        assert_eq!(Some(true), from.contains(&at("2024-06")).unwrap());
+        assert_eq!(
+            Some(true),
+            from.contains(&at("2024-06-15T06:00:00Z")).unwrap()
+        );
+        assert_eq!(
+            Some(false),
+            from.contains(&at("2024-06-15T05:59:59Z")).unwrap()
+        );
+        assert_eq!(Some(true), from.contains(&at("2099")).unwrap());
+        assert_eq!(Some(true), Period::default().contains(&at("1900")).unwrap());
+        assert!(
+            PeriodBuilder::default()
+                .with_end("20-10-2025")
+                .build()
+                .contains(&at("2024"))
+                .is_err()
+        );
+    }
+
+    fn period(start: Option<&str>, end: Option<&str>) -> Period {
+        let mut builder = PeriodBuilder::default();
+        if let Some(start) = start {
+            builder = builder.with_start(start);
+        }
+        if let Some(end) = end {
+            builder = builder.with_end(end);
+        }
+        builder.build()
+    }
+
+    #[test]
+    fn test_period_algebra_should_follow_partial_precision() {
+        let year = period(Some("2024"), Some("2024"));
+        let spring = period(Some("2024-03-01"), Some("2024-05-31"));
+        let from_may = period(Some("2024-05-15T12:00:00Z"), None);
+        let next_year = period(Some("2025-01-01"), Some("2025-06"));
+        let later = period(Some("2026"), None);
+
+        assert_eq!(Some(true), year.contains_period(&spring).unwrap());
+        assert_eq!(Some(false), spring.contains_period(&year).unwrap());
+        assert_eq!(Some(false), year.contains_period(&from_may).unwrap());
+        assert_eq!(
+            Some(true),
+            Period::default().contains_period(&from_may).unwrap()
+        );
+
+        assert_eq!(Some(true), spring.overlaps(&from_may).unwrap());
+        assert_eq!(Some(false), year.overlaps(&next_year).unwrap());
+        assert_eq!(
+            Some(period(Some("2024-05-15T12:00:00Z"), Some("2024-05-31"))),
+            spring.intersection(&from_may).unwrap()
+        );
+        assert_eq!(
+            Some(period(Some("2024-05-15T12:00:00Z"), Some("2024"))),
+            from_may.intersection(&year).unwrap()
+        );
+        assert_eq!(None, year.intersection(&next_year).unwrap());
+
+        assert_eq!(
+            Some(period(Some("2024"), Some("2025-06"))),
+            year.union(&next_year).unwrap()
+        );
+        assert_eq!(
+            Some(period(Some("2024-03-01"), None)),
+            spring.union(&from_may).unwrap()
+        );
+        assert_eq!(None, year.union(&later).unwrap());
+
+        assert_eq!(Some(Duration::ZERO), year.gap(&next_year).unwrap());
+        assert_eq!(
+            Some(Duration::from_secs(184 * 86_400)),
+            later.gap(&next_year).unwrap()
+        );
+        assert_eq!(None, spring.gap(&from_may).unwrap());
+
+        assert_eq!(
+            Some(Duration::from_secs(366 * 86_400)),
+            year.duration().unwrap()
+        );
+        assert_eq!(
+            Some(Duration::from_secs(92 * 86_400)),
+            spring.duration().unwrap()
+        );
+        assert_eq!(None, from_may.duration().unwrap());
+        assert_eq!(
+            "invalid period: end 2023 is before start 2024-03-01",
+            period(Some("2024-03-01"), Some("2023"))
+                .duration()
+                .unwrap_err()
+                .to_string()
+        );
+    }
+
+    #[test]
+    fn test_period_algebra_should_follow_compare_ranges_without_offsets() {
+        let local = period(Some("2024-05-01T10:00:00"), Some("2024-05-01T12:00:00"));
+        let from_utc = period(Some("2024-05-01T13:00:00Z"), None);
+        let from_later = period(Some("2024-05-03T00:00:00Z"), None);
+
+        assert_eq!(
+            None,
+            at("2024-05-01T12:00:00").compare_ranges(&at("2024-05-01T13:00:00Z"))
+        );
+        assert_eq!(None, local.overlaps(&from_utc).unwrap());
+        assert_eq!(None, from_utc.contains_period(&local).unwrap());
+        assert_eq!(None, local.contains(&at("2024-05-01T11:00:00Z")).unwrap());
+        assert_eq!(
+            Some(true),
+            local.contains(&at("2024-05-01T11:00:00")).unwrap()
+        );
+        assert_eq!(Some(false), local.overlaps(&from_later).unwrap());
+        assert!(local.intersection(&from_utc).is_err());
+        assert!(local.union(&from_utc).is_err());
+        assert!(local.gap(&from_later).is_err());
+        assert_eq!(
+            Some(Duration::from_secs(2 * 3_600 + 1)),
+            local.duration().unwrap()
+        );
+        assert!(
+            period(Some("2024-05-01T10:00:00"), Some("2024-05-01T12:00:00Z"))
+                .duration()
+                .is_err()
+        )
+    }
+
+    #[test]
+    fn test_contains_should_leave_dates_open_against_offsets() {
+        use crate::r4::elements::Identifier;
+
+        let until = period(None, Some("2024-12-31"));
+        let identifier = Identifier {
+            period: Some(until.clone()),
+            ..Default::default()
+        };
+        let evening = at("2024-12-31T23:00:00-05:00");
+
+        assert_eq!(None, until.contains(&evening).unwrap());
+        assert_eq!(
+            None,
+            at("2024-12-31").compare_ranges(&at("2025-01-01T04:00:00Z"))
+        );
+        assert_eq!(
+            Some(true),
+            until.contains(&at("2024-12-31T23:00:00")).unwrap()
+        );
+        assert_eq!(
+            Some(true),
+            until.contains(&at("2024-12-30T23:00:00-05:00")).unwrap()
+        );
+        assert_eq!(
+            Some(false),
+            until.contains(&at("2025-01-01T14:00:00Z")).unwrap()
+        );
+        assert!(!identifier.is_effective_at(&evening).unwrap());
+        assert!(
+            identifier
+                .is_effective_at(&at("2024-12-30T23:00:00-05:00"))
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_effective_should_follow_period_and_active_flag() {
+        use crate::r4::{
+            elements::{ContactPoint, Identifier},
+            resources::{Endpoint, EndpointStatus, PractitionerRole},
+        };
+
+        let period = |start: &str, end: &str| {
+            Some(
+                PeriodBuilder::default()
+                    .with_start(start)
+                    .with_end(end)
+                    .build(),
+            )
+        };
+        let telecoms = [
+            ContactPoint {
+                value: Some("old".to_string()),
+                period: period("2010", "2023-12-31"),
+                ..Default::default()
+            },
+            ContactPoint {
+                value: Some("current".to_string()),
+                period: period("2024-01", "2024-12"),
+                ..Default::default()
+            },
+            ContactPoint {
+                value: Some("always".to_string()),
+                ..Default::default()
+            },
+        ];
+        let identifiers = [Identifier {
+            value: Some("123".to_string()),
+            period: period("2024", "2024"),
+            ..Default::default()
+        }];
+        let mut role = PractitionerRole {
+            period: period("2024-03-01", "2024-03-31"),
+            ..Default::default()
+        };
+        let mut endpoint = Endpoint {
+            status: EndpointStatus::Active,
+            ..Default::default()
+        };
+        let instant = at("2024-03-15T12:00:00Z");
+
+        let values: Vec<&str> = effective_at(&telecoms, &instant)
+            .unwrap()
+            .into_iter()
+            .filter_map(|telecom| telecom.value.as_deref())
+            .collect();
+        assert_eq!(vec!["current", "always"], values);
+        assert_eq!(1, effective_at(&identifiers, &instant).unwrap().len());
+        assert!(role.is_effective_at(&instant).unwrap());
+        assert!(!role.is_effective_at(&at("2024-04-01")).unwrap());
+        assert!(role.is_effective_at(&at("2024")).unwrap());
+        role.active = Some(false);
+        assert!(!role.is_effective_at(&instant).unwrap());
+        assert!(endpoint.currently_effective().unwrap());
+        endpoint.status = EndpointStatus::Suspended;
+        assert!(!endpoint.currently_effective().unwrap());
+        assert_eq!(1, currently_effective(&telecoms).unwrap().len());
+        assert!(
+            !ContactPoint {
+                period: period("2024-03-15T10:00:00", "2024-03-15T14:00:00"),
+                ..Default::default()
+            }
+            .is_effective_at(&instant)
+            .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_effective_should_exclude_old_values() {
+        use crate::r4::elements::{ContactPoint, HumanName, Identifier};
+
+        let old = Some("old".to_string());
+        let telecom = ContactPoint {
+            r#use: old.clone(),
+            ..Default::default()
+        };
+        let identifier = Identifier {
+            r#use: old.clone(),
+            ..Default::default()
+        };
+        let name = HumanName {
+            r#use: old,
+            period: Some(PeriodBuilder::default().with_start("2020").build()),
+            ..Default::default()
+        };
+        let instant = at("2024-03-15T12:00:00Z");
+
+        assert!(!telecom.is_effective_at(&instant).unwrap());
+        assert!(!identifier.is_effective_at(&instant).unwrap());
+        assert!(!name.currently_effective().unwrap());
+        assert!(
+            ContactPoint {
+                r#use: Some("work".to_string()),
+                ..Default::default()
+            }
+            .is_effective_at(&instant)
+            .unwrap()
+        )
+    }
 }
@@ -3,19 +3,30 @@ use serde::{Deserialize, Serialize};
 use crate::{
     FhirError,
     r4::{
-        elements::Element,
+        elements::{Element, serialize_optional_decimal},
         resources::ResourceType,
     },
 };
 // TODO: Consider adding Comperator Set
-#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Default)]
 pub struct Quantity {
     #[serde(flatten)]
     pub element: Element,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(serialize_with = "serialize_optional_decimal")]
     pub value: Option<f32>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub comparator: Option<String>, // to be resolved later
+
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub unit: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub system: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub code: Option<String>,
 }
 
@@ -86,6 +97,10 @@ impl QuantityBuilder {
             code: self.code,
         }
     }
+
+    pub fn try_build(self) -> Result<Quantity, FhirError> {
+        Ok(self.build())
+    }
 }
 
 #[cfg(test)]
@@ -12,7 +12,11 @@ use crate::{
 pub struct Range {
     #[serde(flatten)]
     pub element: Element,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub low: Option<Quantity>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub high: Option<Quantity>,
 }
 
@@ -62,6 +66,10 @@ impl RangeBuilder {
             high: self.high,
         }
     }
+
+    pub fn try_build(self) -> Result<Range, FhirError> {
+        Ok(self.build())
+    }
 }
 
 #[cfg(test)]
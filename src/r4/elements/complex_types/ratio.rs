@@ -12,7 +12,12 @@ use crate::{
 pub struct Ratio {
     #[serde(flatten)]
     pub element: Element,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub numerator: Option<Quantity>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "denominator")]
     pub denomenator: Option<Quantity>,
 }
 
@@ -62,6 +67,10 @@ impl RatioBuilder {
             denomenator: self.denomenator,
         }
     }
+
+    pub fn try_build(self) -> Result<Ratio, FhirError> {
+        Ok(self.build())
+    }
 }
 
 #[cfg(test)]
@@ -82,7 +91,7 @@ mod test {
                     "system": "any system",
                     "code": "exact code"
                 },
-                "denomenator": {        
+                "denominator": {        
                     "id": "quantity-2",
                     "value": 20.00,
                     "comparator": "the comparator",
@@ -3,22 +3,41 @@ use serde::{Deserialize, Serialize};
 use crate::{
     FhirError,
     r4::{
-        elements::{Element, Quantity},
+        elements::{Element, Quantity, serialize_optional_decimal},
         resources::ResourceType,
     },
 };
 
 #[derive(Debug, Serialize, Deserialize, PartialEq)]
-#[serde(rename_all(serialize = "snake_case", deserialize = "camelCase"))]
+#[serde(rename_all(serialize = "camelCase", deserialize = "camelCase"))]
 pub struct SampledData {
     #[serde(flatten)]
     pub element: Element,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub origin: Option<Quantity>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(serialize_with = "serialize_optional_decimal")]
     pub period: Option<f32>, // this should be positive I think
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(serialize_with = "serialize_optional_decimal")]
     pub factor: Option<f32>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(serialize_with = "serialize_optional_decimal")]
     pub lower_limit: Option<f32>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(serialize_with = "serialize_optional_decimal")]
     pub upper_limit: Option<f32>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "dimensions")]
     pub dimentions: Option<u32>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub data: Option<String>,
 }
 
@@ -103,6 +122,18 @@ impl SampledDataBuilder {
             data: self.data,
         }
     }
+
+    pub fn try_build(self) -> Result<SampledData, FhirError> {
+        FhirError::require(
+            "SampledData",
+            &[
+                ("origin", self.origin.is_some()),
+                ("period", self.period.is_some()),
+                ("dimensions", self.dimentions.is_some()),
+            ],
+        )?;
+        Ok(self.build())
+    }
 }
 
 #[cfg(test)]
@@ -125,7 +156,7 @@ mod test {
                 "factor": 10.00,
                 "lowerLimit": 10.00,
                 "upperLimit": 20.00,
-                "dimentions": 50
+                "dimensions": 50
             }
         "#;
         let origin = QuantityBuilder::default()
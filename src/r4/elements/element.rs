@@ -1,9 +1,9 @@
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Serialize, Serializer};
 
 use crate::r4::resources::ResourceType;
 
 #[derive(Debug, PartialEq, Clone, Serialize, Deserialize, Default)]
-#[serde(rename_all(serialize = "snake_case", deserialize = "camelCase"))]
+#[serde(rename_all(serialize = "camelCase", deserialize = "camelCase"))]
 pub struct Element {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub id: Option<String>,
@@ -28,3 +28,26 @@ impl ResourceType for Element {
 impl ResourceType for BackboneElement {
     const TYPE: &'static str = "BackboneElement";
 }
+
+/// Writes an `f32` as the shortest decimal that reads back as the same value,
+/// so that `-83.69` is not written as `-83.69000244140625`.
+pub(crate) fn serialize_decimal<S: Serializer>(
+    value: &f32,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.serialize_f64(widen(*value))
+}
+
+pub(crate) fn serialize_optional_decimal<S: Serializer>(
+    value: &Option<f32>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    match value {
+        Some(value) => serializer.serialize_some(&widen(*value)),
+        None => serializer.serialize_none(),
+    }
+}
+
+fn widen(value: f32) -> f64 {
+    value.to_string().parse().unwrap_or(f64::from(value))
+}
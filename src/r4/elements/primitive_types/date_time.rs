@@ -0,0 +1,953 @@
+use std::{
+    cmp::Ordering,
+    fmt,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use crate::FhirError;
+
+pub const MILLIS_PER_DAY: i64 = 86_400_000;
+
+/// The largest offset from UTC a time without an offset may be in.
+pub(crate) const MAX_OFFSET_MILLIS: i64 = 14 * 3_600_000;
+
+/// The precision of a partial date, date/time or time value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum DateTimePrecision {
+    Year,
+    Month,
+    Day,
+    Hour,
+    Minute,
+    Second,
+    Millisecond,
+}
+
+impl DateTimePrecision {
+    /// Seconds and milliseconds are a single precision when comparing values.
+    fn comparable(self) -> Self {
+        match self {
+            DateTimePrecision::Millisecond => DateTimePrecision::Second,
+            precision => precision,
+        }
+    }
+}
+
+/// The calendar duration units usable in date arithmetic.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CalendarUnit {
+    Year,
+    Month,
+    Week,
+    Day,
+    Hour,
+    Minute,
+    Second,
+    Millisecond,
+}
+
+impl CalendarUnit {
+    /// Parses the FHIRPath calendar keywords (`year`, `months`, ...) and their
+    /// UCUM counterparts (`a`, `mo`, `wk`, `d`, `h`, `min`, `s`, `ms`).
+    pub fn parse(unit: &str) -> Option<Self> {
+        match unit {
+            "year" | "years" | "a" => Some(CalendarUnit::Year),
+            "month" | "months" | "mo" => Some(CalendarUnit::Month),
+            "week" | "weeks" | "wk" => Some(CalendarUnit::Week),
+            "day" | "days" | "d" => Some(CalendarUnit::Day),
+            "hour" | "hours" | "h" => Some(CalendarUnit::Hour),
+            "minute" | "minutes" | "min" => Some(CalendarUnit::Minute),
+            "second" | "seconds" | "s" => Some(CalendarUnit::Second),
+            "millisecond" | "milliseconds" | "ms" => Some(CalendarUnit::Millisecond),
+            _ => None,
+        }
+    }
+
+    pub fn keyword(self) -> &'static str {
+        match self {
+            CalendarUnit::Year => "year",
+            CalendarUnit::Month => "month",
+            CalendarUnit::Week => "week",
+            CalendarUnit::Day => "day",
+            CalendarUnit::Hour => "hour",
+            CalendarUnit::Minute => "minute",
+            CalendarUnit::Second => "second",
+            CalendarUnit::Millisecond => "millisecond",
+        }
+    }
+
+    fn precision(self) -> DateTimePrecision {
+        match self {
+            CalendarUnit::Year => DateTimePrecision::Year,
+            CalendarUnit::Month => DateTimePrecision::Month,
+            CalendarUnit::Week | CalendarUnit::Day => DateTimePrecision::Day,
+            CalendarUnit::Hour => DateTimePrecision::Hour,
+            CalendarUnit::Minute => DateTimePrecision::Minute,
+            CalendarUnit::Second => DateTimePrecision::Second,
+            CalendarUnit::Millisecond => DateTimePrecision::Millisecond,
+        }
+    }
+
+    /// The unit of a precision and how many of them make up one unit of the
+    /// next coarser precision, used to truncate durations finer than a value.
+    fn coarser(self) -> Option<(CalendarUnit, i64)> {
+        match self {
+            CalendarUnit::Millisecond => Some((CalendarUnit::Second, 1_000)),
+            CalendarUnit::Second => Some((CalendarUnit::Minute, 60)),
+            CalendarUnit::Minute => Some((CalendarUnit::Hour, 60)),
+            CalendarUnit::Hour => Some((CalendarUnit::Day, 24)),
+            CalendarUnit::Week => Some((CalendarUnit::Day, 1)),
+            CalendarUnit::Day => Some((CalendarUnit::Month, 30)),
+            CalendarUnit::Month => Some((CalendarUnit::Year, 12)),
+            CalendarUnit::Year => None,
+        }
+    }
+}
+
+/// A FHIR `date`, `dateTime` or `instant` that keeps the precision it was
+/// given with, e.g. `2024`, `2024-03` or `2024-03-01T10:00:00+01:00`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FhirDateTime {
+    year: i32,
+    month: u8,
+    day: u8,
+    hour: u8,
+    minute: u8,
+    second: u8,
+    millisecond: u16,
+    precision: DateTimePrecision,
+    /// Offset from UTC in minutes, `None` when no timezone was given.
+    offset: Option<i32>,
+}
+
+impl FhirDateTime {
+    /// A date with day precision.
+    pub fn from_ymd(year: i32, month: u8, day: u8) -> Result<Self, FhirError> {
+        let value = FhirDateTime {
+            year,
+            month,
+            day,
+            hour: 0,
+            minute: 0,
+            second: 0,
+            millisecond: 0,
+            precision: DateTimePrecision::Day,
+            offset: None,
+        };
+        value.validate()
+    }
+
+    pub fn parse(value: &str) -> Result<Self, FhirError> {
+        let invalid = || FhirError::DateTimeParseError(value.to_string());
+
+        let (date, time) = match value.split_once('T') {
+            Some((date, time)) => (date, Some(time)),
+            None => (value, None),
+        };
+
+        let mut parts = date.split('-');
+        let year = parts
+            .next()
+            .filter(|y| y.len() == 4)
+            .and_then(parse_digits)
+            .ok_or_else(invalid)?;
+        let month = parts
+            .next()
+            .map(|m| parse_two(m).ok_or_else(invalid))
+            .transpose()?;
+        let day = parts
+            .next()
+            .map(|d| parse_two(d).ok_or_else(invalid))
+            .transpose()?;
+        if parts.next().is_some() || (day.is_some() && month.is_none()) {
+            return Err(invalid());
+        }
+
+        let mut result = FhirDateTime {
+            year: year as i32,
+            month: month.unwrap_or(1),
+            day: day.unwrap_or(1),
+            hour: 0,
+            minute: 0,
+            second: 0,
+            millisecond: 0,
+            precision: match (month, day) {
+                (None, _) => DateTimePrecision::Year,
+                (Some(_), None) => DateTimePrecision::Month,
+                (Some(_), Some(_)) => DateTimePrecision::Day,
+            },
+            offset: None,
+        };
+
+        if let Some(time) = time.filter(|t| !t.is_empty()) {
+            if result.precision != DateTimePrecision::Day {
+                return Err(invalid());
+            }
+            let (time, offset) = split_offset(time).ok_or_else(invalid)?;
+            let parsed = FhirTime::parse(time).map_err(|_| invalid())?;
+            result.hour = parsed.hour;
+            result.minute = parsed.minute;
+            result.second = parsed.second;
+            result.millisecond = parsed.millisecond;
+            result.precision = parsed.precision;
+            result.offset = offset;
+        }
+
+        result.validate().map_err(|_| invalid())
+    }
+
+    /// The current instant in UTC with millisecond precision.
+    pub fn now() -> Self {
+        let millis = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis() as i64)
+            .unwrap_or_default();
+        Self::from_epoch_millis(millis, Some(0), DateTimePrecision::Millisecond)
+    }
+
+    /// The current date in UTC.
+    pub fn today() -> Self {
+        let mut today = Self::now().truncate(DateTimePrecision::Day);
+        today.offset = None;
+        today
+    }
+
+    pub fn year(&self) -> i32 {
+        self.year
+    }
+
+    pub fn month(&self) -> Option<u8> {
+        (self.precision >= DateTimePrecision::Month).then_some(self.month)
+    }
+
+    pub fn day(&self) -> Option<u8> {
+        (self.precision >= DateTimePrecision::Day).then_some(self.day)
+    }
+
+    pub fn hour(&self) -> Option<u8> {
+        (self.precision >= DateTimePrecision::Hour).then_some(self.hour)
+    }
+
+    pub fn minute(&self) -> Option<u8> {
+        (self.precision >= DateTimePrecision::Minute).then_some(self.minute)
+    }
+
+    pub fn second(&self) -> Option<u8> {
+        (self.precision >= DateTimePrecision::Second).then_some(self.second)
+    }
+
+    pub fn millisecond(&self) -> Option<u16> {
+        (self.precision >= DateTimePrecision::Millisecond).then_some(self.millisecond)
+    }
+
+    pub fn precision(&self) -> DateTimePrecision {
+        self.precision
+    }
+
+    /// Offset from UTC in minutes, `None` when no timezone was given.
+    pub fn offset(&self) -> Option<i32> {
+        self.offset
+    }
+
+    /// `true` when the value has a time component.
+    pub fn has_time(&self) -> bool {
+        self.precision >= DateTimePrecision::Hour
+    }
+
+    /// The time component, when the value has one.
+    pub fn time(&self) -> Option<FhirTime> {
+        self.has_time().then_some(FhirTime {
+            hour: self.hour,
+            minute: self.minute,
+            second: self.second,
+            millisecond: self.millisecond,
+            precision: self.precision,
+        })
+    }
+
+    /// Drops the components finer than `precision`.
+    pub fn truncate(&self, precision: DateTimePrecision) -> Self {
+        if precision >= self.precision {
+            return *self;
+        }
+        let mut result = *self;
+        result.precision = precision;
+        if precision < DateTimePrecision::Millisecond {
+            result.millisecond = 0;
+        }
+        if precision < DateTimePrecision::Second {
+            result.second = 0;
+        }
+        if precision < DateTimePrecision::Minute {
+            result.minute = 0;
+        }
+        if precision < DateTimePrecision::Hour {
+            result.hour = 0;
+            result.offset = None;
+        }
+        if precision < DateTimePrecision::Day {
+            result.day = 1;
+        }
+        if precision < DateTimePrecision::Month {
+            result.month = 1;
+        }
+        result
+    }
+
+    /// Milliseconds since the Unix epoch of the earliest instant the value
+    /// covers, a missing offset is read as `default_offset`.
+    pub fn epoch_millis(&self, default_offset: i32) -> i64 {
+        let days = days_from_civil(self.year, self.month, self.day);
+        let offset = self.offset.unwrap_or(default_offset);
+        days * MILLIS_PER_DAY
+            + i64::from(self.hour) * 3_600_000
+            + i64::from(self.minute) * 60_000
+            + i64::from(self.second) * 1_000
+            + i64::from(self.millisecond)
+            - i64::from(offset) * 60_000
+    }
+
+    /// The value at an instant, expressed in `offset` (UTC when `None`).
+    pub fn from_epoch_millis(
+        millis: i64,
+        offset: Option<i32>,
+        precision: DateTimePrecision,
+    ) -> Self {
+        let local = millis + i64::from(offset.unwrap_or(0)) * 60_000;
+        let (days, rem) = (
+            local.div_euclid(MILLIS_PER_DAY),
+            local.rem_euclid(MILLIS_PER_DAY),
+        );
+        let (year, month, day) = civil_from_days(days);
+        FhirDateTime {
+            year,
+            month,
+            day,
+            hour: (rem / 3_600_000) as u8,
+            minute: (rem % 3_600_000 / 60_000) as u8,
+            second: (rem % 60_000 / 1_000) as u8,
+            millisecond: (rem % 1_000) as u16,
+            precision: DateTimePrecision::Millisecond,
+            offset,
+        }
+        .truncate(precision)
+    }
+
+    /// The earliest instant covered by the value, at millisecond precision.
+    pub fn lower_bound(&self) -> Self {
+        let mut result = *self;
+        result.precision = DateTimePrecision::Millisecond;
+        result
+    }
+
+    /// The latest instant covered by the value, at millisecond precision.
+    pub fn upper_bound(&self) -> Self {
+        let mut result = self.lower_bound();
+        if self.precision < DateTimePrecision::Month {
+            result.month = 12;
+        }
+        if self.precision < DateTimePrecision::Day {
+            result.day = days_in_month(result.year, result.month);
+        }
+        if self.precision < DateTimePrecision::Hour {
+            result.hour = 23;
+        }
+        if self.precision < DateTimePrecision::Minute {
+            result.minute = 59;
+        }
+        if self.precision < DateTimePrecision::Second {
+            result.second = 59;
+        }
+        if self.precision < DateTimePrecision::Millisecond {
+            result.millisecond = 999;
+        }
+        result
+    }
+
+    /// The same instant expressed in UTC, values without a time are returned as-is.
+    pub fn to_utc(&self) -> Self {
+        match self.offset {
+            Some(offset) if offset != 0 && self.has_time() => {
+                Self::from_epoch_millis(self.epoch_millis(0), Some(0), self.precision)
+            }
+            _ => *self,
+        }
+    }
+
+    /// Compares two values following the FHIRPath rules: components are
+    /// compared down to the coarsest precision of the two, and the result is
+    /// `None` (indeterminate) when they are equal that far but differ in
+    /// precision. Values with a time are compared in UTC, a missing offset
+    /// being read as UTC.
+    pub fn compare(&self, other: &Self) -> Option<Ordering> {
+        let (a, b) = if self.has_time() || other.has_time() {
+            (self.to_utc(), other.to_utc())
+        } else {
+            (*self, *other)
+        };
+        let (pa, pb) = (a.precision.comparable(), b.precision.comparable());
+        let common = pa.min(pb);
+
+        let components = [
+            (
+                DateTimePrecision::Year,
+                i64::from(a.year),
+                i64::from(b.year),
+            ),
+            (DateTimePrecision::Month, a.month.into(), b.month.into()),
+            (DateTimePrecision::Day, a.day.into(), b.day.into()),
+            (DateTimePrecision::Hour, a.hour.into(), b.hour.into()),
+            (DateTimePrecision::Minute, a.minute.into(), b.minute.into()),
+            (
+                DateTimePrecision::Second,
+                i64::from(a.second) * 1_000 + i64::from(a.millisecond),
+                i64::from(b.second) * 1_000 + i64::from(b.millisecond),
+            ),
+        ];
+        for (precision, x, y) in components {
+            if precision > common {
+                break;
+            }
+            match x.cmp(&y) {
+                Ordering::Equal => {}
+                ordering => return Some(ordering),
+            }
+        }
+
+        (pa == pb).then_some(Ordering::Equal)
+    }
+
+    /// Compares the spans of time two values may denote, `None` when they
+    /// overlap and the order cannot be told.
+    ///
+    /// Unlike [`compare`](Self::compare), a value spans its whole precision,
+    /// so `2024` is before `2025-03` but indeterminate against `2024-12-31`.
+    /// A value without an offset, a bare date included, may be in any zone
+    /// when compared with one that has an offset, between UTC-14:00 and
+    /// UTC+14:00. Two values without an offset are taken to be in the same
+    /// zone. Values spanning the very same time, such as `2024` and `2024`,
+    /// are `Equal`.
+    pub fn compare_ranges(&self, other: &Self) -> Option<Ordering> {
+        let span = |value: &Self, other: &Self| {
+            let widen = if value.offset.is_none() && other.offset.is_some() {
+                MAX_OFFSET_MILLIS
+            } else {
+                0
+            };
+            (
+                value.lower_bound().epoch_millis(0) - widen,
+                value.upper_bound().epoch_millis(0) + widen,
+            )
+        };
+        let (a, b) = (span(self, other), span(other, self));
+        if a.1 < b.0 {
+            Some(Ordering::Less)
+        } else if a.0 > b.1 {
+            Some(Ordering::Greater)
+        } else {
+            (a == b).then_some(Ordering::Equal)
+        }
+    }
+
+    /// Adds a calendar duration. Years and months keep the day of month,
+    /// clamped to the length of the resulting month; durations finer than the
+    /// precision of the value are truncated to it.
+    pub fn add(&self, amount: i64, unit: CalendarUnit) -> Result<Self, FhirError> {
+        if unit.precision() > self.precision
+            && let Some((coarser, factor)) = unit.coarser()
+        {
+            return self.add(amount / factor, coarser);
+        }
+
+        let mut result = *self;
+        match unit {
+            CalendarUnit::Year | CalendarUnit::Month => {
+                let months = if unit == CalendarUnit::Year {
+                    amount.checked_mul(12)
+                } else {
+                    Some(amount)
+                };
+                let total = months
+                    .and_then(|m| {
+                        (i64::from(self.year) * 12 + i64::from(self.month) - 1).checked_add(m)
+                    })
+                    .ok_or_else(|| out_of_range(self))?;
+                result.year =
+                    i32::try_from(total.div_euclid(12)).map_err(|_| out_of_range(self))?;
+                result.month = (total.rem_euclid(12) + 1) as u8;
+                result.day = result.day.min(days_in_month(result.year, result.month));
+            }
+            _ => {
+                let millis = match unit {
+                    CalendarUnit::Week => amount.checked_mul(7 * MILLIS_PER_DAY),
+                    CalendarUnit::Day => amount.checked_mul(MILLIS_PER_DAY),
+                    CalendarUnit::Hour => amount.checked_mul(3_600_000),
+                    CalendarUnit::Minute => amount.checked_mul(60_000),
+                    CalendarUnit::Second => amount.checked_mul(1_000),
+                    _ => Some(amount),
+                }
+                .and_then(|m| self.epoch_millis(self.offset.unwrap_or(0)).checked_add(m))
+                .ok_or_else(|| out_of_range(self))?;
+                result = Self::from_epoch_millis(millis, self.offset, self.precision);
+                if !self.has_time() {
+                    result.offset = None;
+                }
+            }
+        }
+
+        if !(1..=9999).contains(&result.year) {
+            return Err(out_of_range(self));
+        }
+        Ok(result)
+    }
+
+    fn validate(self) -> Result<Self, FhirError> {
+        let valid = (1..=9999).contains(&self.year)
+            && (1..=12).contains(&self.month)
+            && (1..=days_in_month(self.year, self.month)).contains(&self.day)
+            && self.hour < 24
+            && self.minute < 60
+            && self.second < 60
+            && self.millisecond < 1_000
+            && self.offset.is_none_or(|o| o.abs() <= 14 * 60);
+        if valid {
+            Ok(self)
+        } else {
+            Err(FhirError::DateTimeParseError(self.to_string()))
+        }
+    }
+}
+
+impl fmt::Display for FhirDateTime {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:04}", self.year)?;
+        if self.precision >= DateTimePrecision::Month {
+            write!(f, "-{:02}", self.month)?;
+        }
+        if self.precision >= DateTimePrecision::Day {
+            write!(f, "-{:02}", self.day)?;
+        }
+        if let Some(time) = self.time() {
+            write!(f, "T{time}")?;
+            match self.offset {
+                Some(0) => write!(f, "Z")?,
+                Some(offset) => write!(
+                    f,
+                    "{}{:02}:{:02}",
+                    if offset < 0 { '-' } else { '+' },
+                    offset.abs() / 60,
+                    offset.abs() % 60
+                )?,
+                None => {}
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A FHIR `time` with its precision, e.g. `14`, `14:30` or `14:30:00.250`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FhirTime {
+    hour: u8,
+    minute: u8,
+    second: u8,
+    millisecond: u16,
+    precision: DateTimePrecision,
+}
+
+impl FhirTime {
+    pub fn from_hms(hour: u8, minute: u8, second: u8) -> Result<Self, FhirError> {
+        let time = FhirTime {
+            hour,
+            minute,
+            second,
+            millisecond: 0,
+            precision: DateTimePrecision::Second,
+        };
+        if hour < 24 && minute < 60 && second < 60 {
+            Ok(time)
+        } else {
+            Err(FhirError::DateTimeParseError(time.to_string()))
+        }
+    }
+
+    pub fn parse(value: &str) -> Result<Self, FhirError> {
+        let invalid = || FhirError::DateTimeParseError(value.to_string());
+
+        let (hms, fraction) = match value.split_once('.') {
+            Some((hms, fraction)) => (hms, Some(fraction)),
+            None => (value, None),
+        };
+        let parts: Vec<u8> = hms
+            .split(':')
+            .map(|p| parse_two(p).ok_or_else(invalid))
+            .collect::<Result<_, _>>()?;
+        let (hour, minute, second, precision) = match parts[..] {
+            [h] => (h, 0, 0, DateTimePrecision::Hour),
+            [h, m] => (h, m, 0, DateTimePrecision::Minute),
+            [h, m, s] => (h, m, s, DateTimePrecision::Second),
+            _ => return Err(invalid()),
+        };
+
+        let (millisecond, precision) = match fraction {
+            Some(fraction) => {
+                if precision != DateTimePrecision::Second
+                    || fraction.is_empty()
+                    || !fraction.bytes().all(|b| b.is_ascii_digit())
+                {
+                    return Err(invalid());
+                }
+                let millis: String = fraction.chars().chain("00".chars()).take(3).collect();
+                (
+                    millis.parse::<u16>().map_err(|_| invalid())?,
+                    DateTimePrecision::Millisecond,
+                )
+            }
+            None => (0, precision),
+        };
+
+        if hour >= 24 || minute >= 60 || second >= 60 {
+            return Err(invalid());
+        }
+        Ok(FhirTime {
+            hour,
+            minute,
+            second,
+            millisecond,
+            precision,
+        })
+    }
+
+    /// The current UTC time of day.
+    pub fn now() -> Self {
+        FhirDateTime::now().time().unwrap_or_default()
+    }
+
+    pub fn hour(&self) -> u8 {
+        self.hour
+    }
+
+    pub fn minute(&self) -> Option<u8> {
+        (self.precision >= DateTimePrecision::Minute).then_some(self.minute)
+    }
+
+    pub fn second(&self) -> Option<u8> {
+        (self.precision >= DateTimePrecision::Second).then_some(self.second)
+    }
+
+    pub fn millisecond(&self) -> Option<u16> {
+        (self.precision >= DateTimePrecision::Millisecond).then_some(self.millisecond)
+    }
+
+    pub fn precision(&self) -> DateTimePrecision {
+        self.precision
+    }
+
+    /// Milliseconds since midnight of the earliest instant the value covers.
+    pub fn millis_of_day(&self) -> i64 {
+        i64::from(self.hour) * 3_600_000
+            + i64::from(self.minute) * 60_000
+            + i64::from(self.second) * 1_000
+            + i64::from(self.millisecond)
+    }
+
+    /// Compares two times, `None` when they are equal down to the coarsest
+    /// precision of the two but differ in precision.
+    pub fn compare(&self, other: &Self) -> Option<Ordering> {
+        let as_date_time = |t: &FhirTime| FhirDateTime {
+            year: 1970,
+            month: 1,
+            day: 1,
+            hour: t.hour,
+            minute: t.minute,
+            second: t.second,
+            millisecond: t.millisecond,
+            precision: t.precision,
+            offset: None,
+        };
+        as_date_time(self).compare(&as_date_time(other))
+    }
+
+    /// Adds a duration of hours or finer, wrapping around midnight.
+    pub fn add(&self, amount: i64, unit: CalendarUnit) -> Result<Self, FhirError> {
+        let factor = match unit {
+            CalendarUnit::Hour => 3_600_000,
+            CalendarUnit::Minute => 60_000,
+            CalendarUnit::Second => 1_000,
+            CalendarUnit::Millisecond => 1,
+            _ => {
+                return Err(FhirError::DateTimeParseError(format!(
+                    "cannot add {} to time {self}",
+                    unit.keyword()
+                )));
+            }
+        };
+        let millis = amount
+            .checked_mul(factor)
+            .map(|m| {
+                (self.millis_of_day() + m.rem_euclid(MILLIS_PER_DAY)).rem_euclid(MILLIS_PER_DAY)
+            })
+            .unwrap_or_default();
+        Ok(FhirTime {
+            hour: (millis / 3_600_000) as u8,
+            minute: (millis % 3_600_000 / 60_000) as u8,
+            second: (millis % 60_000 / 1_000) as u8,
+            millisecond: (millis % 1_000) as u16,
+            precision: self.precision,
+        })
+    }
+}
+
+impl Default for FhirTime {
+    fn default() -> Self {
+        FhirTime {
+            hour: 0,
+            minute: 0,
+            second: 0,
+            millisecond: 0,
+            precision: DateTimePrecision::Second,
+        }
+    }
+}
+
+impl fmt::Display for FhirTime {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:02}", self.hour)?;
+        if self.precision >= DateTimePrecision::Minute {
+            write!(f, ":{:02}", self.minute)?;
+        }
+        if self.precision >= DateTimePrecision::Second {
+            write!(f, ":{:02}", self.second)?;
+        }
+        if self.precision >= DateTimePrecision::Millisecond {
+            write!(f, ".{:03}", self.millisecond)?;
+        }
+        Ok(())
+    }
+}
+
+pub fn is_leap_year(year: i32) -> bool {
+    (year % 4 == 0 && year % 100 != 0) || year % 400 == 0
+}
+
+pub fn days_in_month(year: i32, month: u8) -> u8 {
+    match month {
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// Days since 1970-01-01, see http://howardhinnant.github.io/date_algorithms.html
+pub fn days_from_civil(year: i32, month: u8, day: u8) -> i64 {
+    let year = i64::from(year) - i64::from(month <= 2);
+    let era = year.div_euclid(400);
+    let yoe = year - era * 400;
+    let month = i64::from(month);
+    let doy = (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5 + i64::from(day) - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146_097 + doe - 719_468
+}
+
+/// The inverse of `days_from_civil`.
+pub fn civil_from_days(days: i64) -> (i32, u8, u8) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    (year as i32, month as u8, day as u8)
+}
+
+fn parse_digits(value: &str) -> Option<u32> {
+    value
+        .bytes()
+        .all(|b| b.is_ascii_digit())
+        .then(|| value.parse().ok())
+        .flatten()
+}
+
+fn parse_two(value: &str) -> Option<u8> {
+    (value.len() == 2)
+        .then(|| parse_digits(value))
+        .flatten()
+        .map(|v| v as u8)
+}
+
+/// Splits a trailing `Z` or `+hh:mm`/`-hh:mm` from a time.
+fn split_offset(time: &str) -> Option<(&str, Option<i32>)> {
+    if let Some(time) = time.strip_suffix('Z') {
+        return Some((time, Some(0)));
+    }
+    let Some(index) = time.rfind(['+', '-']) else {
+        return Some((time, None));
+    };
+    let (time, offset) = time.split_at(index);
+    let sign = if offset.starts_with('-') { -1 } else { 1 };
+    let (hours, minutes) = offset[1..].split_once(':')?;
+    let (hours, minutes) = (parse_two(hours)?, parse_two(minutes)?);
+    if minutes >= 60 {
+        return None;
+    }
+    Some((
+        time,
+        Some(sign * (i32::from(hours) * 60 + i32::from(minutes))),
+    ))
+}
+
+fn out_of_range(value: &FhirDateTime) -> FhirError {
+    FhirError::DateTimeParseError(format!("date arithmetic on {value} is out of range"))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_should_keep_precision() {
+        let cases = [
+            ("2024", DateTimePrecision::Year),
+            ("2024-02", DateTimePrecision::Month),
+            ("2024-02-29", DateTimePrecision::Day),
+            ("2024-02-29T10", DateTimePrecision::Hour),
+            ("2024-02-29T10:30", DateTimePrecision::Minute),
+            ("2024-02-29T10:30:15+01:00", DateTimePrecision::Second),
+            ("2024-02-29T10:30:15.250Z", DateTimePrecision::Millisecond),
+        ];
+
+        for (value, expected) in cases {
+            let actual = FhirDateTime::parse(value).unwrap();
+
+            assert_eq!(expected, actual.precision());
+            assert_eq!(value, actual.to_string())
+        }
+    }
+
+    #[test]
+    fn test_parse_should_reject_invalid_values() {
+        for value in [
+            "24",
+            "2023-02-29",
+            "2024-13",
+            "2024-01-01T25:00",
+            "2024-1-01",
+            "2024T10",
+        ] {
+            assert!(FhirDateTime::parse(value).is_err(), "{value}")
+        }
+    }
+
+    #[test]
+    fn test_compare_should_be_indeterminate_across_precisions() {
+        let date = |v| FhirDateTime::parse(v).unwrap();
+
+        assert_eq!(Some(Ordering::Less), date("2023").compare(&date("2024-05")));
+        assert_eq!(None, date("2024").compare(&date("2024-05")));
+        assert_eq!(
+            Some(Ordering::Equal),
+            date("2024-05-01T10:00:00+02:00").compare(&date("2024-05-01T08:00:00Z"))
+        );
+        assert_eq!(
+            Some(Ordering::Equal),
+            date("2024-05-01T10:00:00").compare(&date("2024-05-01T10:00:00.000"))
+        )
+    }
+
+    #[test]
+    fn test_compare_ranges_should_be_indeterminate_when_spans_overlap() {
+        let date = |v| FhirDateTime::parse(v).unwrap();
+
+        assert_eq!(
+            Some(Ordering::Less),
+            date("2024").compare_ranges(&date("2025-03"))
+        );
+        assert_eq!(None, date("2024").compare_ranges(&date("2024-12-31")));
+        assert_eq!(
+            Some(Ordering::Greater),
+            date("2024-06-15").compare_ranges(&date("2024-06-14T09:59:59Z"))
+        );
+        assert_eq!(
+            None,
+            date("2024-06-15").compare_ranges(&date("2024-06-14T23:59:59Z"))
+        );
+        assert_eq!(
+            None,
+            date("2024-06-15T10:00:00").compare_ranges(&date("2024-06-15T20:00:00Z"))
+        );
+        assert_eq!(
+            Some(Ordering::Less),
+            date("2024-06-15T10:00:00").compare_ranges(&date("2024-06-16T01:00:00Z"))
+        );
+        assert_eq!(
+            Some(Ordering::Less),
+            date("2024-06-15T10:00:00").compare_ranges(&date("2024-06-15T10:00:01"))
+        );
+        assert_eq!(
+            Some(Ordering::Equal),
+            date("2024-05-01T10:00:00.000+02:00").compare_ranges(&date("2024-05-01T08:00:00.000Z"))
+        );
+        assert_eq!(
+            Some(Ordering::Equal),
+            date("2024-05-01T10:00:00+02:00").compare_ranges(&date("2024-05-01T08:00:00Z"))
+        );
+        assert_eq!(
+            None,
+            date("2024-05-01T10:00:00").compare_ranges(&date("2024-05-01T10:00:00Z"))
+        );
+    }
+
+    #[test]
+    fn test_add_should_clamp_and_truncate() {
+        let date = |v| FhirDateTime::parse(v).unwrap();
+
+        assert_eq!(
+            date("2024-02-29"),
+            date("2024-01-31").add(1, CalendarUnit::Month).unwrap()
+        );
+        assert_eq!(
+            date("2025-02-28"),
+            date("2024-02-29").add(1, CalendarUnit::Year).unwrap()
+        );
+        assert_eq!(
+            date("2024-03-01T01:00:00+01:00"),
+            date("2024-02-29T23:00:00+01:00")
+                .add(2, CalendarUnit::Hour)
+                .unwrap()
+        );
+        assert_eq!(
+            date("2024"),
+            date("2023").add(400, CalendarUnit::Day).unwrap()
+        );
+        assert_eq!(
+            date("2023-12-25"),
+            date("2024-01-01").add(-1, CalendarUnit::Week).unwrap()
+        )
+    }
+
+    #[test]
+    fn test_bounds_should_cover_precision() {
+        let value = FhirDateTime::parse("2024-02").unwrap();
+
+        assert_eq!("2024-02-01T00:00:00.000", value.lower_bound().to_string());
+        assert_eq!("2024-02-29T23:59:59.999", value.upper_bound().to_string())
+    }
+
+    #[test]
+    fn test_time_should_wrap_around_midnight() {
+        let time = FhirTime::parse("23:30").unwrap();
+
+        assert_eq!(
+            "01:00",
+            time.add(90, CalendarUnit::Minute).unwrap().to_string()
+        );
+        assert_eq!(
+            Some(Ordering::Less),
+            time.compare(&FhirTime::parse("23:45:10").unwrap())
+        )
+    }
+}
@@ -0,0 +1,357 @@
+use crate::{
+    FhirError,
+    r4::elements::{MILLIS_PER_DAY, civil_from_days, days_from_civil, days_in_month},
+};
+
+/// Maps instants to the UTC offset in force, used to turn the local times of
+/// availability and opening hours into instants.
+pub trait TimeZone {
+    /// The offset from UTC in minutes at `utc_millis`, milliseconds since the
+    /// Unix epoch.
+    fn offset_at(&self, utc_millis: i64) -> i32;
+
+    /// The instant at which the wall clock shows `local_millis`.
+    ///
+    /// A local time skipped by a forward transition is read with the offset
+    /// from before the transition, so it lands just after it. A local time
+    /// repeated by a backward transition resolves to the earlier instant.
+    fn to_utc(&self, local_millis: i64) -> i64 {
+        let before = self.offset_at(local_millis - MILLIS_PER_DAY);
+        let after = self.offset_at(local_millis + MILLIS_PER_DAY);
+        let valid = |offset: i32| {
+            let utc = local_millis - i64::from(offset) * 60_000;
+            (self.offset_at(utc) == offset).then_some(utc)
+        };
+        match (valid(before), valid(after)) {
+            (Some(left), Some(right)) => left.min(right),
+            (Some(utc), None) | (None, Some(utc)) => utc,
+            (None, None) => local_millis - i64::from(before) * 60_000,
+        }
+    }
+
+    /// The wall clock time at `utc_millis`.
+    fn to_local(&self, utc_millis: i64) -> i64 {
+        utc_millis + i64::from(self.offset_at(utc_millis)) * 60_000
+    }
+}
+
+impl<T: TimeZone + ?Sized> TimeZone for &T {
+    fn offset_at(&self, utc_millis: i64) -> i32 {
+        (**self).offset_at(utc_millis)
+    }
+}
+
+/// A zone that keeps the same offset all year, in minutes east of UTC.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FixedOffset(pub i32);
+
+impl FixedOffset {
+    pub const UTC: FixedOffset = FixedOffset(0);
+}
+
+impl TimeZone for FixedOffset {
+    fn offset_at(&self, _utc_millis: i64) -> i32 {
+        self.0
+    }
+}
+
+/// A recurring daylight saving transition, `M<month>.<week>.<weekday>` in a
+/// POSIX TZ string.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TransitionRule {
+    pub month: u8,
+    /// 1 to 4 for the n-th `weekday` of the month, 5 for the last one.
+    pub week: u8,
+    /// 0 for Sunday to 6 for Saturday.
+    pub weekday: u8,
+    /// Local time of the transition in seconds, may be negative or exceed a
+    /// day.
+    pub time: i64,
+}
+
+impl TransitionRule {
+    /// Local milliseconds since the epoch of the transition in `year`.
+    pub(crate) fn local_millis(&self, year: i32) -> i64 {
+        let first = days_from_civil(year, self.month, 1);
+        // 1970-01-01 was a Thursday.
+        let first_weekday = (first + 4).rem_euclid(7);
+        let mut day = first + (i64::from(self.weekday) - first_weekday).rem_euclid(7);
+        day += 7 * (i64::from(self.week) - 1);
+        let last = first + i64::from(days_in_month(year, self.month)) - 1;
+        while day > last {
+            day -= 7;
+        }
+        day * MILLIS_PER_DAY + self.time * 1_000
+    }
+}
+
+/// A zone described by a POSIX TZ string such as `CET-1CEST,M3.5.0,M10.5.0/3`,
+/// the format found at the end of every tzdata file.
+///
+/// Only the rules in force today are known, historic changes to a zone are
+/// not.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PosixTimeZone {
+    pub standard_name: String,
+    /// Minutes east of UTC outside daylight saving time.
+    pub standard_offset: i32,
+    pub daylight_name: Option<String>,
+    pub daylight_offset: i32,
+    pub rules: Option<(TransitionRule, TransitionRule)>,
+}
+
+impl PosixTimeZone {
+    pub fn parse(value: &str) -> Result<Self, FhirError> {
+        let invalid = || FhirError::DateTimeParseError(format!("invalid TZ string '{value}'"));
+        let mut rest = value;
+
+        let standard_name = take_name(&mut rest).ok_or_else(invalid)?;
+        // POSIX offsets count west of UTC, `CET-1` is one hour ahead.
+        let standard_offset = -take_offset(&mut rest).ok_or_else(invalid)? / 60;
+        if rest.is_empty() {
+            return Ok(Self {
+                standard_name,
+                standard_offset,
+                daylight_name: None,
+                daylight_offset: standard_offset,
+                rules: None,
+            });
+        }
+
+        let daylight_name = take_name(&mut rest).ok_or_else(invalid)?;
+        let daylight_offset = if rest.starts_with(',') || rest.is_empty() {
+            standard_offset + 60
+        } else {
+            -take_offset(&mut rest).ok_or_else(invalid)? / 60
+        };
+        let rules = match rest.strip_prefix(',') {
+            Some(rules) => {
+                let (start, end) = rules.split_once(',').ok_or_else(invalid)?;
+                (
+                    parse_rule(start).ok_or_else(invalid)?,
+                    parse_rule(end).ok_or_else(invalid)?,
+                )
+            }
+            // The rules of the United States, the POSIX default.
+            None if rest.is_empty() => (
+                TransitionRule {
+                    month: 3,
+                    week: 2,
+                    weekday: 0,
+                    time: 7_200,
+                },
+                TransitionRule {
+                    month: 11,
+                    week: 1,
+                    weekday: 0,
+                    time: 7_200,
+                },
+            ),
+            None => return Err(invalid()),
+        };
+        Ok(Self {
+            standard_name,
+            standard_offset,
+            daylight_name: Some(daylight_name),
+            daylight_offset,
+            rules: Some(rules),
+        })
+    }
+
+    /// Whether daylight saving time is in force at `utc_millis`.
+    pub fn is_daylight(&self, utc_millis: i64) -> bool {
+        let Some((start, end)) = &self.rules else {
+            return false;
+        };
+        let standard = utc_millis + i64::from(self.standard_offset) * 60_000;
+        let (year, _, _) = civil_from_days(standard.div_euclid(MILLIS_PER_DAY));
+        // The start is given in standard time, the end in daylight time.
+        let start = start.local_millis(year) - i64::from(self.standard_offset) * 60_000;
+        let end = end.local_millis(year) - i64::from(self.daylight_offset) * 60_000;
+        if start < end {
+            (start..end).contains(&utc_millis)
+        } else {
+            !(end..start).contains(&utc_millis)
+        }
+    }
+
+    /// The abbreviation in use at `utc_millis`, e.g. `CET` or `CEST`.
+    pub fn name_at(&self, utc_millis: i64) -> &str {
+        match &self.daylight_name {
+            Some(name) if self.is_daylight(utc_millis) => name,
+            _ => &self.standard_name,
+        }
+    }
+}
+
+impl TimeZone for PosixTimeZone {
+    fn offset_at(&self, utc_millis: i64) -> i32 {
+        if self.is_daylight(utc_millis) {
+            self.daylight_offset
+        } else {
+            self.standard_offset
+        }
+    }
+}
+
+/// Takes an abbreviation, alphabetic or quoted as in `<+03>`.
+fn take_name(rest: &mut &str) -> Option<String> {
+    let (name, remainder) = match rest.strip_prefix('<') {
+        Some(quoted) => {
+            let (name, remainder) = quoted.split_once('>')?;
+            (name, remainder)
+        }
+        None => {
+            let end = rest
+                .find(|c: char| !c.is_ascii_alphabetic())
+                .unwrap_or(rest.len());
+            rest.split_at(end)
+        }
+    };
+    if name.len() < 3 {
+        return None;
+    }
+    *rest = remainder;
+    Some(name.to_string())
+}
+
+/// Takes a `[+-]hh[:mm[:ss]]` offset or time, returning seconds.
+fn take_offset(rest: &mut &str) -> Option<i32> {
+    let end = rest
+        .find(|c: char| !(c.is_ascii_digit() || matches!(c, '+' | '-' | ':')))
+        .unwrap_or(rest.len());
+    let (value, remainder) = rest.split_at(end);
+    let seconds = parse_seconds(value)?;
+    *rest = remainder;
+    i32::try_from(seconds).ok()
+}
+
+fn parse_seconds(value: &str) -> Option<i64> {
+    let (sign, value) = match value.as_bytes().first()? {
+        b'-' => (-1, &value[1..]),
+        b'+' => (1, &value[1..]),
+        _ => (1, value),
+    };
+    let mut parts = value.split(':');
+    let hours: i64 = parts.next()?.parse().ok()?;
+    let minutes: i64 = parts.next().map_or(Some(0), |part| part.parse().ok())?;
+    let seconds: i64 = parts.next().map_or(Some(0), |part| part.parse().ok())?;
+    if parts.next().is_some() || hours > 167 || minutes > 59 || seconds > 59 {
+        return None;
+    }
+    Some(sign * (hours * 3_600 + minutes * 60 + seconds))
+}
+
+/// Parses `Mm.w.d[/time]`, the Julian day forms are not supported.
+fn parse_rule(value: &str) -> Option<TransitionRule> {
+    let (date, time) = match value.split_once('/') {
+        Some((date, time)) => (date, parse_seconds(time)?),
+        None => (value, 7_200),
+    };
+    let mut parts = date.strip_prefix('M')?.split('.');
+    let month: u8 = parts.next()?.parse().ok()?;
+    let week: u8 = parts.next()?.parse().ok()?;
+    let weekday: u8 = parts.next()?.parse().ok()?;
+    if parts.next().is_some()
+        || !(1..=12).contains(&month)
+        || !(1..=5).contains(&week)
+        || weekday > 6
+    {
+        return None;
+    }
+    Some(TransitionRule {
+        month,
+        week,
+        weekday,
+        time,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use crate::r4::elements::FhirDateTime;
+
+    use super::*;
+
+    fn utc(value: &str) -> i64 {
+        FhirDateTime::parse(value).unwrap().epoch_millis(0)
+    }
+
+    #[test]
+    fn test_parse_should_read_posix_tz_strings() {
+        let amsterdam = PosixTimeZone::parse("CET-1CEST,M3.5.0,M10.5.0/3").unwrap();
+        assert_eq!(60, amsterdam.standard_offset);
+        assert_eq!(120, amsterdam.daylight_offset);
+        assert_eq!(
+            Some((
+                TransitionRule {
+                    month: 3,
+                    week: 5,
+                    weekday: 0,
+                    time: 7_200
+                },
+                TransitionRule {
+                    month: 10,
+                    week: 5,
+                    weekday: 0,
+                    time: 10_800
+                }
+            )),
+            amsterdam.rules
+        );
+
+        let india = PosixTimeZone::parse("IST-5:30").unwrap();
+        assert_eq!(330, india.offset_at(0));
+        let quoted = PosixTimeZone::parse("<-03>3").unwrap();
+        assert_eq!(-180, quoted.standard_offset);
+        assert!(PosixTimeZone::parse("CET").is_err());
+        assert!(PosixTimeZone::parse("CET-1CEST,J60,M10.5.0").is_err());
+    }
+
+    #[test]
+    fn test_offset_at_should_follow_transitions() {
+        let amsterdam = PosixTimeZone::parse("CET-1CEST,M3.5.0,M10.5.0/3").unwrap();
+        // 2024-03-31 and 2024-10-27 are the last Sundays of the months.
+        assert_eq!(60, amsterdam.offset_at(utc("2024-03-31T00:59:59Z")));
+        assert_eq!(120, amsterdam.offset_at(utc("2024-03-31T01:00:00Z")));
+        assert_eq!(120, amsterdam.offset_at(utc("2024-10-27T00:59:59Z")));
+        assert_eq!(60, amsterdam.offset_at(utc("2024-10-27T01:00:00Z")));
+        assert_eq!("CEST", amsterdam.name_at(utc("2024-07-01T00:00:00Z")));
+
+        let new_york = PosixTimeZone::parse("EST5EDT,M3.2.0,M11.1.0").unwrap();
+        assert_eq!(-300, new_york.offset_at(utc("2024-03-10T06:59:00Z")));
+        assert_eq!(-240, new_york.offset_at(utc("2024-03-10T07:00:00Z")));
+        assert_eq!(-300, new_york.offset_at(utc("2024-11-03T06:00:00Z")));
+
+        // The southern hemisphere observes daylight saving time over new year.
+        let sydney = PosixTimeZone::parse("AEST-10AEDT,M10.1.0,M4.1.0/3").unwrap();
+        assert_eq!(660, sydney.offset_at(utc("2024-01-15T00:00:00Z")));
+        assert_eq!(600, sydney.offset_at(utc("2024-07-15T00:00:00Z")));
+    }
+
+    #[test]
+    fn test_to_utc_should_resolve_gaps_and_overlaps() {
+        let amsterdam = PosixTimeZone::parse("CET-1CEST,M3.5.0,M10.5.0/3").unwrap();
+        let local = |value: &str| FhirDateTime::parse(value).unwrap().epoch_millis(0);
+
+        assert_eq!(
+            utc("2024-07-01T10:00:00Z"),
+            amsterdam.to_utc(local("2024-07-01T12:00:00Z"))
+        );
+        // 02:30 does not exist on 2024-03-31, it is read as 03:30 CEST.
+        assert_eq!(
+            utc("2024-03-31T01:30:00Z"),
+            amsterdam.to_utc(local("2024-03-31T02:30:00Z"))
+        );
+        // 02:30 happens twice on 2024-10-27, the first one is taken.
+        assert_eq!(
+            utc("2024-10-27T00:30:00Z"),
+            amsterdam.to_utc(local("2024-10-27T02:30:00Z"))
+        );
+        assert_eq!(
+            local("2024-10-27T02:30:00Z"),
+            amsterdam.to_local(utc("2024-10-27T01:30:00Z"))
+        );
+        assert_eq!(0, FixedOffset::UTC.to_utc(0));
+    }
+}
@@ -7,13 +7,16 @@ use crate::{
     r4::{
         elements::{complex_types::Identifier, element::Element},
         resources::{
-            Endpoint, HealthcareService, Location, Organization, Practitioner, ResourceType,
+            CodeSystem, ConceptMap, Device, DeviceDefinition, Endpoint, HealthcareService,
+            Location, NamingSystem, Organization, OrganizationAffiliation, Practitioner,
+            PractitionerRole, ReferenceTarget, ResourceType, StructureDefinition, ValueSet,
         },
+        terminology::{OID_URN_PREFIX, UUID_URN_PREFIX, is_oid},
     },
 };
 
 #[derive(Debug, Serialize, PartialEq, Deserialize, Default, Clone)]
-#[serde(rename_all(serialize = "snake_case", deserialize = "camelCase"))]
+#[serde(rename_all(serialize = "camelCase", deserialize = "camelCase"))]
 pub struct Reference<T: ResourceType> {
     #[serde(flatten)]
     pub element: Element,
@@ -48,6 +51,248 @@ where
     }
 }
 
+impl<T: ResourceType> Reference<T> {
+    /// Parses `reference` into its structured form, returns `Ok(None)` for a
+    /// logical reference that only carries an identifier or display.
+    ///
+    /// Fails when the reference is malformed or when the resource type, taken
+    /// from the reference itself or from the `type` element, is not `T`.
+    pub fn target(&self) -> Result<Option<ParsedReference<'_>>, FhirError> {
+        // `type` is a URI, `http://hl7.org/fhir/StructureDefinition/Organization`
+        // names the same type as `Organization`.
+        if let Some(r#type) = &self.r#type
+            && r#type.rsplit('/').next() != Some(T::TYPE)
+        {
+            return Err(FhirError::ReferenceError(format!(
+                "type '{type}' does not match the expected target {}",
+                T::TYPE
+            )));
+        }
+        let Some(reference) = &self.reference else {
+            return Ok(None);
+        };
+        let parsed = ParsedReference::parse(reference)?;
+        if let Some(resource_type) = parsed.resource_type()
+            && resource_type != T::TYPE
+        {
+            return Err(FhirError::ReferenceError(format!(
+                "'{reference}' does not point to a {}",
+                T::TYPE
+            )));
+        }
+        Ok(Some(parsed))
+    }
+
+    /// Returns the id of the target when the reference names it, which is the
+    /// case for relative and RESTful absolute references.
+    pub fn target_id(&self) -> Option<&str> {
+        self.target().ok().flatten().and_then(|parsed| parsed.id())
+    }
+}
+
+impl<T: ReferenceTarget> Reference<T> {
+    /// Creates a reference to `resource`, filling `reference` with the
+    /// relative `Type/id` when the resource has an id.
+    pub fn to(resource: &T) -> Self {
+        Reference {
+            element: Element::default(),
+            reference: resource.resource_id().map(|id| format!("{}/{id}", T::TYPE)),
+            r#type: Some(T::TYPE.to_string()),
+            display: resource.reference_display(),
+            identifier: None,
+            _m: PhantomData,
+        }
+    }
+}
+
+/// The untyped view of a `Reference<T>`, shared by every `ReferenceTypes`
+/// variant.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct ReferenceView<'a> {
+    /// `T::TYPE`, the declared target type.
+    pub target_type: &'static str,
+    pub reference: Option<&'a str>,
+    pub r#type: Option<&'a str>,
+    pub display: Option<&'a str>,
+    pub identifier: &'a [Identifier],
+}
+
+impl<T: ResourceType> Reference<T> {
+    pub fn view(&self) -> ReferenceView<'_> {
+        ReferenceView {
+            target_type: T::TYPE,
+            reference: self.reference.as_deref(),
+            r#type: self.r#type.as_deref(),
+            display: self.display.as_deref(),
+            identifier: self.identifier.as_deref().unwrap_or_default(),
+        }
+    }
+}
+
+/// The structured form of `Reference.reference`.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum ParsedReference<'a> {
+    /// `Type/id` or `Type/id/_history/version`, relative to the server base.
+    Relative {
+        resource_type: &'a str,
+        id: &'a str,
+        version: Option<&'a str>,
+    },
+    /// A RESTful absolute URL `base/Type/id` with an optional `_history`.
+    Absolute {
+        base: &'a str,
+        resource_type: &'a str,
+        id: &'a str,
+        version: Option<&'a str>,
+    },
+    /// Any other absolute URL, the target type cannot be derived from it.
+    Url(&'a str),
+    /// `#id`, a resource contained in the referencing resource, `#` alone
+    /// refers to the container itself.
+    Contained(&'a str),
+    /// `urn:uuid:` followed by a UUID, typically a `fullUrl` inside a Bundle.
+    Uuid(&'a str),
+    /// `urn:oid:` followed by an OID.
+    Oid(&'a str),
+}
+
+const HISTORY_SEGMENT: &str = "_history";
+
+impl<'a> ParsedReference<'a> {
+    pub fn parse(reference: &'a str) -> Result<Self, FhirError> {
+        let invalid =
+            |reason: &str| Err(FhirError::ReferenceError(format!("'{reference}' {reason}")));
+        if reference.is_empty() {
+            return invalid("is empty");
+        }
+        if let Some(id) = reference.strip_prefix('#') {
+            if !id.is_empty() && !is_id(id) {
+                return invalid("is not a valid contained reference");
+            }
+            return Ok(Self::Contained(id));
+        }
+        if let Some(uuid) = strip_prefix_ignore_case(reference, UUID_URN_PREFIX) {
+            if !is_uuid(uuid) {
+                return invalid("is not a valid urn:uuid");
+            }
+            return Ok(Self::Uuid(uuid));
+        }
+        if let Some(oid) = strip_prefix_ignore_case(reference, OID_URN_PREFIX) {
+            if !is_oid(oid) {
+                return invalid("is not a valid urn:oid");
+            }
+            return Ok(Self::Oid(oid));
+        }
+        if reference.contains('?') {
+            return invalid("is a conditional reference, which cannot be stored");
+        }
+        if let Some((scheme, _)) = reference.split_once("://") {
+            if scheme.is_empty()
+                || !scheme
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || "+-.".contains(c))
+            {
+                return invalid("is not a valid URL");
+            }
+            return Ok(match split_rest_path(reference) {
+                Some((base, resource_type, id, version)) if !base.ends_with(':') => {
+                    Self::Absolute {
+                        base,
+                        resource_type,
+                        id,
+                        version,
+                    }
+                }
+                _ => Self::Url(reference),
+            });
+        }
+        match split_rest_path(reference) {
+            Some(("", resource_type, id, version)) => Ok(Self::Relative {
+                resource_type,
+                id,
+                version,
+            }),
+            _ => invalid("is not of the form Type/id or Type/id/_history/version"),
+        }
+    }
+
+    /// The resource type named by the reference, `None` when it cannot be
+    /// derived from the reference alone.
+    pub fn resource_type(&self) -> Option<&'a str> {
+        match self {
+            Self::Relative { resource_type, .. } | Self::Absolute { resource_type, .. } => {
+                Some(resource_type)
+            }
+            _ => None,
+        }
+    }
+
+    pub fn id(&self) -> Option<&'a str> {
+        match self {
+            Self::Relative { id, .. } | Self::Absolute { id, .. } => Some(id),
+            Self::Contained(id) if !id.is_empty() => Some(id),
+            _ => None,
+        }
+    }
+
+    pub fn version(&self) -> Option<&'a str> {
+        match self {
+            Self::Relative { version, .. } | Self::Absolute { version, .. } => *version,
+            _ => None,
+        }
+    }
+}
+
+/// Splits `[base/]Type/id[/_history/version]`, the base is empty for a
+/// relative reference.
+fn split_rest_path(path: &str) -> Option<(&str, &str, &str, Option<&str>)> {
+    let segments: Vec<&str> = path.split('/').collect();
+    let (head, version) = match segments.as_slice() {
+        [head @ .., HISTORY_SEGMENT, version] if is_id(version) => (head, Some(*version)),
+        _ => (segments.as_slice(), None),
+    };
+    let [base @ .., resource_type, id] = head else {
+        return None;
+    };
+    if !is_resource_type(resource_type) || !is_id(id) {
+        return None;
+    }
+    let base_len = base.iter().map(|segment| segment.len() + 1).sum::<usize>();
+    Some((
+        path[..base_len].trim_end_matches('/'),
+        resource_type,
+        id,
+        version,
+    ))
+}
+
+fn is_resource_type(value: &str) -> bool {
+    value.chars().next().is_some_and(|c| c.is_ascii_uppercase())
+        && value.chars().all(|c| c.is_ascii_alphanumeric())
+}
+
+/// The R4 `id` datatype: 1 to 64 letters, digits, `-` and `.`.
+fn is_id(value: &str) -> bool {
+    (1..=64).contains(&value.len())
+        && value
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '.')
+}
+
+fn is_uuid(value: &str) -> bool {
+    value.len() == 36
+        && value.char_indices().all(|(index, c)| match index {
+            8 | 13 | 18 | 23 => c == '-',
+            _ => c.is_ascii_hexdigit(),
+        })
+}
+
+fn strip_prefix_ignore_case<'a>(value: &'a str, prefix: &str) -> Option<&'a str> {
+    let head = value.get(..prefix.len())?;
+    head.eq_ignore_ascii_case(prefix)
+        .then(|| &value[prefix.len()..])
+}
+
 #[derive(Debug, PartialEq)]
 pub enum ReferenceTypes<'a> {
     ReferenceOrganization(&'a Reference<Organization>),
@@ -55,6 +300,15 @@ pub enum ReferenceTypes<'a> {
     ReferenceLocation(&'a Reference<Location>),
     ReferecenceHealthcareServce(&'a Reference<HealthcareService>),
     ReferencePractitioner(&'a Reference<Practitioner>),
+    ReferenceDevice(&'a Reference<Device>),
+    ReferenceDeviceDefinition(&'a Reference<DeviceDefinition>),
+    ReferencePractitionerRole(&'a Reference<PractitionerRole>),
+    ReferenceOrganizationAffiliation(&'a Reference<OrganizationAffiliation>),
+    ReferenceCodeSystem(&'a Reference<CodeSystem>),
+    ReferenceConceptMap(&'a Reference<ConceptMap>),
+    ReferenceNamingSystem(&'a Reference<NamingSystem>),
+    ReferenceStructureDefinition(&'a Reference<StructureDefinition>),
+    ReferenceValueSet(&'a Reference<ValueSet>),
 }
 
 impl<'a> From<&'a Reference<Endpoint>> for ReferenceTypes<'a> {
@@ -87,8 +341,266 @@ impl<'a> From<&'a Reference<Practitioner>> for ReferenceTypes<'a> {
     }
 }
 
+impl<'a> From<&'a Reference<Device>> for ReferenceTypes<'a> {
+    fn from(value: &'a Reference<Device>) -> Self {
+        Self::ReferenceDevice(value)
+    }
+}
+
+impl<'a> From<&'a Reference<DeviceDefinition>> for ReferenceTypes<'a> {
+    fn from(value: &'a Reference<DeviceDefinition>) -> Self {
+        Self::ReferenceDeviceDefinition(value)
+    }
+}
+
+impl<'a> From<&'a Reference<PractitionerRole>> for ReferenceTypes<'a> {
+    fn from(value: &'a Reference<PractitionerRole>) -> Self {
+        Self::ReferencePractitionerRole(value)
+    }
+}
+
+impl<'a> From<&'a Reference<OrganizationAffiliation>> for ReferenceTypes<'a> {
+    fn from(value: &'a Reference<OrganizationAffiliation>) -> Self {
+        Self::ReferenceOrganizationAffiliation(value)
+    }
+}
+
+impl<'a> From<&'a Reference<CodeSystem>> for ReferenceTypes<'a> {
+    fn from(value: &'a Reference<CodeSystem>) -> Self {
+        Self::ReferenceCodeSystem(value)
+    }
+}
+
+impl<'a> From<&'a Reference<ConceptMap>> for ReferenceTypes<'a> {
+    fn from(value: &'a Reference<ConceptMap>) -> Self {
+        Self::ReferenceConceptMap(value)
+    }
+}
+
+impl<'a> From<&'a Reference<NamingSystem>> for ReferenceTypes<'a> {
+    fn from(value: &'a Reference<NamingSystem>) -> Self {
+        Self::ReferenceNamingSystem(value)
+    }
+}
+
+impl<'a> From<&'a Reference<StructureDefinition>> for ReferenceTypes<'a> {
+    fn from(value: &'a Reference<StructureDefinition>) -> Self {
+        Self::ReferenceStructureDefinition(value)
+    }
+}
+
+impl<'a> From<&'a Reference<ValueSet>> for ReferenceTypes<'a> {
+    fn from(value: &'a Reference<ValueSet>) -> Self {
+        Self::ReferenceValueSet(value)
+    }
+}
+
+impl<'a> ReferenceTypes<'a> {
+    pub fn view(&self) -> ReferenceView<'a> {
+        match self {
+            Self::ReferenceOrganization(reference) => reference.view(),
+            Self::ReferenceEndpoint(reference) => reference.view(),
+            Self::ReferenceLocation(reference) => reference.view(),
+            Self::ReferecenceHealthcareServce(reference) => reference.view(),
+            Self::ReferencePractitioner(reference) => reference.view(),
+            Self::ReferenceDevice(reference) => reference.view(),
+            Self::ReferenceDeviceDefinition(reference) => reference.view(),
+            Self::ReferencePractitionerRole(reference) => reference.view(),
+            Self::ReferenceOrganizationAffiliation(reference) => reference.view(),
+            Self::ReferenceCodeSystem(reference) => reference.view(),
+            Self::ReferenceConceptMap(reference) => reference.view(),
+            Self::ReferenceNamingSystem(reference) => reference.view(),
+            Self::ReferenceStructureDefinition(reference) => reference.view(),
+            Self::ReferenceValueSet(reference) => reference.view(),
+        }
+    }
+
+    /// See [`Reference::target`].
+    pub fn target(&self) -> Result<Option<ParsedReference<'a>>, FhirError> {
+        match self {
+            Self::ReferenceOrganization(reference) => reference.target(),
+            Self::ReferenceEndpoint(reference) => reference.target(),
+            Self::ReferenceLocation(reference) => reference.target(),
+            Self::ReferecenceHealthcareServce(reference) => reference.target(),
+            Self::ReferencePractitioner(reference) => reference.target(),
+            Self::ReferenceDevice(reference) => reference.target(),
+            Self::ReferenceDeviceDefinition(reference) => reference.target(),
+            Self::ReferencePractitionerRole(reference) => reference.target(),
+            Self::ReferenceOrganizationAffiliation(reference) => reference.target(),
+            Self::ReferenceCodeSystem(reference) => reference.target(),
+            Self::ReferenceConceptMap(reference) => reference.target(),
+            Self::ReferenceNamingSystem(reference) => reference.target(),
+            Self::ReferenceStructureDefinition(reference) => reference.target(),
+            Self::ReferenceValueSet(reference) => reference.target(),
+        }
+    }
+}
+
 pub trait GetResourceReferences {
-    fn get_references(&self) -> Vec<ReferenceTypes>;
+    fn get_references(&self) -> Vec<ReferenceTypes<'_>>;
+}
+
+impl GetResourceReferences for Identifier {
+    fn get_references(&self) -> Vec<ReferenceTypes<'_>> {
+        self.assigner.iter().map(ReferenceTypes::from).collect()
+    }
+}
+
+/// The mutable counterpart of [`ReferenceTypes`].
+#[derive(Debug, PartialEq)]
+pub enum ReferenceTypesMut<'a> {
+    ReferenceOrganization(&'a mut Reference<Organization>),
+    ReferenceEndpoint(&'a mut Reference<Endpoint>),
+    ReferenceLocation(&'a mut Reference<Location>),
+    ReferecenceHealthcareServce(&'a mut Reference<HealthcareService>),
+    ReferencePractitioner(&'a mut Reference<Practitioner>),
+    ReferenceDevice(&'a mut Reference<Device>),
+    ReferenceDeviceDefinition(&'a mut Reference<DeviceDefinition>),
+    ReferencePractitionerRole(&'a mut Reference<PractitionerRole>),
+    ReferenceOrganizationAffiliation(&'a mut Reference<OrganizationAffiliation>),
+    ReferenceCodeSystem(&'a mut Reference<CodeSystem>),
+    ReferenceConceptMap(&'a mut Reference<ConceptMap>),
+    ReferenceNamingSystem(&'a mut Reference<NamingSystem>),
+    ReferenceStructureDefinition(&'a mut Reference<StructureDefinition>),
+    ReferenceValueSet(&'a mut Reference<ValueSet>),
+}
+
+impl<'a> From<&'a mut Reference<Organization>> for ReferenceTypesMut<'a> {
+    fn from(value: &'a mut Reference<Organization>) -> Self {
+        Self::ReferenceOrganization(value)
+    }
+}
+
+impl<'a> From<&'a mut Reference<Endpoint>> for ReferenceTypesMut<'a> {
+    fn from(value: &'a mut Reference<Endpoint>) -> Self {
+        Self::ReferenceEndpoint(value)
+    }
+}
+
+impl<'a> From<&'a mut Reference<Location>> for ReferenceTypesMut<'a> {
+    fn from(value: &'a mut Reference<Location>) -> Self {
+        Self::ReferenceLocation(value)
+    }
+}
+
+impl<'a> From<&'a mut Reference<HealthcareService>> for ReferenceTypesMut<'a> {
+    fn from(value: &'a mut Reference<HealthcareService>) -> Self {
+        Self::ReferecenceHealthcareServce(value)
+    }
+}
+
+impl<'a> From<&'a mut Reference<Practitioner>> for ReferenceTypesMut<'a> {
+    fn from(value: &'a mut Reference<Practitioner>) -> Self {
+        Self::ReferencePractitioner(value)
+    }
+}
+
+impl<'a> From<&'a mut Reference<Device>> for ReferenceTypesMut<'a> {
+    fn from(value: &'a mut Reference<Device>) -> Self {
+        Self::ReferenceDevice(value)
+    }
+}
+
+impl<'a> From<&'a mut Reference<DeviceDefinition>> for ReferenceTypesMut<'a> {
+    fn from(value: &'a mut Reference<DeviceDefinition>) -> Self {
+        Self::ReferenceDeviceDefinition(value)
+    }
+}
+
+impl<'a> From<&'a mut Reference<PractitionerRole>> for ReferenceTypesMut<'a> {
+    fn from(value: &'a mut Reference<PractitionerRole>) -> Self {
+        Self::ReferencePractitionerRole(value)
+    }
+}
+
+impl<'a> From<&'a mut Reference<OrganizationAffiliation>> for ReferenceTypesMut<'a> {
+    fn from(value: &'a mut Reference<OrganizationAffiliation>) -> Self {
+        Self::ReferenceOrganizationAffiliation(value)
+    }
+}
+
+impl<'a> From<&'a mut Reference<CodeSystem>> for ReferenceTypesMut<'a> {
+    fn from(value: &'a mut Reference<CodeSystem>) -> Self {
+        Self::ReferenceCodeSystem(value)
+    }
+}
+
+impl<'a> From<&'a mut Reference<ConceptMap>> for ReferenceTypesMut<'a> {
+    fn from(value: &'a mut Reference<ConceptMap>) -> Self {
+        Self::ReferenceConceptMap(value)
+    }
+}
+
+impl<'a> From<&'a mut Reference<NamingSystem>> for ReferenceTypesMut<'a> {
+    fn from(value: &'a mut Reference<NamingSystem>) -> Self {
+        Self::ReferenceNamingSystem(value)
+    }
+}
+
+impl<'a> From<&'a mut Reference<StructureDefinition>> for ReferenceTypesMut<'a> {
+    fn from(value: &'a mut Reference<StructureDefinition>) -> Self {
+        Self::ReferenceStructureDefinition(value)
+    }
+}
+
+impl<'a> From<&'a mut Reference<ValueSet>> for ReferenceTypesMut<'a> {
+    fn from(value: &'a mut Reference<ValueSet>) -> Self {
+        Self::ReferenceValueSet(value)
+    }
+}
+
+impl ReferenceTypesMut<'_> {
+    pub fn view(&self) -> ReferenceView<'_> {
+        match self {
+            Self::ReferenceOrganization(reference) => reference.view(),
+            Self::ReferenceEndpoint(reference) => reference.view(),
+            Self::ReferenceLocation(reference) => reference.view(),
+            Self::ReferecenceHealthcareServce(reference) => reference.view(),
+            Self::ReferencePractitioner(reference) => reference.view(),
+            Self::ReferenceDevice(reference) => reference.view(),
+            Self::ReferenceDeviceDefinition(reference) => reference.view(),
+            Self::ReferencePractitionerRole(reference) => reference.view(),
+            Self::ReferenceOrganizationAffiliation(reference) => reference.view(),
+            Self::ReferenceCodeSystem(reference) => reference.view(),
+            Self::ReferenceConceptMap(reference) => reference.view(),
+            Self::ReferenceNamingSystem(reference) => reference.view(),
+            Self::ReferenceStructureDefinition(reference) => reference.view(),
+            Self::ReferenceValueSet(reference) => reference.view(),
+        }
+    }
+
+    /// Mutable access to `Reference.reference`.
+    pub fn reference_mut(&mut self) -> &mut Option<String> {
+        match self {
+            Self::ReferenceOrganization(reference) => &mut reference.reference,
+            Self::ReferenceEndpoint(reference) => &mut reference.reference,
+            Self::ReferenceLocation(reference) => &mut reference.reference,
+            Self::ReferecenceHealthcareServce(reference) => &mut reference.reference,
+            Self::ReferencePractitioner(reference) => &mut reference.reference,
+            Self::ReferenceDevice(reference) => &mut reference.reference,
+            Self::ReferenceDeviceDefinition(reference) => &mut reference.reference,
+            Self::ReferencePractitionerRole(reference) => &mut reference.reference,
+            Self::ReferenceOrganizationAffiliation(reference) => &mut reference.reference,
+            Self::ReferenceCodeSystem(reference) => &mut reference.reference,
+            Self::ReferenceConceptMap(reference) => &mut reference.reference,
+            Self::ReferenceNamingSystem(reference) => &mut reference.reference,
+            Self::ReferenceStructureDefinition(reference) => &mut reference.reference,
+            Self::ReferenceValueSet(reference) => &mut reference.reference,
+        }
+    }
+}
+
+pub trait GetResourceReferencesMut {
+    fn get_references_mut(&mut self) -> Vec<ReferenceTypesMut<'_>>;
+}
+
+impl GetResourceReferencesMut for Identifier {
+    fn get_references_mut(&mut self) -> Vec<ReferenceTypesMut<'_>> {
+        self.assigner
+            .iter_mut()
+            .map(ReferenceTypesMut::from)
+            .collect()
+    }
 }
 
 #[derive(Default)]
@@ -145,10 +657,23 @@ impl ReferenceBuilder {
             _m: PhantomData,
         }
     }
+
+    /// Like [`Self::build`], but fails when the reference is malformed or
+    /// does not point to a `T`.
+    pub fn try_build<T: ResourceType>(self) -> Result<Reference<T>, FhirError> {
+        let reference = self.build();
+        reference.target()?;
+        Ok(reference)
+    }
 }
 
 #[cfg(test)]
 mod test {
+    use crate::r4::{
+        elements::HumanNameBuilder,
+        resources::{OrganizationBuilder, PractitionerBuilder, PractitionerRoleBuilder},
+    };
+
     use super::*;
 
     #[test]
@@ -195,4 +720,153 @@ mod test {
 
         assert_eq!(expected, actual)
     }
+
+    #[test]
+    fn test_parse_reference_forms() {
+        let cases = [
+            (
+                "Organization/1",
+                ParsedReference::Relative {
+                    resource_type: "Organization",
+                    id: "1",
+                    version: None,
+                },
+            ),
+            (
+                "Organization/org.a-1/_history/3",
+                ParsedReference::Relative {
+                    resource_type: "Organization",
+                    id: "org.a-1",
+                    version: Some("3"),
+                },
+            ),
+            (
+                "https://example.org/fhir/Location/loc-1/_history/2",
+                ParsedReference::Absolute {
+                    base: "https://example.org/fhir",
+                    resource_type: "Location",
+                    id: "loc-1",
+                    version: Some("2"),
+                },
+            ),
+            (
+                "https://example.org/directory/entry",
+                ParsedReference::Url("https://example.org/directory/entry"),
+            ),
+            ("#ep1", ParsedReference::Contained("ep1")),
+            ("#", ParsedReference::Contained("")),
+            (
+                "urn:uuid:9d3e5b2a-3c1f-4a8e-9b6d-0f1e2d3c4b5a",
+                ParsedReference::Uuid("9d3e5b2a-3c1f-4a8e-9b6d-0f1e2d3c4b5a"),
+            ),
+            (
+                "urn:oid:2.16.840.1.113883.2.4.6.1",
+                ParsedReference::Oid("2.16.840.1.113883.2.4.6.1"),
+            ),
+        ];
+
+        for (reference, expected) in cases {
+            assert_eq!(expected, ParsedReference::parse(reference).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_parse_invalid_references_should_fail() {
+        for reference in [
+            "",
+            "Organization",
+            "organization/1",
+            "Organization/1/_history",
+            "Organization?identifier=123",
+            "urn:uuid:not-a-uuid",
+            "urn:oid:3.1",
+            "#not valid",
+        ] {
+            assert!(
+                ParsedReference::parse(reference).is_err(),
+                "{reference} should not parse"
+            );
+        }
+    }
+
+    #[test]
+    fn test_target_should_check_resource_type() {
+        let reference = ReferenceBuilder::default()
+            .with_reference("Endpoint/1")
+            .build::<Organization>();
+        assert_eq!(
+            "invalid reference: 'Endpoint/1' does not point to a Organization",
+            reference.target().unwrap_err().to_string()
+        );
+
+        let reference = ReferenceBuilder::default()
+            .with_reference("#ep1")
+            .with_type("Endpoint")
+            .build::<Organization>();
+        assert!(reference.target().is_err());
+
+        let reference = ReferenceBuilder::default()
+            .with_reference("https://example.org/fhir/Organization/1")
+            .with_type("http://hl7.org/fhir/StructureDefinition/Organization")
+            .build::<Organization>();
+        assert_eq!(Some("1"), reference.target_id());
+
+        let reference = ReferenceBuilder::default()
+            .with_display("Acme")
+            .build::<Organization>();
+        assert_eq!(None, reference.target().unwrap());
+    }
+
+    #[test]
+    fn test_try_build_should_reject_mismatched_target() {
+        let result = ReferenceBuilder::default()
+            .with_reference("Endpoint/1")
+            .try_build::<Organization>();
+
+        assert!(result.is_err());
+        assert!(
+            ReferenceBuilder::default()
+                .with_reference("Endpoint/1")
+                .try_build::<Endpoint>()
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn test_to_should_fill_reference_type_and_display() {
+        let organization = OrganizationBuilder::new("org-1").name("Acme").build();
+
+        let reference = Reference::to(&organization);
+
+        assert_eq!(Some("Organization/org-1".to_string()), reference.reference);
+        assert_eq!(Some("Organization".to_string()), reference.r#type);
+        assert_eq!(Some("Acme".to_string()), reference.display);
+        assert!(reference.target().is_ok());
+
+        let practitioner = PractitionerBuilder::new("pr-1")
+            .add_name(
+                HumanNameBuilder::default()
+                    .add_prefix("Dr.")
+                    .add_given("Anna")
+                    .with_family("Smit")
+                    .build(),
+            )
+            .build();
+        assert_eq!(
+            Some("Dr. Anna Smit".to_string()),
+            Reference::to(&practitioner).display
+        );
+    }
+
+    #[test]
+    fn test_reference_types_should_cover_practitioner_role() {
+        let role = PractitionerRoleBuilder::new("role-1").build();
+        let reference = Reference::to(&role);
+
+        let view = ReferenceTypes::from(&reference).view();
+
+        assert_eq!("PractitionerRole", view.target_type);
+        assert_eq!(Some("PractitionerRole/role-1"), view.reference);
+        assert_eq!(None, view.display);
+    }
 }
@@ -0,0 +1,946 @@
+//! The FHIRPath function library, see <http://hl7.org/fhirpath/N1/#functions>.
+
+use std::borrow::Cow;
+
+use serde_json::{Value, json};
+
+use crate::{
+    FhirError,
+    r4::elements::{DateTimePrecision, FhirDateTime, FhirTime},
+};
+
+use super::{
+    evaluator::{
+        Collection, Evaluator, Scope, child, contains, distinct, node, push_json, singleton,
+        to_boolean,
+    },
+    parser::{Expression, TypeSpecifier},
+    regex::Regex,
+    value::{FhirPathValue, convert_unit, format_decimal, is_calendar_keyword},
+};
+
+fn error(message: String) -> FhirError {
+    FhirError::FhirPathError(message)
+}
+
+fn one<'a>(value: Option<FhirPathValue<'a>>) -> Collection<'a> {
+    value.into_iter().collect()
+}
+
+fn boolean<'a>(value: bool) -> Collection<'a> {
+    vec![FhirPathValue::Boolean(value)]
+}
+
+impl<'a> Evaluator<'_, 'a> {
+    pub(super) fn call(
+        &self,
+        name: &str,
+        input: Collection<'a>,
+        arguments: &[Expression],
+        scope: &Scope<'a>,
+    ) -> Result<Collection<'a>, FhirError> {
+        let arity = |min: usize, max: usize| {
+            if (min..=max).contains(&arguments.len()) {
+                Ok(())
+            } else {
+                Err(error(format!(
+                    "{name}() expects {min}..{max} arguments, got {}",
+                    arguments.len()
+                )))
+            }
+        };
+
+        match name {
+            // existence
+            "empty" => arity(0, 0).map(|_| boolean(input.is_empty())),
+            "exists" => {
+                arity(0, 1)?;
+                let matching = match arguments.first() {
+                    Some(criteria) => self.filter(input, criteria)?,
+                    None => input,
+                };
+                Ok(boolean(!matching.is_empty()))
+            }
+            "all" => {
+                arity(1, 1)?;
+                for (index, item) in input.into_iter().enumerate() {
+                    let result = self.lambda(&arguments[0], item, index, None)?;
+                    if to_boolean(result)? != Some(true) {
+                        return Ok(boolean(false));
+                    }
+                }
+                Ok(boolean(true))
+            }
+            "allTrue" | "anyTrue" | "allFalse" | "anyFalse" => {
+                arity(0, 0)?;
+                let expected = name.ends_with("True");
+                let mut values = input.iter().map(|item| match item {
+                    FhirPathValue::Boolean(b) => Ok(*b == expected),
+                    other => Err(error(format!("{name}() expects booleans, got {other}"))),
+                });
+                Ok(boolean(if name.starts_with("all") {
+                    values.try_fold(true, |all, v| v.map(|v| all && v))?
+                } else {
+                    values.try_fold(false, |any, v| v.map(|v| any || v))?
+                }))
+            }
+            "subsetOf" | "supersetOf" => {
+                arity(1, 1)?;
+                let other = self.argument(arguments, 0, scope)?;
+                let (subset, superset) = if name == "subsetOf" {
+                    (&input, &other)
+                } else {
+                    (&other, &input)
+                };
+                Ok(boolean(subset.iter().all(|item| contains(superset, item))))
+            }
+            "count" => arity(0, 0).map(|_| vec![FhirPathValue::Integer(input.len() as i64)]),
+            "distinct" => arity(0, 0).map(|_| distinct(input)),
+            "isDistinct" => {
+                arity(0, 0)?;
+                let count = input.len();
+                Ok(boolean(distinct(input).len() == count))
+            }
+
+            // filtering and projection
+            "where" => {
+                arity(1, 1)?;
+                self.filter(input, &arguments[0])
+            }
+            "select" => {
+                arity(1, 1)?;
+                let mut output = Vec::new();
+                for (index, item) in input.into_iter().enumerate() {
+                    output.extend(self.lambda(&arguments[0], item, index, None)?);
+                }
+                Ok(output)
+            }
+            "repeat" => {
+                arity(1, 1)?;
+                let mut output: Collection<'a> = Vec::new();
+                let mut queue = input;
+                while !queue.is_empty() {
+                    let mut next = Vec::new();
+                    for (index, item) in queue.into_iter().enumerate() {
+                        for projected in self.lambda(&arguments[0], item, index, None)? {
+                            if !contains(&output, &projected) {
+                                output.push(projected.clone());
+                                next.push(projected);
+                            }
+                        }
+                    }
+                    queue = next;
+                }
+                Ok(output)
+            }
+            "ofType" => {
+                arity(1, 1)?;
+                let specifier = type_argument(name, &arguments[0])?;
+                Ok(input
+                    .into_iter()
+                    .filter(|item| item.is_type(&specifier))
+                    .collect())
+            }
+            "is" | "as" => {
+                arity(1, 1)?;
+                let specifier = type_argument(name, &arguments[0])?;
+                let Some(item) = singleton(input)? else {
+                    return Ok(Vec::new());
+                };
+                let is_type = item.is_type(&specifier);
+                Ok(if name == "is" {
+                    boolean(is_type)
+                } else {
+                    one(is_type.then_some(item))
+                })
+            }
+
+            // subsetting
+            "single" => arity(0, 0).and_then(|_| singleton(input).map(one)),
+            "first" => arity(0, 0).map(|_| one(input.into_iter().next())),
+            "last" => arity(0, 0).map(|_| one(input.into_iter().last())),
+            "tail" => arity(0, 0).map(|_| input.into_iter().skip(1).collect()),
+            "skip" | "take" => {
+                arity(1, 1)?;
+                let count = self.integer_argument(arguments, 0, scope)?.unwrap_or(0);
+                let count = usize::try_from(count).unwrap_or(0);
+                Ok(if name == "skip" {
+                    input.into_iter().skip(count).collect()
+                } else {
+                    input.into_iter().take(count).collect()
+                })
+            }
+            "intersect" => {
+                arity(1, 1)?;
+                let other = self.argument(arguments, 0, scope)?;
+                Ok(distinct(
+                    input.into_iter().filter(|item| contains(&other, item)),
+                ))
+            }
+            "exclude" => {
+                arity(1, 1)?;
+                let other = self.argument(arguments, 0, scope)?;
+                Ok(input
+                    .into_iter()
+                    .filter(|item| !contains(&other, item))
+                    .collect())
+            }
+
+            // combining
+            "union" => {
+                arity(1, 1)?;
+                let other = self.argument(arguments, 0, scope)?;
+                Ok(distinct(input.into_iter().chain(other)))
+            }
+            "combine" => {
+                arity(1, 1)?;
+                let other = self.argument(arguments, 0, scope)?;
+                Ok(input.into_iter().chain(other).collect())
+            }
+
+            // conversion
+            "iif" => {
+                arity(2, 3)?;
+                let scope = Scope {
+                    this: input,
+                    ..scope.clone()
+                };
+                match to_boolean(self.evaluate(&arguments[0], &scope)?)? {
+                    Some(true) => self.evaluate(&arguments[1], &scope),
+                    _ => match arguments.get(2) {
+                        Some(otherwise) => self.evaluate(otherwise, &scope),
+                        None => Ok(Vec::new()),
+                    },
+                }
+            }
+            "toBoolean" | "toInteger" | "toDecimal" | "toString" | "toDate" | "toDateTime"
+            | "toTime" => {
+                arity(0, 0)?;
+                Ok(one(convert(name, singleton(input)?)))
+            }
+            "toQuantity" => {
+                arity(0, 1)?;
+                let unit = self.string_argument(arguments, 0, scope)?;
+                Ok(one(to_quantity(singleton(input)?, unit.as_deref())))
+            }
+            "convertsToBoolean" | "convertsToInteger" | "convertsToDecimal"
+            | "convertsToString" | "convertsToDate" | "convertsToDateTime" | "convertsToTime"
+            | "convertsToQuantity" => {
+                arity(0, 1)?;
+                let Some(item) = singleton(input)? else {
+                    return Ok(Vec::new());
+                };
+                let target = name.replacen("convertsTo", "to", 1);
+                Ok(boolean(if target == "toQuantity" {
+                    let unit = self.string_argument(arguments, 0, scope)?;
+                    to_quantity(Some(item), unit.as_deref()).is_some()
+                } else {
+                    convert(&target, Some(item)).is_some()
+                }))
+            }
+
+            // strings
+            "indexOf" => {
+                arity(1, 1)?;
+                let (Some(text), Some(search)) = (
+                    input_string(name, input)?,
+                    self.string_argument(arguments, 0, scope)?,
+                ) else {
+                    return Ok(Vec::new());
+                };
+                let index = text
+                    .find(&search)
+                    .map_or(-1, |byte| text[..byte].chars().count() as i64);
+                Ok(vec![FhirPathValue::Integer(index)])
+            }
+            "substring" => {
+                arity(1, 2)?;
+                let (Some(text), Some(start)) = (
+                    input_string(name, input)?,
+                    self.integer_argument(arguments, 0, scope)?,
+                ) else {
+                    return Ok(Vec::new());
+                };
+                let length = self.integer_argument(arguments, 1, scope)?;
+                let chars: Vec<char> = text.chars().collect();
+                let Some(start) = usize::try_from(start).ok().filter(|s| *s < chars.len()) else {
+                    return Ok(Vec::new());
+                };
+                let end = match length {
+                    Some(length) => start
+                        .saturating_add(usize::try_from(length).unwrap_or(0))
+                        .min(chars.len()),
+                    None => chars.len(),
+                };
+                Ok(vec![FhirPathValue::String(
+                    chars[start..end].iter().collect(),
+                )])
+            }
+            "startsWith" | "endsWith" | "contains" => {
+                arity(1, 1)?;
+                let (Some(text), Some(search)) = (
+                    input_string(name, input)?,
+                    self.string_argument(arguments, 0, scope)?,
+                ) else {
+                    return Ok(Vec::new());
+                };
+                Ok(boolean(match name {
+                    "startsWith" => text.starts_with(&search),
+                    "endsWith" => text.ends_with(&search),
+                    _ => text.contains(&search),
+                }))
+            }
+            "upper" | "lower" | "trim" | "length" | "toChars" => {
+                arity(0, 0)?;
+                let Some(text) = input_string(name, input)? else {
+                    return Ok(Vec::new());
+                };
+                Ok(match name {
+                    "upper" => vec![FhirPathValue::String(text.to_uppercase())],
+                    "lower" => vec![FhirPathValue::String(text.to_lowercase())],
+                    "trim" => vec![FhirPathValue::String(text.trim().to_string())],
+                    "length" => vec![FhirPathValue::Integer(text.chars().count() as i64)],
+                    _ => text
+                        .chars()
+                        .map(|c| FhirPathValue::String(c.to_string()))
+                        .collect(),
+                })
+            }
+            "replace" | "replaceMatches" => {
+                arity(2, 2)?;
+                let (Some(text), Some(pattern), Some(substitution)) = (
+                    input_string(name, input)?,
+                    self.string_argument(arguments, 0, scope)?,
+                    self.string_argument(arguments, 1, scope)?,
+                ) else {
+                    return Ok(Vec::new());
+                };
+                Ok(vec![FhirPathValue::String(if name == "replace" {
+                    text.replace(&pattern, &substitution)
+                } else {
+                    Regex::new(&pattern)?.replace_all(&text, &substitution)
+                })])
+            }
+            "matches" => {
+                arity(1, 1)?;
+                let (Some(text), Some(pattern)) = (
+                    input_string(name, input)?,
+                    self.string_argument(arguments, 0, scope)?,
+                ) else {
+                    return Ok(Vec::new());
+                };
+                Ok(boolean(Regex::new(&pattern)?.is_match(&text)))
+            }
+            "split" => {
+                arity(1, 1)?;
+                let (Some(text), Some(separator)) = (
+                    input_string(name, input)?,
+                    self.string_argument(arguments, 0, scope)?,
+                ) else {
+                    return Ok(Vec::new());
+                };
+                Ok(text
+                    .split(&separator)
+                    .map(|part| FhirPathValue::String(part.to_string()))
+                    .collect())
+            }
+            "join" => {
+                arity(0, 1)?;
+                let separator = self
+                    .string_argument(arguments, 0, scope)?
+                    .unwrap_or_default();
+                let parts = input
+                    .iter()
+                    .map(|item| {
+                        item.as_str()
+                            .map(str::to_string)
+                            .ok_or_else(|| error(format!("join() expects strings, got {item}")))
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                Ok(vec![FhirPathValue::String(parts.join(&separator))])
+            }
+
+            // math
+            "abs" | "ceiling" | "exp" | "floor" | "ln" | "sqrt" | "truncate" => {
+                arity(0, 0)?;
+                let Some(item) = singleton(input)? else {
+                    return Ok(Vec::new());
+                };
+                math(name, item)
+            }
+            "log" | "power" => {
+                arity(1, 1)?;
+                let (Some(item), Some(argument)) = (
+                    singleton(input)?,
+                    singleton(self.argument(arguments, 0, scope)?)?,
+                ) else {
+                    return Ok(Vec::new());
+                };
+                let (Some(x), Some(y)) = (as_number(&item), as_number(&argument)) else {
+                    return Err(error(format!("{name}() expects numbers")));
+                };
+                if name == "power"
+                    && let (FhirPathValue::Integer(base), FhirPathValue::Integer(exponent)) =
+                        (&item, &argument)
+                    && let Ok(exponent) = u32::try_from(*exponent)
+                {
+                    return Ok(one(base.checked_pow(exponent).map(FhirPathValue::Integer)));
+                }
+                let result = if name == "log" {
+                    x.ln() / y.ln()
+                } else {
+                    x.powf(y)
+                };
+                Ok(one(result
+                    .is_finite()
+                    .then_some(FhirPathValue::Decimal(result))))
+            }
+            "round" => {
+                arity(0, 1)?;
+                let Some(item) = singleton(input)? else {
+                    return Ok(Vec::new());
+                };
+                let precision = self.integer_argument(arguments, 0, scope)?.unwrap_or(0);
+                let (Some(value), Ok(precision)) = (as_number(&item), i32::try_from(precision))
+                else {
+                    return Err(error(format!("round() can not be applied to {item}")));
+                };
+                if precision < 0 {
+                    return Err(error("round() precision must not be negative".to_string()));
+                }
+                let factor = 10f64.powi(precision);
+                Ok(vec![FhirPathValue::Decimal(
+                    (value * factor).round() / factor,
+                )])
+            }
+
+            // tree navigation
+            "children" => arity(0, 0).map(|_| children(&input)),
+            "descendants" => {
+                arity(0, 0)?;
+                let mut output = Vec::new();
+                let mut level = children(&input);
+                while !level.is_empty() {
+                    let next = children(&level);
+                    output.extend(level);
+                    level = next;
+                }
+                Ok(output)
+            }
+
+            // utility
+            "trace" => arity(1, 2).map(|_| input),
+            "now" => arity(0, 0).map(|_| vec![FhirPathValue::DateTime(self.now())]),
+            "today" => arity(0, 0).map(|_| {
+                vec![FhirPathValue::Date(
+                    self.now().truncate(DateTimePrecision::Day),
+                )]
+            }),
+            "timeOfDay" => arity(0, 0).map(|_| one(self.now().time().map(FhirPathValue::Time))),
+            "not" => {
+                arity(0, 0)?;
+                Ok(one(to_boolean(input)?.map(|b| FhirPathValue::Boolean(!b))))
+            }
+            "aggregate" => {
+                arity(1, 2)?;
+                let mut total = match arguments.get(1) {
+                    Some(init) => self.evaluate(init, scope)?,
+                    None => Vec::new(),
+                };
+                for (index, item) in input.into_iter().enumerate() {
+                    total = self.lambda(&arguments[0], item, index, Some(total))?;
+                }
+                Ok(total)
+            }
+            "type" => arity(0, 0).map(|_| {
+                input
+                    .iter()
+                    .filter_map(FhirPathValue::type_name)
+                    .map(|(namespace, name)| {
+                        FhirPathValue::Node(
+                            Cow::Owned(json!({"namespace": namespace, "name": name})),
+                            Some("SimpleTypeInfo".to_string()),
+                        )
+                    })
+                    .collect()
+            }),
+
+            // FHIR additions
+            "resolve" => arity(0, 0).map(|_| self.resolve(&input)),
+            "extension" => {
+                arity(1, 1)?;
+                let Some(url) = self.string_argument(arguments, 0, scope)? else {
+                    return Ok(Vec::new());
+                };
+                let mut output = Vec::new();
+                for item in &input {
+                    if let FhirPathValue::Node(value, _) = item
+                        && let Some(extensions) = child(value, "extension")
+                    {
+                        let mut all = Vec::new();
+                        push_json(&mut all, extensions, Some("Extension"));
+                        output.extend(all.into_iter().filter(|extension| {
+                            extension
+                                .as_node()
+                                .and_then(|e| e.get("url"))
+                                .and_then(Value::as_str)
+                                == Some(url.as_str())
+                        }));
+                    }
+                }
+                Ok(output)
+            }
+            "hasValue" => arity(0, 0)
+                .map(|_| boolean(matches!(input.as_slice(), [item] if item.as_node().is_none()))),
+            "getValue" => arity(0, 0).map(|_| {
+                one(singleton(input)
+                    .ok()
+                    .flatten()
+                    .filter(|item| item.as_node().is_none()))
+            }),
+            "htmlChecks" => arity(0, 0).map(|_| boolean(true)),
+            _ => Err(error(format!("unknown function {name}()"))),
+        }
+    }
+
+    fn argument(
+        &self,
+        arguments: &[Expression],
+        index: usize,
+        scope: &Scope<'a>,
+    ) -> Result<Collection<'a>, FhirError> {
+        match arguments.get(index) {
+            Some(argument) => self.evaluate(argument, scope),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    fn string_argument(
+        &self,
+        arguments: &[Expression],
+        index: usize,
+        scope: &Scope<'a>,
+    ) -> Result<Option<String>, FhirError> {
+        match singleton(self.argument(arguments, index, scope)?)? {
+            Some(FhirPathValue::String(s)) => Ok(Some(s)),
+            Some(other) => Err(error(format!("expected a string argument, got {other}"))),
+            None => Ok(None),
+        }
+    }
+
+    fn integer_argument(
+        &self,
+        arguments: &[Expression],
+        index: usize,
+        scope: &Scope<'a>,
+    ) -> Result<Option<i64>, FhirError> {
+        match singleton(self.argument(arguments, index, scope)?)? {
+            Some(FhirPathValue::Integer(i)) => Ok(Some(i)),
+            Some(other) => Err(error(format!("expected an integer argument, got {other}"))),
+            None => Ok(None),
+        }
+    }
+
+    /// Evaluates `expression` with `item` as `$this`.
+    fn lambda(
+        &self,
+        expression: &Expression,
+        item: FhirPathValue<'a>,
+        index: usize,
+        accumulator: Option<Collection<'a>>,
+    ) -> Result<Collection<'a>, FhirError> {
+        self.evaluate(
+            expression,
+            &Scope {
+                this: vec![item],
+                index: Some(index as i64),
+                total: accumulator,
+            },
+        )
+    }
+
+    fn filter(
+        &self,
+        input: Collection<'a>,
+        criteria: &Expression,
+    ) -> Result<Collection<'a>, FhirError> {
+        let mut output = Vec::new();
+        for (index, item) in input.into_iter().enumerate() {
+            if to_boolean(self.lambda(criteria, item.clone(), index, None)?)? == Some(true) {
+                output.push(item);
+            }
+        }
+        Ok(output)
+    }
+
+    /// Follows references, `#id` to the resources contained in `%resource`.
+    fn resolve(&self, input: &[FhirPathValue<'a>]) -> Collection<'a> {
+        let mut output = Vec::new();
+        for item in input {
+            let reference = match item {
+                FhirPathValue::String(reference) => Some(reference.as_str()),
+                FhirPathValue::Node(value, _) => value.get("reference").and_then(Value::as_str),
+                _ => None,
+            };
+            let Some(reference) = reference else {
+                continue;
+            };
+
+            let resolved = match reference.strip_prefix('#') {
+                Some(id) => self
+                    .context
+                    .resource()
+                    .get("contained")
+                    .and_then(Value::as_array)
+                    .and_then(|contained| {
+                        contained
+                            .iter()
+                            .find(|r| r.get("id").and_then(Value::as_str) == Some(id))
+                    }),
+                None => self.context.resolver().and_then(|r| r.resolve(reference)),
+            };
+            if let Some(resource) = resolved {
+                output.extend(node(resource));
+            }
+        }
+        output
+    }
+}
+
+fn type_argument(name: &str, argument: &Expression) -> Result<TypeSpecifier, FhirError> {
+    argument
+        .as_type_specifier()
+        .ok_or_else(|| error(format!("{name}() expects a type name")))
+}
+
+fn input_string(name: &str, input: Collection) -> Result<Option<String>, FhirError> {
+    match singleton(input)? {
+        Some(FhirPathValue::String(s)) => Ok(Some(s)),
+        Some(other) => Err(error(format!("{name}() expects a string, got {other}"))),
+        None => Ok(None),
+    }
+}
+
+fn as_number(value: &FhirPathValue) -> Option<f64> {
+    match value {
+        FhirPathValue::Integer(i) => Some(*i as f64),
+        FhirPathValue::Decimal(d) => Some(*d),
+        _ => None,
+    }
+}
+
+fn math<'a>(name: &str, item: FhirPathValue<'a>) -> Result<Collection<'a>, FhirError> {
+    if name == "abs" {
+        return Ok(vec![match item {
+            FhirPathValue::Integer(i) => FhirPathValue::Integer(i.abs()),
+            FhirPathValue::Decimal(d) => FhirPathValue::Decimal(d.abs()),
+            FhirPathValue::Quantity(v, unit) => FhirPathValue::Quantity(v.abs(), unit),
+            other => return Err(error(format!("abs() can not be applied to {other}"))),
+        }]);
+    }
+    let Some(value) = as_number(&item) else {
+        return Err(error(format!("{name}() can not be applied to {item}")));
+    };
+    let result = match name {
+        "ceiling" => return Ok(vec![FhirPathValue::Integer(value.ceil() as i64)]),
+        "floor" => return Ok(vec![FhirPathValue::Integer(value.floor() as i64)]),
+        "truncate" => return Ok(vec![FhirPathValue::Integer(value.trunc() as i64)]),
+        "exp" => value.exp(),
+        "ln" => value.ln(),
+        _ => value.sqrt(),
+    };
+    Ok(one(result
+        .is_finite()
+        .then_some(FhirPathValue::Decimal(result))))
+}
+
+fn children<'a>(input: &[FhirPathValue<'a>]) -> Collection<'a> {
+    let mut output = Vec::new();
+    for item in input {
+        let FhirPathValue::Node(value, _) = item else {
+            continue;
+        };
+        let Some(object) = value.as_object() else {
+            continue;
+        };
+        for key in object.keys().filter(|k| *k != "resourceType") {
+            if let Some(child) = child(value, key) {
+                push_json(&mut output, child, None);
+            }
+        }
+    }
+    output
+}
+
+const TRUE_STRINGS: [&str; 6] = ["true", "t", "yes", "y", "1", "1.0"];
+const FALSE_STRINGS: [&str; 6] = ["false", "f", "no", "n", "0", "0.0"];
+
+/// The `toX()` conversions, `None` when the value can not be converted.
+fn convert<'a>(name: &str, item: Option<FhirPathValue<'a>>) -> Option<FhirPathValue<'a>> {
+    use FhirPathValue::*;
+    let item = item?.into_system();
+    match (name, item) {
+        ("toBoolean", Boolean(b)) => Some(Boolean(b)),
+        ("toBoolean", Integer(i)) => match i {
+            0 => Some(Boolean(false)),
+            1 => Some(Boolean(true)),
+            _ => None,
+        },
+        ("toBoolean", Decimal(d)) => (d == 0.0 || d == 1.0).then_some(Boolean(d == 1.0)),
+        ("toBoolean", String(s)) => {
+            let lower = s.to_lowercase();
+            if TRUE_STRINGS.contains(&lower.as_str()) {
+                Some(Boolean(true))
+            } else if FALSE_STRINGS.contains(&lower.as_str()) {
+                Some(Boolean(false))
+            } else {
+                None
+            }
+        }
+        ("toInteger", Integer(i)) => Some(Integer(i)),
+        ("toInteger", Boolean(b)) => Some(Integer(i64::from(b))),
+        ("toInteger", String(s)) => {
+            let digits = s.strip_prefix(['+', '-']).unwrap_or(&s);
+            (!digits.is_empty() && digits.bytes().all(|b| b.is_ascii_digit()))
+                .then(|| s.parse().ok().map(Integer))
+                .flatten()
+        }
+        ("toDecimal", Integer(i)) => Some(Decimal(i as f64)),
+        ("toDecimal", Decimal(d)) => Some(Decimal(d)),
+        ("toDecimal", Boolean(b)) => Some(Decimal(if b { 1.0 } else { 0.0 })),
+        ("toDecimal", String(s)) => is_decimal(&s)
+            .then(|| s.parse().ok().map(Decimal))
+            .flatten(),
+        ("toString", Node(..)) => None,
+        ("toString", Decimal(d)) => Some(String(format_decimal(d))),
+        ("toString", item) => Some(String(item.to_string())),
+        ("toDate", Date(d) | DateTime(d)) => {
+            Some(Date(d.truncate(d.precision().min(DateTimePrecision::Day))))
+        }
+        ("toDate", String(s)) => FhirDateTime::parse(&s)
+            .ok()
+            .map(|d| Date(d.truncate(d.precision().min(DateTimePrecision::Day)))),
+        ("toDateTime", Date(d) | DateTime(d)) => Some(DateTime(d)),
+        ("toDateTime", String(s)) => FhirDateTime::parse(&s).ok().map(DateTime),
+        ("toTime", Time(t)) => Some(Time(t)),
+        ("toTime", String(s)) => FhirTime::parse(&s).ok().map(Time),
+        _ => None,
+    }
+}
+
+fn is_decimal(text: &str) -> bool {
+    let unsigned = text.strip_prefix(['+', '-']).unwrap_or(text);
+    let (integral, fraction) = match unsigned.split_once('.') {
+        Some((integral, fraction)) => (integral, Some(fraction)),
+        None => (unsigned, None),
+    };
+    let digits = |s: &str| !s.is_empty() && s.bytes().all(|b| b.is_ascii_digit());
+    digits(integral) && fraction.is_none_or(digits)
+}
+
+fn to_quantity<'a>(
+    item: Option<FhirPathValue<'a>>,
+    unit: Option<&str>,
+) -> Option<FhirPathValue<'a>> {
+    let (value, from) = match item? {
+        FhirPathValue::Integer(i) => (i as f64, "1".to_string()),
+        FhirPathValue::Decimal(d) => (d, "1".to_string()),
+        FhirPathValue::Boolean(b) => (if b { 1.0 } else { 0.0 }, "1".to_string()),
+        FhirPathValue::String(s) => {
+            let (number, unit) = match s.split_once(' ') {
+                Some((number, unit)) => (number, unit.trim()),
+                None => (s.as_str(), ""),
+            };
+            if !is_decimal(number) {
+                return None;
+            }
+            let unit = match unit.strip_prefix('\'').and_then(|u| u.strip_suffix('\'')) {
+                Some(quoted) => quoted.to_string(),
+                None if unit.is_empty() => "1".to_string(),
+                None if is_calendar_keyword(unit) => unit.to_string(),
+                None => return None,
+            };
+            (number.parse().ok()?, unit)
+        }
+        item => item.as_quantity()?,
+    };
+    match unit {
+        Some(unit) => {
+            convert_unit(value, &from, unit).map(|v| FhirPathValue::Quantity(v, unit.to_string()))
+        }
+        None => Some(FhirPathValue::Quantity(value, from)),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::json;
+
+    use crate::r4::fhirpath::{
+        evaluator::{EvaluationContext, ReferenceResolver},
+        parser::FhirPath,
+    };
+
+    use super::*;
+
+    fn patient() -> Value {
+        json!({
+            "resourceType": "Patient",
+            "id": "p1",
+            "extension": [{
+                "url": "http://example.org/ext/birth-place",
+                "valueString": "Utrecht"
+            }],
+            "name": [
+                {"use": "official", "family": "Jansen", "given": ["Anna", "Maria"]},
+                {"use": "nickname", "given": ["Annie"]}
+            ],
+            "telecom": [
+                {"system": "phone", "value": "+31 30 123 4567", "rank": 2},
+                {"system": "email", "value": "anna@example.org", "rank": 1}
+            ],
+            "managingOrganization": {"reference": "Organization/o1"}
+        })
+    }
+
+    fn evaluate(source: &str) -> Result<Vec<String>, FhirError> {
+        let resource = patient();
+        let now = FhirDateTime::parse("2024-05-10T09:30:00Z")?;
+        let organizations =
+            vec![json!({"resourceType": "Organization", "id": "o1", "name": "Acme"})];
+        let resolver: &dyn ReferenceResolver = &organizations;
+        let context = EvaluationContext::new(&resource)
+            .with_now(now)
+            .with_resolver(resolver);
+        Ok(FhirPath::parse(source)?
+            .evaluate_in(&context, &resource)?
+            .iter()
+            .map(ToString::to_string)
+            .collect())
+    }
+
+    fn assert_evaluates(source: &str, expected: &[&str]) {
+        assert_eq!(expected, evaluate(source).unwrap().as_slice(), "{source}")
+    }
+
+    #[test]
+    fn test_existence_functions_should_succeed() {
+        assert_evaluates("name.empty()", &["false"]);
+        assert_evaluates("name.exists(use = 'nickname')", &["true"]);
+        assert_evaluates("name.all(given.exists())", &["true"]);
+        assert_evaluates("(true | false).anyTrue()", &["true"]);
+        assert_evaluates("(true | false).allTrue()", &["false"]);
+        assert_evaluates("name.given.subsetOf(name.given | 'Eva')", &["true"]);
+        assert_evaluates("name.given.supersetOf('Anna')", &["true"]);
+        assert_evaluates("name.given.count()", &["3"]);
+        assert_evaluates("(1 | 1 | 2).isDistinct()", &["true"]);
+        assert_evaluates("(1 | 2).combine(2).isDistinct()", &["false"]);
+        assert!(evaluate("(1 | 'a').allTrue()").is_err());
+        assert!(evaluate("name.empty(1)").is_err())
+    }
+
+    #[test]
+    fn test_filtering_functions_should_succeed() {
+        assert_evaluates("name.where(use = 'official').family", &["Jansen"]);
+        assert_evaluates("name.select(given.first())", &["Anna", "Annie"]);
+        assert_evaluates("telecom.where(rank < 2).value", &["anna@example.org"]);
+        assert_evaluates("name.given.where($index = 1)", &["Maria"]);
+        assert_evaluates("children().ofType(HumanName).exists()", &["false"]);
+        assert_evaluates("name.repeat(given).count()", &["3"]);
+        assert_evaluates("telecom.rank.first().is(Integer)", &["true"]);
+        assert_evaluates("extension.value.as(string)", &["Utrecht"]);
+        assert!(evaluate("telecom.rank.is(Integer)").is_err());
+        assert!(evaluate("name.ofType()").is_err())
+    }
+
+    #[test]
+    fn test_subsetting_and_combining_functions_should_succeed() {
+        assert_evaluates("name.given.first()", &["Anna"]);
+        assert_evaluates("name.given.last()", &["Annie"]);
+        assert_evaluates("name.given.tail()", &["Maria", "Annie"]);
+        assert_evaluates("name.given.skip(1).take(1)", &["Maria"]);
+        assert_evaluates("(1 | 2 | 3).intersect(2 | 3 | 4)", &["2", "3"]);
+        assert_evaluates("(1 | 2 | 3).exclude(2)", &["1", "3"]);
+        assert_evaluates("(1 | 2).union(2 | 3)", &["1", "2", "3"]);
+        assert_evaluates("(1 | 2).combine(2)", &["1", "2", "2"]);
+        assert!(evaluate("name.given.single()").is_err())
+    }
+
+    #[test]
+    fn test_conversion_functions_should_succeed() {
+        assert_evaluates("iif(name.exists(), 'named', 'anonymous')", &["named"]);
+        assert_evaluates("iif({}, 1)", &[]);
+        assert_evaluates("'yes'.toBoolean()", &["true"]);
+        assert_evaluates("'maybe'.toBoolean()", &[]);
+        assert_evaluates("'-12'.toInteger()", &["-12"]);
+        assert_evaluates("'1.50'.toDecimal()", &["1.5"]);
+        assert_evaluates("1.toDecimal().toString()", &["1.0"]);
+        assert_evaluates("'2024-05-10T10:00:00Z'.toDate()", &["2024-05-10"]);
+        assert_evaluates("'4 days'.toQuantity()", &["4 days"]);
+        assert_evaluates("'5.5 \\'mg\\''.toQuantity('g')", &["0.0055 'g'"]);
+        assert_evaluates("'abc'.convertsToInteger()", &["false"]);
+        assert_evaluates("'10:15'.convertsToTime()", &["true"]);
+        assert!(evaluate("iif(1 | 2, 'a', 'b')").is_err())
+    }
+
+    #[test]
+    fn test_string_functions_should_succeed() {
+        assert_evaluates("'Jansen'.indexOf('se')", &["3"]);
+        assert_evaluates("'Jansen'.substring(1, 3)", &["ans"]);
+        assert_evaluates("'Jansen'.substring(10)", &[]);
+        assert_evaluates("'Jansen'.startsWith('Ja')", &["true"]);
+        assert_evaluates("'Jansen'.endsWith('Ja')", &["false"]);
+        assert_evaluates("'Jansen'.contains('nse')", &["true"]);
+        assert_evaluates("'Jansen'.upper()", &["JANSEN"]);
+        assert_evaluates("' Jansen '.trim().length()", &["6"]);
+        assert_evaluates("'abc'.toChars()", &["a", "b", "c"]);
+        assert_evaluates("'a-b-c'.replace('-', '+')", &["a+b+c"]);
+        assert_evaluates(
+            "'2024-05-10'.replaceMatches('(\\\\d+)-(\\\\d+)-(\\\\d+)', '$3/$2/$1')",
+            &["10/05/2024"],
+        );
+        assert_evaluates("'N8000123'.matches('^N[0-9]+$')", &["true"]);
+        assert_evaluates("'a,b,c'.split(',').count()", &["3"]);
+        assert_evaluates("name.given.join(' ')", &["Anna Maria Annie"]);
+        assert!(evaluate("'Jansen'.matches('(ab')").is_err());
+        assert!(evaluate("name.given.upper()").is_err());
+        assert!(evaluate("1.startsWith('1')").is_err())
+    }
+
+    #[test]
+    fn test_math_functions_should_succeed() {
+        assert_evaluates("(-5).abs()", &["5"]);
+        assert_evaluates("(-5.5 'mg').abs()", &["5.5 'mg'"]);
+        assert_evaluates("1.1.ceiling()", &["2"]);
+        assert_evaluates("1.9.floor()", &["1"]);
+        assert_evaluates("(-1.9).truncate()", &["-1"]);
+        assert_evaluates("16.sqrt()", &["4.0"]);
+        assert_evaluates("(-1).sqrt()", &[]);
+        assert_evaluates("100.log(10)", &["2.0"]);
+        assert_evaluates("2.power(10)", &["1024"]);
+        assert_evaluates("3.14159.round(2)", &["3.14"]);
+        assert_evaluates("0.exp()", &["1.0"]);
+        assert!(evaluate("'a'.abs()").is_err())
+    }
+
+    #[test]
+    fn test_tree_and_utility_functions_should_succeed() {
+        assert_evaluates("name.first().children().count()", &["4"]);
+        assert_evaluates("name.descendants().count()", &["6"]);
+        assert_evaluates("name.given.trace('given').count()", &["3"]);
+        assert_evaluates("now()", &["2024-05-10T09:30:00Z"]);
+        assert_evaluates("today()", &["2024-05-10"]);
+        assert_evaluates("timeOfDay()", &["09:30:00"]);
+        assert_evaluates("(name.exists()).not()", &["false"]);
+        assert_evaluates("(1 | 2 | 3).aggregate($this + $total, 0)", &["6"]);
+        assert_evaluates("telecom.first().rank.type().name", &["Integer"]);
+        assert_evaluates("managingOrganization.resolve().name", &["Acme"]);
+        assert_evaluates(
+            "extension('http://example.org/ext/birth-place').value",
+            &["Utrecht"],
+        );
+        assert_evaluates("name.given.first().hasValue()", &["true"]);
+        assert_evaluates("name.given.first().getValue()", &["Anna"]);
+        assert!(evaluate("name.not()").is_err())
+    }
+}
@@ -0,0 +1,754 @@
+use std::fmt;
+
+use crate::{
+    FhirError,
+    r4::elements::{FhirDateTime, FhirTime},
+};
+
+use super::value::is_calendar_keyword;
+
+/// A literal value of a FHIRPath expression.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Literal {
+    Boolean(bool),
+    String(String),
+    Integer(i64),
+    Decimal(f64),
+    Date(FhirDateTime),
+    DateTime(FhirDateTime),
+    Time(FhirTime),
+    Quantity(f64, String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnaryOperator {
+    Plus,
+    Minus,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BinaryOperator {
+    Multiply,
+    Divide,
+    Div,
+    Mod,
+    Add,
+    Subtract,
+    Concatenate,
+    Union,
+    LessThan,
+    LessOrEqual,
+    GreaterThan,
+    GreaterOrEqual,
+    Equal,
+    NotEqual,
+    Equivalent,
+    NotEquivalent,
+    In,
+    Contains,
+    And,
+    Or,
+    Xor,
+    Implies,
+}
+
+impl BinaryOperator {
+    fn from_token(token: &Token) -> Option<Self> {
+        Some(match token {
+            Token::Symbol(symbol) => match *symbol {
+                "*" => BinaryOperator::Multiply,
+                "/" => BinaryOperator::Divide,
+                "+" => BinaryOperator::Add,
+                "-" => BinaryOperator::Subtract,
+                "&" => BinaryOperator::Concatenate,
+                "|" => BinaryOperator::Union,
+                "<" => BinaryOperator::LessThan,
+                "<=" => BinaryOperator::LessOrEqual,
+                ">" => BinaryOperator::GreaterThan,
+                ">=" => BinaryOperator::GreaterOrEqual,
+                "=" => BinaryOperator::Equal,
+                "!=" => BinaryOperator::NotEqual,
+                "~" => BinaryOperator::Equivalent,
+                "!~" => BinaryOperator::NotEquivalent,
+                _ => return None,
+            },
+            Token::Identifier(name) => match name.as_str() {
+                "div" => BinaryOperator::Div,
+                "mod" => BinaryOperator::Mod,
+                "in" => BinaryOperator::In,
+                "contains" => BinaryOperator::Contains,
+                "and" => BinaryOperator::And,
+                "or" => BinaryOperator::Or,
+                "xor" => BinaryOperator::Xor,
+                "implies" => BinaryOperator::Implies,
+                _ => return None,
+            },
+            _ => return None,
+        })
+    }
+
+    /// Binding power, higher binds tighter. All operators are left associative.
+    fn precedence(self) -> u8 {
+        match self {
+            BinaryOperator::Multiply
+            | BinaryOperator::Divide
+            | BinaryOperator::Div
+            | BinaryOperator::Mod => 10,
+            BinaryOperator::Add | BinaryOperator::Subtract | BinaryOperator::Concatenate => 9,
+            BinaryOperator::Union => 7,
+            BinaryOperator::LessThan
+            | BinaryOperator::LessOrEqual
+            | BinaryOperator::GreaterThan
+            | BinaryOperator::GreaterOrEqual => 6,
+            BinaryOperator::Equal
+            | BinaryOperator::NotEqual
+            | BinaryOperator::Equivalent
+            | BinaryOperator::NotEquivalent => 5,
+            BinaryOperator::In | BinaryOperator::Contains => 4,
+            BinaryOperator::And => 3,
+            BinaryOperator::Or | BinaryOperator::Xor => 2,
+            BinaryOperator::Implies => 1,
+        }
+    }
+}
+
+/// Precedence of the `is` and `as` type operators.
+const TYPE_OPERATOR_PRECEDENCE: u8 = 8;
+/// Precedence of the unary `+` and `-` operators.
+const UNARY_PRECEDENCE: u8 = 11;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TypeOperator {
+    Is,
+    As,
+}
+
+/// A possibly qualified type name, e.g. `Quantity`, `FHIR.Patient` or `System.String`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TypeSpecifier {
+    pub namespace: Option<String>,
+    pub name: String,
+}
+
+impl fmt::Display for TypeSpecifier {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.namespace {
+            Some(namespace) => write!(f, "{namespace}.{}", self.name),
+            None => write!(f, "{}", self.name),
+        }
+    }
+}
+
+/// The syntax tree of a FHIRPath expression.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expression {
+    /// The empty collection `{}`.
+    Empty,
+    Literal(Literal),
+    /// A member of the focus, or the focus itself when it names its type.
+    Identifier(String),
+    /// An environment variable, e.g. `%resource`.
+    Variable(String),
+    This,
+    Index,
+    Total,
+    Member(Box<Expression>, String),
+    Function {
+        target: Option<Box<Expression>>,
+        name: String,
+        arguments: Vec<Expression>,
+    },
+    Indexer(Box<Expression>, Box<Expression>),
+    Unary(UnaryOperator, Box<Expression>),
+    Binary(BinaryOperator, Box<Expression>, Box<Expression>),
+    Type(TypeOperator, Box<Expression>, TypeSpecifier),
+}
+
+impl Expression {
+    /// The type named by a function argument such as `ofType(Quantity)`.
+    pub fn as_type_specifier(&self) -> Option<TypeSpecifier> {
+        match self {
+            Expression::Identifier(name) => Some(TypeSpecifier {
+                namespace: None,
+                name: name.clone(),
+            }),
+            Expression::Member(target, name) => match target.as_ref() {
+                Expression::Identifier(namespace) => Some(TypeSpecifier {
+                    namespace: Some(namespace.clone()),
+                    name: name.clone(),
+                }),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Identifier(String),
+    /// A `backtick` delimited identifier, never a keyword.
+    Delimited(String),
+    String(String),
+    Number(String),
+    DateTime(String),
+    Time(String),
+    Variable(String),
+    Special(&'static str),
+    Symbol(&'static str),
+}
+
+const SYMBOLS: [&str; 22] = [
+    "<=", ">=", "!=", "!~", ".", "[", "]", "(", ")", "{", "}", ",", "+", "-", "*", "/", "&", "|",
+    "=", "~", "<", ">",
+];
+
+fn tokenize(source: &str) -> Result<Vec<Token>, FhirError> {
+    let chars: Vec<char> = source.chars().collect();
+    let mut tokens = Vec::new();
+    let mut pos = 0;
+    let error = |message: String| FhirError::FhirPathError(format!("{message} in '{source}'"));
+
+    while let Some(&c) = chars.get(pos) {
+        let rest = &chars[pos..];
+        if c.is_whitespace() {
+            pos += 1;
+        } else if rest.starts_with(&['/', '/']) {
+            while chars.get(pos).is_some_and(|c| *c != '\n') {
+                pos += 1;
+            }
+        } else if rest.starts_with(&['/', '*']) {
+            let end = (pos + 2..chars.len().saturating_sub(1))
+                .find(|i| chars[*i] == '*' && chars[*i + 1] == '/')
+                .ok_or_else(|| error("unterminated comment".to_string()))?;
+            pos = end + 2;
+        } else if c == '\'' || c == '`' {
+            let (text, end) = read_quoted(&chars, pos).map_err(error)?;
+            tokens.push(if c == '\'' {
+                Token::String(text)
+            } else {
+                Token::Delimited(text)
+            });
+            pos = end;
+        } else if c.is_ascii_digit() {
+            let start = pos;
+            while chars.get(pos).is_some_and(char::is_ascii_digit) {
+                pos += 1;
+            }
+            if chars.get(pos) == Some(&'.') && chars.get(pos + 1).is_some_and(char::is_ascii_digit)
+            {
+                pos += 1;
+                while chars.get(pos).is_some_and(char::is_ascii_digit) {
+                    pos += 1;
+                }
+            }
+            tokens.push(Token::Number(chars[start..pos].iter().collect()));
+        } else if c.is_alphabetic() || c == '_' {
+            let start = pos;
+            while chars
+                .get(pos)
+                .is_some_and(|c| c.is_alphanumeric() || *c == '_')
+            {
+                pos += 1;
+            }
+            tokens.push(Token::Identifier(chars[start..pos].iter().collect()));
+        } else if c == '@' {
+            let start = pos + 1;
+            pos = start;
+            while let Some(&c) = chars.get(pos) {
+                let next_is_digit = chars.get(pos + 1).is_some_and(char::is_ascii_digit);
+                let accepted = c.is_ascii_digit()
+                    || c == 'T'
+                    || c == 'Z'
+                    || c == ':'
+                    || ((c == '-' || c == '+' || c == '.') && next_is_digit);
+                if !accepted {
+                    break;
+                }
+                pos += 1;
+            }
+            let text: String = chars[start..pos].iter().collect();
+            tokens.push(match text.strip_prefix('T') {
+                Some(time) => Token::Time(time.to_string()),
+                None => Token::DateTime(text),
+            });
+        } else if c == '%' {
+            pos += 1;
+            match chars.get(pos) {
+                Some('`' | '\'') => {
+                    let (text, end) = read_quoted(&chars, pos).map_err(error)?;
+                    tokens.push(Token::Variable(text));
+                    pos = end;
+                }
+                _ => {
+                    let start = pos;
+                    while chars
+                        .get(pos)
+                        .is_some_and(|c| c.is_alphanumeric() || *c == '_' || *c == '-')
+                    {
+                        pos += 1;
+                    }
+                    if start == pos {
+                        return Err(error("expected a variable name after '%'".to_string()));
+                    }
+                    tokens.push(Token::Variable(chars[start..pos].iter().collect()));
+                }
+            }
+        } else if c == '$' {
+            let name: String = rest
+                .iter()
+                .skip(1)
+                .take_while(|c| c.is_alphabetic())
+                .collect();
+            let special = match name.as_str() {
+                "this" => "$this",
+                "index" => "$index",
+                "total" => "$total",
+                _ => return Err(error(format!("unknown special variable '${name}'"))),
+            };
+            tokens.push(Token::Special(special));
+            pos += name.len() + 1;
+        } else {
+            let symbol = SYMBOLS
+                .iter()
+                .find(|s| rest.starts_with(&s.chars().collect::<Vec<_>>()))
+                .ok_or_else(|| error(format!("unexpected character '{c}'")))?;
+            tokens.push(Token::Symbol(symbol));
+            pos += symbol.len();
+        }
+    }
+    Ok(tokens)
+}
+
+/// Reads a `'` or `` ` `` delimited string starting at `start`, returning its
+/// unescaped text and the position after the closing delimiter.
+fn read_quoted(chars: &[char], start: usize) -> Result<(String, usize), String> {
+    let delimiter = chars[start];
+    let mut text = String::new();
+    let mut pos = start + 1;
+    loop {
+        let Some(&c) = chars.get(pos) else {
+            return Err(format!("unterminated {delimiter} literal"));
+        };
+        pos += 1;
+        match c {
+            c if c == delimiter => return Ok((text, pos)),
+            '\\' => {
+                let Some(&escaped) = chars.get(pos) else {
+                    return Err("unterminated escape".to_string());
+                };
+                pos += 1;
+                text.push(match escaped {
+                    't' => '\t',
+                    'n' => '\n',
+                    'r' => '\r',
+                    'f' => '\u{c}',
+                    'u' => {
+                        let hex: String = chars.iter().skip(pos).take(4).collect();
+                        pos += 4;
+                        u32::from_str_radix(&hex, 16)
+                            .ok()
+                            .filter(|_| hex.len() == 4)
+                            .and_then(char::from_u32)
+                            .ok_or_else(|| format!("invalid unicode escape '\\u{hex}'"))?
+                    }
+                    c => c,
+                });
+            }
+            c => text.push(c),
+        }
+    }
+}
+
+/// A parsed FHIRPath expression, see <http://hl7.org/fhirpath/N1/>.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FhirPath {
+    source: String,
+    expression: Expression,
+}
+
+impl FhirPath {
+    pub fn parse(source: &str) -> Result<Self, FhirError> {
+        let mut parser = Parser {
+            source,
+            tokens: tokenize(source)?,
+            pos: 0,
+        };
+        let expression = parser.parse_expression(0)?;
+        if let Some(token) = parser.peek() {
+            return Err(parser.error(&format!("unexpected {token:?}")));
+        }
+        Ok(FhirPath {
+            source: source.to_string(),
+            expression,
+        })
+    }
+
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    pub fn expression(&self) -> &Expression {
+        &self.expression
+    }
+}
+
+impl fmt::Display for FhirPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.source)
+    }
+}
+
+struct Parser<'s> {
+    source: &'s str,
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser<'_> {
+    fn error(&self, message: &str) -> FhirError {
+        FhirError::FhirPathError(format!("{message} in '{}'", self.source))
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn is_symbol(&self, symbol: &str) -> bool {
+        matches!(self.peek(), Some(Token::Symbol(s)) if *s == symbol)
+    }
+
+    fn expect_symbol(&mut self, symbol: &str) -> Result<(), FhirError> {
+        if self.is_symbol(symbol) {
+            self.pos += 1;
+            Ok(())
+        } else {
+            Err(self.error(&format!("expected '{symbol}'")))
+        }
+    }
+
+    fn parse_expression(&mut self, min_precedence: u8) -> Result<Expression, FhirError> {
+        let mut left = self.parse_prefix()?;
+
+        while let Some(token) = self.peek() {
+            if let Token::Identifier(keyword) = token
+                && (keyword == "is" || keyword == "as")
+            {
+                if TYPE_OPERATOR_PRECEDENCE <= min_precedence {
+                    break;
+                }
+                let operator = if keyword == "is" {
+                    TypeOperator::Is
+                } else {
+                    TypeOperator::As
+                };
+                self.pos += 1;
+                let specifier = self.parse_type_specifier()?;
+                left = Expression::Type(operator, Box::new(left), specifier);
+                continue;
+            }
+
+            let Some(operator) = BinaryOperator::from_token(token) else {
+                break;
+            };
+            let precedence = operator.precedence();
+            if precedence <= min_precedence {
+                break;
+            }
+            self.pos += 1;
+            let right = self.parse_expression(precedence)?;
+            left = Expression::Binary(operator, Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    fn parse_type_specifier(&mut self) -> Result<TypeSpecifier, FhirError> {
+        let name = match self.next() {
+            Some(Token::Identifier(name) | Token::Delimited(name)) => name,
+            _ => return Err(self.error("expected a type name")),
+        };
+        if (name == "FHIR" || name == "System") && self.is_symbol(".") {
+            self.pos += 1;
+            return match self.next() {
+                Some(Token::Identifier(type_name) | Token::Delimited(type_name)) => {
+                    Ok(TypeSpecifier {
+                        namespace: Some(name),
+                        name: type_name,
+                    })
+                }
+                _ => Err(self.error("expected a type name")),
+            };
+        }
+        Ok(TypeSpecifier {
+            namespace: None,
+            name,
+        })
+    }
+
+    fn parse_prefix(&mut self) -> Result<Expression, FhirError> {
+        if self.is_symbol("-") || self.is_symbol("+") {
+            let operator = if self.is_symbol("-") {
+                UnaryOperator::Minus
+            } else {
+                UnaryOperator::Plus
+            };
+            self.pos += 1;
+            let operand = self.parse_expression(UNARY_PRECEDENCE)?;
+            return Ok(match (operator, operand) {
+                (UnaryOperator::Minus, Expression::Literal(Literal::Integer(i))) => {
+                    Expression::Literal(Literal::Integer(-i))
+                }
+                (UnaryOperator::Minus, Expression::Literal(Literal::Decimal(d))) => {
+                    Expression::Literal(Literal::Decimal(-d))
+                }
+                (UnaryOperator::Minus, Expression::Literal(Literal::Quantity(v, unit))) => {
+                    Expression::Literal(Literal::Quantity(-v, unit))
+                }
+                (operator, operand) => Expression::Unary(operator, Box::new(operand)),
+            });
+        }
+
+        let term = self.parse_term()?;
+        self.parse_postfix(term)
+    }
+
+    fn parse_postfix(&mut self, mut expression: Expression) -> Result<Expression, FhirError> {
+        loop {
+            if self.is_symbol(".") {
+                self.pos += 1;
+                let name = match self.next() {
+                    Some(Token::Identifier(name) | Token::Delimited(name)) => name,
+                    _ => return Err(self.error("expected an identifier after '.'")),
+                };
+                expression = if self.is_symbol("(") {
+                    Expression::Function {
+                        target: Some(Box::new(expression)),
+                        name,
+                        arguments: self.parse_arguments()?,
+                    }
+                } else {
+                    Expression::Member(Box::new(expression), name)
+                };
+            } else if self.is_symbol("[") {
+                self.pos += 1;
+                let index = self.parse_expression(0)?;
+                self.expect_symbol("]")?;
+                expression = Expression::Indexer(Box::new(expression), Box::new(index));
+            } else {
+                return Ok(expression);
+            }
+        }
+    }
+
+    fn parse_arguments(&mut self) -> Result<Vec<Expression>, FhirError> {
+        self.expect_symbol("(")?;
+        let mut arguments = Vec::new();
+        if self.is_symbol(")") {
+            self.pos += 1;
+            return Ok(arguments);
+        }
+        loop {
+            arguments.push(self.parse_expression(0)?);
+            if self.is_symbol(",") {
+                self.pos += 1;
+            } else {
+                self.expect_symbol(")")?;
+                return Ok(arguments);
+            }
+        }
+    }
+
+    fn parse_term(&mut self) -> Result<Expression, FhirError> {
+        let Some(token) = self.next() else {
+            return Err(self.error("unexpected end of expression"));
+        };
+        Ok(match token {
+            Token::Symbol("(") => {
+                let expression = self.parse_expression(0)?;
+                self.expect_symbol(")")?;
+                expression
+            }
+            Token::Symbol("{") => {
+                self.expect_symbol("}")?;
+                Expression::Empty
+            }
+            Token::Identifier(name) if name == "true" || name == "false" => {
+                Expression::Literal(Literal::Boolean(name == "true"))
+            }
+            Token::Identifier(name) | Token::Delimited(name) => {
+                if self.is_symbol("(") {
+                    Expression::Function {
+                        target: None,
+                        name,
+                        arguments: self.parse_arguments()?,
+                    }
+                } else {
+                    Expression::Identifier(name)
+                }
+            }
+            Token::String(text) => Expression::Literal(Literal::String(text)),
+            Token::Number(number) => self.parse_number(&number)?,
+            Token::DateTime(text) => {
+                let value = FhirDateTime::parse(&text)
+                    .map_err(|_| self.error(&format!("invalid date/time literal '@{text}'")))?;
+                Expression::Literal(if text.contains('T') {
+                    Literal::DateTime(value)
+                } else {
+                    Literal::Date(value)
+                })
+            }
+            Token::Time(text) => Expression::Literal(Literal::Time(
+                FhirTime::parse(&text)
+                    .map_err(|_| self.error(&format!("invalid time literal '@T{text}'")))?,
+            )),
+            Token::Variable(name) => Expression::Variable(name),
+            Token::Special("$this") => Expression::This,
+            Token::Special("$index") => Expression::Index,
+            Token::Special(_) => Expression::Total,
+            Token::Symbol(symbol) => return Err(self.error(&format!("unexpected '{symbol}'"))),
+        })
+    }
+
+    /// A number literal, or a quantity when followed by a unit.
+    fn parse_number(&mut self, number: &str) -> Result<Expression, FhirError> {
+        let unit = match self.peek() {
+            Some(Token::String(unit)) => Some(unit.clone()),
+            Some(Token::Identifier(keyword)) if is_calendar_keyword(keyword) => {
+                Some(keyword.clone())
+            }
+            _ => None,
+        };
+        let invalid = |parser: &Self| parser.error(&format!("invalid number '{number}'"));
+
+        if let Some(unit) = unit {
+            self.pos += 1;
+            let value = number.parse::<f64>().map_err(|_| invalid(self))?;
+            return Ok(Expression::Literal(Literal::Quantity(value, unit)));
+        }
+        Ok(Expression::Literal(if number.contains('.') {
+            Literal::Decimal(number.parse().map_err(|_| invalid(self))?)
+        } else {
+            Literal::Integer(number.parse().map_err(|_| invalid(self))?)
+        }))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn identifier(name: &str) -> Box<Expression> {
+        Box::new(Expression::Identifier(name.to_string()))
+    }
+
+    #[test]
+    fn test_parse_should_respect_precedence() {
+        let expected = Expression::Binary(
+            BinaryOperator::Or,
+            Box::new(Expression::Binary(
+                BinaryOperator::Equal,
+                Box::new(Expression::Binary(
+                    BinaryOperator::Add,
+                    Box::new(Expression::Literal(Literal::Integer(1))),
+                    Box::new(Expression::Binary(
+                        BinaryOperator::Multiply,
+                        Box::new(Expression::Literal(Literal::Integer(2))),
+                        Box::new(Expression::Literal(Literal::Integer(3))),
+                    )),
+                )),
+                Box::new(Expression::Literal(Literal::Integer(7))),
+            )),
+            Box::new(Expression::Type(
+                TypeOperator::Is,
+                identifier("active"),
+                TypeSpecifier {
+                    namespace: Some("System".to_string()),
+                    name: "Boolean".to_string(),
+                },
+            )),
+        );
+
+        let actual = FhirPath::parse("1 + 2 * 3 = 7 or active is System.Boolean").unwrap();
+
+        assert_eq!(&expected, actual.expression())
+    }
+
+    #[test]
+    fn test_parse_should_read_invocations_and_literals() {
+        let expected = Expression::Function {
+            target: Some(Box::new(Expression::Indexer(
+                Box::new(Expression::Member(
+                    identifier("Patient"),
+                    "name".to_string(),
+                )),
+                Box::new(Expression::Literal(Literal::Integer(0))),
+            ))),
+            name: "where".to_string(),
+            arguments: vec![Expression::Binary(
+                BinaryOperator::GreaterOrEqual,
+                Box::new(Expression::Member(
+                    identifier("period"),
+                    "start".to_string(),
+                )),
+                Box::new(Expression::Binary(
+                    BinaryOperator::Subtract,
+                    Box::new(Expression::Literal(Literal::Date(
+                        FhirDateTime::parse("2020-01").unwrap(),
+                    ))),
+                    Box::new(Expression::Literal(Literal::Quantity(
+                        2.0,
+                        "years".to_string(),
+                    ))),
+                )),
+            )],
+        };
+
+        let actual =
+            FhirPath::parse("Patient.name[0].where(period.start >= @2020-01 - 2 years) // note")
+                .unwrap();
+
+        assert_eq!(&expected, actual.expression())
+    }
+
+    #[test]
+    fn test_parse_should_read_strings_variables_and_specials() {
+        let actual = FhirPath::parse("%`vs-gender` | 'P\\u0065ter\\'s' | $this.`div`").unwrap();
+
+        assert_eq!(
+            &Expression::Binary(
+                BinaryOperator::Union,
+                Box::new(Expression::Binary(
+                    BinaryOperator::Union,
+                    Box::new(Expression::Variable("vs-gender".to_string())),
+                    Box::new(Expression::Literal(Literal::String("Peter's".to_string()))),
+                )),
+                Box::new(Expression::Member(
+                    Box::new(Expression::This),
+                    "div".to_string()
+                )),
+            ),
+            actual.expression()
+        )
+    }
+
+    #[test]
+    fn test_parse_should_reject_invalid_expressions() {
+        for source in [
+            "Patient.",
+            "name.where(",
+            "1 +",
+            "'open",
+            "@2020-13",
+            "a b",
+            "$that",
+        ] {
+            assert!(FhirPath::parse(source).is_err(), "{source}")
+        }
+    }
+}
@@ -0,0 +1,639 @@
+//! A small backtracking regular expression engine covering the syntax used by
+//! FHIR invariants: literals, `.`, character classes, `\d \w \s` and their
+//! negations, anchors, word boundaries, groups, alternation and greedy or
+//! lazy quantifiers (`* + ? {n} {n,} {n,m}`).
+
+use crate::FhirError;
+
+/// Upper bound on backtracking steps, protecting against pathological patterns.
+const MAX_STEPS: usize = 1_000_000;
+
+#[derive(Debug, Clone, PartialEq)]
+enum ClassItem {
+    Char(char),
+    Range(char, char),
+    Digit(bool),
+    Word(bool),
+    Space(bool),
+}
+
+impl ClassItem {
+    fn matches(&self, c: char) -> bool {
+        match self {
+            ClassItem::Char(x) => *x == c,
+            ClassItem::Range(from, to) => (*from..=*to).contains(&c),
+            ClassItem::Digit(negated) => c.is_ascii_digit() != *negated,
+            ClassItem::Word(negated) => is_word(c) != *negated,
+            ClassItem::Space(negated) => c.is_whitespace() != *negated,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Node {
+    Empty,
+    Char(char),
+    Any,
+    Class(Vec<ClassItem>, bool),
+    Start,
+    End,
+    WordBoundary(bool),
+    Group(Box<Node>, Option<usize>),
+    Concat(Vec<Node>),
+    Alternate(Vec<Node>),
+    Repeat {
+        node: Box<Node>,
+        min: usize,
+        max: Option<usize>,
+        greedy: bool,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Instruction {
+    Char(char),
+    Any,
+    Class(Vec<ClassItem>, bool),
+    Start,
+    End,
+    WordBoundary(bool),
+    Save(usize),
+    Split(usize, usize),
+    Jump(usize),
+    Match,
+}
+
+/// A compiled regular expression.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Regex {
+    program: Vec<Instruction>,
+    groups: usize,
+}
+
+/// The span of each capture group of a match, group 0 being the whole match,
+/// as char indices.
+pub type Captures = Vec<Option<(usize, usize)>>;
+
+impl Regex {
+    pub fn new(pattern: &str) -> Result<Self, FhirError> {
+        let mut parser = PatternParser {
+            chars: pattern.chars().collect(),
+            pos: 0,
+            groups: 0,
+        };
+        let node = parser.parse_alternation()?;
+        if parser.pos < parser.chars.len() {
+            return Err(regex_error(pattern, "unbalanced ')'"));
+        }
+
+        let mut program = vec![Instruction::Save(0)];
+        compile(&node, &mut program);
+        program.push(Instruction::Save(1));
+        program.push(Instruction::Match);
+        Ok(Regex {
+            program,
+            groups: parser.groups + 1,
+        })
+    }
+
+    /// `true` when the expression matches anywhere in `text`.
+    pub fn is_match(&self, text: &str) -> bool {
+        self.captures(text).is_some()
+    }
+
+    /// `true` when the expression matches the whole of `text`.
+    pub fn is_full_match(&self, text: &str) -> bool {
+        let chars: Vec<char> = text.chars().collect();
+        self.run(&chars, 0, true).is_some()
+    }
+
+    /// The captures of the first match in `text`.
+    pub fn captures(&self, text: &str) -> Option<Captures> {
+        let chars: Vec<char> = text.chars().collect();
+        (0..=chars.len()).find_map(|start| self.run(&chars, start, false))
+    }
+
+    /// Replaces every match, `$1`..`$9` in `replacement` refer to capture groups.
+    pub fn replace_all(&self, text: &str, replacement: &str) -> String {
+        let chars: Vec<char> = text.chars().collect();
+        let mut result = String::new();
+        let mut position = 0;
+
+        while position <= chars.len() {
+            let Some((start, captures)) =
+                (position..=chars.len()).find_map(|s| self.run(&chars, s, false).map(|c| (s, c)))
+            else {
+                break;
+            };
+            let end = captures
+                .first()
+                .copied()
+                .flatten()
+                .map_or(start, |(_, end)| end);
+
+            result.extend(&chars[position..start]);
+            expand_replacement(&mut result, replacement, &chars, &captures);
+
+            if end == start {
+                if let Some(c) = chars.get(start) {
+                    result.push(*c);
+                }
+                position = start + 1;
+            } else {
+                position = end;
+            }
+        }
+        if position < chars.len() {
+            result.extend(&chars[position..]);
+        }
+        result
+    }
+
+    fn run(&self, chars: &[char], start: usize, full: bool) -> Option<Captures> {
+        let mut stack: Vec<(usize, usize, Vec<Option<usize>>)> =
+            vec![(0, start, vec![None; self.groups * 2])];
+        let mut steps = 0;
+
+        while let Some((mut pc, mut sp, mut slots)) = stack.pop() {
+            loop {
+                steps += 1;
+                if steps > MAX_STEPS {
+                    return None;
+                }
+                let Some(instruction) = self.program.get(pc) else {
+                    break;
+                };
+                match instruction {
+                    Instruction::Char(c) => {
+                        if chars.get(sp) != Some(c) {
+                            break;
+                        }
+                        pc += 1;
+                        sp += 1;
+                    }
+                    Instruction::Any => {
+                        if chars.get(sp).is_none_or(|c| *c == '\n') {
+                            break;
+                        }
+                        pc += 1;
+                        sp += 1;
+                    }
+                    Instruction::Class(items, negated) => {
+                        let Some(c) = chars.get(sp) else {
+                            break;
+                        };
+                        if items.iter().any(|i| i.matches(*c)) == *negated {
+                            break;
+                        }
+                        pc += 1;
+                        sp += 1;
+                    }
+                    Instruction::Start => {
+                        if sp != 0 {
+                            break;
+                        }
+                        pc += 1;
+                    }
+                    Instruction::End => {
+                        if sp != chars.len() {
+                            break;
+                        }
+                        pc += 1;
+                    }
+                    Instruction::WordBoundary(negated) => {
+                        let before = sp > 0 && chars.get(sp - 1).is_some_and(|c| is_word(*c));
+                        let after = chars.get(sp).is_some_and(|c| is_word(*c));
+                        if (before != after) == *negated {
+                            break;
+                        }
+                        pc += 1;
+                    }
+                    Instruction::Save(slot) => {
+                        if let Some(s) = slots.get_mut(*slot) {
+                            *s = Some(sp);
+                        }
+                        pc += 1;
+                    }
+                    Instruction::Split(first, second) => {
+                        stack.push((*second, sp, slots.clone()));
+                        pc = *first;
+                    }
+                    Instruction::Jump(target) => pc = *target,
+                    Instruction::Match => {
+                        if full && sp != chars.len() {
+                            break;
+                        }
+                        return Some(
+                            slots
+                                .chunks(2)
+                                .map(|pair| match pair {
+                                    [Some(start), Some(end)] => Some((*start, *end)),
+                                    _ => None,
+                                })
+                                .collect(),
+                        );
+                    }
+                }
+            }
+        }
+        None
+    }
+}
+
+fn expand_replacement(result: &mut String, replacement: &str, chars: &[char], captures: &Captures) {
+    let mut iter = replacement.chars().peekable();
+    while let Some(c) = iter.next() {
+        if c == '$'
+            && let Some(group) = iter.peek().and_then(|d| d.to_digit(10))
+        {
+            iter.next();
+            if let Some(Some((start, end))) = captures.get(group as usize) {
+                result.extend(&chars[*start..*end]);
+            }
+        } else {
+            result.push(c);
+        }
+    }
+}
+
+fn is_word(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_'
+}
+
+fn compile(node: &Node, program: &mut Vec<Instruction>) {
+    match node {
+        Node::Empty => {}
+        Node::Char(c) => program.push(Instruction::Char(*c)),
+        Node::Any => program.push(Instruction::Any),
+        Node::Class(items, negated) => program.push(Instruction::Class(items.clone(), *negated)),
+        Node::Start => program.push(Instruction::Start),
+        Node::End => program.push(Instruction::End),
+        Node::WordBoundary(negated) => program.push(Instruction::WordBoundary(*negated)),
+        Node::Group(node, index) => match index {
+            Some(index) => {
+                program.push(Instruction::Save(index * 2));
+                compile(node, program);
+                program.push(Instruction::Save(index * 2 + 1));
+            }
+            None => compile(node, program),
+        },
+        Node::Concat(nodes) => nodes.iter().for_each(|n| compile(n, program)),
+        Node::Alternate(nodes) => {
+            let mut jumps = Vec::new();
+            for (i, n) in nodes.iter().enumerate() {
+                if i + 1 < nodes.len() {
+                    let split = program.len();
+                    program.push(Instruction::Split(split + 1, 0));
+                    compile(n, program);
+                    jumps.push(program.len());
+                    program.push(Instruction::Jump(0));
+                    let next = program.len();
+                    program[split] = Instruction::Split(split + 1, next);
+                } else {
+                    compile(n, program);
+                }
+            }
+            let end = program.len();
+            for jump in jumps {
+                program[jump] = Instruction::Jump(end);
+            }
+        }
+        Node::Repeat {
+            node,
+            min,
+            max,
+            greedy,
+        } => {
+            for _ in 0..*min {
+                compile(node, program);
+            }
+            let split = |program: &mut Vec<Instruction>, body: usize, exit: usize| {
+                if *greedy {
+                    Instruction::Split(body, exit)
+                } else {
+                    Instruction::Split(exit, body)
+                }
+                .clone_into(&mut program[body - 1]);
+            };
+            match max {
+                None => {
+                    let start = program.len();
+                    program.push(Instruction::Jump(0));
+                    compile(node, program);
+                    program.push(Instruction::Jump(start));
+                    let exit = program.len();
+                    split(program, start + 1, exit);
+                }
+                Some(max) => {
+                    let mut splits = Vec::new();
+                    for _ in *min..*max {
+                        splits.push(program.len());
+                        program.push(Instruction::Jump(0));
+                        compile(node, program);
+                    }
+                    let exit = program.len();
+                    for start in splits {
+                        split(program, start + 1, exit);
+                    }
+                }
+            }
+        }
+    }
+}
+
+struct PatternParser {
+    chars: Vec<char>,
+    pos: usize,
+    groups: usize,
+}
+
+impl PatternParser {
+    fn pattern(&self) -> String {
+        self.chars.iter().collect()
+    }
+
+    fn error(&self, message: &str) -> FhirError {
+        regex_error(&self.pattern(), message)
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let c = self.peek();
+        self.pos += 1;
+        c
+    }
+
+    fn parse_alternation(&mut self) -> Result<Node, FhirError> {
+        let mut branches = vec![self.parse_concat()?];
+        while self.peek() == Some('|') {
+            self.pos += 1;
+            branches.push(self.parse_concat()?);
+        }
+        Ok(match branches.len() {
+            1 => branches.remove(0),
+            _ => Node::Alternate(branches),
+        })
+    }
+
+    fn parse_concat(&mut self) -> Result<Node, FhirError> {
+        let mut nodes = Vec::new();
+        while let Some(c) = self.peek() {
+            if c == '|' || c == ')' {
+                break;
+            }
+            let atom = self.parse_atom()?;
+            nodes.push(self.parse_quantifier(atom)?);
+        }
+        Ok(match nodes.len() {
+            0 => Node::Empty,
+            1 => nodes.remove(0),
+            _ => Node::Concat(nodes),
+        })
+    }
+
+    fn parse_quantifier(&mut self, atom: Node) -> Result<Node, FhirError> {
+        let (min, max) = match self.peek() {
+            Some('*') => (0, None),
+            Some('+') => (1, None),
+            Some('?') => (0, Some(1)),
+            Some('{') => {
+                self.pos += 1;
+                let min = self
+                    .parse_number()
+                    .ok_or_else(|| self.error("expected a count"))?;
+                let max = if self.peek() == Some(',') {
+                    self.pos += 1;
+                    self.parse_number()
+                } else {
+                    Some(min)
+                };
+                if self.peek() != Some('}') || max.is_some_and(|m| m < min) {
+                    return Err(self.error("invalid counted repetition"));
+                }
+                (min, max)
+            }
+            _ => return Ok(atom),
+        };
+        self.pos += 1;
+
+        let greedy = if self.peek() == Some('?') {
+            self.pos += 1;
+            false
+        } else {
+            true
+        };
+        if matches!(atom, Node::Start | Node::End | Node::Empty) {
+            return Err(self.error("nothing to repeat"));
+        }
+        Ok(Node::Repeat {
+            node: Box::new(atom),
+            min,
+            max,
+            greedy,
+        })
+    }
+
+    fn parse_number(&mut self) -> Option<usize> {
+        let start = self.pos;
+        while self.peek().is_some_and(|c| c.is_ascii_digit()) {
+            self.pos += 1;
+        }
+        self.chars[start..self.pos]
+            .iter()
+            .collect::<String>()
+            .parse()
+            .ok()
+    }
+
+    fn parse_atom(&mut self) -> Result<Node, FhirError> {
+        let Some(c) = self.next() else {
+            return Err(self.error("unexpected end of pattern"));
+        };
+        Ok(match c {
+            '.' => Node::Any,
+            '^' => Node::Start,
+            '$' => Node::End,
+            '(' => {
+                let index = if self.chars[self.pos..].starts_with(&['?', ':']) {
+                    self.pos += 2;
+                    None
+                } else {
+                    self.groups += 1;
+                    Some(self.groups)
+                };
+                let node = self.parse_alternation()?;
+                if self.next() != Some(')') {
+                    return Err(self.error("missing ')'"));
+                }
+                Node::Group(Box::new(node), index)
+            }
+            '[' => self.parse_class()?,
+            '\\' => match self.parse_escape()? {
+                Escape::Item(ClassItem::Char(c)) => Node::Char(c),
+                Escape::Item(item) => Node::Class(vec![item], false),
+                Escape::WordBoundary(negated) => Node::WordBoundary(negated),
+            },
+            '*' | '+' | '?' | '{' => return Err(self.error("nothing to repeat")),
+            c => Node::Char(c),
+        })
+    }
+
+    fn parse_class(&mut self) -> Result<Node, FhirError> {
+        let negated = if self.peek() == Some('^') {
+            self.pos += 1;
+            true
+        } else {
+            false
+        };
+
+        let mut items = Vec::new();
+        let mut first = true;
+        loop {
+            let Some(c) = self.next() else {
+                return Err(self.error("missing ']'"));
+            };
+            let item = match c {
+                ']' if !first => break,
+                '\\' => match self.parse_escape()? {
+                    Escape::Item(item) => item,
+                    Escape::WordBoundary(_) => ClassItem::Char('\u{8}'),
+                },
+                c => ClassItem::Char(c),
+            };
+            first = false;
+
+            if let ClassItem::Char(from) = item
+                && self.peek() == Some('-')
+                && self.chars.get(self.pos + 1).is_some_and(|c| *c != ']')
+            {
+                self.pos += 1;
+                let to = match self.next() {
+                    Some('\\') => match self.parse_escape()? {
+                        Escape::Item(ClassItem::Char(to)) => to,
+                        _ => return Err(self.error("invalid class range")),
+                    },
+                    Some(to) => to,
+                    None => return Err(self.error("missing ']'")),
+                };
+                if to < from {
+                    return Err(self.error("invalid class range"));
+                }
+                items.push(ClassItem::Range(from, to));
+            } else {
+                items.push(item);
+            }
+        }
+        Ok(Node::Class(items, negated))
+    }
+
+    fn parse_escape(&mut self) -> Result<Escape, FhirError> {
+        let Some(c) = self.next() else {
+            return Err(self.error("trailing '\\'"));
+        };
+        Ok(Escape::Item(match c {
+            'd' => ClassItem::Digit(false),
+            'D' => ClassItem::Digit(true),
+            'w' => ClassItem::Word(false),
+            'W' => ClassItem::Word(true),
+            's' => ClassItem::Space(false),
+            'S' => ClassItem::Space(true),
+            'b' => return Ok(Escape::WordBoundary(false)),
+            'B' => return Ok(Escape::WordBoundary(true)),
+            't' => ClassItem::Char('\t'),
+            'n' => ClassItem::Char('\n'),
+            'r' => ClassItem::Char('\r'),
+            'f' => ClassItem::Char('\u{c}'),
+            'u' => {
+                let hex: String = self.chars.iter().skip(self.pos).take(4).collect();
+                let code = u32::from_str_radix(&hex, 16)
+                    .ok()
+                    .filter(|_| hex.len() == 4)
+                    .and_then(char::from_u32)
+                    .ok_or_else(|| self.error("invalid unicode escape"))?;
+                self.pos += 4;
+                ClassItem::Char(code)
+            }
+            c if c.is_ascii_alphanumeric() => {
+                return Err(self.error(&format!("unsupported escape '\\{c}'")));
+            }
+            c => ClassItem::Char(c),
+        }))
+    }
+}
+
+enum Escape {
+    Item(ClassItem),
+    WordBoundary(bool),
+}
+
+fn regex_error(pattern: &str, message: &str) -> FhirError {
+    FhirError::FhirPathError(format!("invalid regular expression '{pattern}': {message}"))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_is_match_should_succeed() {
+        let cases = [
+            ("^[A-Z][a-z]+$", "Peter", true),
+            ("^[A-Z][a-z]+$", "peter", false),
+            ("\\d{3}-\\d{4}", "call 555-1234 now", true),
+            ("^(ab|cd)+e?$", "abcdab", true),
+            ("^(ab|cd)+e?$", "abce", false),
+            ("^\\S+@\\S+\\.\\S+$", "info@example.org", true),
+            ("colou?r", "color", true),
+            ("^a{2,3}$", "aaaa", false),
+            ("\\bcare\\b", "primary care unit", true),
+            ("^a\\{2}$", "a{2}", true),
+            (
+                "[^\\s\\.,:;\\'\"\\/|?!@#$%&*()\\[\\]{}]{1,64}",
+                "org-1",
+                true,
+            ),
+        ];
+
+        for (pattern, text, expected) in cases {
+            assert_eq!(
+                expected,
+                Regex::new(pattern).unwrap().is_match(text),
+                "{pattern} on {text}"
+            )
+        }
+    }
+
+    #[test]
+    fn test_is_full_match_should_anchor_both_ends() {
+        let regex = Regex::new("[0-9]+").unwrap();
+
+        assert!(regex.is_full_match("2024"));
+        assert!(!regex.is_full_match("v2024"))
+    }
+
+    #[test]
+    fn test_replace_all_should_expand_groups() {
+        let regex = Regex::new("(\\w+)@(\\w+)").unwrap();
+
+        assert_eq!(
+            "example at info, org at admin",
+            regex.replace_all("info@example, admin@org", "$2 at $1")
+        );
+        assert_eq!(
+            "x-b-x",
+            Regex::new("a*?c|a").unwrap().replace_all("a-b-a", "x")
+        )
+    }
+
+    #[test]
+    fn test_new_should_reject_invalid_patterns() {
+        for pattern in [
+            "(ab", "ab)", "[a-", "*a", "a{3,2}", "a{2", "a{b}", "{2}", "\\q",
+        ] {
+            assert!(Regex::new(pattern).is_err(), "{pattern}")
+        }
+    }
+}
@@ -0,0 +1,673 @@
+use std::{borrow::Cow, cmp::Ordering, fmt};
+
+use serde_json::Value;
+
+use crate::{
+    FhirError,
+    r4::elements::{CalendarUnit, FhirDateTime, FhirTime},
+};
+
+use super::parser::TypeSpecifier;
+
+pub const UCUM_SYSTEM: &str = "http://unitsofmeasure.org";
+
+/// An item of a FHIRPath collection: a System primitive or an element of the
+/// resource being evaluated.
+#[derive(Debug, Clone, PartialEq)]
+pub enum FhirPathValue<'a> {
+    Boolean(bool),
+    String(String),
+    Integer(i64),
+    Decimal(f64),
+    Date(FhirDateTime),
+    DateTime(FhirDateTime),
+    Time(FhirTime),
+    /// A value with a UCUM unit or calendar duration keyword.
+    Quantity(f64, String),
+    /// A complex element or resource, with its FHIR type when it is known
+    /// from `resourceType` or a choice element suffix.
+    Node(Cow<'a, Value>, Option<String>),
+    /// A date, dateTime, instant or time element of a resource: the System
+    /// value it holds and its FHIR type, which `type()` and `is()` report.
+    Primitive(Box<FhirPathValue<'a>>, &'static str),
+}
+
+/// The FHIR primitive types and the System type they are represented by.
+const FHIR_PRIMITIVES: [(&str, &str); 20] = [
+    ("boolean", "Boolean"),
+    ("string", "String"),
+    ("code", "String"),
+    ("id", "String"),
+    ("uri", "String"),
+    ("url", "String"),
+    ("canonical", "String"),
+    ("oid", "String"),
+    ("uuid", "String"),
+    ("markdown", "String"),
+    ("base64Binary", "String"),
+    ("xhtml", "String"),
+    ("integer", "Integer"),
+    ("positiveInt", "Integer"),
+    ("unsignedInt", "Integer"),
+    ("decimal", "Decimal"),
+    ("date", "Date"),
+    ("dateTime", "DateTime"),
+    ("instant", "DateTime"),
+    ("time", "Time"),
+];
+
+/// The date, dateTime, instant and time elements by `Type.element`, for the
+/// resources and datatypes of this crate and the FHIRPath suite examples.
+/// Elements of a complex type are only found when the type of their parent
+/// is known, see [`FhirPathValue::Node`].
+const FHIR_TEMPORAL_ELEMENTS: [(&str, &str); 19] = [
+    ("Annotation.time", "dateTime"),
+    ("Attachment.creation", "dateTime"),
+    ("CodeSystem.date", "dateTime"),
+    ("ConceptMap.date", "dateTime"),
+    ("Device.expirationDate", "dateTime"),
+    ("Device.manufactureDate", "dateTime"),
+    ("Meta.lastUpdated", "instant"),
+    ("NamingSystem.date", "dateTime"),
+    ("Observation.issued", "instant"),
+    ("Patient.birthDate", "date"),
+    ("Period.end", "dateTime"),
+    ("Period.start", "dateTime"),
+    ("Practitioner.birthDate", "date"),
+    ("Questionnaire.date", "dateTime"),
+    ("Signature.when", "instant"),
+    ("StructureDefinition.date", "dateTime"),
+    ("Timing.event", "dateTime"),
+    ("ValueSet.date", "dateTime"),
+    ("ValueSet.lockedDate", "date"),
+];
+
+/// The FHIR type of the date or time element `name` of a `parent` type.
+pub(super) fn temporal_element_type(parent: Option<&str>, name: &str) -> Option<&'static str> {
+    let parent = parent?;
+    FHIR_TEMPORAL_ELEMENTS
+        .iter()
+        .find(|(path, _)| {
+            path.strip_prefix(parent)
+                .and_then(|rest| rest.strip_prefix('.'))
+                == Some(name)
+        })
+        .map(|(_, fhir_type)| *fhir_type)
+}
+
+impl<'a> FhirPathValue<'a> {
+    /// Converts a JSON value found at `key` of an element. `type_name` is the
+    /// FHIR type of the element or a choice type suffix, date and time strings
+    /// of those types become a [`FhirPathValue::Primitive`].
+    pub fn from_json(value: Cow<'a, Value>, type_name: Option<&str>) -> Option<Self> {
+        Some(match value.as_ref() {
+            Value::Null => return None,
+            Value::Bool(b) => FhirPathValue::Boolean(*b),
+            Value::Number(n) => match n.as_i64() {
+                Some(i) => FhirPathValue::Integer(i),
+                None => FhirPathValue::Decimal(n.as_f64()?),
+            },
+            Value::String(s) => {
+                let value = match type_name {
+                    Some("Date" | "date") => FhirDateTime::parse(s)
+                        .ok()
+                        .map(|d| (FhirPathValue::Date(d), "date")),
+                    Some("DateTime" | "dateTime") => FhirDateTime::parse(s)
+                        .ok()
+                        .map(|d| (FhirPathValue::DateTime(d), "dateTime")),
+                    Some("Instant" | "instant") => FhirDateTime::parse(s)
+                        .ok()
+                        .map(|d| (FhirPathValue::DateTime(d), "instant")),
+                    Some("Time" | "time") => FhirTime::parse(s)
+                        .ok()
+                        .map(|t| (FhirPathValue::Time(t), "time")),
+                    _ => None,
+                };
+                match value {
+                    Some((value, fhir_type)) => {
+                        FhirPathValue::Primitive(Box::new(value), fhir_type)
+                    }
+                    None => FhirPathValue::String(s.clone()),
+                }
+            }
+            Value::Object(object) => {
+                let type_name = object
+                    .get("resourceType")
+                    .and_then(Value::as_str)
+                    .or(type_name)
+                    .map(str::to_string);
+                FhirPathValue::Node(value, type_name)
+            }
+            Value::Array(_) => FhirPathValue::Node(value, None),
+        })
+    }
+
+    /// Detaches the value from the resource it was evaluated over.
+    pub fn into_owned(self) -> FhirPathValue<'static> {
+        match self {
+            FhirPathValue::Boolean(b) => FhirPathValue::Boolean(b),
+            FhirPathValue::String(s) => FhirPathValue::String(s),
+            FhirPathValue::Integer(i) => FhirPathValue::Integer(i),
+            FhirPathValue::Decimal(d) => FhirPathValue::Decimal(d),
+            FhirPathValue::Date(d) => FhirPathValue::Date(d),
+            FhirPathValue::DateTime(d) => FhirPathValue::DateTime(d),
+            FhirPathValue::Time(t) => FhirPathValue::Time(t),
+            FhirPathValue::Quantity(v, unit) => FhirPathValue::Quantity(v, unit),
+            FhirPathValue::Node(node, type_name) => {
+                FhirPathValue::Node(Cow::Owned(node.into_owned()), type_name)
+            }
+            FhirPathValue::Primitive(value, fhir_type) => {
+                FhirPathValue::Primitive(Box::new(value.into_owned()), fhir_type)
+            }
+        }
+    }
+
+    /// The System value held by a FHIR primitive element, other values as
+    /// they are.
+    pub fn into_system(self) -> FhirPathValue<'a> {
+        match self {
+            FhirPathValue::Primitive(value, _) => *value,
+            value => value,
+        }
+    }
+
+    fn system(&self) -> &FhirPathValue<'a> {
+        match self {
+            FhirPathValue::Primitive(value, _) => value,
+            value => value,
+        }
+    }
+
+    /// The JSON representation of the value.
+    pub fn to_json(&self) -> Value {
+        match self {
+            FhirPathValue::Boolean(b) => Value::Bool(*b),
+            FhirPathValue::String(s) => Value::String(s.clone()),
+            FhirPathValue::Integer(i) => Value::from(*i),
+            FhirPathValue::Decimal(d) => Value::from(*d),
+            FhirPathValue::Date(d) | FhirPathValue::DateTime(d) => Value::String(d.to_string()),
+            FhirPathValue::Time(t) => Value::String(t.to_string()),
+            FhirPathValue::Quantity(value, unit) => {
+                let mut quantity = serde_json::Map::new();
+                quantity.insert("value".to_string(), Value::from(*value));
+                quantity.insert("unit".to_string(), Value::String(unit.clone()));
+                if !is_calendar_keyword(unit) {
+                    quantity.insert("system".to_string(), Value::String(UCUM_SYSTEM.into()));
+                    quantity.insert("code".to_string(), Value::String(unit.clone()));
+                }
+                Value::Object(quantity)
+            }
+            FhirPathValue::Node(node, _) => node.as_ref().clone(),
+            FhirPathValue::Primitive(value, _) => value.to_json(),
+        }
+    }
+
+    /// The element or resource, when the value is a node.
+    pub fn as_node(&self) -> Option<&Value> {
+        match self {
+            FhirPathValue::Node(node, _) => Some(node.as_ref()),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            FhirPathValue::String(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    /// The namespace and name of the type of the value, when known.
+    pub fn type_name(&self) -> Option<(&'static str, String)> {
+        let system = |name: &str| Some(("System", name.to_string()));
+        match self {
+            FhirPathValue::Boolean(_) => system("Boolean"),
+            FhirPathValue::String(_) => system("String"),
+            FhirPathValue::Integer(_) => system("Integer"),
+            FhirPathValue::Decimal(_) => system("Decimal"),
+            FhirPathValue::Date(_) => system("Date"),
+            FhirPathValue::DateTime(_) => system("DateTime"),
+            FhirPathValue::Time(_) => system("Time"),
+            FhirPathValue::Quantity(..) => system("Quantity"),
+            FhirPathValue::Node(_, type_name) => type_name.clone().map(|t| ("FHIR", t)),
+            FhirPathValue::Primitive(_, fhir_type) => Some(("FHIR", fhir_type.to_string())),
+        }
+    }
+
+    /// Whether the value is of `specifier` or a type derived from it. FHIR
+    /// primitive types match the System type that represents them, strings
+    /// match date types when they parse as such. Date and time elements match
+    /// their own FHIR type only, `Patient.birthDate` is a `date` and not a
+    /// `dateTime`.
+    pub fn is_type(&self, specifier: &TypeSpecifier) -> bool {
+        let namespace = specifier.namespace.as_deref();
+        let name = specifier.name.as_str();
+
+        if let FhirPathValue::Primitive(value, fhir_type) = self {
+            return (namespace != Some("System") && name == *fhir_type)
+                || (namespace != Some("FHIR")
+                    && name.starts_with(|c: char| c.is_ascii_uppercase())
+                    && value.is_type(specifier));
+        }
+
+        if namespace != Some("System")
+            && let Some((_, system)) = FHIR_PRIMITIVES.iter().find(|(fhir, _)| *fhir == name)
+        {
+            return match (self, *system) {
+                (FhirPathValue::String(s), "Date") => {
+                    FhirDateTime::parse(s).is_ok_and(|d| !d.has_time())
+                }
+                (FhirPathValue::String(s), "DateTime") => FhirDateTime::parse(s).is_ok(),
+                (FhirPathValue::String(s), "Time") => FhirTime::parse(s).is_ok(),
+                _ => self
+                    .type_name()
+                    .is_some_and(|(ns, t)| ns == "System" && t == *system),
+            };
+        }
+
+        match self {
+            FhirPathValue::Node(node, type_name) => {
+                namespace != Some("System")
+                    && (type_name.as_deref() == Some(name)
+                        || (matches!(name, "Resource" | "DomainResource")
+                            && node.get("resourceType").is_some())
+                        || name == "Element"
+                        || (name == "BackboneElement" && type_name.is_none()))
+            }
+            FhirPathValue::String(s)
+                if namespace != Some("FHIR") && matches!(name, "Date" | "DateTime" | "Time") =>
+            {
+                match name {
+                    "Date" => FhirDateTime::parse(s).is_ok_and(|d| !d.has_time()),
+                    "DateTime" => FhirDateTime::parse(s).is_ok_and(|d| d.has_time()),
+                    _ => FhirTime::parse(s).is_ok(),
+                }
+            }
+            _ => namespace != Some("FHIR") && self.type_name().is_some_and(|(_, t)| t == name),
+        }
+    }
+
+    /// The value as a quantity, FHIR Quantity elements are converted using
+    /// their UCUM code or unit.
+    pub fn as_quantity(&self) -> Option<(f64, String)> {
+        match self {
+            FhirPathValue::Quantity(value, unit) => Some((*value, unit.clone())),
+            FhirPathValue::Node(node, _) => {
+                let value = node.get("value")?.as_f64()?;
+                let unit = node
+                    .get("code")
+                    .filter(|_| node.get("system").and_then(Value::as_str) == Some(UCUM_SYSTEM))
+                    .or_else(|| node.get("unit"))
+                    .or_else(|| node.get("code"))
+                    .and_then(Value::as_str)
+                    .unwrap_or("1");
+                Some((value, unit.to_string()))
+            }
+            _ => None,
+        }
+    }
+
+    /// Dates, date/times and strings holding one.
+    fn as_date_time(&self) -> Option<FhirDateTime> {
+        match self {
+            FhirPathValue::Date(d) | FhirPathValue::DateTime(d) => Some(*d),
+            FhirPathValue::String(s) => FhirDateTime::parse(s).ok(),
+            FhirPathValue::Primitive(value, _) => value.as_date_time(),
+            _ => None,
+        }
+    }
+
+    fn as_time(&self) -> Option<FhirTime> {
+        match self {
+            FhirPathValue::Time(t) => Some(*t),
+            FhirPathValue::String(s) => FhirTime::parse(s).ok(),
+            FhirPathValue::Primitive(value, _) => value.as_time(),
+            _ => None,
+        }
+    }
+
+    fn as_number(&self) -> Option<f64> {
+        match self {
+            FhirPathValue::Integer(i) => Some(*i as f64),
+            FhirPathValue::Decimal(d) => Some(*d),
+            _ => None,
+        }
+    }
+
+    /// FHIRPath `=`, `None` when the result is empty because dates of
+    /// different precision can not be compared. A number equals a quantity
+    /// with the UCUM unit `'1'`.
+    pub fn equals(&self, other: &FhirPathValue) -> Option<bool> {
+        use FhirPathValue::*;
+        match (self, other) {
+            (Primitive(..), _) | (_, Primitive(..)) => self.system().equals(other.system()),
+            (Boolean(a), Boolean(b)) => Some(a == b),
+            (String(a), String(b)) => Some(a == b),
+            (Integer(a), Integer(b)) => Some(a == b),
+            (Integer(_) | Decimal(_), Integer(_) | Decimal(_)) => {
+                let (a, b) = (self.as_number()?, other.as_number()?);
+                Some(decimal_eq(a, b))
+            }
+            (Date(_) | DateTime(_), Date(_) | DateTime(_) | String(_))
+            | (String(_), Date(_) | DateTime(_)) => {
+                match (self.as_date_time(), other.as_date_time()) {
+                    (Some(a), Some(b)) => a.compare(&b).map(|o| o == Ordering::Equal),
+                    _ => Some(false),
+                }
+            }
+            (Time(_), Time(_) | String(_)) | (String(_), Time(_)) => {
+                match (self.as_time(), other.as_time()) {
+                    (Some(a), Some(b)) => a.compare(&b).map(|o| o == Ordering::Equal),
+                    _ => Some(false),
+                }
+            }
+            (Quantity(value, unit), Integer(_) | Decimal(_))
+            | (Integer(_) | Decimal(_), Quantity(value, unit))
+                if unit == "1" =>
+            {
+                let number = self.as_number().or(other.as_number())?;
+                Some(decimal_eq(*value, number))
+            }
+            (Quantity(..), _) | (_, Quantity(..)) => {
+                match (self.as_quantity(), other.as_quantity()) {
+                    (Some(a), Some(b)) => compare_quantities(&a, &b).map(|o| o == Ordering::Equal),
+                    _ => Some(false),
+                }
+            }
+            (Node(a, _), Node(b, _)) => Some(a == b),
+            _ => Some(false),
+        }
+    }
+
+    /// FHIRPath `~`: strings ignore case and whitespace differences, decimals
+    /// are compared at the precision of the least precise one.
+    pub fn equivalent(&self, other: &FhirPathValue) -> bool {
+        use FhirPathValue::*;
+        match (self, other) {
+            (Primitive(..), _) | (_, Primitive(..)) => self.system().equivalent(other.system()),
+            (String(a), String(b)) => normalize(a) == normalize(b),
+            (Integer(_) | Decimal(_), Integer(_) | Decimal(_)) => {
+                let (Some(a), Some(b)) = (self.as_number(), other.as_number()) else {
+                    return false;
+                };
+                let digits = decimal_places(a).min(decimal_places(b));
+                let factor = 10f64.powi(digits as i32);
+                (a * factor).round() == (b * factor).round()
+            }
+            (Node(a, _), Node(b, _)) => json_equivalent(a, b),
+            _ => self.equals(other).unwrap_or(false),
+        }
+    }
+
+    /// Orders two values of comparable types, `Ok(None)` when the order is
+    /// indeterminate, an error when the types can not be compared.
+    pub fn compare(&self, other: &FhirPathValue) -> Result<Option<Ordering>, FhirError> {
+        use FhirPathValue::*;
+        let ordering = match (self, other) {
+            (Primitive(..), _) | (_, Primitive(..)) => {
+                return self.system().compare(other.system());
+            }
+            (String(a), String(b)) => Some(a.cmp(b)),
+            (Integer(a), Integer(b)) => Some(a.cmp(b)),
+            (Integer(_) | Decimal(_), Integer(_) | Decimal(_)) => {
+                let (a, b) = (self.as_number(), other.as_number());
+                a.zip(b).and_then(|(a, b)| a.partial_cmp(&b))
+            }
+            (Date(_) | DateTime(_), Date(_) | DateTime(_) | String(_))
+            | (String(_), Date(_) | DateTime(_)) => {
+                let (Some(a), Some(b)) = (self.as_date_time(), other.as_date_time()) else {
+                    return Err(incomparable(self, other));
+                };
+                a.compare(&b)
+            }
+            (Time(_), Time(_) | String(_)) | (String(_), Time(_)) => {
+                let (Some(a), Some(b)) = (self.as_time(), other.as_time()) else {
+                    return Err(incomparable(self, other));
+                };
+                a.compare(&b)
+            }
+            (Quantity(..) | Node(..), Quantity(..) | Node(..)) => {
+                let (Some(a), Some(b)) = (self.as_quantity(), other.as_quantity()) else {
+                    return Err(incomparable(self, other));
+                };
+                compare_quantities(&a, &b)
+            }
+            _ => return Err(incomparable(self, other)),
+        };
+        Ok(ordering)
+    }
+}
+
+impl fmt::Display for FhirPathValue<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FhirPathValue::Boolean(b) => write!(f, "{b}"),
+            FhirPathValue::String(s) => write!(f, "{s}"),
+            FhirPathValue::Integer(i) => write!(f, "{i}"),
+            FhirPathValue::Decimal(d) => write!(f, "{}", format_decimal(*d)),
+            FhirPathValue::Date(d) | FhirPathValue::DateTime(d) => write!(f, "{d}"),
+            FhirPathValue::Time(t) => write!(f, "{t}"),
+            FhirPathValue::Quantity(value, unit) => {
+                let value = format_decimal(*value);
+                let value = value.strip_suffix(".0").unwrap_or(&value);
+                if is_calendar_keyword(unit) {
+                    write!(f, "{value} {unit}")
+                } else {
+                    write!(f, "{value} '{unit}'")
+                }
+            }
+            FhirPathValue::Node(node, _) => write!(f, "{node}"),
+            FhirPathValue::Primitive(value, _) => write!(f, "{value}"),
+        }
+    }
+}
+
+/// Whether `unit` is a calendar duration keyword such as `days`, rather
+/// than a UCUM code.
+pub fn is_calendar_keyword(unit: &str) -> bool {
+    CalendarUnit::parse(unit).is_some_and(|u| unit.starts_with(u.keyword()))
+}
+
+/// Formats a decimal with up to 8 fraction digits and at least one.
+pub fn format_decimal(value: f64) -> String {
+    let text = format!("{value:.8}");
+    let text = text.trim_end_matches('0');
+    match text.strip_suffix('.') {
+        Some(integral) => format!("{integral}.0"),
+        None => text.to_string(),
+    }
+}
+
+fn decimal_places(value: f64) -> usize {
+    format_decimal(value)
+        .split_once('.')
+        .map_or(0, |(_, fraction)| fraction.trim_end_matches('0').len())
+}
+
+/// Decimals are equal when they agree to 8 fraction digits, the precision
+/// FHIRPath implementations are required to support.
+fn decimal_eq(a: f64, b: f64) -> bool {
+    (a * 1e8).round() == (b * 1e8).round()
+}
+
+fn normalize(value: &str) -> String {
+    value
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+fn json_equivalent(a: &Value, b: &Value) -> bool {
+    match (a, b) {
+        (Value::String(a), Value::String(b)) => normalize(a) == normalize(b),
+        (Value::Array(a), Value::Array(b)) => {
+            a.len() == b.len() && a.iter().all(|x| b.iter().any(|y| json_equivalent(x, y)))
+        }
+        (Value::Object(a), Value::Object(b)) => {
+            let keys = |o: &serde_json::Map<String, Value>| {
+                o.keys()
+                    .filter(|k| *k != "id" && *k != "extension")
+                    .cloned()
+                    .collect::<Vec<_>>()
+            };
+            keys(a) == keys(b)
+                && keys(a).iter().all(|k| {
+                    a.get(k)
+                        .zip(b.get(k))
+                        .is_some_and(|(x, y)| json_equivalent(x, y))
+                })
+        }
+        (a, b) => a == b,
+    }
+}
+
+fn incomparable(a: &FhirPathValue, b: &FhirPathValue) -> FhirError {
+    FhirError::FhirPathError(format!("can not compare {a} with {b}"))
+}
+
+/// The dimension and factor to the base unit of the UCUM units and calendar
+/// durations that can be converted into each other.
+fn unit_factor(unit: &str) -> Option<(&'static str, f64)> {
+    if let Some(calendar) = CalendarUnit::parse(unit).filter(|_| is_calendar_keyword(unit)) {
+        return match calendar {
+            CalendarUnit::Week => Some(("time", 604_800.0)),
+            CalendarUnit::Day => Some(("time", 86_400.0)),
+            CalendarUnit::Hour => Some(("time", 3_600.0)),
+            CalendarUnit::Minute => Some(("time", 60.0)),
+            CalendarUnit::Second => Some(("time", 1.0)),
+            CalendarUnit::Millisecond => Some(("time", 0.001)),
+            CalendarUnit::Year | CalendarUnit::Month => None,
+        };
+    }
+    Some(match unit {
+        "1" => ("1", 1.0),
+        "%" => ("1", 0.01),
+        "kg" => ("mass", 1_000.0),
+        "g" => ("mass", 1.0),
+        "mg" => ("mass", 0.001),
+        "ug" => ("mass", 0.000_001),
+        "[lb_av]" => ("mass", 453.592_37),
+        "km" => ("length", 1_000.0),
+        "m" => ("length", 1.0),
+        "cm" => ("length", 0.01),
+        "mm" => ("length", 0.001),
+        "[in_i]" => ("length", 0.0254),
+        "[ft_i]" => ("length", 0.3048),
+        "L" | "l" => ("volume", 1.0),
+        "dL" | "dl" => ("volume", 0.1),
+        "mL" | "ml" => ("volume", 0.001),
+        "wk" => ("time", 604_800.0),
+        "d" => ("time", 86_400.0),
+        "h" => ("time", 3_600.0),
+        "min" => ("time", 60.0),
+        "s" => ("time", 1.0),
+        "ms" => ("time", 0.001),
+        _ => return None,
+    })
+}
+
+/// Converts `value` between UCUM units or calendar durations of the same
+/// dimension.
+pub fn convert_unit(value: f64, from: &str, to: &str) -> Option<f64> {
+    if from == to {
+        return Some(value);
+    }
+    let ((df, ff), (dt, ft)) = (unit_factor(from)?, unit_factor(to)?);
+    (df == dt).then(|| value * ff / ft)
+}
+
+/// Compares quantities, converting between known UCUM units. Calendar years
+/// and months only compare with the same keyword.
+pub fn compare_quantities(a: &(f64, String), b: &(f64, String)) -> Option<Ordering> {
+    if a.1 == b.1 {
+        return a.0.partial_cmp(&b.0);
+    }
+    let normalize_calendar = |unit: &str| {
+        CalendarUnit::parse(unit)
+            .filter(|_| is_calendar_keyword(unit))
+            .map(|u| u.keyword().to_string())
+            .unwrap_or_else(|| unit.to_string())
+    };
+    if normalize_calendar(&a.1) == normalize_calendar(&b.1) {
+        return a.0.partial_cmp(&b.0);
+    }
+    let ((da, fa), (db, fb)) = (unit_factor(&a.1)?, unit_factor(&b.1)?);
+    if da != db {
+        return None;
+    }
+    let (x, y) = (a.0 * fa, b.0 * fb);
+    if decimal_eq(x, y) {
+        Some(Ordering::Equal)
+    } else {
+        x.partial_cmp(&y)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_equals_should_follow_fhirpath_rules() {
+        let date = |s: &str| FhirPathValue::DateTime(FhirDateTime::parse(s).unwrap());
+
+        assert_eq!(
+            Some(true),
+            FhirPathValue::Integer(1).equals(&FhirPathValue::Decimal(1.0))
+        );
+        assert_eq!(
+            Some(true),
+            date("2012-04-15").equals(&FhirPathValue::String("2012-04-15".to_string()))
+        );
+        assert_eq!(
+            None,
+            date("2012-04-15").equals(&date("2012-04-15T10:00:00"))
+        );
+        assert_eq!(
+            Some(true),
+            FhirPathValue::Quantity(4.0, "g".to_string())
+                .equals(&FhirPathValue::Quantity(4000.0, "mg".to_string()))
+        );
+        assert_eq!(
+            Some(true),
+            FhirPathValue::Quantity(1.0, "1".to_string()).equals(&FhirPathValue::Integer(1))
+        );
+        assert_eq!(
+            Some(false),
+            FhirPathValue::Decimal(1.5).equals(&FhirPathValue::Quantity(1.0, "1".to_string()))
+        );
+        assert_eq!(
+            Some(false),
+            FhirPathValue::Quantity(1.0, "mg".to_string()).equals(&FhirPathValue::Integer(1))
+        );
+        assert_eq!(
+            Some(false),
+            FhirPathValue::String("a".to_string()).equals(&FhirPathValue::Integer(1))
+        )
+    }
+
+    #[test]
+    fn test_equivalent_should_normalize() {
+        assert!(
+            FhirPathValue::String("Peter  James".to_string())
+                .equivalent(&FhirPathValue::String("peter james".to_string()))
+        );
+        assert!(FhirPathValue::Decimal(1.2).equivalent(&FhirPathValue::Decimal(1.23)));
+        assert!(!FhirPathValue::Decimal(1.3).equivalent(&FhirPathValue::Decimal(1.23)))
+    }
+
+    #[test]
+    fn test_format_decimal_should_trim() {
+        assert_eq!("3.3", format_decimal(1.1 + 2.2));
+        assert_eq!("2.0", format_decimal(2.0));
+        assert_eq!(
+            "1 'mg'",
+            FhirPathValue::Quantity(1.0, "mg".to_string()).to_string()
+        );
+        assert_eq!(
+            "4 days",
+            FhirPathValue::Quantity(4.0, "days".to_string()).to_string()
+        )
+    }
+}
@@ -0,0 +1,868 @@
+use std::cmp::Ordering;
+
+use crate::{
+    FhirError,
+    r4::{
+        elements::{
+            Address, AvailableTime, ContactPoint, FhirDateTime, FhirTime, HumanName, Identifier,
+            NotAvailable, Period, Reference,
+        },
+        profiling::{IssueSeverity, IssueType, ValidationIssue},
+        resources::{
+            Endpoint, HealthcareService, HoursOfOperation, Location, Organization,
+            OrganizationAffiliation, Practitioner, PractitionerRole, ResourceType,
+        },
+    },
+};
+
+const CONTACT_POINT_SYSTEMS: [&str; 7] = ["phone", "fax", "email", "pager", "url", "sms", "other"];
+const CONTACT_POINT_USES: [&str; 5] = ["home", "work", "temp", "old", "mobile"];
+const ADDRESS_USES: [&str; 5] = ["home", "work", "temp", "old", "billing"];
+const NAME_USES: [&str; 7] = [
+    "usual",
+    "official",
+    "temp",
+    "nickname",
+    "anonymous",
+    "old",
+    "maiden",
+];
+const IDENTIFIER_USES: [&str; 5] = ["usual", "official", "temp", "secondary", "old"];
+const LOCATION_MODES: [&str; 2] = ["instance", "kind"];
+
+/// Checks the built-in R4 invariants of a resource without a profile, such as
+/// `org-1` or `per-1`, together with value checks the spec states in prose
+/// (coordinate ranges, time formats, opening hours consistency).
+pub trait Validate {
+    fn validate(&self) -> Vec<ValidationIssue>;
+
+    /// Returns `true` when [`Validate::validate`] reports no errors, warnings
+    /// are allowed.
+    fn is_valid(&self) -> bool {
+        !self.validate().iter().any(ValidationIssue::is_error)
+    }
+}
+
+/// Collects issues while walking a resource, paths are FHIRPath expressions
+/// rooted at the resource type.
+#[derive(Default)]
+struct InvariantChecker {
+    issues: Vec<ValidationIssue>,
+}
+
+impl InvariantChecker {
+    fn push(
+        &mut self,
+        severity: IssueSeverity,
+        code: IssueType,
+        location: impl Into<String>,
+        message: impl Into<String>,
+    ) {
+        self.issues
+            .push(ValidationIssue::new(severity, code, location, message));
+    }
+
+    fn invariant(
+        &mut self,
+        severity: IssueSeverity,
+        key: &str,
+        location: impl Into<String>,
+        message: impl Into<String>,
+    ) {
+        let mut issue = ValidationIssue::new(
+            severity,
+            IssueType::Invariant,
+            location,
+            format!("{key}: {}", message.into()),
+        );
+        issue.constraint = Some(key.to_string());
+        self.issues.push(issue);
+    }
+
+    fn code(&mut self, path: &str, value: Option<&String>, allowed: &[&str]) {
+        if let Some(value) = value
+            && !allowed.contains(&value.as_str())
+        {
+            self.push(
+                IssueSeverity::Error,
+                IssueType::CodeInvalid,
+                path,
+                format!(
+                    "'{value}' is not one of the allowed codes: {}",
+                    allowed.join(", ")
+                ),
+            );
+        }
+    }
+
+    fn date_time(&mut self, path: &str, value: Option<&String>) -> Option<FhirDateTime> {
+        let value = value?;
+        match FhirDateTime::parse(value) {
+            Ok(date_time) => Some(date_time),
+            Err(_) => {
+                self.push(
+                    IssueSeverity::Error,
+                    IssueType::Value,
+                    path,
+                    format!("'{value}' is not a valid dateTime"),
+                );
+                None
+            }
+        }
+    }
+
+    fn time(&mut self, path: &str, value: Option<&String>) -> Option<FhirTime> {
+        let value = value?;
+        match FhirTime::parse(value) {
+            Ok(time) => Some(time),
+            Err(_) => {
+                self.push(
+                    IssueSeverity::Error,
+                    IssueType::Value,
+                    path,
+                    format!("'{value}' is not a valid time"),
+                );
+                None
+            }
+        }
+    }
+
+    fn period(&mut self, path: &str, period: Option<&Period>) {
+        let Some(period) = period else {
+            return;
+        };
+        let start = self.date_time(&format!("{path}.start"), period.start.as_ref());
+        let end = self.date_time(&format!("{path}.end"), period.end.as_ref());
+        // An indeterminate comparison (different precisions) passes, as the
+        // FHIRPath expression of per-1 evaluates to empty in that case.
+        if let (Some(start), Some(end)) = (start, end)
+            && start.compare(&end) == Some(Ordering::Greater)
+        {
+            self.invariant(
+                IssueSeverity::Error,
+                "per-1",
+                path,
+                format!("If present, start SHALL have a lower value than end ({start} > {end})"),
+            );
+        }
+    }
+
+    fn identifiers(&mut self, path: &str, identifiers: Option<&Vec<Identifier>>) {
+        for (index, identifier) in identifiers.iter().copied().flatten().enumerate() {
+            let path = format!("{path}[{index}]");
+            self.code(
+                &format!("{path}.use"),
+                identifier.r#use.as_ref(),
+                &IDENTIFIER_USES,
+            );
+            self.period(&format!("{path}.period"), identifier.period.as_ref());
+            self.reference(&format!("{path}.assigner"), identifier.assigner.as_ref());
+        }
+    }
+
+    fn reference<T: ResourceType>(&mut self, path: &str, reference: Option<&Reference<T>>) {
+        if let Some(reference) = reference
+            && let Err(error) = reference.target()
+        {
+            let message = match error {
+                FhirError::ReferenceError(message) => message,
+                error => error.to_string(),
+            };
+            self.push(IssueSeverity::Error, IssueType::Value, path, message);
+        }
+    }
+
+    fn references<T: ResourceType>(&mut self, path: &str, references: Option<&Vec<Reference<T>>>) {
+        for (index, reference) in references.iter().copied().flatten().enumerate() {
+            self.reference(&format!("{path}[{index}]"), Some(reference));
+        }
+    }
+
+    fn name(&mut self, path: &str, name: &HumanName) {
+        self.code(&format!("{path}.use"), name.r#use.as_ref(), &NAME_USES);
+        self.period(&format!("{path}.period"), name.period.as_ref());
+    }
+
+    fn names(&mut self, path: &str, names: Option<&Vec<HumanName>>) {
+        for (index, name) in names.iter().copied().flatten().enumerate() {
+            self.name(&format!("{path}[{index}]"), name);
+        }
+    }
+
+    fn address(&mut self, path: &str, address: &Address) {
+        self.code(
+            &format!("{path}.use"),
+            address.r#use.as_ref(),
+            &ADDRESS_USES,
+        );
+        self.period(&format!("{path}.period"), address.period.as_ref());
+    }
+
+    fn addresses(&mut self, path: &str, addresses: Option<&Vec<Address>>) {
+        for (index, address) in addresses.iter().copied().flatten().enumerate() {
+            self.address(&format!("{path}[{index}]"), address);
+        }
+    }
+
+    fn telecoms(&mut self, path: &str, telecoms: Option<&Vec<ContactPoint>>) {
+        for (index, telecom) in telecoms.iter().copied().flatten().enumerate() {
+            let path = format!("{path}[{index}]");
+            if telecom.value.is_some() && telecom.system.is_none() {
+                self.invariant(
+                    IssueSeverity::Error,
+                    "cpt-2",
+                    &path,
+                    "A system is required if a value is provided.",
+                );
+            }
+            self.code(
+                &format!("{path}.system"),
+                telecom.system.as_ref(),
+                &CONTACT_POINT_SYSTEMS,
+            );
+            self.code(
+                &format!("{path}.use"),
+                telecom.r#use.as_ref(),
+                &CONTACT_POINT_USES,
+            );
+            self.period(&format!("{path}.period"), telecom.period.as_ref());
+        }
+    }
+
+    /// Checks a pair of opening and closing times, a closing time that is not
+    /// after the opening time is reported as a warning since it is commonly
+    /// used for overnight hours.
+    fn opening_hours(
+        &mut self,
+        path: &str,
+        all_day: Option<bool>,
+        (start_name, start): (&str, Option<&String>),
+        (end_name, end): (&str, Option<&String>),
+    ) {
+        let start_time = self.time(&format!("{path}.{start_name}"), start);
+        let end_time = self.time(&format!("{path}.{end_name}"), end);
+        if all_day == Some(true) {
+            if start.is_some() || end.is_some() {
+                self.push(
+                    IssueSeverity::Warning,
+                    IssueType::Value,
+                    path,
+                    format!("{start_name} and {end_name} are ignored when allDay is true"),
+                );
+            }
+            return;
+        }
+        if start.is_some() != end.is_some() {
+            let missing = if start.is_none() {
+                start_name
+            } else {
+                end_name
+            };
+            self.push(
+                IssueSeverity::Warning,
+                IssueType::Required,
+                path,
+                format!("{missing} is missing, the opening hours are open-ended"),
+            );
+        }
+        if let (Some(start_time), Some(end_time)) = (start_time, end_time)
+            && start_time.millis_of_day() >= end_time.millis_of_day()
+        {
+            self.push(
+                IssueSeverity::Warning,
+                IssueType::Value,
+                path,
+                format!(
+                    "{end_name} {end_time} is not after {start_name} {start_time}, \
+                     the hours span midnight or are empty"
+                ),
+            );
+        }
+    }
+
+    fn available_times(&mut self, path: &str, available_times: Option<&Vec<AvailableTime>>) {
+        for (index, available_time) in available_times.iter().copied().flatten().enumerate() {
+            self.opening_hours(
+                &format!("{path}[{index}]"),
+                available_time.all_day,
+                (
+                    "availableStartTime",
+                    available_time.available_start_time.as_ref(),
+                ),
+                (
+                    "availableEndTime",
+                    available_time.available_end_time.as_ref(),
+                ),
+            );
+        }
+    }
+
+    fn not_availables(&mut self, path: &str, not_availables: Option<&Vec<NotAvailable>>) {
+        for (index, not_available) in not_availables.iter().copied().flatten().enumerate() {
+            let path = format!("{path}[{index}]");
+            if not_available.description.trim().is_empty() {
+                self.push(
+                    IssueSeverity::Error,
+                    IssueType::Required,
+                    format!("{path}.description"),
+                    "description is mandatory",
+                );
+            }
+            self.period(&format!("{path}.during"), not_available.during.as_ref());
+        }
+    }
+
+    fn hours_of_operation(&mut self, path: &str, hours: Option<&Vec<HoursOfOperation>>) {
+        for (index, hours) in hours.iter().copied().flatten().enumerate() {
+            self.opening_hours(
+                &format!("{path}[{index}]"),
+                hours.all_day,
+                ("operationTime", hours.operation_time.as_ref()),
+                ("closingTime", hours.closing_time.as_ref()),
+            );
+        }
+    }
+
+    fn coordinate(&mut self, path: &str, value: f32, limit: f32) {
+        if !value.is_finite() || value.abs() > limit {
+            self.push(
+                IssueSeverity::Error,
+                IssueType::Value,
+                path,
+                format!("{value} is outside the WGS84 range -{limit}..{limit}"),
+            );
+        }
+    }
+
+    fn finish(self) -> Vec<ValidationIssue> {
+        self.issues
+    }
+}
+
+impl Validate for Organization {
+    fn validate(&self) -> Vec<ValidationIssue> {
+        let root = Self::TYPE;
+        let mut checker = InvariantChecker::default();
+        let has_identifier = self.identifier.as_ref().is_some_and(|ids| !ids.is_empty());
+        if !has_identifier && self.name.is_none() {
+            checker.invariant(
+                IssueSeverity::Error,
+                "org-1",
+                root,
+                "The organization SHALL at least have a name or an identifier, and possibly more than one",
+            );
+        }
+        for (index, address) in self.address.iter().flatten().enumerate() {
+            if address.r#use.as_deref() == Some("home") {
+                checker.invariant(
+                    IssueSeverity::Error,
+                    "org-2",
+                    format!("{root}.address[{index}]"),
+                    "An address of an organization can never be of use 'home'",
+                );
+            }
+        }
+        for (index, telecom) in self.telecom.iter().flatten().enumerate() {
+            if telecom.r#use.as_deref() == Some("home") {
+                checker.invariant(
+                    IssueSeverity::Error,
+                    "org-3",
+                    format!("{root}.telecom[{index}]"),
+                    "The telecom of an organization can never be of use 'home'",
+                );
+            }
+        }
+        checker.identifiers(&format!("{root}.identifier"), self.identifier.as_ref());
+        checker.telecoms(&format!("{root}.telecom"), self.telecom.as_ref());
+        checker.addresses(&format!("{root}.address"), self.address.as_ref());
+        for (index, contact) in self.contact.iter().flatten().enumerate() {
+            let path = format!("{root}.contact[{index}]");
+            if let Some(name) = &contact.name {
+                checker.name(&format!("{path}.name"), name);
+            }
+            checker.telecoms(&format!("{path}.telecom"), contact.telecom.as_ref());
+            if let Some(address) = &contact.address {
+                checker.address(&format!("{path}.address"), address);
+            }
+        }
+        checker.reference(&format!("{root}.partOf"), self.part_of.as_ref());
+        checker.references(&format!("{root}.endpoint"), self.endpoint.as_ref());
+        checker.finish()
+    }
+}
+
+impl Validate for Location {
+    fn validate(&self) -> Vec<ValidationIssue> {
+        let root = Self::TYPE;
+        let mut checker = InvariantChecker::default();
+        checker.identifiers(&format!("{root}.identifier"), self.identifier.as_ref());
+        checker.code(&format!("{root}.mode"), self.mode.as_ref(), &LOCATION_MODES);
+        checker.telecoms(&format!("{root}.telecom"), self.telecom.as_ref());
+        if let Some(address) = &self.address {
+            checker.address(&format!("{root}.address"), address);
+        }
+        if let Some(position) = &self.position {
+            checker.coordinate(
+                &format!("{root}.position.latitude"),
+                position.latitude,
+                90.0,
+            );
+            checker.coordinate(
+                &format!("{root}.position.longitude"),
+                position.longitude,
+                180.0,
+            );
+            if let Some(altitude) = position.altitude
+                && !altitude.is_finite()
+            {
+                checker.push(
+                    IssueSeverity::Error,
+                    IssueType::Value,
+                    format!("{root}.position.altitude"),
+                    format!("{altitude} is not a valid altitude"),
+                );
+            }
+        }
+        checker.hours_of_operation(
+            &format!("{root}.hoursOfOperation"),
+            self.hours_of_operation.as_ref(),
+        );
+        checker.reference(
+            &format!("{root}.managingOrganization"),
+            self.managing_organization.as_ref(),
+        );
+        checker.reference(&format!("{root}.partOf"), self.part_of.as_ref());
+        checker.reference(&format!("{root}.endpoint"), self.endpoint.as_ref());
+        checker.finish()
+    }
+}
+
+impl Validate for Endpoint {
+    fn validate(&self) -> Vec<ValidationIssue> {
+        let root = Self::TYPE;
+        let mut checker = InvariantChecker::default();
+        checker.identifiers(&format!("{root}.identifier"), self.identifier.as_ref());
+        if self.address.trim().is_empty() {
+            checker.push(
+                IssueSeverity::Error,
+                IssueType::Required,
+                format!("{root}.address"),
+                "address is mandatory",
+            );
+        }
+        if self.payload_type.is_empty() {
+            checker.push(
+                IssueSeverity::Error,
+                IssueType::Required,
+                format!("{root}.payloadType"),
+                "at least one payloadType is required",
+            );
+        }
+        for (index, mime_type) in self.payload_mime_type.iter().flatten().enumerate() {
+            let valid = mime_type
+                .split_once('/')
+                .is_some_and(|(r#type, subtype)| !r#type.is_empty() && !subtype.is_empty());
+            if !valid {
+                checker.push(
+                    IssueSeverity::Error,
+                    IssueType::Value,
+                    format!("{root}.payloadMimeType[{index}]"),
+                    format!("'{mime_type}' is not a mime type"),
+                );
+            }
+        }
+        checker.telecoms(&format!("{root}.contact"), self.contact.as_ref());
+        checker.period(&format!("{root}.period"), self.period.as_ref());
+        checker.reference(
+            &format!("{root}.managingOrganization"),
+            self.managing_organization.as_ref(),
+        );
+        checker.finish()
+    }
+}
+
+impl Validate for Practitioner {
+    fn validate(&self) -> Vec<ValidationIssue> {
+        let root = Self::TYPE;
+        let mut checker = InvariantChecker::default();
+        checker.identifiers(&format!("{root}.identifier"), self.identifier.as_ref());
+        checker.names(&format!("{root}.name"), self.name.as_ref());
+        checker.telecoms(&format!("{root}.telecom"), self.telecom.as_ref());
+        checker.addresses(&format!("{root}.address"), self.address.as_ref());
+        if let Some(birth_date) = &self.birth_date {
+            let path = format!("{root}.birthDate");
+            if let Some(date) = checker.date_time(&path, Some(birth_date))
+                && date.has_time()
+            {
+                checker.push(
+                    IssueSeverity::Error,
+                    IssueType::Value,
+                    path,
+                    format!("'{birth_date}' is not a date"),
+                );
+            }
+        }
+        for (index, qualification) in self.qualification.iter().flatten().enumerate() {
+            let path = format!("{root}.qualification[{index}]");
+            checker.identifiers(
+                &format!("{path}.identifier"),
+                qualification.identifier.as_ref(),
+            );
+            checker.period(&format!("{path}.period"), qualification.period.as_ref());
+            checker.reference(&format!("{path}.issuer"), qualification.issuer.as_ref());
+        }
+        checker.finish()
+    }
+}
+
+impl Validate for PractitionerRole {
+    fn validate(&self) -> Vec<ValidationIssue> {
+        let root = Self::TYPE;
+        let mut checker = InvariantChecker::default();
+        checker.identifiers(&format!("{root}.identifier"), self.identifier.as_ref());
+        checker.period(&format!("{root}.period"), self.period.as_ref());
+        checker.telecoms(&format!("{root}.telecom"), self.telecom.as_ref());
+        checker.available_times(
+            &format!("{root}.availableTime"),
+            self.available_time.as_ref(),
+        );
+        checker.not_availables(&format!("{root}.notAvailable"), self.not_available.as_ref());
+        checker.reference(&format!("{root}.practitioner"), self.practitioner.as_ref());
+        checker.reference(&format!("{root}.organization"), self.organization.as_ref());
+        checker.references(&format!("{root}.location"), self.location.as_ref());
+        checker.references(
+            &format!("{root}.healthcareService"),
+            self.healthcare_service.as_ref(),
+        );
+        checker.references(&format!("{root}.endpoint"), self.endpoint.as_ref());
+        checker.finish()
+    }
+}
+
+impl Validate for HealthcareService {
+    fn validate(&self) -> Vec<ValidationIssue> {
+        let root = Self::TYPE;
+        let mut checker = InvariantChecker::default();
+        checker.identifiers(&format!("{root}.identifier"), self.identifier.as_ref());
+        checker.telecoms(&format!("{root}.telecom"), self.telecom.as_ref());
+        checker.available_times(
+            &format!("{root}.availableTime"),
+            self.available_time.as_ref(),
+        );
+        checker.not_availables(&format!("{root}.notAvailable"), self.not_available.as_ref());
+        checker.reference(&format!("{root}.providedBy"), self.provided_by.as_ref());
+        checker.references(&format!("{root}.location"), self.location.as_ref());
+        checker.references(&format!("{root}.coverageArea"), self.coverage_area.as_ref());
+        checker.references(&format!("{root}.endpoint"), self.endpoint.as_ref());
+        checker.finish()
+    }
+}
+
+impl Validate for OrganizationAffiliation {
+    fn validate(&self) -> Vec<ValidationIssue> {
+        let root = Self::TYPE;
+        let mut checker = InvariantChecker::default();
+        checker.identifiers(&format!("{root}.identifier"), self.identifier.as_ref());
+        checker.period(&format!("{root}.period"), self.period.as_ref());
+        checker.telecoms(&format!("{root}.telecom"), self.telecom.as_ref());
+        checker.reference(&format!("{root}.organization"), self.organization.as_ref());
+        checker.reference(
+            &format!("{root}.participatingOrganization"),
+            self.participating_organization.as_ref(),
+        );
+        checker.references(&format!("{root}.network"), self.network.as_ref());
+        checker.references(&format!("{root}.location"), self.location.as_ref());
+        checker.references(
+            &format!("{root}.healthcareService"),
+            self.healthcare_service.as_ref(),
+        );
+        checker.references(&format!("{root}.endpoint"), self.endpoint.as_ref());
+        checker.finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::r4::{
+        elements::{
+            AddressBuilder, AvailableTimeBuilder, ContactPointBuilder, NotAvailableBuilder,
+            PeriodBuilder, ReferenceBuilder,
+        },
+        resources::{
+            EndpointBuilder, HoursOfOperationBuilder, LocationBuilder, LocationPositionBuilder,
+            OrganizationBuilder, PractitionerRoleBuilder,
+        },
+    };
+
+    use super::*;
+
+    fn summary(issues: &[ValidationIssue]) -> Vec<(IssueSeverity, &str, Option<&str>)> {
+        issues
+            .iter()
+            .map(|issue| {
+                (
+                    issue.severity,
+                    issue.location.as_str(),
+                    issue.constraint.as_deref(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_fixtures_should_have_no_errors() {
+        let fixtures: Vec<Vec<ValidationIssue>> = vec![
+            Organization::from_json(include_str!(
+                "../../../fixtures/r4/resources/organization.json"
+            ))
+            .unwrap()
+            .validate(),
+            Location::from_json(include_str!("../../../fixtures/r4/resources/location.json"))
+                .unwrap()
+                .validate(),
+            Endpoint::from_json(include_str!("../../../fixtures/r4/resources/endpoint.json"))
+                .unwrap()
+                .validate(),
+            Practitioner::from_json(include_str!(
+                "../../../fixtures/r4/resources/practitioner.json"
+            ))
+            .unwrap()
+            .validate(),
+            PractitionerRole::from_json(include_str!(
+                "../../../fixtures/r4/resources/practitioner_role.json"
+            ))
+            .unwrap()
+            .validate(),
+            HealthcareService::from_json(include_str!(
+                "../../../fixtures/r4/resources/healthcare_service.json"
+            ))
+            .unwrap()
+            .validate(),
+            OrganizationAffiliation::from_json(include_str!(
+                "../../../fixtures/r4/resources/organization_affiliation.json"
+            ))
+            .unwrap()
+            .validate(),
+        ];
+
+        for issues in fixtures {
+            assert!(!issues.iter().any(ValidationIssue::is_error), "{issues:?}");
+        }
+    }
+
+    #[test]
+    fn test_organization_invariants() {
+        let organization = OrganizationBuilder::new("org-1")
+            .add_telecom(
+                ContactPointBuilder::new("home-phone")
+                    .with_system("phone")
+                    .with_value("555-0100")
+                    .with_use("home")
+                    .build(),
+            )
+            .add_telecom(
+                ContactPointBuilder::new("no-system")
+                    .with_value("x")
+                    .build(),
+            )
+            .add_address(AddressBuilder::new("home").r#use("home").build())
+            .build();
+
+        let issues = organization.validate();
+
+        assert_eq!(
+            summary(&issues),
+            vec![
+                (IssueSeverity::Error, "Organization", Some("org-1")),
+                (
+                    IssueSeverity::Error,
+                    "Organization.address[0]",
+                    Some("org-2")
+                ),
+                (
+                    IssueSeverity::Error,
+                    "Organization.telecom[0]",
+                    Some("org-3")
+                ),
+                (
+                    IssueSeverity::Error,
+                    "Organization.telecom[1]",
+                    Some("cpt-2")
+                ),
+            ]
+        );
+        assert!(!organization.is_valid());
+        assert!(
+            OrganizationBuilder::new("org-2")
+                .name("Acme")
+                .build()
+                .is_valid()
+        );
+    }
+
+    #[test]
+    fn test_period_start_after_end_should_fail() {
+        let role = PractitionerRoleBuilder::new("role-1")
+            .period(
+                PeriodBuilder::default()
+                    .with_start("2024-05-01")
+                    .with_end("2023-01-01")
+                    .build(),
+            )
+            .add_telecom(
+                ContactPointBuilder::new("email")
+                    .with_system("email")
+                    .with_value("a@b.org")
+                    .with_period(
+                        PeriodBuilder::default()
+                            .with_start("2024")
+                            .with_end("2024-03")
+                            .build(),
+                    )
+                    .build(),
+            )
+            .build();
+
+        let issues = role.validate();
+
+        assert_eq!(
+            summary(&issues),
+            vec![(
+                IssueSeverity::Error,
+                "PractitionerRole.period",
+                Some("per-1")
+            )]
+        );
+    }
+
+    #[test]
+    fn test_location_position_should_be_in_range() {
+        let location = LocationBuilder::new("loc-1")
+            .position(
+                LocationPositionBuilder::new("pos")
+                    .latitude(91.5)
+                    .longitude(-180.0)
+                    .build(),
+            )
+            .mode("somewhere")
+            .add_hours_of_operation(
+                HoursOfOperationBuilder::new("hours")
+                    .operation_time("18:00")
+                    .closing_time("8am")
+                    .build(),
+            )
+            .build();
+
+        let issues = location.validate();
+
+        assert_eq!(
+            issues
+                .iter()
+                .map(|issue| (issue.code, issue.location.as_str()))
+                .collect::<Vec<_>>(),
+            vec![
+                (IssueType::CodeInvalid, "Location.mode"),
+                (IssueType::Value, "Location.position.latitude"),
+                (IssueType::Value, "Location.hoursOfOperation[0].closingTime"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_available_time_consistency() {
+        let role = PractitionerRoleBuilder::new("role-1")
+            .add_available_time(
+                AvailableTimeBuilder::new("all-day")
+                    .with_all_day(true)
+                    .with_available_start_time("08:00")
+                    .build(),
+            )
+            .add_available_time(
+                AvailableTimeBuilder::new("overnight")
+                    .with_available_start_time("22:00")
+                    .with_available_end_time("06:00")
+                    .build(),
+            )
+            .add_available_time(
+                AvailableTimeBuilder::new("open-ended")
+                    .with_available_start_time("09:00")
+                    .build(),
+            )
+            .add_not_available_time(
+                NotAvailableBuilder::new("holiday")
+                    .with_during(
+                        PeriodBuilder::default()
+                            .with_start("2024-12-25")
+                            .with_end("2024-12-24")
+                            .build(),
+                    )
+                    .build(),
+            )
+            .build();
+
+        let issues = role.validate();
+
+        assert_eq!(
+            issues
+                .iter()
+                .map(|issue| (issue.severity, issue.location.as_str()))
+                .collect::<Vec<_>>(),
+            vec![
+                (IssueSeverity::Warning, "PractitionerRole.availableTime[0]"),
+                (IssueSeverity::Warning, "PractitionerRole.availableTime[1]"),
+                (IssueSeverity::Warning, "PractitionerRole.availableTime[2]"),
+                (
+                    IssueSeverity::Error,
+                    "PractitionerRole.notAvailable[0].description"
+                ),
+                (
+                    IssueSeverity::Error,
+                    "PractitionerRole.notAvailable[0].during"
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_endpoint_mandatory_elements() {
+        let endpoint = EndpointBuilder::new("ep-1").add_mime_type("json").build();
+
+        let issues = endpoint.validate();
+
+        assert_eq!(
+            issues
+                .iter()
+                .map(|issue| (issue.code, issue.location.as_str()))
+                .collect::<Vec<_>>(),
+            vec![
+                (IssueType::Required, "Endpoint.address"),
+                (IssueType::Required, "Endpoint.payloadType"),
+                (IssueType::Value, "Endpoint.payloadMimeType[0]"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_references_should_point_to_the_expected_type() {
+        let organization = OrganizationBuilder::new("org-1")
+            .name("Acme")
+            .part_of(
+                ReferenceBuilder::default()
+                    .with_reference("Endpoint/1")
+                    .build(),
+            )
+            .build();
+
+        let issues = organization.validate();
+
+        assert_eq!(
+            vec![ValidationIssue::new(
+                IssueSeverity::Error,
+                IssueType::Value,
+                "Organization.partOf",
+                "'Endpoint/1' does not point to a Organization"
+            )],
+            issues
+        );
+    }
+}
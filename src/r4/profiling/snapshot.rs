@@ -0,0 +1,724 @@
+use std::{collections::HashMap, path::Path};
+
+use crate::{
+    FhirError,
+    r4::{
+        elements::{ElementDefinition, ElementDefinitionBase},
+        resources::{
+            ResourceType, StructureDefinition, StructureDefinitionSnapshot, load_json_dir,
+        },
+    },
+};
+
+/// Canonical url prefix of the definitions published with the FHIR specification.
+pub const FHIR_STRUCTURE_DEFINITION_BASE: &str = "http://hl7.org/fhir/StructureDefinition/";
+
+/// Limits the depth of `baseDefinition` chains, guarding against cycles.
+const MAX_DERIVATION_DEPTH: usize = 32;
+
+/// A set of `StructureDefinition`s keyed by canonical url, used to resolve
+/// base definitions and datatypes while generating snapshots.
+#[derive(Debug, Default)]
+pub struct ProfileRegistry {
+    definitions: HashMap<String, StructureDefinition>,
+}
+
+impl ProfileRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add(&mut self, definition: StructureDefinition) -> Result<(), FhirError> {
+        if definition.url.is_empty() {
+            return Err(profile_error("StructureDefinition must have a url"));
+        }
+        self.definitions.insert(definition.url.clone(), definition);
+        Ok(())
+    }
+
+    /// Adds a `StructureDefinition` from its JSON representation, returns `false`
+    /// when the resource is of another type.
+    pub fn add_json(&mut self, data: &str) -> Result<bool, FhirError> {
+        let value: serde_json::Value = serde_json::from_str(data)?;
+        if value.get("resourceType").and_then(|t| t.as_str()) != Some(StructureDefinition::TYPE) {
+            return Ok(false);
+        }
+        self.add(serde_json::from_value(value)?)?;
+        Ok(true)
+    }
+
+    /// Loads every `StructureDefinition` from the `.json` files in a directory,
+    /// returns the number of definitions that were loaded.
+    pub fn load_dir(&mut self, path: impl AsRef<Path>) -> Result<usize, FhirError> {
+        load_json_dir(path.as_ref(), |data| self.add_json(data))
+    }
+
+    /// Resolves a canonical (`url` or `url|version`) to a definition.
+    pub fn get(&self, canonical: &str) -> Option<&StructureDefinition> {
+        let url = canonical.split('|').next().unwrap_or(canonical);
+        self.definitions.get(url)
+    }
+
+    /// Returns the definition of a FHIR resource or datatype by its type name.
+    pub fn get_type(&self, type_name: &str) -> Option<&StructureDefinition> {
+        self.get(&format!("{FHIR_STRUCTURE_DEFINITION_BASE}{type_name}"))
+    }
+
+    /// Returns the registered definition with its snapshot, generating it from
+    /// the differential when it has none.
+    pub fn snapshot(&self, canonical: &str) -> Result<StructureDefinition, FhirError> {
+        let definition = self
+            .get(canonical)
+            .ok_or_else(|| profile_error(format!("unknown StructureDefinition {canonical}")))?;
+        self.generate_snapshot(definition)
+    }
+
+    /// Generates the snapshot of a profile by applying its differential to the
+    /// snapshot of its `baseDefinition`. Definitions without a differential are
+    /// returned as-is when they already carry a snapshot.
+    pub fn generate_snapshot(
+        &self,
+        profile: &StructureDefinition,
+    ) -> Result<StructureDefinition, FhirError> {
+        let mut chain = Vec::new();
+        self.generate(profile, &mut chain)
+    }
+
+    fn generate(
+        &self,
+        profile: &StructureDefinition,
+        chain: &mut Vec<String>,
+    ) -> Result<StructureDefinition, FhirError> {
+        let differential = match &profile.differential {
+            Some(differential) => &differential.element,
+            None if profile.snapshot.is_some() => return Ok(profile.clone()),
+            None => {
+                return Err(profile_error(format!(
+                    "{} has neither a snapshot nor a differential",
+                    profile.url
+                )));
+            }
+        };
+
+        if chain.contains(&profile.url) || chain.len() >= MAX_DERIVATION_DEPTH {
+            return Err(profile_error(format!(
+                "circular baseDefinition chain at {}",
+                profile.url
+            )));
+        }
+        chain.push(profile.url.clone());
+
+        let base_url = profile
+            .base_definition
+            .as_deref()
+            .ok_or_else(|| profile_error(format!("{} has no baseDefinition", profile.url)))?;
+        let base = self
+            .get(base_url)
+            .ok_or_else(|| profile_error(format!("unknown baseDefinition {base_url}")))?;
+        let base = self.generate(base, chain)?;
+        chain.pop();
+
+        let mut elements: Vec<ElementDefinition> = base
+            .snapshot_elements()
+            .iter()
+            .map(|e| {
+                let mut element = rebase(e, &base.r#type, &profile.r#type);
+                if element.base.is_none() {
+                    element.base = Some(ElementDefinitionBase {
+                        path: e.path.clone(),
+                        min: e.min.unwrap_or(0),
+                        max: e.max.clone().unwrap_or_else(|| "*".to_string()),
+                        ..Default::default()
+                    });
+                }
+                element
+            })
+            .collect();
+
+        for diff in differential {
+            self.apply(&mut elements, diff)?;
+        }
+
+        let mut result = profile.clone();
+        result.snapshot = Some(StructureDefinitionSnapshot {
+            element: elements,
+            ..Default::default()
+        });
+        Ok(result)
+    }
+
+    fn apply(
+        &self,
+        elements: &mut Vec<ElementDefinition>,
+        diff: &ElementDefinition,
+    ) -> Result<(), FhirError> {
+        let id = differential_id(diff);
+
+        loop {
+            if let Some(position) = elements.iter().position(|e| e.element_id() == id) {
+                return merge(&mut elements[position], diff);
+            }
+
+            let (parent, last) = id
+                .rsplit_once('.')
+                .ok_or_else(|| profile_error(format!("element {id} is not in the base")))?;
+
+            if let Some((name, slice_name)) = last.split_once(':')
+                && elements.iter().any(|e| e.element_id() == parent)
+            {
+                add_slice(elements, &format!("{parent}.{name}"), slice_name)?;
+                continue;
+            }
+
+            if !self.expand_ancestor(elements, &id)? {
+                return Err(profile_error(format!("element {id} is not in the base")));
+            }
+        }
+    }
+
+    /// Inserts the datatype children of the nearest ancestor of `id` found in
+    /// `elements`, returns `false` when there was nothing to expand.
+    fn expand_ancestor(
+        &self,
+        elements: &mut Vec<ElementDefinition>,
+        id: &str,
+    ) -> Result<bool, FhirError> {
+        let mut ancestor = id;
+        let position = loop {
+            let Some((parent, _)) = ancestor.rsplit_once('.') else {
+                return Ok(false);
+            };
+            ancestor = parent;
+            if let Some(position) = elements.iter().position(|e| e.element_id() == ancestor) {
+                break position;
+            }
+        };
+
+        let prefix = format!("{ancestor}.");
+        if elements
+            .get(position + 1)
+            .is_some_and(|e| e.element_id().starts_with(&prefix))
+        {
+            return Ok(false);
+        }
+
+        let owner = &elements[position];
+        let [type_code] = owner.type_codes()[..] else {
+            return Err(profile_error(format!(
+                "cannot expand {ancestor}, it does not have exactly one type"
+            )));
+        };
+        let datatype = self.get_type(type_code).ok_or_else(|| {
+            profile_error(format!("no StructureDefinition for datatype {type_code}"))
+        })?;
+        let datatype = self.generate_snapshot(datatype)?;
+
+        let owner_path = owner.path.clone();
+        let children: Vec<ElementDefinition> = datatype
+            .snapshot_elements()
+            .iter()
+            .skip(1)
+            .map(|e| {
+                let mut child = e.clone();
+                child.path = replace_root(&e.path, &datatype.r#type, &owner_path);
+                child.backbone_element.element.id =
+                    Some(replace_root(e.element_id(), &datatype.r#type, ancestor));
+                child
+            })
+            .collect();
+
+        let expanded = !children.is_empty();
+        elements.splice(position + 1..position + 1, children);
+        Ok(expanded)
+    }
+}
+
+/// The id of a differential element, derived from its path and slice name
+/// when the element has no id.
+fn differential_id(diff: &ElementDefinition) -> String {
+    match (&diff.backbone_element.element.id, &diff.slice_name) {
+        (Some(id), _) => id.clone(),
+        (None, Some(slice_name)) => format!("{}:{slice_name}", diff.path),
+        (None, None) => diff.path.clone(),
+    }
+}
+
+/// Adds slice `slice_name` of element `unsliced` after its existing slices,
+/// copying the unsliced element and its children.
+fn add_slice(
+    elements: &mut Vec<ElementDefinition>,
+    unsliced: &str,
+    slice_name: &str,
+) -> Result<(), FhirError> {
+    let position = elements
+        .iter()
+        .position(|e| e.element_id() == unsliced)
+        .ok_or_else(|| profile_error(format!("sliced element {unsliced} is not in the base")))?;
+    if elements[position].slicing.is_none() {
+        return Err(profile_error(format!(
+            "slice {slice_name} defined on {unsliced} which has no slicing"
+        )));
+    }
+
+    let children_prefix = format!("{unsliced}.");
+    let slice_prefix = format!("{unsliced}:");
+    let slice_id = format!("{unsliced}:{slice_name}");
+
+    let mut slice: Vec<ElementDefinition> = elements[position..]
+        .iter()
+        .take_while(|e| e.element_id() == unsliced || e.element_id().starts_with(&children_prefix))
+        .map(|e| {
+            let mut element = e.clone();
+            element.backbone_element.element.id =
+                Some(replace_root(e.element_id(), unsliced, &slice_id));
+            element
+        })
+        .collect();
+    if let Some(root) = slice.first_mut() {
+        root.slice_name = Some(slice_name.to_string());
+        root.slicing = None;
+        root.min = Some(0);
+    }
+
+    let end = position
+        + 1
+        + elements[position + 1..]
+            .iter()
+            .take_while(|e| {
+                e.element_id().starts_with(&children_prefix)
+                    || e.element_id().starts_with(&slice_prefix)
+            })
+            .count();
+    elements.splice(end..end, slice);
+    Ok(())
+}
+
+/// Merges a differential element into its snapshot element.
+fn merge(target: &mut ElementDefinition, diff: &ElementDefinition) -> Result<(), FhirError> {
+    let id = target.element_id().to_string();
+
+    if let Some(min) = diff.min
+        && min < target.min.unwrap_or(0)
+    {
+        return Err(profile_error(format!(
+            "{id} loosens min from {} to {min}",
+            target.min.unwrap_or(0)
+        )));
+    }
+    if let Some(max) = diff.max.as_deref()
+        && let Some(base_max) = target.max.as_deref()
+        && exceeds(max, base_max)
+    {
+        return Err(profile_error(format!(
+            "{id} loosens max from {base_max} to {max}"
+        )));
+    }
+    if let Some(types) = &diff.r#type {
+        let allowed = target.type_codes();
+        if let Some(t) = types
+            .iter()
+            .find(|t| !allowed.is_empty() && !allowed.contains(&t.code.as_str()))
+        {
+            return Err(profile_error(format!(
+                "{id} does not allow type {}",
+                t.code
+            )));
+        }
+    }
+
+    override_with(&mut target.representation, &diff.representation);
+    override_with(
+        &mut target.slice_is_constraining,
+        &diff.slice_is_constraining,
+    );
+    override_with(&mut target.label, &diff.label);
+    override_with(&mut target.code, &diff.code);
+    override_with(&mut target.slicing, &diff.slicing);
+    override_with(&mut target.short, &diff.short);
+    override_with(&mut target.definition, &diff.definition);
+    override_with(&mut target.comment, &diff.comment);
+    override_with(&mut target.requirements, &diff.requirements);
+    override_with(&mut target.min, &diff.min);
+    override_with(&mut target.max, &diff.max);
+    override_with(&mut target.content_reference, &diff.content_reference);
+    override_with(&mut target.r#type, &diff.r#type);
+    override_with(&mut target.meaning_when_missing, &diff.meaning_when_missing);
+    override_with(&mut target.order_meaning, &diff.order_meaning);
+    override_with(&mut target.example, &diff.example);
+    override_with(&mut target.max_length, &diff.max_length);
+    override_with(&mut target.must_support, &diff.must_support);
+    override_with(&mut target.is_modifier, &diff.is_modifier);
+    override_with(&mut target.is_modifier_reason, &diff.is_modifier_reason);
+    override_with(&mut target.is_summary, &diff.is_summary);
+    override_with(&mut target.binding, &diff.binding);
+
+    append_unique(&mut target.alias, &diff.alias, |a, b| a == b);
+    append_unique(&mut target.condition, &diff.condition, |a, b| a == b);
+    append_unique(&mut target.constraint, &diff.constraint, |a, b| {
+        a.key == b.key
+    });
+    append_unique(&mut target.mapping, &diff.mapping, |a, b| a == b);
+
+    for (key, value) in &diff.choice_values {
+        target.choice_values.insert(key.clone(), value.clone());
+    }
+    Ok(())
+}
+
+fn override_with<T: Clone>(target: &mut Option<T>, diff: &Option<T>) {
+    if diff.is_some() {
+        target.clone_from(diff);
+    }
+}
+
+fn append_unique<T: Clone>(
+    target: &mut Option<Vec<T>>,
+    diff: &Option<Vec<T>>,
+    same: impl Fn(&T, &T) -> bool,
+) {
+    let Some(diff) = diff else {
+        return;
+    };
+    let items = target.get_or_insert_with(Vec::new);
+    for item in diff {
+        if !items.iter().any(|existing| same(existing, item)) {
+            items.push(item.clone());
+        }
+    }
+}
+
+/// `true` when cardinality `max` allows more repetitions than `base_max`.
+fn exceeds(max: &str, base_max: &str) -> bool {
+    match (max, base_max) {
+        (_, "*") => false,
+        ("*", _) => true,
+        (max, base_max) => match (max.parse::<u32>(), base_max.parse::<u32>()) {
+            (Ok(max), Ok(base_max)) => max > base_max,
+            _ => false,
+        },
+    }
+}
+
+/// Re-roots an element of `from_type` onto `to_type`, used when a definition
+/// is derived from the definition of another type.
+fn rebase(element: &ElementDefinition, from_type: &str, to_type: &str) -> ElementDefinition {
+    let mut element = element.clone();
+    if from_type != to_type {
+        element.path = replace_root(&element.path, from_type, to_type);
+        if let Some(id) = element.backbone_element.element.id.as_mut() {
+            *id = replace_root(id, from_type, to_type);
+        }
+    }
+    element
+}
+
+/// Replaces the leading `root` of a dotted path or id with `replacement`.
+fn replace_root(path: &str, root: &str, replacement: &str) -> String {
+    match path.strip_prefix(root) {
+        Some(rest) if rest.is_empty() || rest.starts_with(['.', ':']) => {
+            format!("{replacement}{rest}")
+        }
+        _ => path.to_string(),
+    }
+}
+
+fn profile_error(message: impl Into<String>) -> FhirError {
+    FhirError::ProfileError(message.into())
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::json;
+
+    use crate::r4::{
+        elements::ElementDefinitionBuilder,
+        resources::{StructureDefinitionBuilder, TypeDerivationRule},
+    };
+
+    use super::*;
+
+    const DIRECTORY_ORGANIZATION: &str =
+        "http://example.org/StructureDefinition/directory-organization";
+
+    fn registry() -> ProfileRegistry {
+        let mut registry = ProfileRegistry::new();
+        registry
+            .load_dir(concat!(env!("CARGO_MANIFEST_DIR"), "/fixtures/r4/profiles"))
+            .unwrap();
+        registry
+    }
+
+    fn location_profile(elements: Vec<ElementDefinition>) -> StructureDefinition {
+        elements
+            .into_iter()
+            .fold(
+                StructureDefinitionBuilder::new("directory-location")
+                    .url("http://example.org/StructureDefinition/directory-location")
+                    .name("DirectoryLocation")
+                    .r#type("Location")
+                    .base_definition("http://hl7.org/fhir/StructureDefinition/Location")
+                    .derivation(TypeDerivationRule::Constraint),
+                |builder, element| builder.add_differential_element(element),
+            )
+            .build()
+    }
+
+    fn ids(definition: &StructureDefinition) -> Vec<&str> {
+        definition
+            .snapshot_elements()
+            .iter()
+            .map(|e| e.element_id())
+            .collect()
+    }
+
+    #[test]
+    fn test_load_dir_should_succeed() {
+        let mut registry = ProfileRegistry::new();
+
+        let actual = registry
+            .load_dir(concat!(env!("CARGO_MANIFEST_DIR"), "/fixtures/r4/profiles"))
+            .unwrap();
+
+        assert_eq!(4, actual);
+        assert!(registry.get_type("Organization").is_some());
+        assert!(
+            registry
+                .get(&format!("{DIRECTORY_ORGANIZATION}|1.0.0"))
+                .is_some()
+        )
+    }
+
+    #[test]
+    fn test_snapshot_should_return_base_definition_unchanged() {
+        let registry = registry();
+        let expected = registry.get_type("Organization").unwrap().clone();
+
+        let actual = registry
+            .snapshot("http://hl7.org/fhir/StructureDefinition/Organization")
+            .unwrap();
+
+        assert_eq!(expected, actual)
+    }
+
+    #[test]
+    fn test_snapshot_should_apply_differential() {
+        let registry = registry();
+        let base = registry.get_type("Organization").unwrap();
+
+        let actual = registry.snapshot(DIRECTORY_ORGANIZATION).unwrap();
+
+        // the uzovi slice and the 8 expanded Identifier children
+        assert_eq!(
+            base.snapshot_elements().len() + 9,
+            actual.snapshot_elements().len()
+        );
+
+        let name = actual.find_element("Organization.name").unwrap();
+        assert_eq!(Some(1), name.min);
+        assert_eq!(Some("1"), name.max.as_deref());
+        assert_eq!(Some(true), name.must_support);
+        assert_eq!(Some(0), name.base.as_ref().map(|b| b.min));
+
+        let active = actual.find_element("Organization.active").unwrap();
+        assert_eq!(Some(("Boolean", &json!(true))), active.pattern());
+        assert_eq!(Some(true), active.is_modifier);
+
+        let root = actual.find_element("Organization").unwrap();
+        assert_eq!(
+            Some("org-1"),
+            root.constraint
+                .as_ref()
+                .and_then(|c| c.first())
+                .map(|c| c.key.as_str())
+        )
+    }
+
+    #[test]
+    fn test_snapshot_should_insert_slices_after_sliced_element() {
+        let registry = registry();
+
+        let actual = registry.snapshot(DIRECTORY_ORGANIZATION).unwrap();
+
+        let ids = ids(&actual);
+        let position = ids
+            .iter()
+            .position(|id| *id == "Organization.identifier")
+            .unwrap();
+        assert_eq!(
+            vec![
+                "Organization.identifier",
+                "Organization.identifier:uzovi",
+                "Organization.identifier:uzovi.id",
+                "Organization.identifier:uzovi.extension",
+                "Organization.identifier:uzovi.use",
+                "Organization.identifier:uzovi.type",
+                "Organization.identifier:uzovi.system",
+                "Organization.identifier:uzovi.value",
+                "Organization.identifier:uzovi.period",
+                "Organization.identifier:uzovi.assigner",
+                "Organization.active",
+            ],
+            ids[position..position + 11].to_vec()
+        );
+
+        let slice = actual
+            .find_element("Organization.identifier:uzovi")
+            .unwrap();
+        assert_eq!(Some("uzovi"), slice.slice_name.as_deref());
+        assert_eq!(Some("1"), slice.max.as_deref());
+        assert!(slice.slicing.is_none());
+
+        let system = actual
+            .find_element("Organization.identifier:uzovi.system")
+            .unwrap();
+        assert_eq!("Organization.identifier.system", system.path);
+        assert_eq!(Some(1), system.min);
+        assert_eq!(
+            Some(("Uri", &json!("http://fhir.nl/fhir/NamingSystem/uzovi"))),
+            system.fixed()
+        );
+        assert_eq!(
+            Some("Identifier.system"),
+            system.base.as_ref().map(|b| b.path.as_str())
+        )
+    }
+
+    #[test]
+    fn test_snapshot_should_resolve_profile_chain() {
+        let mut registry = registry();
+        registry
+            .add(
+                StructureDefinitionBuilder::new("regional-organization")
+                    .url("http://example.org/StructureDefinition/regional-organization")
+                    .name("RegionalOrganization")
+                    .r#type("Organization")
+                    .base_definition(DIRECTORY_ORGANIZATION)
+                    .derivation(TypeDerivationRule::Constraint)
+                    .add_differential_element(
+                        ElementDefinitionBuilder::new("Organization.alias")
+                            .with_path("Organization.alias")
+                            .with_max("0")
+                            .build(),
+                    )
+                    .build(),
+            )
+            .unwrap();
+
+        let actual = registry
+            .snapshot("http://example.org/StructureDefinition/regional-organization")
+            .unwrap();
+
+        assert!(
+            actual
+                .find_element("Organization.identifier:uzovi.value")
+                .is_some()
+        );
+        assert_eq!(
+            Some("0"),
+            actual
+                .find_element("Organization.alias")
+                .and_then(|e| e.max.as_deref())
+        )
+    }
+
+    #[test]
+    fn test_generate_snapshot_should_constrain_location() {
+        let registry = registry();
+        let profile = location_profile(vec![
+            ElementDefinitionBuilder::new("Location.position")
+                .with_path("Location.position")
+                .with_min(1)
+                .build(),
+            ElementDefinitionBuilder::default()
+                .with_path("Location.hoursOfOperation.daysOfWeek")
+                .with_min(1)
+                .build(),
+            ElementDefinitionBuilder::new("Location.partOf")
+                .with_path("Location.partOf")
+                .add_reference_type("Location")
+                .with_must_support(true)
+                .build(),
+        ]);
+
+        let actual = registry.generate_snapshot(&profile).unwrap();
+
+        assert_eq!(ids(registry.get_type("Location").unwrap()), ids(&actual));
+        assert_eq!(
+            Some(1),
+            actual.find_element("Location.position").unwrap().min
+        );
+        assert_eq!(
+            Some(1),
+            actual
+                .find_element("Location.hoursOfOperation.daysOfWeek")
+                .unwrap()
+                .min
+        );
+        assert_eq!(
+            Some(true),
+            actual.find_element("Location.partOf").unwrap().must_support
+        )
+    }
+
+    #[test]
+    fn test_generate_snapshot_should_reject_loosened_cardinality() {
+        let registry = registry();
+        let looser_max = location_profile(vec![
+            ElementDefinitionBuilder::new("Location.name")
+                .with_path("Location.name")
+                .with_max("*")
+                .build(),
+        ]);
+        let looser_min = location_profile(vec![
+            ElementDefinitionBuilder::new("Location.position.latitude")
+                .with_path("Location.position.latitude")
+                .with_min(0)
+                .build(),
+        ]);
+
+        assert!(matches!(
+            registry.generate_snapshot(&looser_max),
+            Err(FhirError::ProfileError(_))
+        ));
+        assert!(matches!(
+            registry.generate_snapshot(&looser_min),
+            Err(FhirError::ProfileError(_))
+        ))
+    }
+
+    #[test]
+    fn test_generate_snapshot_should_reject_unknown_elements() {
+        let registry = registry();
+        let unknown_element = location_profile(vec![
+            ElementDefinitionBuilder::new("Location.colour")
+                .with_path("Location.colour")
+                .with_min(1)
+                .build(),
+        ]);
+        let unknown_datatype = location_profile(vec![
+            ElementDefinitionBuilder::new("Location.address.city")
+                .with_path("Location.address.city")
+                .with_min(1)
+                .build(),
+        ]);
+        let unsliced = location_profile(vec![
+            ElementDefinitionBuilder::new("Location.type:ward")
+                .with_path("Location.type")
+                .with_slice_name("ward")
+                .build(),
+        ]);
+        let wrong_type = location_profile(vec![
+            ElementDefinitionBuilder::new("Location.name")
+                .with_path("Location.name")
+                .add_type("markdown")
+                .build(),
+        ]);
+
+        for profile in [unknown_element, unknown_datatype, unsliced, wrong_type] {
+            assert!(matches!(
+                registry.generate_snapshot(&profile),
+                Err(FhirError::ProfileError(_))
+            ))
+        }
+    }
+}
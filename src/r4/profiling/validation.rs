@@ -0,0 +1,1082 @@
+use std::collections::HashSet;
+
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::{
+    FhirError,
+    r4::{
+        elements::{
+            BindingStrength, CodeableConcept, Coding, ConstraintSeverity, DiscriminatorType,
+            ElementDefinition, ParsedReference, SlicingRules,
+        },
+        fhirpath::FhirPathEvaluator,
+        resources::{ResourceType, StructureDefinition, ValueSetExpansionContains},
+        terminology::TerminologyEngine,
+    },
+};
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, PartialOrd, Ord)]
+pub enum IssueSeverity {
+    Error,
+    Warning,
+    Information,
+}
+
+/// The kind of problem, following the `OperationOutcome` issue types.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum IssueType {
+    Structure,
+    Required,
+    Value,
+    CodeInvalid,
+    Invariant,
+    NotSupported,
+}
+
+/// A single validation finding, `location` is a FHIRPath expression such as
+/// `Organization.identifier[0].system`.
+#[derive(Debug, PartialEq, Clone)]
+pub struct ValidationIssue {
+    pub severity: IssueSeverity,
+    pub code: IssueType,
+    pub location: String,
+    pub message: String,
+    /// The key of the invariant that was violated, for `IssueType::Invariant`.
+    pub constraint: Option<String>,
+}
+
+impl ValidationIssue {
+    pub fn new(
+        severity: IssueSeverity,
+        code: IssueType,
+        location: impl Into<String>,
+        message: impl Into<String>,
+    ) -> Self {
+        ValidationIssue {
+            severity,
+            code,
+            location: location.into(),
+            message: message.into(),
+            constraint: None,
+        }
+    }
+
+    pub fn is_error(&self) -> bool {
+        self.severity == IssueSeverity::Error
+    }
+}
+
+/// Evaluates the FHIRPath expressions of `ElementDefinition.constraint`.
+pub trait ConstraintEvaluator {
+    /// Evaluates `expression` with `context` as its focus and `resource` as
+    /// `%resource`, returning whether the constraint holds.
+    fn evaluate(
+        &self,
+        expression: &str,
+        context: &Value,
+        resource: &Value,
+    ) -> Result<bool, FhirError>;
+}
+
+impl<F> ConstraintEvaluator for F
+where
+    F: Fn(&str, &Value, &Value) -> Result<bool, FhirError>,
+{
+    fn evaluate(
+        &self,
+        expression: &str,
+        context: &Value,
+        resource: &Value,
+    ) -> Result<bool, FhirError> {
+        self(expression, context, resource)
+    }
+}
+
+/// Validates resources against the snapshot of a profile: cardinality, fixed
+/// and pattern values, slicing, types, bindings and invariants. Bindings are
+/// only checked when a terminology engine is supplied, invariants are
+/// evaluated with [`FhirPathEvaluator`] unless another evaluator is given.
+#[derive(Default)]
+pub struct ProfileValidator<'a> {
+    terminology: Option<&'a TerminologyEngine>,
+    evaluator: Option<&'a dyn ConstraintEvaluator>,
+}
+
+impl<'a> ProfileValidator<'a> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_terminology(mut self, terminology: &'a TerminologyEngine) -> Self {
+        self.terminology = Some(terminology);
+        self
+    }
+
+    pub fn with_constraint_evaluator(mut self, evaluator: &'a dyn ConstraintEvaluator) -> Self {
+        self.evaluator = Some(evaluator);
+        self
+    }
+
+    /// Validates the JSON representation of a resource against a profile
+    /// that has a snapshot.
+    pub fn validate(
+        &self,
+        resource: &Value,
+        profile: &StructureDefinition,
+    ) -> Result<Vec<ValidationIssue>, FhirError> {
+        let elements = profile.snapshot_elements();
+        let Some(root) = elements.first() else {
+            return Err(FhirError::ProfileError(format!(
+                "{} has no snapshot",
+                profile.url
+            )));
+        };
+
+        let mut run = ValidationRun {
+            validator: self,
+            elements,
+            resource,
+            issues: Vec::new(),
+        };
+
+        let resource_type = resource.get("resourceType").and_then(Value::as_str);
+        if resource_type != Some(profile.r#type.as_str()) {
+            run.error(
+                IssueType::Structure,
+                &root.path,
+                format!(
+                    "expected a {} resource but found {}",
+                    profile.r#type,
+                    resource_type.unwrap_or("no resourceType")
+                ),
+            );
+            return Ok(run.issues);
+        }
+
+        run.validate_item(root, &root.path, resource, None);
+        Ok(run.issues)
+    }
+}
+
+/// Validation against a profile for every resource that serializes to JSON,
+/// `validator` supplies the terminology engine and constraint evaluator.
+pub trait ValidateAgainstProfile {
+    fn validate_against(
+        &self,
+        profile: &StructureDefinition,
+        validator: &ProfileValidator,
+    ) -> Result<Vec<ValidationIssue>, FhirError>;
+}
+
+impl<T: Serialize + ResourceType> ValidateAgainstProfile for T {
+    fn validate_against(
+        &self,
+        profile: &StructureDefinition,
+        validator: &ProfileValidator,
+    ) -> Result<Vec<ValidationIssue>, FhirError> {
+        validator.validate(&serde_json::to_value(self)?, profile)
+    }
+}
+
+struct ValidationRun<'v, 'a> {
+    validator: &'v ProfileValidator<'a>,
+    elements: &'v [ElementDefinition],
+    resource: &'v Value,
+    issues: Vec<ValidationIssue>,
+}
+
+impl<'v> ValidationRun<'v, '_> {
+    fn error(&mut self, code: IssueType, location: &str, message: impl Into<String>) {
+        self.issues.push(ValidationIssue::new(
+            IssueSeverity::Error,
+            code,
+            location,
+            message,
+        ));
+    }
+
+    /// The direct children of the element with id `parent`.
+    fn children(&self, parent: &str) -> Vec<&'v ElementDefinition> {
+        let prefix = format!("{parent}.");
+        self.elements
+            .iter()
+            .filter(|e| {
+                e.element_id()
+                    .strip_prefix(&prefix)
+                    .is_some_and(|rest| !rest.contains(['.', ':']))
+            })
+            .collect()
+    }
+
+    /// The slices defined directly on the element with id `sliced`.
+    fn slices(&self, sliced: &str) -> Vec<&'v ElementDefinition> {
+        let prefix = format!("{sliced}:");
+        self.elements
+            .iter()
+            .filter(|e| {
+                e.element_id()
+                    .strip_prefix(&prefix)
+                    .is_some_and(|rest| !rest.contains('.'))
+            })
+            .collect()
+    }
+
+    fn find(&self, id: &str) -> Option<&'v ElementDefinition> {
+        self.elements.iter().find(|e| e.element_id() == id)
+    }
+
+    fn validate_node(&mut self, parent: &str, node: &Value, location: &str) {
+        let children = self.children(parent);
+        if children.is_empty() {
+            return;
+        }
+        let Some(object) = node.as_object() else {
+            return;
+        };
+
+        let mut known: HashSet<&str> = HashSet::new();
+        for child in children {
+            let name = child.name();
+            let element_location = format!("{location}.{}", name.trim_end_matches("[x]"));
+
+            let mut items: Vec<(String, &Value, Option<&str>)> = Vec::new();
+            let keys: Vec<(&String, Option<&str>)> = match name.strip_suffix("[x]") {
+                Some(prefix) => object
+                    .keys()
+                    .filter_map(|key| {
+                        key.strip_prefix(prefix)
+                            .filter(|t| t.starts_with(|c: char| c.is_ascii_uppercase()))
+                            .map(|t| (key, Some(t)))
+                    })
+                    .collect(),
+                None => object
+                    .get_key_value(name)
+                    .map(|(k, _)| (k, None))
+                    .into_iter()
+                    .collect(),
+            };
+            for (key, type_name) in keys {
+                known.insert(key);
+                let key_location = format!("{location}.{key}");
+                match &object[key] {
+                    Value::Array(values) => items.extend(
+                        values
+                            .iter()
+                            .enumerate()
+                            .map(|(i, v)| (format!("{key_location}[{i}]"), v, type_name)),
+                    ),
+                    Value::Null => {}
+                    value => items.push((key_location, value, type_name)),
+                }
+            }
+
+            self.validate_occurrences(child, &element_location, items);
+        }
+
+        for key in object.keys() {
+            if key != "resourceType" && !key.starts_with('_') && !known.contains(key.as_str()) {
+                self.error(
+                    IssueType::Structure,
+                    &format!("{location}.{key}"),
+                    format!("unknown element '{key}'"),
+                );
+            }
+        }
+    }
+
+    fn validate_occurrences(
+        &mut self,
+        definition: &'v ElementDefinition,
+        location: &str,
+        items: Vec<(String, &Value, Option<&str>)>,
+    ) {
+        self.check_cardinality(definition, location, items.len(), None);
+
+        let Some(slicing) = &definition.slicing else {
+            for (item_location, value, type_name) in items {
+                self.validate_item(definition, &item_location, value, type_name);
+            }
+            return;
+        };
+
+        if !items.is_empty()
+            && slicing
+                .discriminator
+                .iter()
+                .flatten()
+                .any(|d| d.r#type == DiscriminatorType::Profile)
+        {
+            self.issues.push(ValidationIssue::new(
+                IssueSeverity::Warning,
+                IssueType::NotSupported,
+                location,
+                format!(
+                    "profile discriminators are not supported, values of {} are not matched to its slices",
+                    definition.element_id()
+                ),
+            ));
+        }
+
+        let slices = self.slices(definition.element_id());
+        let mut matched: Vec<Vec<(String, &Value, Option<&str>)>> = vec![Vec::new(); slices.len()];
+        for item in items {
+            match slices
+                .iter()
+                .position(|slice| self.matches_slice(definition, slice, item.1))
+            {
+                Some(index) => matched[index].push(item),
+                None => {
+                    if slicing.rules == SlicingRules::Closed {
+                        self.error(
+                            IssueType::Structure,
+                            &item.0,
+                            format!(
+                                "value does not match any slice of closed slicing on {}",
+                                definition.element_id()
+                            ),
+                        );
+                    }
+                    self.validate_item(definition, &item.0, item.1, item.2);
+                }
+            }
+        }
+
+        for (slice, items) in slices.into_iter().zip(matched) {
+            self.check_cardinality(slice, location, items.len(), slice.slice_name.as_deref());
+            for (item_location, value, type_name) in items {
+                self.validate_item(slice, &item_location, value, type_name);
+            }
+        }
+    }
+
+    fn check_cardinality(
+        &mut self,
+        definition: &ElementDefinition,
+        location: &str,
+        count: usize,
+        slice_name: Option<&str>,
+    ) {
+        if !definition.path.contains('.') {
+            return;
+        }
+        let subject = match slice_name {
+            Some(slice_name) => format!("slice '{slice_name}'"),
+            None => definition.path.clone(),
+        };
+
+        let min = definition.min.unwrap_or(0) as usize;
+        if count < min {
+            self.error(
+                IssueType::Required,
+                location,
+                format!("{subject}: minimum required = {min}, but only found {count}"),
+            );
+        }
+        if let Some(max) = definition
+            .max
+            .as_deref()
+            .and_then(|m| m.parse::<usize>().ok())
+            && count > max
+        {
+            self.error(
+                IssueType::Structure,
+                location,
+                format!("{subject}: maximum allowed = {max}, but found {count}"),
+            );
+        }
+    }
+
+    fn validate_item(
+        &mut self,
+        definition: &'v ElementDefinition,
+        location: &str,
+        value: &Value,
+        type_name: Option<&str>,
+    ) {
+        let codes = definition.type_codes();
+        let code = match type_name {
+            Some(type_name) => {
+                if !codes.is_empty() && !codes.iter().any(|c| c.eq_ignore_ascii_case(type_name)) {
+                    self.error(
+                        IssueType::Structure,
+                        location,
+                        format!("type {type_name} is not allowed, expected one of {codes:?}"),
+                    );
+                }
+                codes
+                    .iter()
+                    .copied()
+                    .find(|c| c.eq_ignore_ascii_case(type_name))
+            }
+            None => match codes[..] {
+                [code] => Some(code),
+                _ => None,
+            },
+        };
+
+        if let Some(code) = code {
+            if !matches_json_type(code, value) {
+                self.error(
+                    IssueType::Structure,
+                    location,
+                    format!("expected a value of type {code}"),
+                );
+            }
+            if code == "Reference" {
+                self.check_reference_target(definition, location, value);
+            }
+        }
+
+        if let Some((_, fixed)) = definition.fixed()
+            && fixed != value
+        {
+            self.error(
+                IssueType::Value,
+                location,
+                format!("value must be exactly {fixed}"),
+            );
+        }
+        if let Some((_, pattern)) = definition.pattern()
+            && !matches_pattern(pattern, value)
+        {
+            self.error(
+                IssueType::Value,
+                location,
+                format!("value does not match the pattern {pattern}"),
+            );
+        }
+
+        if let Some(code) = code {
+            self.check_binding(definition, location, value, code);
+        }
+        self.check_constraints(definition, location, value);
+        self.validate_node(definition.element_id(), value, location);
+    }
+
+    fn check_reference_target(
+        &mut self,
+        definition: &ElementDefinition,
+        location: &str,
+        value: &Value,
+    ) {
+        let allowed: Vec<&str> = definition
+            .r#type
+            .iter()
+            .flatten()
+            .filter(|t| t.code == "Reference")
+            .flat_map(|t| t.target_types())
+            .collect();
+        if allowed.is_empty() || allowed.contains(&"Resource") {
+            return;
+        }
+
+        let target = value
+            .get("reference")
+            .and_then(Value::as_str)
+            .and_then(|reference| ParsedReference::parse(reference).ok()?.resource_type())
+            .or_else(|| value.get("type").and_then(Value::as_str));
+        if let Some(target) = target
+            && !allowed.contains(&target)
+        {
+            self.error(
+                IssueType::Structure,
+                location,
+                format!("reference to {target} is not allowed, expected one of {allowed:?}"),
+            );
+        }
+    }
+
+    fn check_binding(
+        &mut self,
+        definition: &ElementDefinition,
+        location: &str,
+        value: &Value,
+        code: &str,
+    ) {
+        let Some(terminology) = self.validator.terminology else {
+            return;
+        };
+        let Some(binding) = &definition.binding else {
+            return;
+        };
+        let severity = match binding.strength {
+            BindingStrength::Required => IssueSeverity::Error,
+            BindingStrength::Extensible => IssueSeverity::Warning,
+            _ => return,
+        };
+        let Some(value_set) = binding.value_set.as_deref() else {
+            return;
+        };
+
+        let result = match code {
+            "code" => match value.as_str() {
+                Some(code) => terminology.expand(value_set).map(|vs| {
+                    let contains = vs.expansion.and_then(|e| e.contains).unwrap_or_default();
+                    expansion_contains(&contains, code)
+                        .then_some(())
+                        .ok_or_else(|| format!("code '{code}' is not in value set '{value_set}'"))
+                }),
+                None => return,
+            },
+            "Coding" => match serde_json::from_value::<Coding>(value.clone()) {
+                Ok(coding) => terminology
+                    .validate_code(value_set, &coding)
+                    .map(|r| r.result.then_some(()).ok_or(r.message.unwrap_or_default())),
+                Err(_) => return,
+            },
+            "CodeableConcept" => match serde_json::from_value::<CodeableConcept>(value.clone()) {
+                Ok(concept) if concept.coding.as_ref().is_some_and(|c| !c.is_empty()) => {
+                    terminology
+                        .validate_codeable_concept(value_set, &concept)
+                        .map(|r| r.result.then_some(()).ok_or(r.message.unwrap_or_default()))
+                }
+                Ok(_) if binding.strength == BindingStrength::Extensible => return,
+                Ok(_) => Ok(Err(format!(
+                    "no coding from required value set '{value_set}'"
+                ))),
+                Err(_) => return,
+            },
+            _ => return,
+        };
+
+        match result {
+            Ok(Ok(())) => {}
+            Ok(Err(message)) => self.issues.push(ValidationIssue::new(
+                severity,
+                IssueType::CodeInvalid,
+                location,
+                message,
+            )),
+            Err(e) => self.issues.push(ValidationIssue::new(
+                IssueSeverity::Warning,
+                IssueType::NotSupported,
+                location,
+                format!("unable to check binding: {e}"),
+            )),
+        }
+    }
+
+    fn check_constraints(&mut self, definition: &ElementDefinition, location: &str, value: &Value) {
+        let fhirpath = FhirPathEvaluator::new();
+        let evaluator = self.validator.evaluator.unwrap_or(&fhirpath);
+        for constraint in definition.constraint.iter().flatten() {
+            let Some(expression) = constraint.expression.as_deref() else {
+                continue;
+            };
+            let issue = match evaluator.evaluate(expression, value, self.resource) {
+                Ok(true) => continue,
+                Ok(false) => ValidationIssue::new(
+                    match constraint.severity {
+                        ConstraintSeverity::Error => IssueSeverity::Error,
+                        ConstraintSeverity::Warning => IssueSeverity::Warning,
+                    },
+                    IssueType::Invariant,
+                    location,
+                    format!("{}: {}", constraint.key, constraint.human),
+                ),
+                Err(e) => ValidationIssue::new(
+                    IssueSeverity::Warning,
+                    IssueType::NotSupported,
+                    location,
+                    format!("unable to evaluate {}: {e}", constraint.key),
+                ),
+            };
+            self.issues.push(ValidationIssue {
+                constraint: Some(constraint.key.clone()),
+                ..issue
+            });
+        }
+    }
+
+    /// Whether `value` belongs to `slice`, according to the discriminators of
+    /// the sliced element.
+    fn matches_slice(
+        &self,
+        sliced: &ElementDefinition,
+        slice: &ElementDefinition,
+        value: &Value,
+    ) -> bool {
+        let discriminators = sliced
+            .slicing
+            .as_ref()
+            .and_then(|s| s.discriminator.as_deref())
+            .unwrap_or_default();
+        if discriminators.is_empty() {
+            return false;
+        }
+
+        discriminators.iter().all(|discriminator| {
+            let (target, candidates) = if discriminator.path == "$this" {
+                (Some(slice), vec![value])
+            } else {
+                (
+                    self.find(&format!("{}.{}", slice.element_id(), discriminator.path)),
+                    navigate(value, &discriminator.path),
+                )
+            };
+            let Some(target) = target else {
+                return false;
+            };
+
+            match discriminator.r#type {
+                DiscriminatorType::Value | DiscriminatorType::Pattern => {
+                    match (target.fixed(), target.pattern()) {
+                        (Some((_, fixed)), _) => candidates.contains(&fixed),
+                        (None, Some((_, pattern))) => {
+                            candidates.iter().any(|c| matches_pattern(pattern, c))
+                        }
+                        (None, None) => false,
+                    }
+                }
+                DiscriminatorType::Exists => {
+                    if target.min.unwrap_or(0) > 0 {
+                        !candidates.is_empty()
+                    } else if target.max.as_deref() == Some("0") {
+                        candidates.is_empty()
+                    } else {
+                        true
+                    }
+                }
+                DiscriminatorType::Type => candidates.iter().any(|c| {
+                    c.get("resourceType")
+                        .and_then(Value::as_str)
+                        .is_some_and(|t| target.type_codes().contains(&t))
+                }),
+                DiscriminatorType::Profile => false,
+            }
+        })
+    }
+}
+
+/// Follows a dotted path through a JSON value, flattening arrays.
+fn navigate<'v>(value: &'v Value, path: &str) -> Vec<&'v Value> {
+    let mut current = vec![value];
+    for segment in path.split('.') {
+        current = current
+            .into_iter()
+            .filter_map(|v| v.get(segment))
+            .flat_map(|v| match v {
+                Value::Array(values) => values.iter().collect(),
+                value => vec![value],
+            })
+            .collect();
+    }
+    current
+}
+
+/// A pattern matches when every property it has is present with the same
+/// value, array items match when each pattern item matches some value item.
+fn matches_pattern(pattern: &Value, value: &Value) -> bool {
+    match (pattern, value) {
+        (Value::Object(pattern), Value::Object(value)) => pattern
+            .iter()
+            .all(|(key, p)| value.get(key).is_some_and(|v| matches_pattern(p, v))),
+        (Value::Array(pattern), Value::Array(values)) => pattern
+            .iter()
+            .all(|p| values.iter().any(|v| matches_pattern(p, v))),
+        (pattern, value) => pattern == value,
+    }
+}
+
+/// Whether a JSON value can hold a FHIR type. Primitive types are the ones
+/// starting with a lowercase letter.
+fn matches_json_type(code: &str, value: &Value) -> bool {
+    match code {
+        "boolean" => value.is_boolean(),
+        "integer" => value.is_i64(),
+        "unsignedInt" => value.is_u64(),
+        "positiveInt" => value.as_u64().is_some_and(|v| v > 0),
+        "decimal" => value.is_number(),
+        code if code.starts_with("http://hl7.org/fhirpath/System.") => {
+            !value.is_object() && !value.is_array()
+        }
+        code if code.starts_with(|c: char| c.is_ascii_lowercase()) => value.is_string(),
+        _ => value.is_object(),
+    }
+}
+
+fn expansion_contains(contains: &[ValueSetExpansionContains], code: &str) -> bool {
+    contains.iter().any(|c| {
+        c.code.as_deref() == Some(code)
+            || expansion_contains(c.contains.as_deref().unwrap_or_default(), code)
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::json;
+
+    use crate::r4::{
+        elements::{
+            CodeableConceptBuilder, CodingBuilder, ContactPointBuilder, ElementDefinitionBuilder,
+            ElementDefinitionDiscriminator, ElementDefinitionSlicing, IdentifierBuilder,
+            ReferenceBuilder,
+        },
+        profiling::ProfileRegistry,
+        resources::{
+            Location, LocationBuilder, Organization, OrganizationBuilder,
+            StructureDefinitionBuilder, TypeDerivationRule,
+        },
+    };
+
+    use super::*;
+
+    const DIRECTORY_ORGANIZATION: &str =
+        "http://example.org/StructureDefinition/directory-organization";
+    const UZOVI: &str = "http://fhir.nl/fhir/NamingSystem/uzovi";
+    const ORG_TYPE: &str = "http://example.org/CodeSystem/organization-type";
+
+    fn registry() -> ProfileRegistry {
+        let mut registry = ProfileRegistry::new();
+        registry
+            .load_dir(concat!(env!("CARGO_MANIFEST_DIR"), "/fixtures/r4/profiles"))
+            .unwrap();
+        registry
+    }
+
+    fn terminology() -> TerminologyEngine {
+        let mut engine = TerminologyEngine::new();
+        engine
+            .load_dir(concat!(
+                env!("CARGO_MANIFEST_DIR"),
+                "/fixtures/r4/resources"
+            ))
+            .unwrap();
+        engine
+    }
+
+    fn organization_type(code: &str) -> CodeableConcept {
+        CodeableConceptBuilder::default()
+            .add_coding(
+                CodingBuilder::default()
+                    .with_system(ORG_TYPE)
+                    .with_code(code)
+                    .build(),
+            )
+            .build()
+    }
+
+    fn directory_organization() -> OrganizationBuilder {
+        OrganizationBuilder::new("acme")
+            .add_identifier(
+                IdentifierBuilder::default()
+                    .with_system(UZOVI)
+                    .with_value("3311")
+                    .build(),
+            )
+            .add_identifier(
+                IdentifierBuilder::default()
+                    .with_system("http://example.org/ids")
+                    .with_value("acme")
+                    .build(),
+            )
+            .active(true)
+            .r#type(vec![organization_type("prov")])
+            .name("Acme Care")
+            .part_of(
+                ReferenceBuilder::default()
+                    .with_reference("Organization/acme-group")
+                    .build(),
+            )
+    }
+
+    fn validate(organization: &Organization) -> Vec<ValidationIssue> {
+        let profile = registry().snapshot(DIRECTORY_ORGANIZATION).unwrap();
+        let engine = terminology();
+        organization
+            .validate_against(&profile, &ProfileValidator::new().with_terminology(&engine))
+            .unwrap()
+    }
+
+    fn errors(issues: Vec<ValidationIssue>) -> Vec<(IssueType, String)> {
+        issues
+            .into_iter()
+            .filter(ValidationIssue::is_error)
+            .map(|i| (i.code, i.location))
+            .collect()
+    }
+
+    #[test]
+    fn test_validate_should_accept_conformant_resource() {
+        let actual = validate(&directory_organization().build());
+
+        assert_eq!(Vec::<(IssueType, String)>::new(), errors(actual))
+    }
+
+    #[test]
+    fn test_validate_should_report_cardinality_fixed_and_binding_issues() {
+        let organization = OrganizationBuilder::new("acme")
+            .active(false)
+            .r#type(vec![organization_type("govt")])
+            .build();
+        let expected = vec![
+            (IssueType::Invariant, "Organization".to_string()),
+            (IssueType::Value, "Organization.active".to_string()),
+            (IssueType::CodeInvalid, "Organization.type[0]".to_string()),
+            (IssueType::Required, "Organization.name".to_string()),
+        ];
+
+        let actual = validate(&organization);
+
+        assert_eq!(expected, errors(actual))
+    }
+
+    #[test]
+    fn test_validate_should_check_slices() {
+        let organization = OrganizationBuilder::new("acme")
+            .add_identifier(IdentifierBuilder::default().with_system(UZOVI).build())
+            .add_identifier(
+                IdentifierBuilder::default()
+                    .with_system(UZOVI)
+                    .with_value("3311")
+                    .build(),
+            )
+            .active(true)
+            .r#type(vec![organization_type("prov")])
+            .name("Acme Care")
+            .build();
+        let expected = vec![
+            (IssueType::Structure, "Organization.identifier".to_string()),
+            (
+                IssueType::Required,
+                "Organization.identifier[0].value".to_string(),
+            ),
+        ];
+
+        let actual = validate(&organization);
+
+        assert_eq!(expected, errors(actual))
+    }
+
+    #[test]
+    fn test_validate_should_check_closed_pattern_slicing() {
+        let registry = registry();
+        let profile = registry
+            .generate_snapshot(
+                &StructureDefinitionBuilder::new("ward-location")
+                    .url("http://example.org/StructureDefinition/ward-location")
+                    .name("WardLocation")
+                    .r#type("Location")
+                    .base_definition("http://hl7.org/fhir/StructureDefinition/Location")
+                    .derivation(TypeDerivationRule::Constraint)
+                    .add_differential_element(
+                        ElementDefinitionBuilder::new("Location.type")
+                            .with_path("Location.type")
+                            .with_slicing(ElementDefinitionSlicing {
+                                discriminator: Some(vec![ElementDefinitionDiscriminator {
+                                    r#type: DiscriminatorType::Pattern,
+                                    path: "$this".to_string(),
+                                    ..Default::default()
+                                }]),
+                                rules: SlicingRules::Closed,
+                                ..Default::default()
+                            })
+                            .build(),
+                    )
+                    .add_differential_element(
+                        ElementDefinitionBuilder::new("Location.type:ward")
+                            .with_path("Location.type")
+                            .with_slice_name("ward")
+                            .with_cardinality(1, "1")
+                            .with_choice_value(
+                                "pattern",
+                                "CodeableConcept",
+                                json!({"coding": [{"system": "http://terminology.hl7.org/CodeSystem/v3-RoleCode", "code": "HU"}]}),
+                            )
+                            .build(),
+                    )
+                    .build(),
+            )
+            .unwrap();
+        let ward = CodeableConceptBuilder::default()
+            .add_coding(
+                CodingBuilder::default()
+                    .with_system("http://terminology.hl7.org/CodeSystem/v3-RoleCode")
+                    .with_code("HU")
+                    .with_display("Hospital unit")
+                    .build(),
+            )
+            .build();
+        let ok = LocationBuilder::new("ward").add_type(ward.clone()).build();
+        let unsliced = LocationBuilder::new("ward")
+            .add_type(ward)
+            .add_type(organization_type("prov"))
+            .build();
+        let expected = vec![(IssueType::Structure, "Location.type[1]".to_string())];
+
+        assert!(
+            errors(
+                ok.validate_against(&profile, &ProfileValidator::new())
+                    .unwrap()
+            )
+            .is_empty()
+        );
+        assert_eq!(
+            expected,
+            errors(
+                unsliced
+                    .validate_against(&profile, &ProfileValidator::new())
+                    .unwrap()
+            )
+        )
+    }
+
+    #[test]
+    fn test_validate_should_warn_about_profile_discriminators() {
+        let profile = registry()
+            .generate_snapshot(
+                &StructureDefinitionBuilder::new("profiled-partof")
+                    .url("http://example.org/StructureDefinition/profiled-partof")
+                    .name("ProfiledPartOf")
+                    .r#type("Location")
+                    .base_definition("http://hl7.org/fhir/StructureDefinition/Location")
+                    .derivation(TypeDerivationRule::Constraint)
+                    .add_differential_element(
+                        ElementDefinitionBuilder::new("Location.type")
+                            .with_path("Location.type")
+                            .with_slicing(ElementDefinitionSlicing {
+                                discriminator: Some(vec![ElementDefinitionDiscriminator {
+                                    r#type: DiscriminatorType::Profile,
+                                    path: "$this".to_string(),
+                                    ..Default::default()
+                                }]),
+                                rules: SlicingRules::Open,
+                                ..Default::default()
+                            })
+                            .build(),
+                    )
+                    .add_differential_element(
+                        ElementDefinitionBuilder::new("Location.type:ward")
+                            .with_path("Location.type")
+                            .with_slice_name("ward")
+                            .build(),
+                    )
+                    .build(),
+            )
+            .unwrap();
+        let location = LocationBuilder::new("ward")
+            .add_type(organization_type("prov"))
+            .build();
+        let expected = vec![ValidationIssue::new(
+            IssueSeverity::Warning,
+            IssueType::NotSupported,
+            "Location.type",
+            "profile discriminators are not supported, values of Location.type are not matched to its slices",
+        )];
+
+        let actual = location
+            .validate_against(&profile, &ProfileValidator::new())
+            .unwrap();
+
+        assert_eq!(expected, actual)
+    }
+
+    #[test]
+    fn test_validate_against_should_check_reference_targets() {
+        let profile = registry()
+            .snapshot("http://hl7.org/fhir/StructureDefinition/Location")
+            .unwrap();
+        let location = LocationBuilder::new("ward")
+            .name("Ward 3")
+            .managing_organization(
+                ReferenceBuilder::default()
+                    .with_reference("http://example.org/fhir/Location/other/_history/2")
+                    .build(),
+            )
+            .part_of(
+                ReferenceBuilder::default()
+                    .with_reference("Location/hospital")
+                    .build(),
+            )
+            .build();
+        let expected = vec![(
+            IssueType::Structure,
+            "Location.managingOrganization".to_string(),
+        )];
+
+        let actual = location
+            .validate_against(&profile, &ProfileValidator::new())
+            .unwrap();
+
+        assert_eq!(expected, errors(actual))
+    }
+
+    #[test]
+    fn test_validate_should_evaluate_constraints() {
+        let profile = registry()
+            .snapshot("http://hl7.org/fhir/StructureDefinition/Organization")
+            .unwrap();
+        let organization = serde_json::to_value(OrganizationBuilder::new("empty").build()).unwrap();
+        let org_1 = |expression: &str, context: &Value, _: &Value| {
+            assert_eq!("(identifier.count() + name.count()) > 0", expression);
+            Ok(context.get("identifier").is_some() || context.get("name").is_some())
+        };
+        let expected = vec![ValidationIssue {
+            severity: IssueSeverity::Error,
+            code: IssueType::Invariant,
+            location: "Organization".to_string(),
+            message: "org-1: The organization SHALL at least have a name or an identifier, and possibly more than one".to_string(),
+            constraint: Some("org-1".to_string()),
+        }];
+
+        let actual = ProfileValidator::new()
+            .with_constraint_evaluator(&org_1)
+            .validate(&organization, &profile)
+            .unwrap();
+
+        assert_eq!(expected, actual)
+    }
+
+    #[test]
+    fn test_validate_should_evaluate_constraints_with_fhirpath_by_default() {
+        let profile = registry()
+            .snapshot("http://hl7.org/fhir/StructureDefinition/Organization")
+            .unwrap();
+        let conformant = directory_organization().build();
+        let home_phone = directory_organization()
+            .add_telecom(
+                ContactPointBuilder::new("home-phone")
+                    .with_system("phone")
+                    .with_value("+31 20 555 0100")
+                    .with_use("home")
+                    .build(),
+            )
+            .build();
+        let expected = vec![ValidationIssue {
+            severity: IssueSeverity::Error,
+            code: IssueType::Invariant,
+            location: "Organization.telecom[0]".to_string(),
+            message: "org-3: The telecom of an organization can never be of use 'home'".to_string(),
+            constraint: Some("org-3".to_string()),
+        }];
+
+        assert!(
+            conformant
+                .validate_against(&profile, &ProfileValidator::new())
+                .unwrap()
+                .is_empty()
+        );
+        assert_eq!(
+            expected,
+            home_phone
+                .validate_against(&profile, &ProfileValidator::new())
+                .unwrap()
+        )
+    }
+
+    #[test]
+    fn test_validate_should_reject_other_resource_types() {
+        let profile = registry().snapshot(DIRECTORY_ORGANIZATION).unwrap();
+        let location = Location::default();
+
+        let actual = location
+            .validate_against(&profile, &ProfileValidator::new())
+            .unwrap();
+
+        assert_eq!(
+            vec![(IssueType::Structure, "Organization".to_string())],
+            errors(actual)
+        )
+    }
+}
@@ -0,0 +1,437 @@
+use crate::{
+    FhirError,
+    r4::{
+        elements::{BackboneElement, CodeableConcept, Identifier},
+        resources::{
+            CodeSystem, CodeSystemConcept, CodeSystemConceptProperty, CodeSystemContentMode,
+            CodeSystemFilter, CodeSystemHierarchyMeaning, CodeSystemProperty, ConceptDesignation,
+            DomainResource, PublicationStatus, Resource, ResourceType,
+        },
+    },
+};
+
+#[derive(Default)]
+pub struct CodeSystemConceptBuilder {
+    backbone_element: BackboneElement,
+    code: String,
+    display: Option<String>,
+    definition: Option<String>,
+    designation: Option<Vec<ConceptDesignation>>,
+    property: Option<Vec<CodeSystemConceptProperty>>,
+    concept: Option<Vec<CodeSystemConcept>>,
+}
+
+impl CodeSystemConceptBuilder {
+    pub fn new(id: impl Into<String>) -> Self {
+        let mut builder = Self::default();
+        builder.backbone_element.element.id = Some(id.into());
+        builder
+    }
+
+    pub fn id(mut self, id: impl Into<String>) -> Self {
+        self.backbone_element.element.id = Some(id.into());
+        self
+    }
+
+    pub fn code(mut self, code: impl Into<String>) -> Self {
+        self.code = code.into();
+        self
+    }
+
+    pub fn display(mut self, display: impl Into<String>) -> Self {
+        self.display = Some(display.into());
+        self
+    }
+
+    pub fn definition(mut self, definition: impl Into<String>) -> Self {
+        self.definition = Some(definition.into());
+        self
+    }
+
+    pub fn designation(mut self, designation: Vec<ConceptDesignation>) -> Self {
+        self.designation = Some(designation);
+        self
+    }
+
+    pub fn add_designation(mut self, designation: ConceptDesignation) -> Self {
+        match &mut self.designation {
+            Some(d) => d.push(designation),
+            None => self.designation = Some(vec![designation]),
+        }
+        self
+    }
+
+    pub fn property(mut self, property: Vec<CodeSystemConceptProperty>) -> Self {
+        self.property = Some(property);
+        self
+    }
+
+    pub fn add_property(mut self, property: CodeSystemConceptProperty) -> Self {
+        match &mut self.property {
+            Some(p) => p.push(property),
+            None => self.property = Some(vec![property]),
+        }
+        self
+    }
+
+    pub fn concept(mut self, concept: Vec<CodeSystemConcept>) -> Self {
+        self.concept = Some(concept);
+        self
+    }
+
+    pub fn add_concept(mut self, concept: CodeSystemConcept) -> Self {
+        match &mut self.concept {
+            Some(c) => c.push(concept),
+            None => self.concept = Some(vec![concept]),
+        }
+        self
+    }
+
+    pub fn build(self) -> CodeSystemConcept {
+        CodeSystemConcept {
+            backbone_element: self.backbone_element,
+            code: self.code,
+            display: self.display,
+            definition: self.definition,
+            designation: self.designation,
+            property: self.property,
+            concept: self.concept,
+        }
+    }
+
+    pub fn try_build(self) -> Result<CodeSystemConcept, FhirError> {
+        FhirError::require("CodeSystemConcept", &[("code", !self.code.is_empty())])?;
+        Ok(self.build())
+    }
+}
+
+pub struct CodeSystemBuilder {
+    domain_resource: DomainResource,
+    url: Option<String>,
+    identifier: Option<Vec<Identifier>>,
+    version: Option<String>,
+    name: Option<String>,
+    title: Option<String>,
+    status: PublicationStatus,
+    experimental: Option<bool>,
+    date: Option<String>, // to be resolved later
+    publisher: Option<String>,
+    description: Option<String>,
+    jurisdiction: Option<Vec<CodeableConcept>>,
+    purpose: Option<String>,
+    copyright: Option<String>,
+    case_sensitive: Option<bool>,
+    value_set: Option<String>,
+    hierarchy_meaning: Option<CodeSystemHierarchyMeaning>,
+    compositional: Option<bool>,
+    version_needed: Option<bool>,
+    content: CodeSystemContentMode,
+    supplements: Option<String>,
+    count: Option<u32>,
+    filter: Option<Vec<CodeSystemFilter>>,
+    property: Option<Vec<CodeSystemProperty>>,
+    concept: Option<Vec<CodeSystemConcept>>,
+    resource_type: String,
+}
+
+impl Default for CodeSystemBuilder {
+    fn default() -> Self {
+        CodeSystemBuilder {
+            domain_resource: DomainResource {
+                ..Default::default()
+            },
+            resource_type: CodeSystem::get_resource_type(),
+            url: None,
+            identifier: None,
+            version: None,
+            name: None,
+            title: None,
+            status: PublicationStatus::Draft,
+            experimental: None,
+            date: None,
+            publisher: None,
+            description: None,
+            jurisdiction: None,
+            purpose: None,
+            copyright: None,
+            case_sensitive: None,
+            value_set: None,
+            hierarchy_meaning: None,
+            compositional: None,
+            version_needed: None,
+            content: CodeSystemContentMode::Complete,
+            supplements: None,
+            count: None,
+            filter: None,
+            property: None,
+            concept: None,
+        }
+    }
+}
+
+impl CodeSystemBuilder {
+    pub fn new(id: impl Into<String>) -> Self {
+        CodeSystemBuilder {
+            domain_resource: DomainResource {
+                resource: Resource {
+                    id: Some(id.into()),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    pub fn id(mut self, id: impl Into<String>) -> Self {
+        self.domain_resource.resource.id = Some(id.into());
+        self
+    }
+
+    pub fn url(mut self, url: impl Into<String>) -> Self {
+        self.url = Some(url.into());
+        self
+    }
+
+    pub fn identifier(mut self, identifier: Vec<Identifier>) -> Self {
+        self.identifier = Some(identifier);
+        self
+    }
+
+    pub fn add_identifier(mut self, identifier: Identifier) -> Self {
+        match &mut self.identifier {
+            Some(ident) => ident.push(identifier),
+            None => self.identifier = Some(vec![identifier]),
+        }
+        self
+    }
+
+    pub fn version(mut self, version: impl Into<String>) -> Self {
+        self.version = Some(version.into());
+        self
+    }
+
+    pub fn name(mut self, name: impl Into<String>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    pub fn title(mut self, title: impl Into<String>) -> Self {
+        self.title = Some(title.into());
+        self
+    }
+
+    pub fn status(mut self, status: PublicationStatus) -> Self {
+        self.status = status;
+        self
+    }
+
+    pub fn experimental(mut self, experimental: bool) -> Self {
+        self.experimental = Some(experimental);
+        self
+    }
+
+    pub fn date(mut self, date: impl Into<String>) -> Self {
+        self.date = Some(date.into());
+        self
+    }
+
+    pub fn publisher(mut self, publisher: impl Into<String>) -> Self {
+        self.publisher = Some(publisher.into());
+        self
+    }
+
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    pub fn jurisdiction(mut self, jurisdiction: Vec<CodeableConcept>) -> Self {
+        self.jurisdiction = Some(jurisdiction);
+        self
+    }
+
+    pub fn purpose(mut self, purpose: impl Into<String>) -> Self {
+        self.purpose = Some(purpose.into());
+        self
+    }
+
+    pub fn copyright(mut self, copyright: impl Into<String>) -> Self {
+        self.copyright = Some(copyright.into());
+        self
+    }
+
+    pub fn case_sensitive(mut self, case_sensitive: bool) -> Self {
+        self.case_sensitive = Some(case_sensitive);
+        self
+    }
+
+    pub fn value_set(mut self, value_set: impl Into<String>) -> Self {
+        self.value_set = Some(value_set.into());
+        self
+    }
+
+    pub fn hierarchy_meaning(mut self, hierarchy_meaning: CodeSystemHierarchyMeaning) -> Self {
+        self.hierarchy_meaning = Some(hierarchy_meaning);
+        self
+    }
+
+    pub fn compositional(mut self, compositional: bool) -> Self {
+        self.compositional = Some(compositional);
+        self
+    }
+
+    pub fn version_needed(mut self, version_needed: bool) -> Self {
+        self.version_needed = Some(version_needed);
+        self
+    }
+
+    pub fn content(mut self, content: CodeSystemContentMode) -> Self {
+        self.content = content;
+        self
+    }
+
+    pub fn supplements(mut self, supplements: impl Into<String>) -> Self {
+        self.supplements = Some(supplements.into());
+        self
+    }
+
+    pub fn count(mut self, count: u32) -> Self {
+        self.count = Some(count);
+        self
+    }
+
+    pub fn filter(mut self, filter: Vec<CodeSystemFilter>) -> Self {
+        self.filter = Some(filter);
+        self
+    }
+
+    pub fn add_filter(mut self, filter: CodeSystemFilter) -> Self {
+        match &mut self.filter {
+            Some(f) => f.push(filter),
+            None => self.filter = Some(vec![filter]),
+        }
+        self
+    }
+
+    pub fn property(mut self, property: Vec<CodeSystemProperty>) -> Self {
+        self.property = Some(property);
+        self
+    }
+
+    pub fn add_property(mut self, property: CodeSystemProperty) -> Self {
+        match &mut self.property {
+            Some(p) => p.push(property),
+            None => self.property = Some(vec![property]),
+        }
+        self
+    }
+
+    pub fn concept(mut self, concept: Vec<CodeSystemConcept>) -> Self {
+        self.concept = Some(concept);
+        self
+    }
+
+    pub fn add_concept(mut self, concept: CodeSystemConcept) -> Self {
+        match &mut self.concept {
+            Some(c) => c.push(concept),
+            None => self.concept = Some(vec![concept]),
+        }
+        self
+    }
+
+    pub fn build(self) -> CodeSystem {
+        CodeSystem {
+            domain_resource: self.domain_resource,
+            url: self.url,
+            identifier: self.identifier,
+            version: self.version,
+            name: self.name,
+            title: self.title,
+            status: self.status,
+            experimental: self.experimental,
+            date: self.date,
+            publisher: self.publisher,
+            description: self.description,
+            jurisdiction: self.jurisdiction,
+            purpose: self.purpose,
+            copyright: self.copyright,
+            case_sensitive: self.case_sensitive,
+            value_set: self.value_set,
+            hierarchy_meaning: self.hierarchy_meaning,
+            compositional: self.compositional,
+            version_needed: self.version_needed,
+            content: self.content,
+            supplements: self.supplements,
+            count: self.count,
+            filter: self.filter,
+            property: self.property,
+            concept: self.concept,
+            resource_type: self.resource_type,
+        }
+    }
+
+    pub fn try_build(self) -> Result<CodeSystem, FhirError> {
+        Ok(self.build())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_build_concept_should_succeed() {
+        let expected = CodeSystemConcept {
+            code: "hosp".to_string(),
+            display: Some("Hospital".to_string()),
+            definition: Some("An acute care institution".to_string()),
+            designation: Some(vec![ConceptDesignation {
+                language: Some("nl".to_string()),
+                value: "Ziekenhuis".to_string(),
+                ..Default::default()
+            }]),
+            ..Default::default()
+        };
+
+        let actual = CodeSystemConceptBuilder::default()
+            .code("hosp")
+            .display("Hospital")
+            .definition("An acute care institution")
+            .add_designation(ConceptDesignation {
+                language: Some("nl".to_string()),
+                value: "Ziekenhuis".to_string(),
+                ..Default::default()
+            })
+            .build();
+
+        assert_eq!(expected, actual)
+    }
+
+    #[test]
+    fn test_build_code_system_should_succeed() {
+        let expected = CodeSystem {
+            domain_resource: DomainResource {
+                resource: Resource {
+                    id: Some("cs-1".to_string()),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            url: Some("http://example.org/cs".to_string()),
+            version: Some("2".to_string()),
+            status: PublicationStatus::Active,
+            hierarchy_meaning: Some(CodeSystemHierarchyMeaning::IsA),
+            ..Default::default()
+        };
+
+        let actual = CodeSystemBuilder::new("cs-1")
+            .url("http://example.org/cs")
+            .version("2")
+            .status(PublicationStatus::Active)
+            .hierarchy_meaning(CodeSystemHierarchyMeaning::IsA)
+            .build();
+
+        assert_eq!(expected, actual)
+    }
+}
@@ -0,0 +1,505 @@
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    FhirError,
+    r4::{
+        elements::{
+            BackboneElement, CodeableConcept, Coding, GetCodeableConceptsMut, GetIdentifiers,
+            GetResourceReferences, Identifier, ReferenceTypes,
+        },
+        resources::{DomainResource, PublicationStatus, ReferenceTarget, ResourceType},
+    },
+};
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+#[serde(rename_all = "kebab-case")]
+pub enum CodeSystemHierarchyMeaning {
+    GroupedBy,
+    IsA,
+    PartOf,
+    ClassifiedWith,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum CodeSystemContentMode {
+    NotPresent,
+    Example,
+    Fragment,
+    #[default]
+    Complete,
+    Supplement,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+#[serde(rename_all = "camelCase")]
+pub enum PropertyType {
+    Code,
+    Coding,
+    String,
+    Integer,
+    Boolean,
+    DateTime,
+    Decimal,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Default)]
+pub struct CodeSystemFilter {
+    #[serde(flatten)]
+    pub backbone_element: BackboneElement,
+
+    pub code: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+
+    pub operator: Vec<String>, // to be resolved later
+
+    pub value: String,
+}
+
+impl ResourceType for CodeSystemFilter {
+    const TYPE: &'static str = "CodeSystemFilter";
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Default)]
+pub struct CodeSystemProperty {
+    #[serde(flatten)]
+    pub backbone_element: BackboneElement,
+
+    pub code: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub uri: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub r#type: Option<PropertyType>,
+}
+
+impl ResourceType for CodeSystemProperty {
+    const TYPE: &'static str = "CodeSystemProperty";
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Default)]
+pub struct ConceptDesignation {
+    #[serde(flatten)]
+    pub backbone_element: BackboneElement,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub r#use: Option<Coding>,
+
+    pub value: String,
+}
+
+impl ResourceType for ConceptDesignation {
+    const TYPE: &'static str = "ConceptDesignation";
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Default)]
+#[serde(rename_all(serialize = "camelCase", deserialize = "camelCase"))]
+pub struct CodeSystemConceptProperty {
+    #[serde(flatten)]
+    pub backbone_element: BackboneElement,
+
+    pub code: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value_code: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value_coding: Option<Coding>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value_string: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value_integer: Option<i64>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value_boolean: Option<bool>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value_date_time: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value_decimal: Option<f64>,
+}
+
+impl ResourceType for CodeSystemConceptProperty {
+    const TYPE: &'static str = "CodeSystemConceptProperty";
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Default)]
+pub struct CodeSystemConcept {
+    #[serde(flatten)]
+    pub backbone_element: BackboneElement,
+
+    pub code: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub display: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub definition: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub designation: Option<Vec<ConceptDesignation>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub property: Option<Vec<CodeSystemConceptProperty>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub concept: Option<Vec<CodeSystemConcept>>,
+}
+
+impl ResourceType for CodeSystemConcept {
+    const TYPE: &'static str = "CodeSystemConcept";
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+#[serde(rename_all(serialize = "camelCase", deserialize = "camelCase"))]
+pub struct CodeSystem {
+    #[serde(flatten)]
+    pub domain_resource: DomainResource,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub identifier: Option<Vec<Identifier>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+
+    pub status: PublicationStatus,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub experimental: Option<bool>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub date: Option<String>, // to be resolved later
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub publisher: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub jurisdiction: Option<Vec<CodeableConcept>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub purpose: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub copyright: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub case_sensitive: Option<bool>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value_set: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hierarchy_meaning: Option<CodeSystemHierarchyMeaning>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub compositional: Option<bool>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version_needed: Option<bool>,
+
+    pub content: CodeSystemContentMode,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub supplements: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub count: Option<u32>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub filter: Option<Vec<CodeSystemFilter>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub property: Option<Vec<CodeSystemProperty>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub concept: Option<Vec<CodeSystemConcept>>,
+
+    #[serde(default = "CodeSystem::get_resource_type")]
+    pub resource_type: String,
+}
+
+impl Default for CodeSystem {
+    fn default() -> Self {
+        CodeSystem {
+            domain_resource: DomainResource {
+                ..Default::default()
+            },
+            resource_type: Self::get_resource_type(),
+            url: None,
+            identifier: None,
+            version: None,
+            name: None,
+            title: None,
+            status: PublicationStatus::Draft,
+            experimental: None,
+            date: None,
+            publisher: None,
+            description: None,
+            jurisdiction: None,
+            purpose: None,
+            copyright: None,
+            case_sensitive: None,
+            value_set: None,
+            hierarchy_meaning: None,
+            compositional: None,
+            version_needed: None,
+            content: CodeSystemContentMode::Complete,
+            supplements: None,
+            count: None,
+            filter: None,
+            property: None,
+            concept: None,
+        }
+    }
+}
+
+impl ResourceType for CodeSystem {
+    const TYPE: &'static str = "CodeSystem";
+}
+
+impl ReferenceTarget for CodeSystem {
+    fn resource_id(&self) -> Option<&str> {
+        self.domain_resource.resource.id.as_deref()
+    }
+
+    fn resource_id_mut(&mut self) -> &mut Option<String> {
+        &mut self.domain_resource.resource.id
+    }
+
+    fn reference_display(&self) -> Option<String> {
+        self.title.clone().or_else(|| self.name.clone())
+    }
+}
+
+impl GetIdentifiers for CodeSystem {
+    fn get_identifiers(&self) -> Vec<&Identifier> {
+        self.identifier.iter().flatten().collect()
+    }
+}
+
+impl GetResourceReferences for CodeSystem {
+    fn get_references(&self) -> Vec<ReferenceTypes<'_>> {
+        self.identifier
+            .iter()
+            .flatten()
+            .flat_map(GetResourceReferences::get_references)
+            .collect()
+    }
+}
+
+impl CodeSystem {
+    pub fn from_json(data: &str) -> Result<Self, FhirError> {
+        Ok(serde_json::from_str(data)?)
+    }
+
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, FhirError> {
+        Self::from_json(&std::fs::read_to_string(path)?)
+    }
+
+    pub fn to_json_value(&self) -> Result<serde_json::Value, FhirError> {
+        Ok(serde_json::to_value(self)?)
+    }
+
+    pub fn to_json_string(&self) -> Result<String, FhirError> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    /// Finds a concept by code anywhere in the concept hierarchy.
+    pub fn find_concept(&self, code: &str) -> Option<&CodeSystemConcept> {
+        fn find<'a>(
+            concepts: &'a [CodeSystemConcept],
+            code: &str,
+        ) -> Option<&'a CodeSystemConcept> {
+            concepts.iter().find_map(|c| {
+                if c.code == code {
+                    Some(c)
+                } else {
+                    c.concept
+                        .as_deref()
+                        .and_then(|children| find(children, code))
+                }
+            })
+        }
+
+        self.concept
+            .as_deref()
+            .and_then(|concepts| find(concepts, code))
+    }
+}
+
+impl GetCodeableConceptsMut for CodeSystem {
+    fn get_codeable_concepts_mut(&mut self) -> Vec<&mut CodeableConcept> {
+        let mut concepts = self.domain_resource.get_codeable_concepts_mut();
+
+        concepts.extend(self.jurisdiction.iter_mut().flatten());
+
+        for identifier in self.identifier.iter_mut().flatten() {
+            concepts.extend(identifier.get_codeable_concepts_mut());
+        }
+
+        concepts
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::json;
+
+    use crate::r4::resources::{CodeSystemBuilder, CodeSystemConceptBuilder, Resource};
+
+    use super::*;
+
+    #[test]
+    fn test_from_json_should_succeed() {
+        let data = include_str!("../../../../fixtures/r4/resources/code_system.json");
+        let expected = CodeSystem {
+            domain_resource: DomainResource {
+                resource: Resource {
+                    id: Some("organization-type".to_string()),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            url: Some("http://example.org/CodeSystem/organization-type".to_string()),
+            version: Some("1.0.0".to_string()),
+            name: Some("OrganizationType".to_string()),
+            status: PublicationStatus::Active,
+            case_sensitive: Some(true),
+            hierarchy_meaning: Some(CodeSystemHierarchyMeaning::IsA),
+            content: CodeSystemContentMode::Complete,
+            property: Some(vec![CodeSystemProperty {
+                code: "status".to_string(),
+                uri: Some("http://hl7.org/fhir/concept-properties#status".to_string()),
+                r#type: Some(PropertyType::Code),
+                ..Default::default()
+            }]),
+            concept: Some(vec![
+                CodeSystemConcept {
+                    code: "prov".to_string(),
+                    display: Some("Healthcare Provider".to_string()),
+                    concept: Some(vec![
+                        CodeSystemConcept {
+                            code: "hosp".to_string(),
+                            display: Some("Hospital".to_string()),
+                            designation: Some(vec![ConceptDesignation {
+                                language: Some("nl".to_string()),
+                                value: "Ziekenhuis".to_string(),
+                                ..Default::default()
+                            }]),
+                            ..Default::default()
+                        },
+                        CodeSystemConcept {
+                            code: "clinic".to_string(),
+                            display: Some("Clinic".to_string()),
+                            property: Some(vec![CodeSystemConceptProperty {
+                                code: "status".to_string(),
+                                value_code: Some("retired".to_string()),
+                                ..Default::default()
+                            }]),
+                            ..Default::default()
+                        },
+                    ]),
+                    ..Default::default()
+                },
+                CodeSystemConcept {
+                    code: "govt".to_string(),
+                    display: Some("Government".to_string()),
+                    ..Default::default()
+                },
+            ]),
+            ..Default::default()
+        };
+
+        let actual = CodeSystem::from_json(data).unwrap();
+
+        assert_eq!(expected, actual)
+    }
+
+    #[test]
+    fn test_from_file_should_succeed() {
+        let path = concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/fixtures/r4/resources/code_system.json"
+        );
+
+        let actual = CodeSystem::from_file(path).unwrap();
+
+        assert_eq!(Some("OrganizationType".to_string()), actual.name)
+    }
+
+    #[test]
+    fn test_from_file_missing_should_fail() {
+        let actual = CodeSystem::from_file("does/not/exist.json");
+
+        assert!(matches!(actual, Err(FhirError::IoError(_))))
+    }
+
+    #[test]
+    fn test_find_concept_should_succeed() {
+        let data = include_str!("../../../../fixtures/r4/resources/code_system.json");
+        let code_system = CodeSystem::from_json(data).unwrap();
+
+        let actual = code_system
+            .find_concept("clinic")
+            .map(|c| c.display.clone());
+
+        assert_eq!(Some(Some("Clinic".to_string())), actual);
+        assert_eq!(None, code_system.find_concept("unknown"))
+    }
+
+    #[test]
+    fn test_to_json_value_should_succeed() {
+        let expected = json!({
+            "resourceType": "CodeSystem",
+            "url": "http://example.org/cs",
+            "status": "active",
+            "content": "fragment",
+            "concept": [
+                {"code": "a", "display": "A", "concept": [{"code": "a1"}]}
+            ]
+        });
+
+        let code_system = CodeSystemBuilder::default()
+            .url("http://example.org/cs")
+            .status(PublicationStatus::Active)
+            .content(CodeSystemContentMode::Fragment)
+            .add_concept(
+                CodeSystemConceptBuilder::default()
+                    .code("a")
+                    .display("A")
+                    .add_concept(CodeSystemConceptBuilder::default().code("a1").build())
+                    .build(),
+            )
+            .build();
+
+        let actual = code_system.to_json_value().unwrap();
+
+        assert_eq!(expected, actual)
+    }
+}
@@ -0,0 +1,380 @@
+use crate::{
+    FhirError,
+    r4::{
+        elements::{BackboneElement, CodeableConcept, Identifier},
+        resources::{
+            ConceptMap, ConceptMapElement, ConceptMapEquivalence, ConceptMapGroup,
+            ConceptMapGroupUnmapped, ConceptMapTarget, DomainResource, PublicationStatus, Resource,
+            ResourceType,
+        },
+    },
+};
+
+#[derive(Default)]
+pub struct ConceptMapGroupBuilder {
+    backbone_element: BackboneElement,
+    source: Option<String>,
+    source_version: Option<String>,
+    target: Option<String>,
+    target_version: Option<String>,
+    element: Vec<ConceptMapElement>,
+    unmapped: Option<ConceptMapGroupUnmapped>,
+}
+
+impl ConceptMapGroupBuilder {
+    pub fn new(id: impl Into<String>) -> Self {
+        let mut builder = Self::default();
+        builder.backbone_element.element.id = Some(id.into());
+        builder
+    }
+
+    pub fn id(mut self, id: impl Into<String>) -> Self {
+        self.backbone_element.element.id = Some(id.into());
+        self
+    }
+
+    pub fn source(mut self, source: impl Into<String>) -> Self {
+        self.source = Some(source.into());
+        self
+    }
+
+    pub fn source_version(mut self, source_version: impl Into<String>) -> Self {
+        self.source_version = Some(source_version.into());
+        self
+    }
+
+    pub fn target(mut self, target: impl Into<String>) -> Self {
+        self.target = Some(target.into());
+        self
+    }
+
+    pub fn target_version(mut self, target_version: impl Into<String>) -> Self {
+        self.target_version = Some(target_version.into());
+        self
+    }
+
+    pub fn element(mut self, element: Vec<ConceptMapElement>) -> Self {
+        self.element = element;
+        self
+    }
+
+    pub fn add_element(mut self, element: ConceptMapElement) -> Self {
+        self.element.push(element);
+        self
+    }
+
+    /// Maps `code` onto `target`, appending to an existing element for `code`.
+    pub fn add_mapping(
+        mut self,
+        code: impl Into<String>,
+        target: impl Into<String>,
+        equivalence: ConceptMapEquivalence,
+    ) -> Self {
+        let code = Some(code.into());
+        let target = ConceptMapTarget {
+            code: Some(target.into()),
+            equivalence,
+            ..Default::default()
+        };
+        match self.element.iter_mut().find(|e| e.code == code) {
+            Some(ConceptMapElement {
+                target: Some(t), ..
+            }) => t.push(target),
+            Some(element) => element.target = Some(vec![target]),
+            None => self.element.push(ConceptMapElement {
+                code,
+                target: Some(vec![target]),
+                ..Default::default()
+            }),
+        }
+        self
+    }
+
+    pub fn unmapped(mut self, unmapped: ConceptMapGroupUnmapped) -> Self {
+        self.unmapped = Some(unmapped);
+        self
+    }
+
+    pub fn build(self) -> ConceptMapGroup {
+        ConceptMapGroup {
+            backbone_element: self.backbone_element,
+            source: self.source,
+            source_version: self.source_version,
+            target: self.target,
+            target_version: self.target_version,
+            element: self.element,
+            unmapped: self.unmapped,
+        }
+    }
+
+    pub fn try_build(self) -> Result<ConceptMapGroup, FhirError> {
+        FhirError::require("ConceptMapGroup", &[("element", !self.element.is_empty())])?;
+        Ok(self.build())
+    }
+}
+
+pub struct ConceptMapBuilder {
+    domain_resource: DomainResource,
+    url: Option<String>,
+    identifier: Option<Identifier>,
+    version: Option<String>,
+    name: Option<String>,
+    title: Option<String>,
+    status: PublicationStatus,
+    experimental: Option<bool>,
+    date: Option<String>, // to be resolved later
+    publisher: Option<String>,
+    description: Option<String>,
+    jurisdiction: Option<Vec<CodeableConcept>>,
+    purpose: Option<String>,
+    copyright: Option<String>,
+    source_uri: Option<String>,
+    source_canonical: Option<String>,
+    target_uri: Option<String>,
+    target_canonical: Option<String>,
+    group: Option<Vec<ConceptMapGroup>>,
+    resource_type: String,
+}
+
+impl Default for ConceptMapBuilder {
+    fn default() -> Self {
+        ConceptMapBuilder {
+            domain_resource: DomainResource {
+                ..Default::default()
+            },
+            resource_type: ConceptMap::get_resource_type(),
+            url: None,
+            identifier: None,
+            version: None,
+            name: None,
+            title: None,
+            status: PublicationStatus::Draft,
+            experimental: None,
+            date: None,
+            publisher: None,
+            description: None,
+            jurisdiction: None,
+            purpose: None,
+            copyright: None,
+            source_uri: None,
+            source_canonical: None,
+            target_uri: None,
+            target_canonical: None,
+            group: None,
+        }
+    }
+}
+
+impl ConceptMapBuilder {
+    pub fn new(id: impl Into<String>) -> Self {
+        ConceptMapBuilder {
+            domain_resource: DomainResource {
+                resource: Resource {
+                    id: Some(id.into()),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    pub fn id(mut self, id: impl Into<String>) -> Self {
+        self.domain_resource.resource.id = Some(id.into());
+        self
+    }
+
+    pub fn url(mut self, url: impl Into<String>) -> Self {
+        self.url = Some(url.into());
+        self
+    }
+
+    pub fn identifier(mut self, identifier: Identifier) -> Self {
+        self.identifier = Some(identifier);
+        self
+    }
+
+    pub fn version(mut self, version: impl Into<String>) -> Self {
+        self.version = Some(version.into());
+        self
+    }
+
+    pub fn name(mut self, name: impl Into<String>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    pub fn title(mut self, title: impl Into<String>) -> Self {
+        self.title = Some(title.into());
+        self
+    }
+
+    pub fn status(mut self, status: PublicationStatus) -> Self {
+        self.status = status;
+        self
+    }
+
+    pub fn experimental(mut self, experimental: bool) -> Self {
+        self.experimental = Some(experimental);
+        self
+    }
+
+    pub fn date(mut self, date: impl Into<String>) -> Self {
+        self.date = Some(date.into());
+        self
+    }
+
+    pub fn publisher(mut self, publisher: impl Into<String>) -> Self {
+        self.publisher = Some(publisher.into());
+        self
+    }
+
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    pub fn jurisdiction(mut self, jurisdiction: Vec<CodeableConcept>) -> Self {
+        self.jurisdiction = Some(jurisdiction);
+        self
+    }
+
+    pub fn purpose(mut self, purpose: impl Into<String>) -> Self {
+        self.purpose = Some(purpose.into());
+        self
+    }
+
+    pub fn copyright(mut self, copyright: impl Into<String>) -> Self {
+        self.copyright = Some(copyright.into());
+        self
+    }
+
+    pub fn source_uri(mut self, source_uri: impl Into<String>) -> Self {
+        self.source_uri = Some(source_uri.into());
+        self
+    }
+
+    pub fn source_canonical(mut self, source_canonical: impl Into<String>) -> Self {
+        self.source_canonical = Some(source_canonical.into());
+        self
+    }
+
+    pub fn target_uri(mut self, target_uri: impl Into<String>) -> Self {
+        self.target_uri = Some(target_uri.into());
+        self
+    }
+
+    pub fn target_canonical(mut self, target_canonical: impl Into<String>) -> Self {
+        self.target_canonical = Some(target_canonical.into());
+        self
+    }
+
+    pub fn group(mut self, group: Vec<ConceptMapGroup>) -> Self {
+        self.group = Some(group);
+        self
+    }
+
+    pub fn add_group(mut self, group: ConceptMapGroup) -> Self {
+        match &mut self.group {
+            Some(g) => g.push(group),
+            None => self.group = Some(vec![group]),
+        }
+        self
+    }
+
+    pub fn build(self) -> ConceptMap {
+        ConceptMap {
+            domain_resource: self.domain_resource,
+            url: self.url,
+            identifier: self.identifier,
+            version: self.version,
+            name: self.name,
+            title: self.title,
+            status: self.status,
+            experimental: self.experimental,
+            date: self.date,
+            publisher: self.publisher,
+            description: self.description,
+            jurisdiction: self.jurisdiction,
+            purpose: self.purpose,
+            copyright: self.copyright,
+            source_uri: self.source_uri,
+            source_canonical: self.source_canonical,
+            target_uri: self.target_uri,
+            target_canonical: self.target_canonical,
+            group: self.group,
+            resource_type: self.resource_type,
+        }
+    }
+
+    pub fn try_build(self) -> Result<ConceptMap, FhirError> {
+        Ok(self.build())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_build_group_should_succeed() {
+        let expected = ConceptMapGroup {
+            source: Some("http://example.org/local".to_string()),
+            target: Some("http://example.org/national".to_string()),
+            element: vec![ConceptMapElement {
+                code: Some("a".to_string()),
+                target: Some(vec![
+                    ConceptMapTarget {
+                        code: Some("x".to_string()),
+                        equivalence: ConceptMapEquivalence::Equivalent,
+                        ..Default::default()
+                    },
+                    ConceptMapTarget {
+                        code: Some("y".to_string()),
+                        equivalence: ConceptMapEquivalence::Narrower,
+                        ..Default::default()
+                    },
+                ]),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+
+        let actual = ConceptMapGroupBuilder::default()
+            .source("http://example.org/local")
+            .target("http://example.org/national")
+            .add_mapping("a", "x", ConceptMapEquivalence::Equivalent)
+            .add_mapping("a", "y", ConceptMapEquivalence::Narrower)
+            .build();
+
+        assert_eq!(expected, actual)
+    }
+
+    #[test]
+    fn test_build_concept_map_should_succeed() {
+        let group = ConceptMapGroupBuilder::default()
+            .source("http://example.org/local")
+            .build();
+        let expected = ConceptMap {
+            domain_resource: DomainResource {
+                resource: Resource {
+                    id: Some("cm-1".to_string()),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            url: Some("http://example.org/cm".to_string()),
+            status: PublicationStatus::Active,
+            group: Some(vec![group.clone()]),
+            ..Default::default()
+        };
+
+        let actual = ConceptMapBuilder::new("cm-1")
+            .url("http://example.org/cm")
+            .status(PublicationStatus::Active)
+            .add_group(group)
+            .build();
+
+        assert_eq!(expected, actual)
+    }
+}
@@ -0,0 +1,471 @@
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    FhirError,
+    r4::{
+        elements::{
+            BackboneElement, CodeableConcept, GetCodeableConceptsMut, GetIdentifiers,
+            GetResourceReferences, Identifier, ReferenceTypes,
+        },
+        resources::{DomainResource, PublicationStatus, ReferenceTarget, ResourceType},
+    },
+};
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Copy, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum ConceptMapEquivalence {
+    RelatedTo,
+    #[default]
+    Equivalent,
+    Equal,
+    Wider,
+    Subsumes,
+    Narrower,
+    Specializes,
+    Inexact,
+    Unmatched,
+    Disjoint,
+}
+
+impl ConceptMapEquivalence {
+    /// `unmatched` and `disjoint` record that there is no valid mapping.
+    pub fn is_match(&self) -> bool {
+        !matches!(
+            self,
+            ConceptMapEquivalence::Unmatched | ConceptMapEquivalence::Disjoint
+        )
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum ConceptMapGroupUnmappedMode {
+    #[default]
+    Provided,
+    Fixed,
+    OtherMap,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Default)]
+pub struct ConceptMapDependsOn {
+    #[serde(flatten)]
+    pub backbone_element: BackboneElement,
+
+    pub property: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub system: Option<String>,
+
+    pub value: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub display: Option<String>,
+}
+
+impl ResourceType for ConceptMapDependsOn {
+    const TYPE: &'static str = "ConceptMapDependsOn";
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Default)]
+#[serde(rename_all(serialize = "camelCase", deserialize = "camelCase"))]
+pub struct ConceptMapTarget {
+    #[serde(flatten)]
+    pub backbone_element: BackboneElement,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub code: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub display: Option<String>,
+
+    pub equivalence: ConceptMapEquivalence,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub comment: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub depends_on: Option<Vec<ConceptMapDependsOn>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub product: Option<Vec<ConceptMapDependsOn>>,
+}
+
+impl ResourceType for ConceptMapTarget {
+    const TYPE: &'static str = "ConceptMapTarget";
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Default)]
+pub struct ConceptMapElement {
+    #[serde(flatten)]
+    pub backbone_element: BackboneElement,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub code: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub display: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub target: Option<Vec<ConceptMapTarget>>,
+}
+
+impl ResourceType for ConceptMapElement {
+    const TYPE: &'static str = "ConceptMapElement";
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Default)]
+pub struct ConceptMapGroupUnmapped {
+    #[serde(flatten)]
+    pub backbone_element: BackboneElement,
+
+    pub mode: ConceptMapGroupUnmappedMode,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub code: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub display: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+}
+
+impl ResourceType for ConceptMapGroupUnmapped {
+    const TYPE: &'static str = "ConceptMapGroupUnmapped";
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Default)]
+#[serde(rename_all(serialize = "camelCase", deserialize = "camelCase"))]
+pub struct ConceptMapGroup {
+    #[serde(flatten)]
+    pub backbone_element: BackboneElement,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source_version: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub target: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub target_version: Option<String>,
+
+    pub element: Vec<ConceptMapElement>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub unmapped: Option<ConceptMapGroupUnmapped>,
+}
+
+impl ResourceType for ConceptMapGroup {
+    const TYPE: &'static str = "ConceptMapGroup";
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+#[serde(rename_all(serialize = "camelCase", deserialize = "camelCase"))]
+pub struct ConceptMap {
+    #[serde(flatten)]
+    pub domain_resource: DomainResource,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub identifier: Option<Identifier>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+
+    pub status: PublicationStatus,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub experimental: Option<bool>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub date: Option<String>, // to be resolved later
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub publisher: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub jurisdiction: Option<Vec<CodeableConcept>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub purpose: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub copyright: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source_uri: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source_canonical: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub target_uri: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub target_canonical: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub group: Option<Vec<ConceptMapGroup>>,
+
+    #[serde(default = "ConceptMap::get_resource_type")]
+    pub resource_type: String,
+}
+
+impl Default for ConceptMap {
+    fn default() -> Self {
+        ConceptMap {
+            domain_resource: DomainResource {
+                ..Default::default()
+            },
+            resource_type: Self::get_resource_type(),
+            url: None,
+            identifier: None,
+            version: None,
+            name: None,
+            title: None,
+            status: PublicationStatus::Draft,
+            experimental: None,
+            date: None,
+            publisher: None,
+            description: None,
+            jurisdiction: None,
+            purpose: None,
+            copyright: None,
+            source_uri: None,
+            source_canonical: None,
+            target_uri: None,
+            target_canonical: None,
+            group: None,
+        }
+    }
+}
+
+impl ResourceType for ConceptMap {
+    const TYPE: &'static str = "ConceptMap";
+}
+
+impl ReferenceTarget for ConceptMap {
+    fn resource_id(&self) -> Option<&str> {
+        self.domain_resource.resource.id.as_deref()
+    }
+
+    fn resource_id_mut(&mut self) -> &mut Option<String> {
+        &mut self.domain_resource.resource.id
+    }
+
+    fn reference_display(&self) -> Option<String> {
+        self.title.clone().or_else(|| self.name.clone())
+    }
+}
+
+impl GetIdentifiers for ConceptMap {
+    fn get_identifiers(&self) -> Vec<&Identifier> {
+        self.identifier.iter().collect()
+    }
+}
+
+impl GetResourceReferences for ConceptMap {
+    fn get_references(&self) -> Vec<ReferenceTypes<'_>> {
+        self.identifier
+            .iter()
+            .flat_map(GetResourceReferences::get_references)
+            .collect()
+    }
+}
+
+impl ConceptMap {
+    pub fn from_json(data: &str) -> Result<Self, FhirError> {
+        Ok(serde_json::from_str(data)?)
+    }
+
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, FhirError> {
+        Self::from_json(&std::fs::read_to_string(path)?)
+    }
+
+    pub fn to_json_value(&self) -> Result<serde_json::Value, FhirError> {
+        Ok(serde_json::to_value(self)?)
+    }
+
+    pub fn to_json_string(&self) -> Result<String, FhirError> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+}
+
+impl GetCodeableConceptsMut for ConceptMap {
+    fn get_codeable_concepts_mut(&mut self) -> Vec<&mut CodeableConcept> {
+        let mut concepts = self.domain_resource.get_codeable_concepts_mut();
+
+        concepts.extend(self.jurisdiction.iter_mut().flatten());
+
+        for identifier in self.identifier.iter_mut() {
+            concepts.extend(identifier.get_codeable_concepts_mut());
+        }
+
+        concepts
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::json;
+
+    use crate::r4::resources::{ConceptMapBuilder, ConceptMapGroupBuilder, Resource};
+
+    use super::*;
+
+    #[test]
+    fn test_from_json_should_succeed() {
+        let data = include_str!("../../../../fixtures/r4/resources/concept_map.json");
+        let expected = ConceptMap {
+            domain_resource: DomainResource {
+                resource: Resource {
+                    id: Some("organization-type-to-hl7".to_string()),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            url: Some("http://example.org/ConceptMap/organization-type-to-hl7".to_string()),
+            version: Some("1.0.0".to_string()),
+            name: Some("OrganizationTypeToHl7".to_string()),
+            status: PublicationStatus::Active,
+            source_canonical: Some(
+                "http://example.org/ValueSet/provider-organization-type".to_string(),
+            ),
+            target_uri: Some("http://terminology.hl7.org/CodeSystem/organization-type".to_string()),
+            group: Some(vec![ConceptMapGroup {
+                source: Some("http://example.org/CodeSystem/organization-type".to_string()),
+                target: Some("http://terminology.hl7.org/CodeSystem/organization-type".to_string()),
+                element: vec![
+                    ConceptMapElement {
+                        code: Some("prov".to_string()),
+                        target: Some(vec![ConceptMapTarget {
+                            code: Some("prov".to_string()),
+                            display: Some("Healthcare Provider".to_string()),
+                            equivalence: ConceptMapEquivalence::Equivalent,
+                            ..Default::default()
+                        }]),
+                        ..Default::default()
+                    },
+                    ConceptMapElement {
+                        code: Some("hosp".to_string()),
+                        target: Some(vec![ConceptMapTarget {
+                            code: Some("prov".to_string()),
+                            display: Some("Healthcare Provider".to_string()),
+                            equivalence: ConceptMapEquivalence::Wider,
+                            ..Default::default()
+                        }]),
+                        ..Default::default()
+                    },
+                    ConceptMapElement {
+                        code: Some("govt".to_string()),
+                        target: Some(vec![ConceptMapTarget {
+                            code: Some("govt".to_string()),
+                            display: Some("Government".to_string()),
+                            equivalence: ConceptMapEquivalence::Equivalent,
+                            ..Default::default()
+                        }]),
+                        ..Default::default()
+                    },
+                    ConceptMapElement {
+                        code: Some("clinic".to_string()),
+                        target: Some(vec![ConceptMapTarget {
+                            equivalence: ConceptMapEquivalence::Unmatched,
+                            comment: Some("Retired code without national equivalent".to_string()),
+                            ..Default::default()
+                        }]),
+                        ..Default::default()
+                    },
+                ],
+                unmapped: Some(ConceptMapGroupUnmapped {
+                    mode: ConceptMapGroupUnmappedMode::Fixed,
+                    code: Some("other".to_string()),
+                    display: Some("Other".to_string()),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            }]),
+            ..Default::default()
+        };
+
+        let actual = ConceptMap::from_json(data).unwrap();
+
+        assert_eq!(expected, actual)
+    }
+
+    #[test]
+    fn test_from_file_should_succeed() {
+        let path = concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/fixtures/r4/resources/concept_map.json"
+        );
+
+        let actual = ConceptMap::from_file(path).unwrap();
+
+        assert_eq!(Some("OrganizationTypeToHl7".to_string()), actual.name)
+    }
+
+    #[test]
+    fn test_to_json_value_should_succeed() {
+        let expected = json!({
+            "resourceType": "ConceptMap",
+            "url": "http://example.org/cm",
+            "status": "draft",
+            "group": [
+                {
+                    "source": "http://example.org/local",
+                    "target": "http://example.org/national",
+                    "element": [
+                        {
+                            "code": "a",
+                            "target": [{"code": "x", "equivalence": "narrower"}]
+                        }
+                    ],
+                    "unmapped": {"mode": "other-map", "url": "http://example.org/cm2"}
+                }
+            ]
+        });
+
+        let concept_map = ConceptMapBuilder::default()
+            .url("http://example.org/cm")
+            .add_group(
+                ConceptMapGroupBuilder::default()
+                    .source("http://example.org/local")
+                    .target("http://example.org/national")
+                    .add_mapping("a", "x", ConceptMapEquivalence::Narrower)
+                    .unmapped(ConceptMapGroupUnmapped {
+                        mode: ConceptMapGroupUnmappedMode::OtherMap,
+                        url: Some("http://example.org/cm2".to_string()),
+                        ..Default::default()
+                    })
+                    .build(),
+            )
+            .build();
+
+        let actual = concept_map.to_json_value().unwrap();
+
+        assert_eq!(expected, actual)
+    }
+
+    #[test]
+    fn test_equivalence_is_match_should_succeed() {
+        assert!(ConceptMapEquivalence::Wider.is_match());
+        assert!(!ConceptMapEquivalence::Unmatched.is_match());
+        assert!(!ConceptMapEquivalence::Disjoint.is_match())
+    }
+}
@@ -0,0 +1,505 @@
+use crate::{
+    FhirError,
+    r4::{
+        elements::{BackboneElement, CodeableConcept, ContactPoint, Identifier, Reference},
+        resources::{
+            Device, DeviceDefinition, DeviceName, DeviceNameType, DeviceProperty,
+            DeviceSpecialization, DeviceStatus, DeviceUdiCarrier, DeviceVersion, DomainResource,
+            Location, Organization, Resource, ResourceType, UdiEntryType,
+        },
+    },
+};
+
+#[derive(Default)]
+pub struct DeviceUdiCarrierBuilder {
+    backbone_element: BackboneElement,
+    device_identifier: Option<String>,
+    issuer: Option<String>,
+    jurisdiction: Option<String>,
+    carrier_aidc: Option<String>,
+    carrier_hrf: Option<String>,
+    entry_type: Option<UdiEntryType>,
+}
+
+impl DeviceUdiCarrierBuilder {
+    pub fn new(id: impl Into<String>) -> Self {
+        let mut builder = Self::default();
+        builder.backbone_element.element.id = Some(id.into());
+        builder
+    }
+
+    pub fn id(mut self, id: impl Into<String>) -> Self {
+        self.backbone_element.element.id = Some(id.into());
+        self
+    }
+
+    pub fn device_identifier(mut self, device_identifier: impl Into<String>) -> Self {
+        self.device_identifier = Some(device_identifier.into());
+        self
+    }
+
+    pub fn issuer(mut self, issuer: impl Into<String>) -> Self {
+        self.issuer = Some(issuer.into());
+        self
+    }
+
+    pub fn jurisdiction(mut self, jurisdiction: impl Into<String>) -> Self {
+        self.jurisdiction = Some(jurisdiction.into());
+        self
+    }
+
+    pub fn carrier_aidc(mut self, carrier_aidc: impl Into<String>) -> Self {
+        self.carrier_aidc = Some(carrier_aidc.into());
+        self
+    }
+
+    pub fn carrier_hrf(mut self, carrier_hrf: impl Into<String>) -> Self {
+        self.carrier_hrf = Some(carrier_hrf.into());
+        self
+    }
+
+    pub fn entry_type(mut self, entry_type: UdiEntryType) -> Self {
+        self.entry_type = Some(entry_type);
+        self
+    }
+
+    pub fn build(self) -> DeviceUdiCarrier {
+        DeviceUdiCarrier {
+            backbone_element: self.backbone_element,
+            device_identifier: self.device_identifier,
+            issuer: self.issuer,
+            jurisdiction: self.jurisdiction,
+            carrier_aidc: self.carrier_aidc,
+            carrier_hrf: self.carrier_hrf,
+            entry_type: self.entry_type,
+        }
+    }
+
+    pub fn try_build(self) -> Result<DeviceUdiCarrier, FhirError> {
+        Ok(self.build())
+    }
+}
+
+#[derive(Default)]
+pub struct DeviceNameBuilder {
+    backbone_element: BackboneElement,
+    name: String,
+    r#type: DeviceNameType,
+}
+
+impl DeviceNameBuilder {
+    pub fn new(id: impl Into<String>) -> Self {
+        let mut builder = Self::default();
+        builder.backbone_element.element.id = Some(id.into());
+        builder
+    }
+
+    pub fn id(mut self, id: impl Into<String>) -> Self {
+        self.backbone_element.element.id = Some(id.into());
+        self
+    }
+
+    pub fn name(mut self, name: impl Into<String>) -> Self {
+        self.name = name.into();
+        self
+    }
+
+    pub fn r#type(mut self, r#type: DeviceNameType) -> Self {
+        self.r#type = r#type;
+        self
+    }
+
+    pub fn build(self) -> DeviceName {
+        DeviceName {
+            backbone_element: self.backbone_element,
+            name: self.name,
+            r#type: self.r#type,
+        }
+    }
+
+    pub fn try_build(self) -> Result<DeviceName, FhirError> {
+        FhirError::require("DeviceName", &[("name", !self.name.is_empty())])?;
+        Ok(self.build())
+    }
+}
+
+pub struct DeviceBuilder {
+    domain_resource: DomainResource,
+    identifier: Option<Vec<Identifier>>,
+    definition: Option<Reference<DeviceDefinition>>,
+    udi_carrier: Option<Vec<DeviceUdiCarrier>>,
+    status: Option<DeviceStatus>,
+    status_reason: Option<Vec<CodeableConcept>>,
+    distinct_identifier: Option<String>,
+    manufacturer: Option<String>,
+    manufacture_date: Option<String>, // to be resolved later
+    expiration_date: Option<String>,  // to be resolved later
+    lot_number: Option<String>,
+    serial_number: Option<String>,
+    device_name: Option<Vec<DeviceName>>,
+    model_number: Option<String>,
+    part_number: Option<String>,
+    r#type: Option<CodeableConcept>,
+    specialization: Option<Vec<DeviceSpecialization>>,
+    version: Option<Vec<DeviceVersion>>,
+    property: Option<Vec<DeviceProperty>>,
+    owner: Option<Reference<Organization>>,
+    contact: Option<Vec<ContactPoint>>,
+    location: Option<Reference<Location>>,
+    url: Option<String>,
+    safety: Option<Vec<CodeableConcept>>,
+    parent: Option<Reference<Device>>,
+    resource_type: String,
+}
+
+impl Default for DeviceBuilder {
+    fn default() -> Self {
+        DeviceBuilder {
+            domain_resource: DomainResource {
+                ..Default::default()
+            },
+            resource_type: Device::get_resource_type(),
+            identifier: None,
+            definition: None,
+            udi_carrier: None,
+            status: None,
+            status_reason: None,
+            distinct_identifier: None,
+            manufacturer: None,
+            manufacture_date: None,
+            expiration_date: None,
+            lot_number: None,
+            serial_number: None,
+            device_name: None,
+            model_number: None,
+            part_number: None,
+            r#type: None,
+            specialization: None,
+            version: None,
+            property: None,
+            owner: None,
+            contact: None,
+            location: None,
+            url: None,
+            safety: None,
+            parent: None,
+        }
+    }
+}
+
+impl DeviceBuilder {
+    pub fn new(id: impl Into<String>) -> Self {
+        DeviceBuilder {
+            domain_resource: DomainResource {
+                resource: Resource {
+                    id: Some(id.into()),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    pub fn id(mut self, id: impl Into<String>) -> Self {
+        self.domain_resource.resource.id = Some(id.into());
+        self
+    }
+
+    pub fn identifier(mut self, identifier: Vec<Identifier>) -> Self {
+        self.identifier = Some(identifier);
+        self
+    }
+
+    pub fn add_identifier(mut self, identifier: Identifier) -> Self {
+        match &mut self.identifier {
+            Some(ident) => ident.push(identifier),
+            None => self.identifier = Some(vec![identifier]),
+        }
+        self
+    }
+
+    pub fn definition(mut self, definition: Reference<DeviceDefinition>) -> Self {
+        self.definition = Some(definition);
+        self
+    }
+
+    pub fn udi_carrier(mut self, udi_carrier: Vec<DeviceUdiCarrier>) -> Self {
+        self.udi_carrier = Some(udi_carrier);
+        self
+    }
+
+    pub fn add_udi_carrier(mut self, udi_carrier: DeviceUdiCarrier) -> Self {
+        match &mut self.udi_carrier {
+            Some(udi) => udi.push(udi_carrier),
+            None => self.udi_carrier = Some(vec![udi_carrier]),
+        }
+        self
+    }
+
+    pub fn status(mut self, status: DeviceStatus) -> Self {
+        self.status = Some(status);
+        self
+    }
+
+    pub fn status_reason(mut self, status_reason: Vec<CodeableConcept>) -> Self {
+        self.status_reason = Some(status_reason);
+        self
+    }
+
+    pub fn add_status_reason(mut self, status_reason: CodeableConcept) -> Self {
+        match &mut self.status_reason {
+            Some(sr) => sr.push(status_reason),
+            None => self.status_reason = Some(vec![status_reason]),
+        }
+        self
+    }
+
+    pub fn distinct_identifier(mut self, distinct_identifier: impl Into<String>) -> Self {
+        self.distinct_identifier = Some(distinct_identifier.into());
+        self
+    }
+
+    pub fn manufacturer(mut self, manufacturer: impl Into<String>) -> Self {
+        self.manufacturer = Some(manufacturer.into());
+        self
+    }
+
+    pub fn manufacture_date(mut self, date: impl Into<String>) -> Self {
+        self.manufacture_date = Some(date.into());
+        self
+    }
+
+    pub fn expiration_date(mut self, date: impl Into<String>) -> Self {
+        self.expiration_date = Some(date.into());
+        self
+    }
+
+    pub fn lot_number(mut self, lot_number: impl Into<String>) -> Self {
+        self.lot_number = Some(lot_number.into());
+        self
+    }
+
+    pub fn serial_number(mut self, serial_number: impl Into<String>) -> Self {
+        self.serial_number = Some(serial_number.into());
+        self
+    }
+
+    pub fn device_name(mut self, device_name: Vec<DeviceName>) -> Self {
+        self.device_name = Some(device_name);
+        self
+    }
+
+    pub fn add_device_name(mut self, device_name: DeviceName) -> Self {
+        match &mut self.device_name {
+            Some(dn) => dn.push(device_name),
+            None => self.device_name = Some(vec![device_name]),
+        }
+        self
+    }
+
+    pub fn model_number(mut self, model_number: impl Into<String>) -> Self {
+        self.model_number = Some(model_number.into());
+        self
+    }
+
+    pub fn part_number(mut self, part_number: impl Into<String>) -> Self {
+        self.part_number = Some(part_number.into());
+        self
+    }
+
+    pub fn r#type(mut self, r#type: CodeableConcept) -> Self {
+        self.r#type = Some(r#type);
+        self
+    }
+
+    pub fn specialization(mut self, specialization: Vec<DeviceSpecialization>) -> Self {
+        self.specialization = Some(specialization);
+        self
+    }
+
+    pub fn add_specialization(mut self, specialization: DeviceSpecialization) -> Self {
+        match &mut self.specialization {
+            Some(s) => s.push(specialization),
+            None => self.specialization = Some(vec![specialization]),
+        }
+        self
+    }
+
+    pub fn version(mut self, version: Vec<DeviceVersion>) -> Self {
+        self.version = Some(version);
+        self
+    }
+
+    pub fn add_version(mut self, version: DeviceVersion) -> Self {
+        match &mut self.version {
+            Some(v) => v.push(version),
+            None => self.version = Some(vec![version]),
+        }
+        self
+    }
+
+    pub fn property(mut self, property: Vec<DeviceProperty>) -> Self {
+        self.property = Some(property);
+        self
+    }
+
+    pub fn add_property(mut self, property: DeviceProperty) -> Self {
+        match &mut self.property {
+            Some(p) => p.push(property),
+            None => self.property = Some(vec![property]),
+        }
+        self
+    }
+
+    pub fn owner(mut self, owner: Reference<Organization>) -> Self {
+        self.owner = Some(owner);
+        self
+    }
+
+    pub fn contact(mut self, contact: Vec<ContactPoint>) -> Self {
+        self.contact = Some(contact);
+        self
+    }
+
+    pub fn add_contact(mut self, contact: ContactPoint) -> Self {
+        match &mut self.contact {
+            Some(c) => c.push(contact),
+            None => self.contact = Some(vec![contact]),
+        }
+        self
+    }
+
+    pub fn location(mut self, location: Reference<Location>) -> Self {
+        self.location = Some(location);
+        self
+    }
+
+    pub fn url(mut self, url: impl Into<String>) -> Self {
+        self.url = Some(url.into());
+        self
+    }
+
+    pub fn safety(mut self, safety: Vec<CodeableConcept>) -> Self {
+        self.safety = Some(safety);
+        self
+    }
+
+    pub fn add_safety(mut self, safety: CodeableConcept) -> Self {
+        match &mut self.safety {
+            Some(s) => s.push(safety),
+            None => self.safety = Some(vec![safety]),
+        }
+        self
+    }
+
+    pub fn parent(mut self, parent: Reference<Device>) -> Self {
+        self.parent = Some(parent);
+        self
+    }
+
+    pub fn build(self) -> Device {
+        Device {
+            domain_resource: self.domain_resource,
+            identifier: self.identifier,
+            definition: self.definition,
+            udi_carrier: self.udi_carrier,
+            status: self.status,
+            status_reason: self.status_reason,
+            distinct_identifier: self.distinct_identifier,
+            manufacturer: self.manufacturer,
+            manufacture_date: self.manufacture_date,
+            expiration_date: self.expiration_date,
+            lot_number: self.lot_number,
+            serial_number: self.serial_number,
+            device_name: self.device_name,
+            model_number: self.model_number,
+            part_number: self.part_number,
+            r#type: self.r#type,
+            specialization: self.specialization,
+            version: self.version,
+            property: self.property,
+            owner: self.owner,
+            contact: self.contact,
+            location: self.location,
+            url: self.url,
+            safety: self.safety,
+            parent: self.parent,
+            resource_type: self.resource_type,
+        }
+    }
+
+    pub fn try_build(self) -> Result<Device, FhirError> {
+        Ok(self.build())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::r4::elements::Element;
+
+    use super::*;
+
+    #[test]
+    fn test_build_udi_carrier_should_succeed() {
+        let expected = DeviceUdiCarrier {
+            backbone_element: BackboneElement {
+                element: Element {
+                    id: Some("udi-1".to_string()),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            device_identifier: Some("00844588003288".to_string()),
+            issuer: Some("http://hl7.org/fhir/NamingSystem/gs1-di".to_string()),
+            carrier_hrf: Some("(01)00844588003288".to_string()),
+            entry_type: Some(UdiEntryType::Barcode),
+            ..Default::default()
+        };
+
+        let actual = DeviceUdiCarrierBuilder::new("udi-1")
+            .device_identifier("00844588003288")
+            .issuer("http://hl7.org/fhir/NamingSystem/gs1-di")
+            .carrier_hrf("(01)00844588003288")
+            .entry_type(UdiEntryType::Barcode)
+            .build();
+
+        assert_eq!(expected, actual)
+    }
+
+    #[test]
+    fn test_build_device_should_succeed() {
+        let expected = Device {
+            domain_resource: DomainResource {
+                resource: Resource {
+                    id: Some("device-1".to_string()),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            status: Some(DeviceStatus::Active),
+            manufacturer: Some("Acme".to_string()),
+            lot_number: Some("LOT-1".to_string()),
+            device_name: Some(vec![DeviceName {
+                name: "Monitor".to_string(),
+                r#type: DeviceNameType::UserFriendlyName,
+                ..Default::default()
+            }]),
+            ..Default::default()
+        };
+
+        let actual = DeviceBuilder::new("device-1")
+            .status(DeviceStatus::Active)
+            .manufacturer("Acme")
+            .lot_number("LOT-1")
+            .add_device_name(
+                DeviceNameBuilder::default()
+                    .name("Monitor")
+                    .r#type(DeviceNameType::UserFriendlyName)
+                    .build(),
+            )
+            .build();
+
+        assert_eq!(expected, actual)
+    }
+}
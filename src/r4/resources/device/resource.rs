@@ -0,0 +1,551 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    FhirError,
+    r4::{
+        elements::{
+            BackboneElement, CodeableConcept, ContactPoint, GetCodeableConceptsMut, GetIdentifiers,
+            GetResourceReferences, Identifier, Quantity, Reference, ReferenceTypes,
+        },
+        resources::{
+            DeviceDefinition, DomainResource, Location, Organization, ReferenceTarget, ResourceType,
+        },
+    },
+};
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+#[serde(rename_all = "kebab-case")]
+pub enum UdiEntryType {
+    Barcode,
+    Rfid,
+    Manual,
+    Card,
+    SelfReported,
+    Unknown,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Default)]
+#[serde(rename_all(serialize = "camelCase", deserialize = "camelCase"))]
+pub struct DeviceUdiCarrier {
+    #[serde(flatten)]
+    pub backbone_element: BackboneElement,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub device_identifier: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub issuer: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub jurisdiction: Option<String>,
+
+    #[serde(rename = "carrierAIDC", skip_serializing_if = "Option::is_none")]
+    pub carrier_aidc: Option<String>,
+
+    #[serde(rename = "carrierHRF", skip_serializing_if = "Option::is_none")]
+    pub carrier_hrf: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub entry_type: Option<UdiEntryType>,
+}
+
+impl ResourceType for DeviceUdiCarrier {
+    const TYPE: &'static str = "DeviceUdiCarrier";
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+#[serde(rename_all = "kebab-case")]
+pub enum DeviceStatus {
+    Active,
+    Inactive,
+    EnteredInError,
+    Unknown,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum DeviceNameType {
+    UdiLabelName,
+    UserFriendlyName,
+    PatientReportedName,
+    ManufacturerName,
+    ModelName,
+    #[default]
+    Other,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Default)]
+pub struct DeviceName {
+    #[serde(flatten)]
+    pub backbone_element: BackboneElement,
+
+    pub name: String,
+
+    pub r#type: DeviceNameType,
+}
+
+impl ResourceType for DeviceName {
+    const TYPE: &'static str = "DeviceName";
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Default)]
+#[serde(rename_all(serialize = "camelCase", deserialize = "camelCase"))]
+pub struct DeviceSpecialization {
+    #[serde(flatten)]
+    pub backbone_element: BackboneElement,
+
+    pub system_type: CodeableConcept,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+}
+
+impl ResourceType for DeviceSpecialization {
+    const TYPE: &'static str = "DeviceSpecialization";
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Default)]
+pub struct DeviceVersion {
+    #[serde(flatten)]
+    pub backbone_element: BackboneElement,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub r#type: Option<CodeableConcept>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub component: Option<Identifier>,
+
+    pub value: String,
+}
+
+impl ResourceType for DeviceVersion {
+    const TYPE: &'static str = "DeviceVersion";
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Default)]
+#[serde(rename_all(serialize = "camelCase", deserialize = "camelCase"))]
+pub struct DeviceProperty {
+    #[serde(flatten)]
+    pub backbone_element: BackboneElement,
+
+    pub r#type: CodeableConcept,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value_quantity: Option<Vec<Quantity>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value_code: Option<Vec<CodeableConcept>>,
+}
+
+impl ResourceType for DeviceProperty {
+    const TYPE: &'static str = "DeviceProperty";
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+#[serde(rename_all(serialize = "camelCase", deserialize = "camelCase"))]
+pub struct Device {
+    #[serde(flatten)]
+    pub domain_resource: DomainResource,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub identifier: Option<Vec<Identifier>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub definition: Option<Reference<DeviceDefinition>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub udi_carrier: Option<Vec<DeviceUdiCarrier>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<DeviceStatus>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status_reason: Option<Vec<CodeableConcept>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub distinct_identifier: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub manufacturer: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub manufacture_date: Option<String>, // to be resolved later
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expiration_date: Option<String>, // to be resolved later
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lot_number: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub serial_number: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub device_name: Option<Vec<DeviceName>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub model_number: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub part_number: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub r#type: Option<CodeableConcept>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub specialization: Option<Vec<DeviceSpecialization>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<Vec<DeviceVersion>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub property: Option<Vec<DeviceProperty>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub owner: Option<Reference<Organization>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub contact: Option<Vec<ContactPoint>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub location: Option<Reference<Location>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub safety: Option<Vec<CodeableConcept>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parent: Option<Reference<Device>>,
+
+    #[serde(default = "Device::get_resource_type")]
+    pub resource_type: String,
+}
+
+impl Default for Device {
+    fn default() -> Self {
+        Device {
+            domain_resource: DomainResource {
+                ..Default::default()
+            },
+            resource_type: Self::get_resource_type(),
+            identifier: None,
+            definition: None,
+            udi_carrier: None,
+            status: None,
+            status_reason: None,
+            distinct_identifier: None,
+            manufacturer: None,
+            manufacture_date: None,
+            expiration_date: None,
+            lot_number: None,
+            serial_number: None,
+            device_name: None,
+            model_number: None,
+            part_number: None,
+            r#type: None,
+            specialization: None,
+            version: None,
+            property: None,
+            owner: None,
+            contact: None,
+            location: None,
+            url: None,
+            safety: None,
+            parent: None,
+        }
+    }
+}
+
+impl ResourceType for Device {
+    const TYPE: &'static str = "Device";
+}
+
+impl ReferenceTarget for Device {
+    fn resource_id(&self) -> Option<&str> {
+        self.domain_resource.resource.id.as_deref()
+    }
+
+    fn resource_id_mut(&mut self) -> &mut Option<String> {
+        &mut self.domain_resource.resource.id
+    }
+
+    fn reference_display(&self) -> Option<String> {
+        self.device_name
+            .iter()
+            .flatten()
+            .map(|name| name.name.clone())
+            .next()
+    }
+}
+
+impl GetIdentifiers for Device {
+    fn get_identifiers(&self) -> Vec<&Identifier> {
+        self.identifier.iter().flatten().collect()
+    }
+}
+
+impl Device {
+    pub fn from_json(data: &str) -> Result<Self, FhirError> {
+        Ok(serde_json::from_str(data)?)
+    }
+
+    pub fn to_json_value(&self) -> Result<serde_json::Value, FhirError> {
+        Ok(serde_json::to_value(self)?)
+    }
+
+    pub fn to_json_string(&self) -> Result<String, FhirError> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+}
+
+impl GetResourceReferences for Device {
+    fn get_references(&self) -> Vec<ReferenceTypes<'_>> {
+        let mut references = Vec::<ReferenceTypes>::new();
+
+        if let Some(definition) = &self.definition {
+            references.push(ReferenceTypes::from(definition));
+        }
+
+        if let Some(owner) = &self.owner {
+            references.push(ReferenceTypes::from(owner));
+        }
+
+        if let Some(location) = &self.location {
+            references.push(ReferenceTypes::from(location));
+        }
+
+        if let Some(parent) = &self.parent {
+            references.push(ReferenceTypes::from(parent));
+        }
+
+        for identifier in self.identifier.iter().flatten() {
+            references.extend(identifier.get_references());
+        }
+
+        references
+    }
+}
+
+impl GetCodeableConceptsMut for DeviceSpecialization {
+    fn get_codeable_concepts_mut(&mut self) -> Vec<&mut CodeableConcept> {
+        vec![&mut self.system_type]
+    }
+}
+
+impl GetCodeableConceptsMut for DeviceVersion {
+    fn get_codeable_concepts_mut(&mut self) -> Vec<&mut CodeableConcept> {
+        let mut concepts = Vec::<&mut CodeableConcept>::new();
+
+        concepts.extend(self.r#type.iter_mut());
+
+        for component in self.component.iter_mut() {
+            concepts.extend(component.get_codeable_concepts_mut());
+        }
+
+        concepts
+    }
+}
+
+impl GetCodeableConceptsMut for DeviceProperty {
+    fn get_codeable_concepts_mut(&mut self) -> Vec<&mut CodeableConcept> {
+        let mut concepts = vec![&mut self.r#type];
+        concepts.extend(self.value_code.iter_mut().flatten());
+
+        concepts
+    }
+}
+
+impl GetCodeableConceptsMut for Device {
+    fn get_codeable_concepts_mut(&mut self) -> Vec<&mut CodeableConcept> {
+        let mut concepts = self.domain_resource.get_codeable_concepts_mut();
+
+        concepts.extend(self.status_reason.iter_mut().flatten());
+        concepts.extend(self.r#type.iter_mut());
+        concepts.extend(self.safety.iter_mut().flatten());
+
+        for identifier in self.identifier.iter_mut().flatten() {
+            concepts.extend(identifier.get_codeable_concepts_mut());
+        }
+
+        for specialization in self.specialization.iter_mut().flatten() {
+            concepts.extend(specialization.get_codeable_concepts_mut());
+        }
+
+        for version in self.version.iter_mut().flatten() {
+            concepts.extend(version.get_codeable_concepts_mut());
+        }
+
+        for property in self.property.iter_mut().flatten() {
+            concepts.extend(property.get_codeable_concepts_mut());
+        }
+
+        concepts
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::json;
+
+    use crate::r4::{
+        elements::{Coding, ReferenceBuilder},
+        resources::{DeviceBuilder, DeviceNameBuilder, Resource},
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_from_json_should_succeed() {
+        let data = include_str!("../../../../fixtures/r4/resources/device.json");
+        let expected = Device {
+            domain_resource: DomainResource {
+                resource: Resource {
+                    id: Some("device-1".to_string()),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            identifier: Some(vec![Identifier {
+                system: Some("http://example.org/devices".to_string()),
+                value: Some("DEV-001".to_string()),
+                ..Default::default()
+            }]),
+            definition: Some(Reference::<DeviceDefinition> {
+                reference: Some("DeviceDefinition/device-definition-1".to_string()),
+                ..Default::default()
+            }),
+            udi_carrier: Some(vec![DeviceUdiCarrier {
+                device_identifier: Some("00844588003288".to_string()),
+                issuer: Some("http://hl7.org/fhir/NamingSystem/gs1-di".to_string()),
+                jurisdiction: Some("http://hl7.org/fhir/NamingSystem/fda-udi".to_string()),
+                carrier_hrf: Some(
+                    "(01)00844588003288(17)141120(10)7654321D(21)10987654d321".to_string(),
+                ),
+                entry_type: Some(UdiEntryType::Barcode),
+                ..Default::default()
+            }]),
+            status: Some(DeviceStatus::Active),
+            manufacturer: Some("Acme Devices, Inc".to_string()),
+            expiration_date: Some("2014-11-20".to_string()),
+            lot_number: Some("7654321D".to_string()),
+            serial_number: Some("10987654d321".to_string()),
+            device_name: Some(vec![DeviceName {
+                name: "FHIR Heart Monitor".to_string(),
+                r#type: DeviceNameType::UserFriendlyName,
+                ..Default::default()
+            }]),
+            model_number: Some("HM-2000".to_string()),
+            r#type: Some(CodeableConcept {
+                coding: Some(vec![Coding {
+                    system: Some("http://snomed.info/sct".to_string()),
+                    code: Some("86184003".to_string()),
+                    display: Some("Electrocardiographic monitor and recorder".to_string()),
+                    ..Default::default()
+                }]),
+                ..Default::default()
+            }),
+            owner: Some(Reference::<Organization> {
+                reference: Some("Organization/org-1".to_string()),
+                display: Some("Burgers University Medical Center".to_string()),
+                ..Default::default()
+            }),
+            contact: Some(vec![ContactPoint {
+                system: Some("phone".to_string()),
+                value: Some("+1-555-123-0000".to_string()),
+                ..Default::default()
+            }]),
+            location: Some(Reference::<Location> {
+                reference: Some("Location/location-1".to_string()),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+
+        let actual = Device::from_json(data).unwrap();
+
+        assert_eq!(expected, actual)
+    }
+
+    #[test]
+    fn test_to_json_value_should_succeed() {
+        let expected = json!({
+            "resourceType": "Device",
+            "id": "device-1",
+            "status": "entered-in-error",
+            "udiCarrier": [
+                {
+                    "deviceIdentifier": "00844588003288",
+                    "carrierHRF": "(01)00844588003288",
+                    "entryType": "self-reported"
+                }
+            ],
+            "deviceName": [
+                {
+                    "name": "Monitor",
+                    "type": "model-name"
+                }
+            ],
+            "owner": {"reference": "Organization/1"}
+        });
+
+        let device = DeviceBuilder::new("device-1")
+            .status(DeviceStatus::EnteredInError)
+            .add_udi_carrier(DeviceUdiCarrier {
+                device_identifier: Some("00844588003288".to_string()),
+                carrier_hrf: Some("(01)00844588003288".to_string()),
+                entry_type: Some(UdiEntryType::SelfReported),
+                ..Default::default()
+            })
+            .add_device_name(
+                DeviceNameBuilder::default()
+                    .name("Monitor")
+                    .r#type(DeviceNameType::ModelName)
+                    .build(),
+            )
+            .owner(
+                ReferenceBuilder::default()
+                    .with_reference("Organization/1")
+                    .build::<Organization>(),
+            )
+            .build();
+
+        let actual = device.to_json_value().unwrap();
+
+        assert_eq!(expected, actual)
+    }
+
+    #[test]
+    fn test_get_references_should_succeed() {
+        let definition = ReferenceBuilder::default()
+            .with_reference("DeviceDefinition/1")
+            .build::<DeviceDefinition>();
+        let owner = ReferenceBuilder::default()
+            .with_reference("Organization/1")
+            .build::<Organization>();
+        let location = ReferenceBuilder::default()
+            .with_reference("Location/1")
+            .build::<Location>();
+        let parent = ReferenceBuilder::default()
+            .with_reference("Device/2")
+            .build::<Device>();
+        let expected = vec![
+            ReferenceTypes::from(&definition),
+            ReferenceTypes::from(&owner),
+            ReferenceTypes::from(&location),
+            ReferenceTypes::from(&parent),
+        ];
+
+        let device = DeviceBuilder::default()
+            .definition(definition.clone())
+            .owner(owner.clone())
+            .location(location.clone())
+            .parent(parent.clone())
+            .build();
+
+        let actual = device.get_references();
+
+        assert_eq!(expected, actual)
+    }
+}
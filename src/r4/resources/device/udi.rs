@@ -0,0 +1,450 @@
+use crate::{FhirError, r4::resources::DeviceUdiCarrier};
+
+pub const GS1_DI_ISSUER: &str = "http://hl7.org/fhir/NamingSystem/gs1-di";
+pub const HIBCC_DI_ISSUER: &str = "http://hl7.org/fhir/NamingSystem/hibcc-dI";
+
+const HIBCC_CHARSET: &str = "0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZ-. $/+%";
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum UdiIssuingAgency {
+    Gs1,
+    Hibcc,
+}
+
+impl UdiIssuingAgency {
+    pub fn issuer_uri(&self) -> &'static str {
+        match self {
+            Self::Gs1 => GS1_DI_ISSUER,
+            Self::Hibcc => HIBCC_DI_ISSUER,
+        }
+    }
+}
+
+/// The production identifiers (PI) of a UDI, dates are rendered as FHIR
+/// dates (`YYYY-MM` or `YYYY-MM-DD`).
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct UdiProductionIdentifiers {
+    pub lot_number: Option<String>,
+    pub serial_number: Option<String>,
+    pub manufacture_date: Option<String>,
+    pub expiration_date: Option<String>,
+}
+
+/// A human readable UDI carrier split into its device identifier (DI) and
+/// production identifiers (PI).
+#[derive(Debug, PartialEq, Clone)]
+pub struct ParsedUdi {
+    pub issuing_agency: UdiIssuingAgency,
+    pub device_identifier: String,
+    pub production_identifiers: UdiProductionIdentifiers,
+}
+
+impl ParsedUdi {
+    pub fn to_udi_carrier(&self, carrier_hrf: impl Into<String>) -> DeviceUdiCarrier {
+        DeviceUdiCarrier {
+            device_identifier: Some(self.device_identifier.clone()),
+            issuer: Some(self.issuing_agency.issuer_uri().to_string()),
+            carrier_hrf: Some(carrier_hrf.into()),
+            ..Default::default()
+        }
+    }
+}
+
+impl DeviceUdiCarrier {
+    /// Parses `carrierHRF`, returns `None` when the carrier has no human readable form.
+    pub fn parse_carrier_hrf(&self) -> Result<Option<ParsedUdi>, FhirError> {
+        self.carrier_hrf.as_deref().map(parse_udi_hrf).transpose()
+    }
+}
+
+/// Parses a human readable UDI string, the issuing agency is detected from the
+/// leading character: `(` for GS1 and `+` for HIBCC.
+pub fn parse_udi_hrf(hrf: &str) -> Result<ParsedUdi, FhirError> {
+    let hrf = hrf.trim();
+    if hrf.starts_with('(') {
+        parse_gs1_hrf(hrf)
+    } else if hrf.starts_with('+') {
+        parse_hibcc_hrf(hrf)
+    } else {
+        Err(udi_error(format!("unknown issuing agency for '{hrf}'")))
+    }
+}
+
+/// Parses a GS1 human readable UDI such as `(01)00844588003288(17)141120(10)7654321D`.
+///
+/// Application identifiers that are not part of the UDI are ignored.
+pub fn parse_gs1_hrf(hrf: &str) -> Result<ParsedUdi, FhirError> {
+    let mut device_identifier = None;
+    let mut pi = UdiProductionIdentifiers::default();
+    let mut rest = hrf.trim();
+
+    while !rest.is_empty() {
+        let Some(stripped) = rest.strip_prefix('(') else {
+            return Err(udi_error(format!("expected '(' in GS1 UDI at '{rest}'")));
+        };
+        let Some((ai, tail)) = stripped.split_once(')') else {
+            return Err(udi_error("unterminated GS1 application identifier"));
+        };
+        let end = tail.find('(').unwrap_or(tail.len());
+        let value = &tail[..end];
+        rest = &tail[end..];
+
+        if value.is_empty() {
+            return Err(udi_error(format!("empty value for GS1 AI ({ai})")));
+        }
+
+        match ai {
+            "01" => {
+                if value.len() != 14 || !value.chars().all(|c| c.is_ascii_digit()) {
+                    return Err(udi_error(format!("GTIN '{value}' must be 14 digits")));
+                }
+                device_identifier = Some(value.to_string());
+            }
+            "10" => pi.lot_number = Some(value.to_string()),
+            "11" => pi.manufacture_date = Some(gs1_date(value)?),
+            "17" => pi.expiration_date = Some(gs1_date(value)?),
+            "21" => pi.serial_number = Some(value.to_string()),
+            _ => {}
+        }
+    }
+
+    Ok(ParsedUdi {
+        issuing_agency: UdiIssuingAgency::Gs1,
+        device_identifier: device_identifier
+            .ok_or_else(|| udi_error("GS1 UDI is missing the (01) device identifier"))?,
+        production_identifiers: pi,
+    })
+}
+
+/// Parses a HIBCC human readable UDI such as `+A99912345/$$52001510X0`.
+///
+/// The trailing check character is validated, the primary data structure
+/// (labeler code, product number and unit of measure) is the device identifier.
+pub fn parse_hibcc_hrf(hrf: &str) -> Result<ParsedUdi, FhirError> {
+    let hrf = hrf.trim();
+    let Some(body) = hrf.strip_prefix('+') else {
+        return Err(udi_error("HIBCC UDI must start with '+'"));
+    };
+    let Some(check) = body.chars().last() else {
+        return Err(udi_error("HIBCC UDI is empty"));
+    };
+    let data = &hrf[..hrf.len() - check.len_utf8()];
+    let expected = hibcc_check_character(data)?;
+    if expected != check {
+        return Err(udi_error(format!(
+            "HIBCC check character '{check}' does not match expected '{expected}'"
+        )));
+    }
+
+    let mut segments = data[1..].split('/');
+    let primary = segments.next().unwrap_or_default();
+    if primary.len() < 6 || !primary.starts_with(|c: char| c.is_ascii_alphabetic()) {
+        return Err(udi_error(format!("invalid HIBCC primary data '{primary}'")));
+    }
+    if !primary.ends_with(|c: char| c.is_ascii_digit()) {
+        return Err(udi_error(
+            "HIBCC primary data must end with a unit of measure digit",
+        ));
+    }
+
+    let mut pi = UdiProductionIdentifiers::default();
+    for segment in segments {
+        parse_hibcc_secondary(segment, &mut pi)?;
+    }
+
+    Ok(ParsedUdi {
+        issuing_agency: UdiIssuingAgency::Hibcc,
+        device_identifier: primary.to_string(),
+        production_identifiers: pi,
+    })
+}
+
+fn parse_hibcc_secondary(
+    segment: &str,
+    pi: &mut UdiProductionIdentifiers,
+) -> Result<(), FhirError> {
+    if let Some(rest) = segment.strip_prefix("$$+") {
+        let (date, value) = hibcc_flagged_date(rest)?;
+        pi.expiration_date = date.or(pi.expiration_date.take());
+        pi.serial_number = Some(value.to_string());
+    } else if let Some(rest) = segment.strip_prefix("$$") {
+        let (date, value) = hibcc_flagged_date(rest)?;
+        pi.expiration_date = date.or(pi.expiration_date.take());
+        pi.lot_number = Some(value.to_string());
+    } else if let Some(rest) = segment.strip_prefix("$+") {
+        pi.serial_number = Some(rest.to_string());
+    } else if let Some(rest) = segment.strip_prefix('$') {
+        pi.lot_number = Some(rest.to_string());
+    } else if let Some(rest) = segment.strip_prefix("16D") {
+        pi.manufacture_date = Some(yyyymmdd(rest)?);
+    } else if let Some(rest) = segment.strip_prefix("14D") {
+        pi.expiration_date = Some(yyyymmdd(rest)?);
+    } else if let Some(rest) = segment.strip_prefix('S') {
+        pi.serial_number = Some(rest.to_string());
+    } else if let Some(date) = segment.get(..5).filter(|date| is_digits(date)) {
+        pi.expiration_date = Some(yyjjj(date)?);
+        pi.lot_number = Some(segment[5..].to_string());
+    } else {
+        return Err(udi_error(format!("unsupported HIBCC segment '{segment}'")));
+    }
+    Ok(())
+}
+
+fn hibcc_flagged_date(rest: &str) -> Result<(Option<String>, &str), FhirError> {
+    let take = |n: usize| -> Result<(&str, &str), FhirError> {
+        match (rest.get(1..1 + n), rest.get(1 + n..)) {
+            (Some(date), Some(value)) if is_digits(date) => Ok((date, value)),
+            _ => Err(udi_error(format!("HIBCC date in '{rest}' is too short"))),
+        }
+    };
+
+    match rest.chars().next() {
+        Some('0') | Some('1') => {
+            let (mmyy, value) = match (rest.get(..4), rest.get(4..)) {
+                (Some(d), Some(v)) if is_digits(d) => (d, v),
+                _ => return Err(udi_error(format!("HIBCC date in '{rest}' is too short"))),
+            };
+            let month = digits(&mmyy[..2])?;
+            check_month(month)?;
+            Ok((Some(format!("20{}-{:02}", &mmyy[2..], month)), value))
+        }
+        Some('2') => {
+            let (d, value) = take(6)?;
+            Ok((Some(full_date(&d[4..6], &d[..2], &d[2..4])?), value))
+        }
+        Some('3') => {
+            let (d, value) = take(6)?;
+            Ok((Some(full_date(&d[..2], &d[2..4], &d[4..6])?), value))
+        }
+        Some('4') => {
+            let (d, value) = take(8)?;
+            Ok((Some(full_date(&d[..2], &d[2..4], &d[4..6])?), value))
+        }
+        Some('5') => {
+            let (d, value) = take(5)?;
+            Ok((Some(yyjjj(d)?), value))
+        }
+        Some('6') => {
+            let (d, value) = take(7)?;
+            Ok((Some(yyjjj(&d[..5])?), value))
+        }
+        Some('7') => Ok((None, &rest[1..])),
+        _ => Err(udi_error(format!("unknown HIBCC date flag in '{rest}'"))),
+    }
+}
+
+fn hibcc_check_character(data: &str) -> Result<char, FhirError> {
+    let mut sum = 0;
+    for c in data.chars() {
+        let Some(value) = HIBCC_CHARSET.find(c) else {
+            return Err(udi_error(format!(
+                "character '{c}' is not allowed in HIBCC"
+            )));
+        };
+        sum += value;
+    }
+    HIBCC_CHARSET
+        .chars()
+        .nth(sum % 43)
+        .ok_or_else(|| udi_error("unable to compute HIBCC check character"))
+}
+
+fn gs1_date(value: &str) -> Result<String, FhirError> {
+    if value.len() != 6 || !is_digits(value) {
+        return Err(udi_error(format!("GS1 date '{value}' must be YYMMDD")));
+    }
+    // a day of `00` means the date only has month precision
+    if &value[4..] == "00" {
+        let month = digits(&value[2..4])?;
+        check_month(month)?;
+        return Ok(format!("20{}-{:02}", &value[..2], month));
+    }
+    full_date(&value[..2], &value[2..4], &value[4..])
+}
+
+fn yyyymmdd(value: &str) -> Result<String, FhirError> {
+    match (value.get(..4), value.get(4..6), value.get(6..8)) {
+        (Some(year), Some(month), Some(day)) if value.len() == 8 => {
+            let (year, month, day) = (digits(year)?, digits(month)?, digits(day)?);
+            check_month(month)?;
+            check_day(year, month, day)?;
+            Ok(format!("{year:04}-{month:02}-{day:02}"))
+        }
+        _ => Err(udi_error(format!("date '{value}' must be YYYYMMDD"))),
+    }
+}
+
+fn full_date(yy: &str, mm: &str, dd: &str) -> Result<String, FhirError> {
+    let year = 2000 + digits(yy)?;
+    let (month, day) = (digits(mm)?, digits(dd)?);
+    check_month(month)?;
+    check_day(year, month, day)?;
+    Ok(format!("{year:04}-{month:02}-{day:02}"))
+}
+
+fn yyjjj(value: &str) -> Result<String, FhirError> {
+    if value.len() != 5 || !is_digits(value) {
+        return Err(udi_error(format!("julian date '{value}' must be YYJJJ")));
+    }
+    let year = 2000 + digits(&value[..2])?;
+    let mut ordinal = digits(&value[2..5])?;
+    if ordinal == 0 || ordinal > if is_leap_year(year) { 366 } else { 365 } {
+        return Err(udi_error(format!("invalid julian day in '{value}'")));
+    }
+    let mut month = 1;
+    while ordinal > days_in_month(year, month) {
+        ordinal -= days_in_month(year, month);
+        month += 1;
+    }
+    Ok(format!("{year:04}-{month:02}-{ordinal:02}"))
+}
+
+/// Whether `value` is made of ASCII digits only, so that it can be sliced
+/// by byte.
+fn is_digits(value: &str) -> bool {
+    value.bytes().all(|b| b.is_ascii_digit())
+}
+
+fn digits(value: &str) -> Result<u32, FhirError> {
+    if value.is_empty() || !value.chars().all(|c| c.is_ascii_digit()) {
+        return Err(udi_error(format!("'{value}' is not numeric")));
+    }
+    value
+        .parse()
+        .map_err(|_| udi_error(format!("'{value}' is not numeric")))
+}
+
+fn check_month(month: u32) -> Result<(), FhirError> {
+    if (1..=12).contains(&month) {
+        Ok(())
+    } else {
+        Err(udi_error(format!("invalid month '{month}'")))
+    }
+}
+
+fn check_day(year: u32, month: u32, day: u32) -> Result<(), FhirError> {
+    if day >= 1 && day <= days_in_month(year, month) {
+        Ok(())
+    } else {
+        Err(udi_error(format!("invalid day '{day}'")))
+    }
+}
+
+fn is_leap_year(year: u32) -> bool {
+    (year.is_multiple_of(4) && !year.is_multiple_of(100)) || year.is_multiple_of(400)
+}
+
+fn days_in_month(year: u32, month: u32) -> u32 {
+    match month {
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+fn udi_error(message: impl Into<String>) -> FhirError {
+    FhirError::UdiParseError(message.into())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_gs1_hrf_should_succeed() {
+        let expected = ParsedUdi {
+            issuing_agency: UdiIssuingAgency::Gs1,
+            device_identifier: "00844588003288".to_string(),
+            production_identifiers: UdiProductionIdentifiers {
+                lot_number: Some("7654321D".to_string()),
+                serial_number: Some("10987654d321".to_string()),
+                manufacture_date: Some("2013-01".to_string()),
+                expiration_date: Some("2014-11-20".to_string()),
+            },
+        };
+
+        let actual =
+            parse_udi_hrf("(01)00844588003288(11)130100(17)141120(10)7654321D(21)10987654d321")
+                .unwrap();
+
+        assert_eq!(expected, actual)
+    }
+
+    #[test]
+    fn test_parse_gs1_hrf_without_di_should_fail() {
+        let actual = parse_gs1_hrf("(17)141120(10)7654321D");
+
+        assert!(matches!(actual, Err(FhirError::UdiParseError(_))))
+    }
+
+    #[test]
+    fn test_parse_gs1_hrf_with_non_ascii_date_should_fail() {
+        let actual = parse_gs1_hrf("(01)00844588003288(17)123é5");
+
+        assert!(matches!(actual, Err(FhirError::UdiParseError(_))));
+        let actual = parse_hibcc_hrf("+A99912345/$$3é2310LOTX");
+        assert!(matches!(actual, Err(FhirError::UdiParseError(_))))
+    }
+
+    #[test]
+    fn test_parse_hibcc_hrf_should_succeed() {
+        let expected = ParsedUdi {
+            issuing_agency: UdiIssuingAgency::Hibcc,
+            device_identifier: "A99912345".to_string(),
+            production_identifiers: UdiProductionIdentifiers {
+                lot_number: Some("10X".to_string()),
+                expiration_date: Some("2020-01-15".to_string()),
+                ..Default::default()
+            },
+        };
+
+        let actual = parse_udi_hrf("+A99912345/$$52001510X0").unwrap();
+
+        assert_eq!(expected, actual)
+    }
+
+    #[test]
+    fn test_parse_hibcc_hrf_with_supplemental_data_should_succeed() {
+        let data = "+H123PARTNO1234567/$$420020216LOT123/SXYZ4567/16D20130202";
+        let check = hibcc_check_character(data).unwrap();
+
+        let actual = parse_hibcc_hrf(&format!("{data}{check}")).unwrap();
+
+        assert_eq!("H123PARTNO1234567", actual.device_identifier);
+        assert_eq!(
+            UdiProductionIdentifiers {
+                lot_number: Some("LOT123".to_string()),
+                serial_number: Some("XYZ4567".to_string()),
+                manufacture_date: Some("2013-02-02".to_string()),
+                expiration_date: Some("2020-02-02".to_string()),
+            },
+            actual.production_identifiers
+        )
+    }
+
+    #[test]
+    fn test_parse_hibcc_hrf_with_wrong_check_character_should_fail() {
+        let actual = parse_hibcc_hrf("+A99912345/$$52001510XZ");
+
+        assert!(matches!(actual, Err(FhirError::UdiParseError(_))))
+    }
+
+    #[test]
+    fn test_parse_carrier_hrf_should_succeed() {
+        let carrier = DeviceUdiCarrier {
+            carrier_hrf: Some("(01)00844588003288(10)LOT1".to_string()),
+            ..Default::default()
+        };
+
+        let parsed = carrier.parse_carrier_hrf().unwrap().unwrap();
+        let actual = parsed.to_udi_carrier("(01)00844588003288(10)LOT1");
+
+        assert_eq!(Some("00844588003288".to_string()), actual.device_identifier);
+        assert_eq!(Some(GS1_DI_ISSUER.to_string()), actual.issuer);
+        assert_eq!(
+            Some("LOT1".to_string()),
+            parsed.production_identifiers.lot_number
+        )
+    }
+}
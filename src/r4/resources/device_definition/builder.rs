@@ -0,0 +1,327 @@
+use crate::{
+    FhirError,
+    r4::{
+        elements::{CodeableConcept, ContactPoint, Identifier, Quantity, Reference},
+        resources::{
+            DeviceDefinition, DeviceDefinitionCapability, DeviceDefinitionMaterial,
+            DeviceDefinitionSpecialization, DeviceDefinitionUdiDeviceIdentifier, DeviceName,
+            DeviceProperty, DomainResource, Organization, Resource, ResourceType,
+        },
+    },
+};
+
+pub struct DeviceDefinitionBuilder {
+    domain_resource: DomainResource,
+    identifier: Option<Vec<Identifier>>,
+    udi_device_identifier: Option<Vec<DeviceDefinitionUdiDeviceIdentifier>>,
+    manufacturer_string: Option<String>,
+    manufacturer_reference: Option<Reference<Organization>>,
+    device_name: Option<Vec<DeviceName>>,
+    model_number: Option<String>,
+    r#type: Option<CodeableConcept>,
+    specialization: Option<Vec<DeviceDefinitionSpecialization>>,
+    version: Option<Vec<String>>,
+    safety: Option<Vec<CodeableConcept>>,
+    language_code: Option<Vec<CodeableConcept>>,
+    capability: Option<Vec<DeviceDefinitionCapability>>,
+    property: Option<Vec<DeviceProperty>>,
+    owner: Option<Reference<Organization>>,
+    contact: Option<Vec<ContactPoint>>,
+    url: Option<String>,
+    online_information: Option<String>,
+    quantity: Option<Quantity>,
+    parent_device: Option<Reference<DeviceDefinition>>,
+    material: Option<Vec<DeviceDefinitionMaterial>>,
+    resource_type: String,
+}
+
+impl Default for DeviceDefinitionBuilder {
+    fn default() -> Self {
+        DeviceDefinitionBuilder {
+            domain_resource: DomainResource {
+                ..Default::default()
+            },
+            resource_type: DeviceDefinition::get_resource_type(),
+            identifier: None,
+            udi_device_identifier: None,
+            manufacturer_string: None,
+            manufacturer_reference: None,
+            device_name: None,
+            model_number: None,
+            r#type: None,
+            specialization: None,
+            version: None,
+            safety: None,
+            language_code: None,
+            capability: None,
+            property: None,
+            owner: None,
+            contact: None,
+            url: None,
+            online_information: None,
+            quantity: None,
+            parent_device: None,
+            material: None,
+        }
+    }
+}
+
+impl DeviceDefinitionBuilder {
+    pub fn new(id: impl Into<String>) -> Self {
+        DeviceDefinitionBuilder {
+            domain_resource: DomainResource {
+                resource: Resource {
+                    id: Some(id.into()),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    pub fn id(mut self, id: impl Into<String>) -> Self {
+        self.domain_resource.resource.id = Some(id.into());
+        self
+    }
+
+    pub fn identifier(mut self, identifier: Vec<Identifier>) -> Self {
+        self.identifier = Some(identifier);
+        self
+    }
+
+    pub fn add_identifier(mut self, identifier: Identifier) -> Self {
+        match &mut self.identifier {
+            Some(ident) => ident.push(identifier),
+            None => self.identifier = Some(vec![identifier]),
+        }
+        self
+    }
+
+    pub fn udi_device_identifier(
+        mut self,
+        udi_device_identifier: Vec<DeviceDefinitionUdiDeviceIdentifier>,
+    ) -> Self {
+        self.udi_device_identifier = Some(udi_device_identifier);
+        self
+    }
+
+    pub fn add_udi_device_identifier(
+        mut self,
+        udi_device_identifier: DeviceDefinitionUdiDeviceIdentifier,
+    ) -> Self {
+        match &mut self.udi_device_identifier {
+            Some(udi) => udi.push(udi_device_identifier),
+            None => self.udi_device_identifier = Some(vec![udi_device_identifier]),
+        }
+        self
+    }
+
+    pub fn manufacturer_string(mut self, manufacturer: impl Into<String>) -> Self {
+        self.manufacturer_string = Some(manufacturer.into());
+        self
+    }
+
+    pub fn manufacturer_reference(mut self, manufacturer: Reference<Organization>) -> Self {
+        self.manufacturer_reference = Some(manufacturer);
+        self
+    }
+
+    pub fn device_name(mut self, device_name: Vec<DeviceName>) -> Self {
+        self.device_name = Some(device_name);
+        self
+    }
+
+    pub fn add_device_name(mut self, device_name: DeviceName) -> Self {
+        match &mut self.device_name {
+            Some(dn) => dn.push(device_name),
+            None => self.device_name = Some(vec![device_name]),
+        }
+        self
+    }
+
+    pub fn model_number(mut self, model_number: impl Into<String>) -> Self {
+        self.model_number = Some(model_number.into());
+        self
+    }
+
+    pub fn r#type(mut self, r#type: CodeableConcept) -> Self {
+        self.r#type = Some(r#type);
+        self
+    }
+
+    pub fn specialization(mut self, specialization: Vec<DeviceDefinitionSpecialization>) -> Self {
+        self.specialization = Some(specialization);
+        self
+    }
+
+    pub fn add_specialization(mut self, specialization: DeviceDefinitionSpecialization) -> Self {
+        match &mut self.specialization {
+            Some(s) => s.push(specialization),
+            None => self.specialization = Some(vec![specialization]),
+        }
+        self
+    }
+
+    pub fn version(mut self, version: Vec<String>) -> Self {
+        self.version = Some(version);
+        self
+    }
+
+    pub fn add_version(mut self, version: impl Into<String>) -> Self {
+        match &mut self.version {
+            Some(v) => v.push(version.into()),
+            None => self.version = Some(vec![version.into()]),
+        }
+        self
+    }
+
+    pub fn safety(mut self, safety: Vec<CodeableConcept>) -> Self {
+        self.safety = Some(safety);
+        self
+    }
+
+    pub fn language_code(mut self, language_code: Vec<CodeableConcept>) -> Self {
+        self.language_code = Some(language_code);
+        self
+    }
+
+    pub fn capability(mut self, capability: Vec<DeviceDefinitionCapability>) -> Self {
+        self.capability = Some(capability);
+        self
+    }
+
+    pub fn add_capability(mut self, capability: DeviceDefinitionCapability) -> Self {
+        match &mut self.capability {
+            Some(c) => c.push(capability),
+            None => self.capability = Some(vec![capability]),
+        }
+        self
+    }
+
+    pub fn property(mut self, property: Vec<DeviceProperty>) -> Self {
+        self.property = Some(property);
+        self
+    }
+
+    pub fn add_property(mut self, property: DeviceProperty) -> Self {
+        match &mut self.property {
+            Some(p) => p.push(property),
+            None => self.property = Some(vec![property]),
+        }
+        self
+    }
+
+    pub fn owner(mut self, owner: Reference<Organization>) -> Self {
+        self.owner = Some(owner);
+        self
+    }
+
+    pub fn contact(mut self, contact: Vec<ContactPoint>) -> Self {
+        self.contact = Some(contact);
+        self
+    }
+
+    pub fn add_contact(mut self, contact: ContactPoint) -> Self {
+        match &mut self.contact {
+            Some(c) => c.push(contact),
+            None => self.contact = Some(vec![contact]),
+        }
+        self
+    }
+
+    pub fn url(mut self, url: impl Into<String>) -> Self {
+        self.url = Some(url.into());
+        self
+    }
+
+    pub fn online_information(mut self, online_information: impl Into<String>) -> Self {
+        self.online_information = Some(online_information.into());
+        self
+    }
+
+    pub fn quantity(mut self, quantity: Quantity) -> Self {
+        self.quantity = Some(quantity);
+        self
+    }
+
+    pub fn parent_device(mut self, parent_device: Reference<DeviceDefinition>) -> Self {
+        self.parent_device = Some(parent_device);
+        self
+    }
+
+    pub fn material(mut self, material: Vec<DeviceDefinitionMaterial>) -> Self {
+        self.material = Some(material);
+        self
+    }
+
+    pub fn add_material(mut self, material: DeviceDefinitionMaterial) -> Self {
+        match &mut self.material {
+            Some(m) => m.push(material),
+            None => self.material = Some(vec![material]),
+        }
+        self
+    }
+
+    pub fn build(self) -> DeviceDefinition {
+        DeviceDefinition {
+            domain_resource: self.domain_resource,
+            identifier: self.identifier,
+            udi_device_identifier: self.udi_device_identifier,
+            manufacturer_string: self.manufacturer_string,
+            manufacturer_reference: self.manufacturer_reference,
+            device_name: self.device_name,
+            model_number: self.model_number,
+            r#type: self.r#type,
+            specialization: self.specialization,
+            version: self.version,
+            safety: self.safety,
+            language_code: self.language_code,
+            capability: self.capability,
+            property: self.property,
+            owner: self.owner,
+            contact: self.contact,
+            url: self.url,
+            online_information: self.online_information,
+            quantity: self.quantity,
+            parent_device: self.parent_device,
+            material: self.material,
+            resource_type: self.resource_type,
+        }
+    }
+
+    pub fn try_build(self) -> Result<DeviceDefinition, FhirError> {
+        Ok(self.build())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_build_should_succeed() {
+        let expected = DeviceDefinition {
+            domain_resource: DomainResource {
+                resource: Resource {
+                    id: Some("device-definition-1".to_string()),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            manufacturer_string: Some("Acme".to_string()),
+            version: Some(vec!["1.0".to_string(), "1.1".to_string()]),
+            url: Some("https://acme.example.org".to_string()),
+            ..Default::default()
+        };
+
+        let actual = DeviceDefinitionBuilder::new("device-definition-1")
+            .manufacturer_string("Acme")
+            .add_version("1.0")
+            .add_version("1.1")
+            .url("https://acme.example.org")
+            .build();
+
+        assert_eq!(expected, actual)
+    }
+}
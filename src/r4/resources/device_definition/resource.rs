@@ -0,0 +1,418 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    FhirError,
+    r4::{
+        elements::{
+            BackboneElement, CodeableConcept, ContactPoint, GetCodeableConceptsMut, GetIdentifiers,
+            GetResourceReferences, Identifier, Quantity, Reference, ReferenceTypes,
+        },
+        resources::{
+            DeviceName, DeviceProperty, DomainResource, Organization, ReferenceTarget, ResourceType,
+        },
+    },
+};
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Default)]
+#[serde(rename_all(serialize = "camelCase", deserialize = "camelCase"))]
+pub struct DeviceDefinitionUdiDeviceIdentifier {
+    #[serde(flatten)]
+    pub backbone_element: BackboneElement,
+
+    pub device_identifier: String,
+
+    pub issuer: String,
+
+    pub jurisdiction: String,
+}
+
+impl ResourceType for DeviceDefinitionUdiDeviceIdentifier {
+    const TYPE: &'static str = "DeviceDefinitionUdiDeviceIdentifier";
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Default)]
+#[serde(rename_all(serialize = "camelCase", deserialize = "camelCase"))]
+pub struct DeviceDefinitionSpecialization {
+    #[serde(flatten)]
+    pub backbone_element: BackboneElement,
+
+    pub system_type: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+}
+
+impl ResourceType for DeviceDefinitionSpecialization {
+    const TYPE: &'static str = "DeviceDefinitionSpecialization";
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Default)]
+pub struct DeviceDefinitionCapability {
+    #[serde(flatten)]
+    pub backbone_element: BackboneElement,
+
+    pub r#type: CodeableConcept,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<Vec<CodeableConcept>>,
+}
+
+impl ResourceType for DeviceDefinitionCapability {
+    const TYPE: &'static str = "DeviceDefinitionCapability";
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Default)]
+#[serde(rename_all(serialize = "camelCase", deserialize = "camelCase"))]
+pub struct DeviceDefinitionMaterial {
+    #[serde(flatten)]
+    pub backbone_element: BackboneElement,
+
+    pub substance: CodeableConcept,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub alternate: Option<bool>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub allergenic_indicator: Option<bool>,
+}
+
+impl ResourceType for DeviceDefinitionMaterial {
+    const TYPE: &'static str = "DeviceDefinitionMaterial";
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+#[serde(rename_all(serialize = "camelCase", deserialize = "camelCase"))]
+pub struct DeviceDefinition {
+    #[serde(flatten)]
+    pub domain_resource: DomainResource,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub identifier: Option<Vec<Identifier>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub udi_device_identifier: Option<Vec<DeviceDefinitionUdiDeviceIdentifier>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub manufacturer_string: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub manufacturer_reference: Option<Reference<Organization>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub device_name: Option<Vec<DeviceName>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub model_number: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub r#type: Option<CodeableConcept>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub specialization: Option<Vec<DeviceDefinitionSpecialization>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<Vec<String>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub safety: Option<Vec<CodeableConcept>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub language_code: Option<Vec<CodeableConcept>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub capability: Option<Vec<DeviceDefinitionCapability>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub property: Option<Vec<DeviceProperty>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub owner: Option<Reference<Organization>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub contact: Option<Vec<ContactPoint>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub online_information: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub quantity: Option<Quantity>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parent_device: Option<Reference<DeviceDefinition>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub material: Option<Vec<DeviceDefinitionMaterial>>,
+
+    #[serde(default = "DeviceDefinition::get_resource_type")]
+    pub resource_type: String,
+}
+
+impl Default for DeviceDefinition {
+    fn default() -> Self {
+        DeviceDefinition {
+            domain_resource: DomainResource {
+                ..Default::default()
+            },
+            resource_type: Self::get_resource_type(),
+            identifier: None,
+            udi_device_identifier: None,
+            manufacturer_string: None,
+            manufacturer_reference: None,
+            device_name: None,
+            model_number: None,
+            r#type: None,
+            specialization: None,
+            version: None,
+            safety: None,
+            language_code: None,
+            capability: None,
+            property: None,
+            owner: None,
+            contact: None,
+            url: None,
+            online_information: None,
+            quantity: None,
+            parent_device: None,
+            material: None,
+        }
+    }
+}
+
+impl ResourceType for DeviceDefinition {
+    const TYPE: &'static str = "DeviceDefinition";
+}
+
+impl ReferenceTarget for DeviceDefinition {
+    fn resource_id(&self) -> Option<&str> {
+        self.domain_resource.resource.id.as_deref()
+    }
+
+    fn resource_id_mut(&mut self) -> &mut Option<String> {
+        &mut self.domain_resource.resource.id
+    }
+
+    fn reference_display(&self) -> Option<String> {
+        self.device_name
+            .iter()
+            .flatten()
+            .map(|name| name.name.clone())
+            .next()
+    }
+}
+
+impl GetIdentifiers for DeviceDefinition {
+    fn get_identifiers(&self) -> Vec<&Identifier> {
+        self.identifier.iter().flatten().collect()
+    }
+}
+
+impl DeviceDefinition {
+    pub fn from_json(data: &str) -> Result<Self, FhirError> {
+        Ok(serde_json::from_str(data)?)
+    }
+
+    pub fn to_json_value(&self) -> Result<serde_json::Value, FhirError> {
+        Ok(serde_json::to_value(self)?)
+    }
+
+    pub fn to_json_string(&self) -> Result<String, FhirError> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+}
+
+impl GetResourceReferences for DeviceDefinition {
+    fn get_references(&self) -> Vec<ReferenceTypes<'_>> {
+        let mut references = Vec::<ReferenceTypes>::new();
+
+        if let Some(manufacturer) = &self.manufacturer_reference {
+            references.push(ReferenceTypes::from(manufacturer));
+        }
+
+        if let Some(owner) = &self.owner {
+            references.push(ReferenceTypes::from(owner));
+        }
+
+        if let Some(parent) = &self.parent_device {
+            references.push(ReferenceTypes::from(parent));
+        }
+
+        for identifier in self.identifier.iter().flatten() {
+            references.extend(identifier.get_references());
+        }
+
+        references
+    }
+}
+
+impl GetCodeableConceptsMut for DeviceDefinitionCapability {
+    fn get_codeable_concepts_mut(&mut self) -> Vec<&mut CodeableConcept> {
+        let mut concepts = vec![&mut self.r#type];
+        concepts.extend(self.description.iter_mut().flatten());
+
+        concepts
+    }
+}
+
+impl GetCodeableConceptsMut for DeviceDefinitionMaterial {
+    fn get_codeable_concepts_mut(&mut self) -> Vec<&mut CodeableConcept> {
+        vec![&mut self.substance]
+    }
+}
+
+impl GetCodeableConceptsMut for DeviceDefinition {
+    fn get_codeable_concepts_mut(&mut self) -> Vec<&mut CodeableConcept> {
+        let mut concepts = self.domain_resource.get_codeable_concepts_mut();
+
+        concepts.extend(self.r#type.iter_mut());
+        concepts.extend(self.safety.iter_mut().flatten());
+        concepts.extend(self.language_code.iter_mut().flatten());
+
+        for identifier in self.identifier.iter_mut().flatten() {
+            concepts.extend(identifier.get_codeable_concepts_mut());
+        }
+
+        for capability in self.capability.iter_mut().flatten() {
+            concepts.extend(capability.get_codeable_concepts_mut());
+        }
+
+        for property in self.property.iter_mut().flatten() {
+            concepts.extend(property.get_codeable_concepts_mut());
+        }
+
+        for material in self.material.iter_mut().flatten() {
+            concepts.extend(material.get_codeable_concepts_mut());
+        }
+
+        concepts
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::json;
+
+    use crate::r4::{
+        elements::{Coding, ReferenceBuilder},
+        resources::{DeviceDefinitionBuilder, DeviceNameType, Resource},
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_from_json_should_succeed() {
+        let data = include_str!("../../../../fixtures/r4/resources/device_definition.json");
+        let expected = DeviceDefinition {
+            domain_resource: DomainResource {
+                resource: Resource {
+                    id: Some("device-definition-1".to_string()),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            identifier: Some(vec![Identifier {
+                value: Some("HM-2000".to_string()),
+                ..Default::default()
+            }]),
+            udi_device_identifier: Some(vec![DeviceDefinitionUdiDeviceIdentifier {
+                device_identifier: "00844588003288".to_string(),
+                issuer: "http://hl7.org/fhir/NamingSystem/gs1-di".to_string(),
+                jurisdiction: "http://hl7.org/fhir/NamingSystem/fda-udi".to_string(),
+                ..Default::default()
+            }]),
+            manufacturer_reference: Some(Reference::<Organization> {
+                reference: Some("Organization/acme".to_string()),
+                display: Some("Acme Devices, Inc".to_string()),
+                ..Default::default()
+            }),
+            device_name: Some(vec![DeviceName {
+                name: "FHIR Heart Monitor".to_string(),
+                r#type: DeviceNameType::ManufacturerName,
+                ..Default::default()
+            }]),
+            model_number: Some("HM-2000".to_string()),
+            r#type: Some(CodeableConcept {
+                coding: Some(vec![Coding {
+                    system: Some("http://snomed.info/sct".to_string()),
+                    code: Some("86184003".to_string()),
+                    ..Default::default()
+                }]),
+                ..Default::default()
+            }),
+            version: Some(vec!["2.1".to_string()]),
+            owner: Some(Reference::<Organization> {
+                reference: Some("Organization/org-1".to_string()),
+                ..Default::default()
+            }),
+            online_information: Some("https://acme.example.org/hm-2000".to_string()),
+            material: Some(vec![DeviceDefinitionMaterial {
+                substance: CodeableConcept {
+                    text: Some("Latex".to_string()),
+                    ..Default::default()
+                },
+                allergenic_indicator: Some(true),
+                ..Default::default()
+            }]),
+            ..Default::default()
+        };
+
+        let actual = DeviceDefinition::from_json(data).unwrap();
+
+        assert_eq!(expected, actual)
+    }
+
+    #[test]
+    fn test_to_json_value_should_succeed() {
+        let expected = json!({
+            "resourceType": "DeviceDefinition",
+            "id": "device-definition-1",
+            "manufacturerString": "Acme",
+            "modelNumber": "HM-2000",
+            "parentDevice": {"reference": "DeviceDefinition/2"}
+        });
+
+        let definition = DeviceDefinitionBuilder::new("device-definition-1")
+            .manufacturer_string("Acme")
+            .model_number("HM-2000")
+            .parent_device(
+                ReferenceBuilder::default()
+                    .with_reference("DeviceDefinition/2")
+                    .build::<DeviceDefinition>(),
+            )
+            .build();
+
+        let actual = definition.to_json_value().unwrap();
+
+        assert_eq!(expected, actual)
+    }
+
+    #[test]
+    fn test_get_references_should_succeed() {
+        let manufacturer = ReferenceBuilder::default()
+            .with_reference("Organization/1")
+            .build::<Organization>();
+        let owner = ReferenceBuilder::default()
+            .with_reference("Organization/2")
+            .build::<Organization>();
+        let parent = ReferenceBuilder::default()
+            .with_reference("DeviceDefinition/2")
+            .build::<DeviceDefinition>();
+        let expected = vec![
+            ReferenceTypes::from(&manufacturer),
+            ReferenceTypes::from(&owner),
+            ReferenceTypes::from(&parent),
+        ];
+
+        let definition = DeviceDefinitionBuilder::default()
+            .manufacturer_reference(manufacturer.clone())
+            .owner(owner.clone())
+            .parent_device(parent.clone())
+            .build();
+
+        let actual = definition.get_references();
+
+        assert_eq!(expected, actual)
+    }
+}
@@ -1,6 +1,11 @@
-use crate::r4::{
-    elements::{CodeableConcept, Coding, ContactPoint, Identifier, Period, Reference},
-    resources::{DomainResource, Endpoint, EndpointStatus, Organization, Resource, ResourceType},
+use crate::{
+    FhirError,
+    r4::{
+        elements::{CodeableConcept, Coding, ContactPoint, Identifier, Period, Reference},
+        resources::{
+            DomainResource, Endpoint, EndpointStatus, Organization, Resource, ResourceType,
+        },
+    },
 };
 
 pub struct EndpointBuilder {
@@ -8,6 +13,7 @@ pub struct EndpointBuilder {
     identifier: Option<Vec<Identifier>>,
     status: EndpointStatus,
     connection_type: Coding,
+    name: Option<String>,
     managing_organization: Option<Reference<Organization>>,
     contact: Option<Vec<ContactPoint>>,
     period: Option<Period>,
@@ -29,6 +35,7 @@ impl Default for EndpointBuilder {
             connection_type: Coding {
                 ..Default::default()
             },
+            name: None,
             managing_organization: None,
             contact: None,
             period: None,
@@ -83,6 +90,11 @@ impl EndpointBuilder {
         self
     }
 
+    pub fn name(mut self, name: impl Into<String>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
     pub fn managing_organization(mut self, managing_organization: Reference<Organization>) -> Self {
         self.managing_organization = Some(managing_organization);
         self
@@ -153,6 +165,7 @@ impl EndpointBuilder {
             identifier: self.identifier,
             status: self.status,
             connection_type: self.connection_type,
+            name: self.name,
             managing_organization: self.managing_organization,
             contact: self.contact,
             period: self.period,
@@ -163,6 +176,18 @@ impl EndpointBuilder {
             resource_type: self.resource_type,
         }
     }
+
+    pub fn try_build(self) -> Result<Endpoint, FhirError> {
+        FhirError::require(
+            "Endpoint",
+            &[
+                ("connectionType", self.connection_type.code.is_some()),
+                ("payloadType", !self.payload_type.is_empty()),
+                ("address", !self.address.trim().is_empty()),
+            ],
+        )?;
+        Ok(self.build())
+    }
 }
 
 #[cfg(test)]
@@ -210,4 +235,32 @@ mod test {
 
         assert_eq!(expected, actual)
     }
+
+    #[test]
+    fn test_try_build_should_list_missing_elements() {
+        let result = EndpointBuilder::new("endpoint-1").try_build();
+
+        match result {
+            Err(FhirError::MissingElements {
+                type_name,
+                elements,
+            }) => {
+                assert_eq!("Endpoint", type_name);
+                assert_eq!(vec!["connectionType", "payloadType", "address"], elements);
+            }
+            other => panic!("expected missing elements, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_try_build_should_succeed() {
+        let endpoint = EndpointBuilder::new("endpoint-1")
+            .connection_type(CodingBuilder::default().with_code("hl7-fhir-rest").build())
+            .add_payload_type(CodeableConceptBuilder::default().with_text("any").build())
+            .address("http://example.com/fhir")
+            .try_build()
+            .unwrap();
+
+        assert_eq!("http://example.com/fhir", endpoint.address);
+    }
 }
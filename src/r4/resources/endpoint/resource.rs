@@ -1,19 +1,19 @@
-
 use serde::{Deserialize, Serialize};
 
 use crate::{
     FhirError,
     r4::{
         elements::{
-            CodeableConcept, Coding, ContactPoint, GetResourceReferences, Identifier, Period,
-            Reference, ReferenceTypes,
+            CodeableConcept, Coding, ContactPoint, Effective, GetCodeableConceptsMut,
+            GetIdentifiers, GetResourceReferences, GetResourceReferencesMut, Identifier, Period,
+            Reference, ReferenceTypes, ReferenceTypesMut,
         },
-        resources::{DomainResource, Organization, ResourceType},
+        resources::{DomainResource, Organization, ReferenceTarget, ResourceType},
     },
 };
 
 #[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Default)]
-#[serde(rename_all(deserialize = "kebab-case", serialize = "lowercase"))]
+#[serde(rename_all = "kebab-case")]
 pub enum EndpointStatus {
     Active,
     Suspended,
@@ -37,6 +37,9 @@ pub struct Endpoint {
 
     pub connection_type: Coding,
 
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+
     #[serde(skip_serializing_if = "Option::is_none")]
     pub managing_organization: Option<Reference<Organization>>,
 
@@ -71,6 +74,7 @@ impl Default for Endpoint {
             connection_type: Coding {
                 ..Default::default()
             },
+            name: None,
             managing_organization: None,
             contact: None,
             period: None,
@@ -89,11 +93,11 @@ impl Endpoint {
     }
 
     pub fn to_json_value(&self) -> Result<serde_json::Value, FhirError> {
-        Ok(serde_json::to_value(&self)?)
+        Ok(serde_json::to_value(self)?)
     }
 
     pub fn to_json_string(&self) -> Result<String, FhirError> {
-        Ok(serde_json::to_string_pretty(&self)?)
+        Ok(serde_json::to_string_pretty(self)?)
     }
 }
 
@@ -101,17 +105,83 @@ impl ResourceType for Endpoint {
     const TYPE: &'static str = "Endpoint";
 }
 
+impl ReferenceTarget for Endpoint {
+    fn resource_id(&self) -> Option<&str> {
+        self.domain_resource.resource.id.as_deref()
+    }
+
+    fn resource_id_mut(&mut self) -> &mut Option<String> {
+        &mut self.domain_resource.resource.id
+    }
+
+    fn reference_display(&self) -> Option<String> {
+        Some(self.address.clone()).filter(|address| !address.is_empty())
+    }
+}
+
+impl GetIdentifiers for Endpoint {
+    fn get_identifiers(&self) -> Vec<&Identifier> {
+        self.identifier.iter().flatten().collect()
+    }
+}
+
+impl Effective for Endpoint {
+    fn period(&self) -> Option<&Period> {
+        self.period.as_ref()
+    }
+
+    /// Only an `active` endpoint is in use, not one that is suspended, off or
+    /// for testing.
+    fn is_active(&self) -> bool {
+        self.status == EndpointStatus::Active
+    }
+}
+
 impl GetResourceReferences for Endpoint {
-    fn get_references(&self) -> Vec<ReferenceTypes> {
+    fn get_references(&self) -> Vec<ReferenceTypes<'_>> {
         let mut references: Vec<ReferenceTypes> = Vec::new();
         if let Some(managing_organization) = &self.managing_organization {
             references.push(ReferenceTypes::from(managing_organization));
         }
 
+        for identifier in self.identifier.iter().flatten() {
+            references.extend(identifier.get_references());
+        }
+
+        references
+    }
+}
+
+impl GetResourceReferencesMut for Endpoint {
+    fn get_references_mut(&mut self) -> Vec<ReferenceTypesMut<'_>> {
+        let mut references = Vec::<ReferenceTypesMut>::new();
+
+        if let Some(managing_organization) = &mut self.managing_organization {
+            references.push(ReferenceTypesMut::from(managing_organization));
+        }
+
+        for identifier in self.identifier.iter_mut().flatten() {
+            references.extend(identifier.get_references_mut());
+        }
+
         references
     }
 }
 
+impl GetCodeableConceptsMut for Endpoint {
+    fn get_codeable_concepts_mut(&mut self) -> Vec<&mut CodeableConcept> {
+        let mut concepts = self.domain_resource.get_codeable_concepts_mut();
+
+        concepts.extend(self.payload_type.iter_mut());
+
+        for identifier in self.identifier.iter_mut().flatten() {
+            concepts.extend(identifier.get_codeable_concepts_mut());
+        }
+
+        concepts
+    }
+}
+
 #[cfg(test)]
 mod test {
     use serde_json::json;
@@ -153,6 +223,7 @@ mod test {
                 display: Some("FHIR REST".to_string()),
                 ..Default::default()
             },
+            name: Some("FHIR REST Endpoint".to_string()),
             managing_organization: Some(Reference::<Organization> {
                 reference: Some("Organization/org-1".to_string()),
                 display: Some("Burgers University Medical Center".to_string()),
@@ -1,11 +1,14 @@
-use crate::r4::{
-    elements::{
-        Attachment, AvailableTime, BackboneElement, CodeableConcept, ContactPoint, Identifier,
-        NotAvailable, Reference,
-    },
-    resources::{
-        DomainResource, Eligibility, Endpoint, HealthcareService, Location, Organization,
-        ResourceType,
+use crate::{
+    FhirError,
+    r4::{
+        elements::{
+            Attachment, AvailableTime, BackboneElement, CodeableConcept, ContactPoint, Identifier,
+            NotAvailable, Reference,
+        },
+        resources::{
+            DomainResource, Eligibility, Endpoint, HealthcareService, Location, Organization,
+            ResourceType,
+        },
     },
 };
 
@@ -45,6 +48,10 @@ impl EligibilityBuilder {
             comment: self.comment,
         }
     }
+
+    pub fn try_build(self) -> Result<Eligibility, FhirError> {
+        Ok(self.build())
+    }
 }
 
 #[derive(Default)]
@@ -55,6 +62,7 @@ pub struct HealthcareServiceBuilder {
     provided_by: Option<Reference<Organization>>,
     category: Option<Vec<CodeableConcept>>,
     r#type: Option<Vec<CodeableConcept>>,
+    speciality: Option<Vec<CodeableConcept>>,
     location: Option<Vec<Reference<Location>>>,
     name: Option<String>,
     comment: Option<String>,
@@ -136,6 +144,19 @@ impl HealthcareServiceBuilder {
         self
     }
 
+    pub fn with_speciality(mut self, specialities: Vec<CodeableConcept>) -> Self {
+        self.speciality = Some(specialities);
+        self
+    }
+
+    pub fn add_speciality(mut self, speciality: CodeableConcept) -> Self {
+        match &mut self.speciality {
+            Some(v) => v.push(speciality),
+            None => self.speciality = Some(vec![speciality]),
+        }
+        self
+    }
+
     pub fn with_location(mut self, locations: Vec<Reference<Location>>) -> Self {
         self.location = Some(locations);
         self
@@ -330,6 +351,7 @@ impl HealthcareServiceBuilder {
             provided_by: self.provided_by,
             category: self.category,
             r#type: self.r#type,
+            speciality: self.speciality,
             location: self.location,
             name: self.name,
             comment: self.comment,
@@ -351,6 +373,10 @@ impl HealthcareServiceBuilder {
             resource_type: HealthcareService::get_resource_type(),
         }
     }
+
+    pub fn try_build(self) -> Result<HealthcareService, FhirError> {
+        Ok(self.build())
+    }
 }
 
 #[cfg(test)]
@@ -5,9 +5,13 @@ use crate::{
     r4::{
         elements::{
             Attachment, AvailableTime, BackboneElement, CodeableConcept, ContactPoint,
-            GetResourceReferences, Identifier, NotAvailable, Reference, ReferenceTypes,
+            GetCodeableConceptsMut, GetIdentifiers, GetResourceReferences,
+            GetResourceReferencesMut, Identifier, NotAvailable, Reference, ReferenceTypes,
+            ReferenceTypesMut,
+        },
+        resources::{
+            DomainResource, Endpoint, Location, Organization, ReferenceTarget, ResourceType,
         },
-        resources::{DomainResource, Endpoint, Location, Organization, ResourceType},
     },
 };
 
@@ -48,6 +52,10 @@ pub struct HealthcareService {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub r#type: Option<Vec<CodeableConcept>>,
 
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "specialty")]
+    pub speciality: Option<Vec<CodeableConcept>>,
+
     #[serde(skip_serializing_if = "Option::is_none")]
     pub location: Option<Vec<Reference<Location>>>,
 
@@ -120,6 +128,7 @@ impl Default for HealthcareService {
             category: None,
             comment: None,
             r#type: None,
+            speciality: None,
             telecom: None,
             location: None,
             not_available: None,
@@ -144,22 +153,42 @@ impl ResourceType for HealthcareService {
     const TYPE: &'static str = "HealthcareService";
 }
 
+impl ReferenceTarget for HealthcareService {
+    fn resource_id(&self) -> Option<&str> {
+        self.domain_resource.resource.id.as_deref()
+    }
+
+    fn resource_id_mut(&mut self) -> &mut Option<String> {
+        &mut self.domain_resource.resource.id
+    }
+
+    fn reference_display(&self) -> Option<String> {
+        self.name.clone()
+    }
+}
+
+impl GetIdentifiers for HealthcareService {
+    fn get_identifiers(&self) -> Vec<&Identifier> {
+        self.identifier.iter().flatten().collect()
+    }
+}
+
 impl HealthcareService {
     pub fn from_json(data: &str) -> Result<Self, FhirError> {
         Ok(serde_json::from_str(data)?)
     }
 
     pub fn to_json_value(&self) -> Result<serde_json::Value, FhirError> {
-        Ok(serde_json::to_value(&self)?)
+        Ok(serde_json::to_value(self)?)
     }
 
     pub fn to_json_string(&self) -> Result<String, FhirError> {
-        Ok(serde_json::to_string_pretty(&self)?)
+        Ok(serde_json::to_string_pretty(self)?)
     }
 }
 
 impl GetResourceReferences for HealthcareService {
-    fn get_references(&self) -> Vec<ReferenceTypes> {
+    fn get_references(&self) -> Vec<ReferenceTypes<'_>> {
         let mut references = Vec::<ReferenceTypes>::new();
 
         if let Some(pb) = &self.provided_by {
@@ -184,10 +213,77 @@ impl GetResourceReferences for HealthcareService {
             }
         }
 
+        for identifier in self.identifier.iter().flatten() {
+            references.extend(identifier.get_references());
+        }
+
         references
     }
 }
 
+impl GetResourceReferencesMut for HealthcareService {
+    fn get_references_mut(&mut self) -> Vec<ReferenceTypesMut<'_>> {
+        let mut references = Vec::<ReferenceTypesMut>::new();
+
+        if let Some(provided_by) = &mut self.provided_by {
+            references.push(ReferenceTypesMut::from(provided_by));
+        }
+
+        for reference in self.location.iter_mut().flatten() {
+            references.push(ReferenceTypesMut::from(reference));
+        }
+
+        for reference in self.coverage_area.iter_mut().flatten() {
+            references.push(ReferenceTypesMut::from(reference));
+        }
+
+        for reference in self.endpoint.iter_mut().flatten() {
+            references.push(ReferenceTypesMut::from(reference));
+        }
+
+        for identifier in self.identifier.iter_mut().flatten() {
+            references.extend(identifier.get_references_mut());
+        }
+
+        references
+    }
+}
+
+impl GetCodeableConceptsMut for Eligibility {
+    fn get_codeable_concepts_mut(&mut self) -> Vec<&mut CodeableConcept> {
+        let mut concepts = Vec::<&mut CodeableConcept>::new();
+
+        concepts.extend(self.code.iter_mut());
+
+        concepts
+    }
+}
+
+impl GetCodeableConceptsMut for HealthcareService {
+    fn get_codeable_concepts_mut(&mut self) -> Vec<&mut CodeableConcept> {
+        let mut concepts = self.domain_resource.get_codeable_concepts_mut();
+
+        concepts.extend(self.category.iter_mut().flatten());
+        concepts.extend(self.r#type.iter_mut().flatten());
+        concepts.extend(self.speciality.iter_mut().flatten());
+        concepts.extend(self.service_provision_code.iter_mut().flatten());
+        concepts.extend(self.program.iter_mut().flatten());
+        concepts.extend(self.characteristic.iter_mut().flatten());
+        concepts.extend(self.communication.iter_mut().flatten());
+        concepts.extend(self.referral_method.iter_mut().flatten());
+
+        for identifier in self.identifier.iter_mut().flatten() {
+            concepts.extend(identifier.get_codeable_concepts_mut());
+        }
+
+        for eligibility in self.eligibility.iter_mut().flatten() {
+            concepts.extend(eligibility.get_codeable_concepts_mut());
+        }
+
+        concepts
+    }
+}
+
 #[cfg(test)]
 mod test {
     use std::vec;
@@ -246,6 +342,15 @@ mod test {
                 text: Some("Emergency Medicine".to_string()),
                 ..Default::default()
             }]),
+            speciality: Some(vec![CodeableConcept {
+                coding: Some(vec![Coding {
+                    system: Some("http://snomed.info/sct".to_string()),
+                    code: Some("773568002".to_string()),
+                    display: Some("Emergency medicine".to_string()),
+                    ..Default::default()
+                }]),
+                ..Default::default()
+            }]),
             location: Some(vec![Reference::<Location> {
                 reference: Some("Location/location-1".to_string()),
                 display: Some("Emergency Department".to_string()),
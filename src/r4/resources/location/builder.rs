@@ -1,19 +1,22 @@
-use crate::r4::{
-    elements::{
-        Address, BackboneElement, CodeableConcept, Coding, ContactPoint, DaysOfWeek, Identifier,
-        Reference,
-    },
-    resources::{
-        DomainResource, Endpoint, HoursOfOperation, Location, LocationPosition, LocationStatus,
-        Organization, ResourceType,
+use crate::{
+    FhirError,
+    r4::{
+        elements::{
+            Address, BackboneElement, CodeableConcept, Coding, ContactPoint, DaysOfWeek, Extension,
+            Identifier, Reference,
+        },
+        resources::{
+            DomainResource, Endpoint, HoursOfOperation, Location, LocationPosition, LocationStatus,
+            Organization, ResourceType,
+        },
     },
 };
 
 #[derive(Default)]
 pub struct LocationPositionBuilder {
     backbone_element: BackboneElement,
-    longitude: f32,
-    latitude: f32,
+    longitude: Option<f32>,
+    latitude: Option<f32>,
     altitude: Option<f32>,
 }
 
@@ -30,12 +33,12 @@ impl LocationPositionBuilder {
     }
 
     pub fn longitude(mut self, longitude: f32) -> Self {
-        self.longitude = longitude;
+        self.longitude = Some(longitude);
         self
     }
 
     pub fn latitude(mut self, latitude: f32) -> Self {
-        self.latitude = latitude;
+        self.latitude = Some(latitude);
         self
     }
 
@@ -47,11 +50,22 @@ impl LocationPositionBuilder {
     pub fn build(self) -> LocationPosition {
         LocationPosition {
             backbone_element: self.backbone_element,
-            longitude: self.longitude,
-            latitude: self.latitude,
+            longitude: self.longitude.unwrap_or_default(),
+            latitude: self.latitude.unwrap_or_default(),
             altitude: self.altitude,
         }
     }
+
+    pub fn try_build(self) -> Result<LocationPosition, FhirError> {
+        FhirError::require(
+            "LocationPosition",
+            &[
+                ("longitude", self.longitude.is_some()),
+                ("latitude", self.latitude.is_some()),
+            ],
+        )?;
+        Ok(self.build())
+    }
 }
 
 #[derive(Default)]
@@ -81,8 +95,8 @@ impl HoursOfOperationBuilder {
 
     pub fn add_day_of_week(mut self, day: DaysOfWeek) -> Self {
         match &mut self.days_of_week {
-            Some(dow) => dow.push(day.into()),
-            None => self.days_of_week = Some(vec![day.into()]),
+            Some(dow) => dow.push(day),
+            None => self.days_of_week = Some(vec![day]),
         }
         self
     }
@@ -111,6 +125,10 @@ impl HoursOfOperationBuilder {
             closing_time: self.closing_time,
         }
     }
+
+    pub fn try_build(self) -> Result<HoursOfOperation, FhirError> {
+        Ok(self.build())
+    }
 }
 
 pub struct LocationBuilder {
@@ -289,6 +307,14 @@ impl LocationBuilder {
         self
     }
 
+    pub fn add_extension(mut self, extension: Extension) -> Self {
+        self.domain_resource
+            .extension
+            .get_or_insert_with(Vec::new)
+            .push(extension);
+        self
+    }
+
     pub fn endpoint(mut self, endpoint: Reference<Endpoint>) -> Self {
         self.endpoint = Some(endpoint);
         self
@@ -317,6 +343,10 @@ impl LocationBuilder {
             resource_type: self.resource_type,
         }
     }
+
+    pub fn try_build(self) -> Result<Location, FhirError> {
+        Ok(self.build())
+    }
 }
 
 #[cfg(test)]
@@ -345,11 +375,13 @@ mod test {
     }
     #[test]
     fn test_build_hours_of_operation_should_succeed() {
-        let mut expected = HoursOfOperation::default();
-        expected.days_of_week = Some(vec![DaysOfWeek::Mon, DaysOfWeek::Thu]);
-        expected.all_day = Some(true);
-        expected.operation_time = Some("all day".to_string());
-        expected.closing_time = Some("midnight".to_string());
+        let expected = HoursOfOperation {
+            days_of_week: Some(vec![DaysOfWeek::Mon, DaysOfWeek::Thu]),
+            all_day: Some(true),
+            operation_time: Some("all day".to_string()),
+            closing_time: Some("midnight".to_string()),
+            ..Default::default()
+        };
 
         let actual = HoursOfOperationBuilder::default()
             .add_day_of_week(DaysOfWeek::Mon)
@@ -398,4 +430,24 @@ mod test {
 
         assert_eq!(expected, actual)
     }
+
+    #[test]
+    fn test_try_build_location_position_should_require_coordinates() {
+        let error = LocationPositionBuilder::new("pos-1")
+            .latitude(52.37)
+            .try_build()
+            .unwrap_err();
+
+        assert_eq!(
+            "LocationPosition is missing mandatory elements: longitude",
+            error.to_string()
+        );
+        assert!(
+            LocationPositionBuilder::new("pos-1")
+                .latitude(0.0)
+                .longitude(0.0)
+                .try_build()
+                .is_ok()
+        );
+    }
 }
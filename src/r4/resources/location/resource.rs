@@ -5,9 +5,11 @@ use crate::{
     r4::{
         elements::{
             Address, BackboneElement, CodeableConcept, Coding, ContactPoint, DaysOfWeek,
-            GetResourceReferences, Identifier, Reference, ReferenceTypes,
+            GetCodeableConceptsMut, GetIdentifiers, GetResourceReferences,
+            GetResourceReferencesMut, Identifier, Reference, ReferenceTypes, ReferenceTypesMut,
+            serialize_decimal, serialize_optional_decimal,
         },
-        resources::{DomainResource, Endpoint, Organization, ResourceType},
+        resources::{DomainResource, Endpoint, Organization, ReferenceTarget, ResourceType},
     },
 };
 
@@ -15,10 +17,15 @@ use crate::{
 pub struct LocationPosition {
     #[serde(flatten)]
     pub backbone_element: BackboneElement,
+
+    #[serde(serialize_with = "serialize_decimal")]
     pub longitude: f32,
+
+    #[serde(serialize_with = "serialize_decimal")]
     pub latitude: f32,
 
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(serialize_with = "serialize_optional_decimal")]
     pub altitude: Option<f32>,
 }
 
@@ -122,6 +129,20 @@ impl ResourceType for Location {
     const TYPE: &'static str = "Location";
 }
 
+impl ReferenceTarget for Location {
+    fn resource_id(&self) -> Option<&str> {
+        self.domain_resource.resource.id.as_deref()
+    }
+
+    fn resource_id_mut(&mut self) -> &mut Option<String> {
+        &mut self.domain_resource.resource.id
+    }
+
+    fn reference_display(&self) -> Option<String> {
+        self.name.clone()
+    }
+}
+
 impl Default for Location {
     fn default() -> Self {
         Location {
@@ -156,16 +177,22 @@ impl Location {
     }
 
     pub fn to_json_value(&self) -> Result<serde_json::Value, FhirError> {
-        Ok(serde_json::to_value(&self)?)
+        Ok(serde_json::to_value(self)?)
     }
 
     pub fn to_json_string(&self) -> Result<String, FhirError> {
-        Ok(serde_json::to_string_pretty(&self)?)
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+}
+
+impl GetIdentifiers for Location {
+    fn get_identifiers(&self) -> Vec<&Identifier> {
+        self.identifier.iter().flatten().collect()
     }
 }
 
 impl GetResourceReferences for Location {
-    fn get_references(&self) -> Vec<ReferenceTypes> {
+    fn get_references(&self) -> Vec<ReferenceTypes<'_>> {
         let mut references = Vec::<ReferenceTypes>::new();
 
         if let Some(man_org) = &self.managing_organization {
@@ -180,10 +207,57 @@ impl GetResourceReferences for Location {
             references.push(ReferenceTypes::from(endpoint));
         }
 
+        for identifier in self.identifier.iter().flatten() {
+            references.extend(identifier.get_references());
+        }
+
         references
     }
 }
 
+impl GetResourceReferencesMut for Location {
+    fn get_references_mut(&mut self) -> Vec<ReferenceTypesMut<'_>> {
+        let mut references = Vec::<ReferenceTypesMut>::new();
+
+        if let Some(managing_organization) = &mut self.managing_organization {
+            references.push(ReferenceTypesMut::from(managing_organization));
+        }
+
+        if let Some(part_of) = &mut self.part_of {
+            references.push(ReferenceTypesMut::from(part_of));
+        }
+
+        if let Some(endpoint) = &mut self.endpoint {
+            references.push(ReferenceTypesMut::from(endpoint));
+        }
+
+        for identifier in self.identifier.iter_mut().flatten() {
+            references.extend(identifier.get_references_mut());
+        }
+
+        references
+    }
+}
+
+impl GetCodeableConceptsMut for Location {
+    fn get_codeable_concepts_mut(&mut self) -> Vec<&mut CodeableConcept> {
+        let mut concepts = self.domain_resource.get_codeable_concepts_mut();
+
+        concepts.extend(self.r#type.iter_mut().flatten());
+        concepts.extend(self.physical_type.iter_mut());
+
+        for identifier in self.identifier.iter_mut().flatten() {
+            concepts.extend(identifier.get_codeable_concepts_mut());
+        }
+
+        for address in self.address.iter_mut() {
+            concepts.extend(address.get_codeable_concepts_mut());
+        }
+
+        concepts
+    }
+}
+
 #[cfg(test)]
 mod test {
 
@@ -347,4 +421,14 @@ mod test {
 
         assert_eq!(expected, actual);
     }
+
+    #[test]
+    fn test_to_json_should_round_trip_fixture() {
+        let data = include_str!("../../../../fixtures/r4/resources/location.json");
+        let expected: serde_json::Value = serde_json::from_str(data).unwrap();
+
+        let actual = serde_json::to_value(Location::from_json(data).unwrap()).unwrap();
+
+        assert_eq!(expected, actual)
+    }
 }
@@ -0,0 +1,197 @@
+use crate::{
+    FhirError,
+    r4::{
+        elements::CodeableConcept,
+        resources::{
+            DomainResource, NamingSystem, NamingSystemType, NamingSystemUniqueId,
+            PublicationStatus, Resource, ResourceType,
+        },
+    },
+};
+
+pub struct NamingSystemBuilder {
+    domain_resource: DomainResource,
+    name: String,
+    status: PublicationStatus,
+    kind: NamingSystemType,
+    date: String, // to be resolved later
+    publisher: Option<String>,
+    responsible: Option<String>,
+    r#type: Option<CodeableConcept>,
+    description: Option<String>,
+    jurisdiction: Option<Vec<CodeableConcept>>,
+    usage: Option<String>,
+    unique_id: Vec<NamingSystemUniqueId>,
+    resource_type: String,
+}
+
+impl Default for NamingSystemBuilder {
+    fn default() -> Self {
+        NamingSystemBuilder {
+            domain_resource: DomainResource {
+                ..Default::default()
+            },
+            resource_type: NamingSystem::get_resource_type(),
+            name: String::new(),
+            status: PublicationStatus::Draft,
+            kind: NamingSystemType::Identifier,
+            date: String::new(),
+            publisher: None,
+            responsible: None,
+            r#type: None,
+            description: None,
+            jurisdiction: None,
+            usage: None,
+            unique_id: Vec::new(),
+        }
+    }
+}
+
+impl NamingSystemBuilder {
+    pub fn new(id: impl Into<String>) -> Self {
+        NamingSystemBuilder {
+            domain_resource: DomainResource {
+                resource: Resource {
+                    id: Some(id.into()),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    pub fn id(mut self, id: impl Into<String>) -> Self {
+        self.domain_resource.resource.id = Some(id.into());
+        self
+    }
+
+    pub fn name(mut self, name: impl Into<String>) -> Self {
+        self.name = name.into();
+        self
+    }
+
+    pub fn status(mut self, status: PublicationStatus) -> Self {
+        self.status = status;
+        self
+    }
+
+    pub fn kind(mut self, kind: NamingSystemType) -> Self {
+        self.kind = kind;
+        self
+    }
+
+    pub fn date(mut self, date: impl Into<String>) -> Self {
+        self.date = date.into();
+        self
+    }
+
+    pub fn publisher(mut self, publisher: impl Into<String>) -> Self {
+        self.publisher = Some(publisher.into());
+        self
+    }
+
+    pub fn responsible(mut self, responsible: impl Into<String>) -> Self {
+        self.responsible = Some(responsible.into());
+        self
+    }
+
+    pub fn r#type(mut self, r#type: CodeableConcept) -> Self {
+        self.r#type = Some(r#type);
+        self
+    }
+
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    pub fn jurisdiction(mut self, jurisdiction: Vec<CodeableConcept>) -> Self {
+        self.jurisdiction = Some(jurisdiction);
+        self
+    }
+
+    pub fn usage(mut self, usage: impl Into<String>) -> Self {
+        self.usage = Some(usage.into());
+        self
+    }
+
+    pub fn unique_id(mut self, unique_id: Vec<NamingSystemUniqueId>) -> Self {
+        self.unique_id = unique_id;
+        self
+    }
+
+    pub fn add_unique_id(mut self, unique_id: NamingSystemUniqueId) -> Self {
+        self.unique_id.push(unique_id);
+        self
+    }
+
+    pub fn build(self) -> NamingSystem {
+        NamingSystem {
+            domain_resource: self.domain_resource,
+            name: self.name,
+            status: self.status,
+            kind: self.kind,
+            date: self.date,
+            publisher: self.publisher,
+            responsible: self.responsible,
+            r#type: self.r#type,
+            description: self.description,
+            jurisdiction: self.jurisdiction,
+            usage: self.usage,
+            unique_id: self.unique_id,
+            resource_type: self.resource_type,
+        }
+    }
+
+    pub fn try_build(self) -> Result<NamingSystem, FhirError> {
+        FhirError::require(
+            "NamingSystem",
+            &[
+                ("name", !self.name.is_empty()),
+                ("date", !self.date.is_empty()),
+                ("uniqueId", !self.unique_id.is_empty()),
+            ],
+        )?;
+        Ok(self.build())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::r4::resources::NamingSystemIdentifierType;
+
+    use super::*;
+
+    #[test]
+    fn test_build_naming_system_should_succeed() {
+        let unique_id = NamingSystemUniqueId {
+            r#type: NamingSystemIdentifierType::Oid,
+            value: "2.16.528.1.1007.3.1".to_string(),
+            ..Default::default()
+        };
+        let expected = NamingSystem {
+            domain_resource: DomainResource {
+                resource: Resource {
+                    id: Some("uzi".to_string()),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            name: "UZI".to_string(),
+            status: PublicationStatus::Active,
+            date: "2025-01-01".to_string(),
+            unique_id: vec![unique_id.clone()],
+            ..Default::default()
+        };
+
+        let actual = NamingSystemBuilder::new("uzi")
+            .name("UZI")
+            .status(PublicationStatus::Active)
+            .date("2025-01-01")
+            .add_unique_id(unique_id)
+            .build();
+
+        assert_eq!(expected, actual)
+    }
+}
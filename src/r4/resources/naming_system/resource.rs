@@ -0,0 +1,283 @@
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    FhirError,
+    r4::{
+        elements::{
+            BackboneElement, CodeableConcept, GetCodeableConceptsMut, GetIdentifiers,
+            GetResourceReferences, Identifier, Period, ReferenceTypes,
+        },
+        resources::{DomainResource, PublicationStatus, ReferenceTarget, ResourceType},
+    },
+};
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum NamingSystemType {
+    CodeSystem,
+    #[default]
+    Identifier,
+    Root,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum NamingSystemIdentifierType {
+    Oid,
+    Uuid,
+    #[default]
+    Uri,
+    Other,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Default)]
+pub struct NamingSystemUniqueId {
+    #[serde(flatten)]
+    pub backbone_element: BackboneElement,
+
+    pub r#type: NamingSystemIdentifierType,
+
+    pub value: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub preferred: Option<bool>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub comment: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub period: Option<Period>,
+}
+
+impl ResourceType for NamingSystemUniqueId {
+    const TYPE: &'static str = "NamingSystemUniqueId";
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+#[serde(rename_all(serialize = "camelCase", deserialize = "camelCase"))]
+pub struct NamingSystem {
+    #[serde(flatten)]
+    pub domain_resource: DomainResource,
+
+    pub name: String,
+
+    pub status: PublicationStatus,
+
+    pub kind: NamingSystemType,
+
+    pub date: String, // to be resolved later
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub publisher: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub responsible: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub r#type: Option<CodeableConcept>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub jurisdiction: Option<Vec<CodeableConcept>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub usage: Option<String>,
+
+    pub unique_id: Vec<NamingSystemUniqueId>,
+
+    #[serde(default = "NamingSystem::get_resource_type")]
+    pub resource_type: String,
+}
+
+impl Default for NamingSystem {
+    fn default() -> Self {
+        NamingSystem {
+            domain_resource: DomainResource {
+                ..Default::default()
+            },
+            resource_type: Self::get_resource_type(),
+            name: String::new(),
+            status: PublicationStatus::Draft,
+            kind: NamingSystemType::Identifier,
+            date: String::new(),
+            publisher: None,
+            responsible: None,
+            r#type: None,
+            description: None,
+            jurisdiction: None,
+            usage: None,
+            unique_id: Vec::new(),
+        }
+    }
+}
+
+impl ResourceType for NamingSystem {
+    const TYPE: &'static str = "NamingSystem";
+}
+
+impl ReferenceTarget for NamingSystem {
+    fn resource_id(&self) -> Option<&str> {
+        self.domain_resource.resource.id.as_deref()
+    }
+
+    fn resource_id_mut(&mut self) -> &mut Option<String> {
+        &mut self.domain_resource.resource.id
+    }
+
+    fn reference_display(&self) -> Option<String> {
+        Some(self.name.clone())
+    }
+}
+
+impl GetIdentifiers for NamingSystem {
+    fn get_identifiers(&self) -> Vec<&Identifier> {
+        Vec::new()
+    }
+}
+
+impl GetResourceReferences for NamingSystem {
+    fn get_references(&self) -> Vec<ReferenceTypes<'_>> {
+        Vec::new()
+    }
+}
+
+impl NamingSystem {
+    pub fn from_json(data: &str) -> Result<Self, FhirError> {
+        Ok(serde_json::from_str(data)?)
+    }
+
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, FhirError> {
+        Self::from_json(&std::fs::read_to_string(path)?)
+    }
+
+    pub fn to_json_value(&self) -> Result<serde_json::Value, FhirError> {
+        Ok(serde_json::to_value(self)?)
+    }
+
+    pub fn to_json_string(&self) -> Result<String, FhirError> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    /// The preferred unique id of the given type, falling back to the first
+    /// one of that type.
+    pub fn preferred_unique_id(&self, r#type: NamingSystemIdentifierType) -> Option<&str> {
+        self.unique_id
+            .iter()
+            .find(|u| u.r#type == r#type && u.preferred == Some(true))
+            .or_else(|| self.unique_id.iter().find(|u| u.r#type == r#type))
+            .map(|u| u.value.as_str())
+    }
+}
+
+impl GetCodeableConceptsMut for NamingSystem {
+    fn get_codeable_concepts_mut(&mut self) -> Vec<&mut CodeableConcept> {
+        let mut concepts = self.domain_resource.get_codeable_concepts_mut();
+
+        concepts.extend(self.r#type.iter_mut());
+        concepts.extend(self.jurisdiction.iter_mut().flatten());
+
+        concepts
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::json;
+
+    use crate::r4::resources::{NamingSystemBuilder, Resource};
+
+    use super::*;
+
+    #[test]
+    fn test_from_json_should_succeed() {
+        let data = include_str!("../../../../fixtures/r4/resources/naming_system.json");
+        let expected = NamingSystem {
+            domain_resource: DomainResource {
+                resource: Resource {
+                    id: Some("uzovi".to_string()),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            name: "UZOVI".to_string(),
+            status: PublicationStatus::Active,
+            kind: NamingSystemType::Identifier,
+            date: "2024-01-01".to_string(),
+            publisher: Some("Vektis".to_string()),
+            description: Some("Dutch health insurer identification".to_string()),
+            unique_id: vec![
+                NamingSystemUniqueId {
+                    r#type: NamingSystemIdentifierType::Oid,
+                    value: "2.16.840.1.113883.2.4.6.4".to_string(),
+                    ..Default::default()
+                },
+                NamingSystemUniqueId {
+                    r#type: NamingSystemIdentifierType::Uri,
+                    value: "http://fhir.nl/fhir/NamingSystem/uzovi".to_string(),
+                    preferred: Some(true),
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+
+        let actual = NamingSystem::from_json(data).unwrap();
+
+        assert_eq!(expected, actual)
+    }
+
+    #[test]
+    fn test_to_json_value_should_succeed() {
+        let expected = json!({
+            "resourceType": "NamingSystem",
+            "name": "Example",
+            "status": "active",
+            "kind": "identifier",
+            "date": "2025-01-01",
+            "uniqueId": [
+                {"type": "uri", "value": "http://example.org/ids", "preferred": true}
+            ]
+        });
+
+        let naming_system = NamingSystemBuilder::default()
+            .name("Example")
+            .status(PublicationStatus::Active)
+            .date("2025-01-01")
+            .add_unique_id(NamingSystemUniqueId {
+                r#type: NamingSystemIdentifierType::Uri,
+                value: "http://example.org/ids".to_string(),
+                preferred: Some(true),
+                ..Default::default()
+            })
+            .build();
+
+        let actual = naming_system.to_json_value().unwrap();
+
+        assert_eq!(expected, actual)
+    }
+
+    #[test]
+    fn test_preferred_unique_id_should_succeed() {
+        let naming_system = NamingSystem::from_json(include_str!(
+            "../../../../fixtures/r4/resources/naming_system.json"
+        ))
+        .unwrap();
+
+        assert_eq!(
+            Some("http://fhir.nl/fhir/NamingSystem/uzovi"),
+            naming_system.preferred_unique_id(NamingSystemIdentifierType::Uri)
+        );
+        assert_eq!(
+            Some("2.16.840.1.113883.2.4.6.4"),
+            naming_system.preferred_unique_id(NamingSystemIdentifierType::Oid)
+        );
+        assert_eq!(
+            None,
+            naming_system.preferred_unique_id(NamingSystemIdentifierType::Uuid)
+        )
+    }
+}
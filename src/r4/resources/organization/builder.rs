@@ -1,11 +1,15 @@
 use std::vec;
 
-use crate::r4::{
-    elements::{
-        Address, BackboneElement, CodeableConcept, ContactPoint, HumanName, Identifier, Reference,
-    },
-    resources::{
-        DomainResource, Endpoint, Organization, OrganizationContact, Resource, ResourceType,
+use crate::{
+    FhirError,
+    r4::{
+        elements::{
+            Address, BackboneElement, CodeableConcept, ContactPoint, HumanName, Identifier,
+            Reference,
+        },
+        resources::{
+            DomainResource, Endpoint, Organization, OrganizationContact, Resource, ResourceType,
+        },
     },
 };
 
@@ -66,6 +70,10 @@ impl OrganizationContactBuilder {
             address: self.address,
         }
     }
+
+    pub fn try_build(self) -> Result<OrganizationContact, FhirError> {
+        Ok(self.build())
+    }
 }
 
 pub struct OrganizationBuilder {
@@ -240,6 +248,10 @@ impl OrganizationBuilder {
             resource_type: self.resource_type,
         }
     }
+
+    pub fn try_build(self) -> Result<Organization, FhirError> {
+        Ok(self.build())
+    }
 }
 
 #[cfg(test)]
@@ -4,10 +4,11 @@ use crate::{
     FhirError,
     r4::{
         elements::{
-            Address, BackboneElement, CodeableConcept, ContactPoint, GetResourceReferences,
-            HumanName, Identifier, Reference, ReferenceTypes,
+            Address, BackboneElement, CodeableConcept, ContactPoint, GetCodeableConceptsMut,
+            GetIdentifiers, GetResourceReferences, GetResourceReferencesMut, HumanName, Identifier,
+            Reference, ReferenceTypes, ReferenceTypesMut,
         },
-        resources::{DomainResource, Endpoint, ResourceType},
+        resources::{DomainResource, Endpoint, ReferenceTarget, ResourceType},
     },
 };
 
@@ -100,11 +101,11 @@ impl Organization {
     }
 
     pub fn to_json_value(&self) -> Result<serde_json::Value, FhirError> {
-        Ok(serde_json::to_value(&self)?)
+        Ok(serde_json::to_value(self)?)
     }
 
     pub fn to_json_string(&self) -> Result<String, FhirError> {
-        Ok(serde_json::to_string_pretty(&self)?)
+        Ok(serde_json::to_string_pretty(self)?)
     }
 }
 
@@ -112,22 +113,102 @@ impl ResourceType for Organization {
     const TYPE: &'static str = "Organization";
 }
 
+impl ReferenceTarget for Organization {
+    fn resource_id(&self) -> Option<&str> {
+        self.domain_resource.resource.id.as_deref()
+    }
+
+    fn resource_id_mut(&mut self) -> &mut Option<String> {
+        &mut self.domain_resource.resource.id
+    }
+
+    fn reference_display(&self) -> Option<String> {
+        self.name.clone()
+    }
+}
+
+impl GetIdentifiers for Organization {
+    fn get_identifiers(&self) -> Vec<&Identifier> {
+        self.identifier.iter().flatten().collect()
+    }
+}
+
 impl GetResourceReferences for Organization {
-    fn get_references(&self) -> Vec<ReferenceTypes> {
+    fn get_references(&self) -> Vec<ReferenceTypes<'_>> {
         let mut references: Vec<ReferenceTypes> = Vec::new();
 
         if let Some(eps) = &self.endpoint {
-            references = eps.into_iter().map(|e| ReferenceTypes::from(e)).collect();
+            references = eps.iter().map(ReferenceTypes::from).collect();
         }
 
         if let Some(part_of) = &self.part_of {
             references.push(ReferenceTypes::from(part_of));
         }
 
+        for identifier in self.identifier.iter().flatten() {
+            references.extend(identifier.get_references());
+        }
+
         references
     }
 }
 
+impl GetResourceReferencesMut for Organization {
+    fn get_references_mut(&mut self) -> Vec<ReferenceTypesMut<'_>> {
+        let mut references = Vec::<ReferenceTypesMut>::new();
+
+        for reference in self.endpoint.iter_mut().flatten() {
+            references.push(ReferenceTypesMut::from(reference));
+        }
+
+        if let Some(part_of) = &mut self.part_of {
+            references.push(ReferenceTypesMut::from(part_of));
+        }
+
+        for identifier in self.identifier.iter_mut().flatten() {
+            references.extend(identifier.get_references_mut());
+        }
+
+        references
+    }
+}
+
+impl GetCodeableConceptsMut for OrganizationContact {
+    fn get_codeable_concepts_mut(&mut self) -> Vec<&mut CodeableConcept> {
+        let mut concepts = Vec::<&mut CodeableConcept>::new();
+
+        concepts.extend(self.purpose.iter_mut());
+
+        for address in self.address.iter_mut() {
+            concepts.extend(address.get_codeable_concepts_mut());
+        }
+
+        concepts
+    }
+}
+
+impl GetCodeableConceptsMut for Organization {
+    fn get_codeable_concepts_mut(&mut self) -> Vec<&mut CodeableConcept> {
+        let mut concepts = self.domain_resource.get_codeable_concepts_mut();
+
+        concepts.extend(self.r#type.iter_mut().flatten());
+
+        for identifier in self.identifier.iter_mut().flatten() {
+            concepts.extend(identifier.get_codeable_concepts_mut());
+        }
+
+        for address in self.address.iter_mut().flatten() {
+            concepts.extend(address.get_codeable_concepts_mut());
+        }
+
+        for contact in self.contact.iter_mut().flatten() {
+            concepts.extend(contact.get_codeable_concepts_mut());
+        }
+
+        concepts
+    }
+}
+
 #[cfg(test)]
 mod test {
     use serde_json::json;
@@ -354,4 +435,14 @@ mod test {
 
         assert_eq!(expected, actual)
     }
+
+    #[test]
+    fn test_to_json_should_round_trip_fixture() {
+        let data = include_str!("../../../../fixtures/r4/resources/organization.json");
+        let expected: serde_json::Value = serde_json::from_str(data).unwrap();
+
+        let actual = serde_json::to_value(Organization::from_json(data).unwrap()).unwrap();
+
+        assert_eq!(expected, actual)
+    }
 }
@@ -1,8 +1,11 @@
-use crate::r4::{
-    elements::{CodeableConcept, ContactPoint, Identifier, Period, Reference},
-    resources::{
-        DomainResource, Endpoint, HealthcareService, Location, Organization,
-        OrganizationAffiliation, ResourceType,
+use crate::{
+    FhirError,
+    r4::{
+        elements::{CodeableConcept, ContactPoint, Identifier, Period, Reference},
+        resources::{
+            DomainResource, Endpoint, HealthcareService, Location, Organization,
+            OrganizationAffiliation, ResourceType,
+        },
     },
 };
 
@@ -178,6 +181,10 @@ impl OrganizationAffiliationBuilder {
             resource_type: OrganizationAffiliation::get_resource_type(),
         }
     }
+
+    pub fn try_build(self) -> Result<OrganizationAffiliation, FhirError> {
+        Ok(self.build())
+    }
 }
 
 #[cfg(test)]
@@ -5,11 +5,13 @@ use crate::{
     FhirError,
     r4::{
         elements::{
-            CodeableConcept, ContactPoint, GetResourceReferences, Identifier, Period, Reference,
-            ReferenceTypes,
+            CodeableConcept, ContactPoint, Effective, GetCodeableConceptsMut, GetIdentifiers,
+            GetResourceReferences, GetResourceReferencesMut, Identifier, Period, Reference,
+            ReferenceTypes, ReferenceTypesMut,
         },
         resources::{
-            DomainResource, Endpoint, HealthcareService, Location, Organization, ResourceType,
+            DomainResource, Endpoint, HealthcareService, Location, Organization, ReferenceTarget,
+            ResourceType,
         },
     },
 };
@@ -42,6 +44,7 @@ pub struct OrganizationAffiliation {
     pub code: Option<Vec<CodeableConcept>>,
 
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "specialty")]
     pub speciality: Option<Vec<CodeableConcept>>,
 
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -87,22 +90,48 @@ impl ResourceType for OrganizationAffiliation {
     const TYPE: &'static str = "OrganizationAffiliation";
 }
 
+impl ReferenceTarget for OrganizationAffiliation {
+    fn resource_id(&self) -> Option<&str> {
+        self.domain_resource.resource.id.as_deref()
+    }
+
+    fn resource_id_mut(&mut self) -> &mut Option<String> {
+        &mut self.domain_resource.resource.id
+    }
+}
+
+impl GetIdentifiers for OrganizationAffiliation {
+    fn get_identifiers(&self) -> Vec<&Identifier> {
+        self.identifier.iter().flatten().collect()
+    }
+}
+
+impl Effective for OrganizationAffiliation {
+    fn period(&self) -> Option<&Period> {
+        self.period.as_ref()
+    }
+
+    fn is_active(&self) -> bool {
+        self.active != Some(false)
+    }
+}
+
 impl OrganizationAffiliation {
     pub fn from_json(data: &str) -> Result<Self, FhirError> {
         Ok(serde_json::from_str(data)?)
     }
 
     pub fn to_json_value(&self) -> Result<Value, FhirError> {
-        Ok(serde_json::to_value(&self)?)
+        Ok(serde_json::to_value(self)?)
     }
 
     pub fn to_json_string(&self) -> Result<String, FhirError> {
-        Ok(serde_json::to_string_pretty(&self)?)
+        Ok(serde_json::to_string_pretty(self)?)
     }
 }
 
 impl GetResourceReferences for OrganizationAffiliation {
-    fn get_references(&self) -> Vec<ReferenceTypes> {
+    fn get_references(&self) -> Vec<ReferenceTypes<'_>> {
         let mut references = Vec::<ReferenceTypes>::new();
 
         if let Some(org) = &self.organization {
@@ -137,10 +166,65 @@ impl GetResourceReferences for OrganizationAffiliation {
             }
         }
 
+        for identifier in self.identifier.iter().flatten() {
+            references.extend(identifier.get_references());
+        }
+
         references
     }
 }
 
+impl GetResourceReferencesMut for OrganizationAffiliation {
+    fn get_references_mut(&mut self) -> Vec<ReferenceTypesMut<'_>> {
+        let mut references = Vec::<ReferenceTypesMut>::new();
+
+        if let Some(organization) = &mut self.organization {
+            references.push(ReferenceTypesMut::from(organization));
+        }
+
+        if let Some(participating_organization) = &mut self.participating_organization {
+            references.push(ReferenceTypesMut::from(participating_organization));
+        }
+
+        for reference in self.network.iter_mut().flatten() {
+            references.push(ReferenceTypesMut::from(reference));
+        }
+
+        for reference in self.location.iter_mut().flatten() {
+            references.push(ReferenceTypesMut::from(reference));
+        }
+
+        for reference in self.healthcare_service.iter_mut().flatten() {
+            references.push(ReferenceTypesMut::from(reference));
+        }
+
+        for reference in self.endpoint.iter_mut().flatten() {
+            references.push(ReferenceTypesMut::from(reference));
+        }
+
+        for identifier in self.identifier.iter_mut().flatten() {
+            references.extend(identifier.get_references_mut());
+        }
+
+        references
+    }
+}
+
+impl GetCodeableConceptsMut for OrganizationAffiliation {
+    fn get_codeable_concepts_mut(&mut self) -> Vec<&mut CodeableConcept> {
+        let mut concepts = self.domain_resource.get_codeable_concepts_mut();
+
+        concepts.extend(self.code.iter_mut().flatten());
+        concepts.extend(self.speciality.iter_mut().flatten());
+
+        for identifier in self.identifier.iter_mut().flatten() {
+            concepts.extend(identifier.get_codeable_concepts_mut());
+        }
+
+        concepts
+    }
+}
+
 #[cfg(test)]
 mod test {
     use std::vec;
@@ -207,7 +291,15 @@ mod test {
                 text: Some("Network membership".to_string()),
                 ..Default::default()
             }]),
-            speciality: None,
+            speciality: Some(vec![CodeableConcept {
+                coding: Some(vec![Coding {
+                    system: Some("http://snomed.info/sct".to_string()),
+                    code: Some("408443003".to_string()),
+                    display: Some("General medical practice".to_string()),
+                    ..Default::default()
+                }]),
+                ..Default::default()
+            }]),
             location: Some(vec![Reference::<Location> {
                 reference: Some("Location/location-1".to_string()),
                 display: Some("Main Campus".to_string()),
@@ -1,11 +1,14 @@
-use crate::r4::{
-    elements::{
-        Address, Attachment, BackboneElement, CodeableConcept, ContactPoint, Element, HumanName,
-        Identifier, Period, Reference,
-    },
-    resources::{
-        DomainResource, Gender, Organization, Practitioner, PractitionerQualification, Resource,
-        ResourceType,
+use crate::{
+    FhirError,
+    r4::{
+        elements::{
+            Address, Attachment, BackboneElement, CodeableConcept, ContactPoint, Element,
+            HumanName, Identifier, Period, Reference,
+        },
+        resources::{
+            DomainResource, Gender, Organization, Practitioner, PractitionerQualification,
+            Resource, ResourceType,
+        },
     },
 };
 
@@ -74,6 +77,21 @@ impl PractitionerQualificationBuilder {
             issuer: self.issuer,
         }
     }
+
+    pub fn try_build(self) -> Result<PractitionerQualification, FhirError> {
+        FhirError::require(
+            "PractitionerQualification",
+            &[(
+                "code",
+                self.code
+                    .coding
+                    .as_ref()
+                    .is_some_and(|coding| !coding.is_empty())
+                    || self.code.text.is_some(),
+            )],
+        )?;
+        Ok(self.build())
+    }
 }
 
 #[derive(Default)]
@@ -232,6 +250,10 @@ impl PractitionerBuilder {
             resource_type: Practitioner::get_resource_type(),
         }
     }
+
+    pub fn try_build(self) -> Result<Practitioner, FhirError> {
+        Ok(self.build())
+    }
 }
 
 #[cfg(test)]
@@ -266,4 +288,22 @@ mod test {
 
         assert_eq!(expected, actual)
     }
+
+    #[test]
+    fn test_try_build_qualification_should_require_code() {
+        let error = PractitionerQualificationBuilder::new("q-1")
+            .try_build()
+            .unwrap_err();
+
+        assert_eq!(
+            "PractitionerQualification is missing mandatory elements: code",
+            error.to_string()
+        );
+        assert!(
+            PractitionerQualificationBuilder::new("q-1")
+                .code(CodeableConceptBuilder::default().with_text("MD").build())
+                .try_build()
+                .is_ok()
+        );
+    }
 }
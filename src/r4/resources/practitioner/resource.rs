@@ -5,9 +5,11 @@ use crate::{
     r4::{
         elements::{
             Address, Attachment, BackboneElement, CodeableConcept, ContactPoint,
-            GetResourceReferences, HumanName, Identifier, Period, Reference, ReferenceTypes,
+            GetCodeableConceptsMut, GetIdentifiers, GetResourceReferences,
+            GetResourceReferencesMut, HumanName, Identifier, Period, Reference, ReferenceTypes,
+            ReferenceTypesMut,
         },
-        resources::{DomainResource, Organization, ResourceType},
+        resources::{DomainResource, Organization, ReferenceTarget, ResourceType},
     },
 };
 
@@ -28,6 +30,22 @@ pub struct PractitionerQualification {
     pub issuer: Option<Reference<Organization>>,
 }
 
+impl GetResourceReferences for PractitionerQualification {
+    fn get_references(&self) -> Vec<ReferenceTypes<'_>> {
+        let mut references = Vec::<ReferenceTypes>::new();
+
+        if let Some(issuer) = &self.issuer {
+            references.push(ReferenceTypes::from(issuer));
+        }
+
+        for identifier in self.identifier.iter().flatten() {
+            references.extend(identifier.get_references());
+        }
+
+        references
+    }
+}
+
 impl ResourceType for PractitionerQualification {
     const TYPE: &'static str = "PractitionerQualification";
 }
@@ -105,43 +123,129 @@ impl ResourceType for Practitioner {
     const TYPE: &'static str = "Practitioner";
 }
 
+impl ReferenceTarget for Practitioner {
+    fn resource_id(&self) -> Option<&str> {
+        self.domain_resource.resource.id.as_deref()
+    }
+
+    fn resource_id_mut(&mut self) -> &mut Option<String> {
+        &mut self.domain_resource.resource.id
+    }
+
+    fn reference_display(&self) -> Option<String> {
+        self.name.iter().flatten().find_map(HumanName::to_text)
+    }
+}
+
 impl Practitioner {
     pub fn from_json(data: &str) -> Result<Self, FhirError> {
         Ok(serde_json::from_str(data)?)
     }
 
     pub fn to_json_string(&self) -> Result<String, FhirError> {
-        Ok(serde_json::to_string_pretty(&self)?)
+        Ok(serde_json::to_string_pretty(self)?)
     }
 
     pub fn to_json_value(&self) -> Result<serde_json::Value, FhirError> {
-        Ok(serde_json::to_value(&self)?)
+        Ok(serde_json::to_value(self)?)
+    }
+}
+
+impl GetIdentifiers for Practitioner {
+    fn get_identifiers(&self) -> Vec<&Identifier> {
+        self.identifier.iter().flatten().collect()
     }
 }
 
 impl GetResourceReferences for Practitioner {
-    fn get_references(&self) -> Vec<ReferenceTypes> {
+    fn get_references(&self) -> Vec<ReferenceTypes<'_>> {
         let mut references = Vec::<ReferenceTypes>::new();
 
-        if let Some(qualifications) = &self.qualification {
-            for q in qualifications {
-                if let Some(issuer) = &q.issuer {
-                    references.push(ReferenceTypes::from(issuer));
-                }
-            }
+        for qualification in self.qualification.iter().flatten() {
+            references.extend(qualification.get_references());
+        }
+
+        for identifier in self.identifier.iter().flatten() {
+            references.extend(identifier.get_references());
+        }
+
+        references
+    }
+}
+
+impl GetResourceReferencesMut for PractitionerQualification {
+    fn get_references_mut(&mut self) -> Vec<ReferenceTypesMut<'_>> {
+        let mut references = Vec::<ReferenceTypesMut>::new();
+
+        if let Some(issuer) = &mut self.issuer {
+            references.push(ReferenceTypesMut::from(issuer));
+        }
+
+        for identifier in self.identifier.iter_mut().flatten() {
+            references.extend(identifier.get_references_mut());
+        }
+
+        references
+    }
+}
+
+impl GetResourceReferencesMut for Practitioner {
+    fn get_references_mut(&mut self) -> Vec<ReferenceTypesMut<'_>> {
+        let mut references = Vec::<ReferenceTypesMut>::new();
+
+        for qualification in self.qualification.iter_mut().flatten() {
+            references.extend(qualification.get_references_mut());
+        }
+
+        for identifier in self.identifier.iter_mut().flatten() {
+            references.extend(identifier.get_references_mut());
         }
 
         references
     }
 }
 
+impl GetCodeableConceptsMut for PractitionerQualification {
+    fn get_codeable_concepts_mut(&mut self) -> Vec<&mut CodeableConcept> {
+        let mut concepts = vec![&mut self.code];
+
+        for identifier in self.identifier.iter_mut().flatten() {
+            concepts.extend(identifier.get_codeable_concepts_mut());
+        }
+
+        concepts
+    }
+}
+
+impl GetCodeableConceptsMut for Practitioner {
+    fn get_codeable_concepts_mut(&mut self) -> Vec<&mut CodeableConcept> {
+        let mut concepts = self.domain_resource.get_codeable_concepts_mut();
+
+        concepts.extend(self.communication.iter_mut().flatten());
+
+        for identifier in self.identifier.iter_mut().flatten() {
+            concepts.extend(identifier.get_codeable_concepts_mut());
+        }
+
+        for address in self.address.iter_mut().flatten() {
+            concepts.extend(address.get_codeable_concepts_mut());
+        }
+
+        for qualification in self.qualification.iter_mut().flatten() {
+            concepts.extend(qualification.get_codeable_concepts_mut());
+        }
+
+        concepts
+    }
+}
+
 #[cfg(test)]
 mod test {
 
     use serde_json::json;
 
     use crate::r4::{
-        elements::{Coding, ReferenceBuilder},
+        elements::{Coding, IdentifierBuilder, ReferenceBuilder},
         resources::{PractitionerBuilder, PractitionerQualificationBuilder, Resource},
     };
 
@@ -279,7 +383,7 @@ mod test {
             .to_json_string()
             .unwrap_or_else(|e| panic!("{e:?}"));
 
-        let actual: serde_json::Value = serde_json::from_str(&value.as_str()).unwrap();
+        let actual: serde_json::Value = serde_json::from_str(value.as_str()).unwrap();
 
         assert_eq!(expected, actual)
     }
@@ -336,4 +440,55 @@ mod test {
 
         assert_eq!(expected, actual)
     }
+
+    #[test]
+    fn test_get_references_should_include_nested_assigners() {
+        let issuer = ReferenceBuilder::default()
+            .with_reference("Organization/board")
+            .build::<Organization>();
+        let registry = ReferenceBuilder::default()
+            .with_reference("Organization/registry")
+            .build::<Organization>();
+        let ministry = ReferenceBuilder::default()
+            .with_reference("Organization/ministry")
+            .build::<Organization>();
+        let practitioner = PractitionerBuilder::default()
+            .add_identifier(
+                IdentifierBuilder::default()
+                    .with_value("123")
+                    .with_assigner(ministry.clone())
+                    .build(),
+            )
+            .add_qualification(
+                PractitionerQualificationBuilder::default()
+                    .issuer(issuer.clone())
+                    .add_identifier(
+                        IdentifierBuilder::default()
+                            .with_value("MD-1")
+                            .with_assigner(registry.clone())
+                            .build(),
+                    )
+                    .build(),
+            )
+            .build();
+        let expected = vec![
+            ReferenceTypes::from(&issuer),
+            ReferenceTypes::from(&registry),
+            ReferenceTypes::from(&ministry),
+        ];
+
+        let actual = practitioner.get_references();
+
+        assert_eq!(expected, actual)
+    }
+
+    #[test]
+    fn test_to_json_should_round_trip_fixture() {
+        let data = include_str!("../../../../fixtures/r4/resources/practitioner.json");
+        let expected: serde_json::Value = serde_json::from_str(data).unwrap();
+
+        let actual = serde_json::to_value(Practitioner::from_json(data).unwrap()).unwrap();
+
+        assert_eq!(expected, actual)
+    }
 }
@@ -1,10 +1,14 @@
-use crate::r4::{
-    elements::{
-        AvailableTime, CodeableConcept, ContactPoint, Identifier, NotAvailable, Period, Reference,
-    },
-    resources::{
-        DomainResource, Endpoint, HealthcareService, Location, Organization, Practitioner,
-        PractitionerRole, Resource, ResourceType,
+use crate::{
+    FhirError,
+    r4::{
+        elements::{
+            AvailableTime, CodeableConcept, ContactPoint, Identifier, NotAvailable, Period,
+            Reference,
+        },
+        resources::{
+            DomainResource, Endpoint, HealthcareService, Location, Organization, Practitioner,
+            PractitionerRole, Resource, ResourceType,
+        },
     },
 };
 
@@ -207,6 +211,10 @@ impl PractitionerRoleBuilder {
             resource_type: PractitionerRole::get_resource_type(),
         }
     }
+
+    pub fn try_build(self) -> Result<PractitionerRole, FhirError> {
+        Ok(self.build())
+    }
 }
 
 #[cfg(test)]
@@ -4,12 +4,13 @@ use crate::{
     FhirError,
     r4::{
         elements::{
-            AvailableTime, CodeableConcept, ContactPoint, GetResourceReferences, Identifier,
-            NotAvailable, Period, Reference, ReferenceTypes,
+            AvailableTime, CodeableConcept, ContactPoint, Effective, GetCodeableConceptsMut,
+            GetIdentifiers, GetResourceReferences, GetResourceReferencesMut, Identifier,
+            NotAvailable, Period, Reference, ReferenceTypes, ReferenceTypesMut,
         },
         resources::{
-            DomainResource, Endpoint, HealthcareService, Location, Organization,
-            Practitioner, ResourceType,
+            DomainResource, Endpoint, HealthcareService, Location, Organization, Practitioner,
+            ReferenceTarget, ResourceType,
         },
     },
 };
@@ -39,6 +40,7 @@ pub struct PractitionerRole {
     pub code: Option<Vec<CodeableConcept>>,
 
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "specialty")]
     pub speciality: Option<Vec<CodeableConcept>>,
 
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -94,22 +96,48 @@ impl ResourceType for PractitionerRole {
     const TYPE: &'static str = "PractitionerRole";
 }
 
+impl ReferenceTarget for PractitionerRole {
+    fn resource_id(&self) -> Option<&str> {
+        self.domain_resource.resource.id.as_deref()
+    }
+
+    fn resource_id_mut(&mut self) -> &mut Option<String> {
+        &mut self.domain_resource.resource.id
+    }
+}
+
+impl GetIdentifiers for PractitionerRole {
+    fn get_identifiers(&self) -> Vec<&Identifier> {
+        self.identifier.iter().flatten().collect()
+    }
+}
+
+impl Effective for PractitionerRole {
+    fn period(&self) -> Option<&Period> {
+        self.period.as_ref()
+    }
+
+    fn is_active(&self) -> bool {
+        self.active != Some(false)
+    }
+}
+
 impl PractitionerRole {
     pub fn from_json(data: &str) -> Result<Self, FhirError> {
         Ok(serde_json::from_str(data)?)
     }
 
     pub fn to_json_string(&self) -> Result<String, FhirError> {
-        Ok(serde_json::to_string_pretty(&self)?)
+        Ok(serde_json::to_string_pretty(self)?)
     }
 
     pub fn to_json_value(&self) -> Result<serde_json::Value, FhirError> {
-        Ok(serde_json::to_value(&self)?)
+        Ok(serde_json::to_value(self)?)
     }
 }
 
 impl GetResourceReferences for PractitionerRole {
-    fn get_references(&self) -> Vec<ReferenceTypes> {
+    fn get_references(&self) -> Vec<ReferenceTypes<'_>> {
         let mut references = Vec::<ReferenceTypes>::new();
 
         if let Some(pract) = &self.practitioner {
@@ -138,10 +166,61 @@ impl GetResourceReferences for PractitionerRole {
             }
         }
 
+        for identifier in self.identifier.iter().flatten() {
+            references.extend(identifier.get_references());
+        }
+
         references
     }
 }
 
+impl GetResourceReferencesMut for PractitionerRole {
+    fn get_references_mut(&mut self) -> Vec<ReferenceTypesMut<'_>> {
+        let mut references = Vec::<ReferenceTypesMut>::new();
+
+        if let Some(practitioner) = &mut self.practitioner {
+            references.push(ReferenceTypesMut::from(practitioner));
+        }
+
+        if let Some(organization) = &mut self.organization {
+            references.push(ReferenceTypesMut::from(organization));
+        }
+
+        for reference in self.location.iter_mut().flatten() {
+            references.push(ReferenceTypesMut::from(reference));
+        }
+
+        for reference in self.healthcare_service.iter_mut().flatten() {
+            references.push(ReferenceTypesMut::from(reference));
+        }
+
+        for reference in self.endpoint.iter_mut().flatten() {
+            references.push(ReferenceTypesMut::from(reference));
+        }
+
+        for identifier in self.identifier.iter_mut().flatten() {
+            references.extend(identifier.get_references_mut());
+        }
+
+        references
+    }
+}
+
+impl GetCodeableConceptsMut for PractitionerRole {
+    fn get_codeable_concepts_mut(&mut self) -> Vec<&mut CodeableConcept> {
+        let mut concepts = self.domain_resource.get_codeable_concepts_mut();
+
+        concepts.extend(self.code.iter_mut().flatten());
+        concepts.extend(self.speciality.iter_mut().flatten());
+
+        for identifier in self.identifier.iter_mut().flatten() {
+            concepts.extend(identifier.get_codeable_concepts_mut());
+        }
+
+        concepts
+    }
+}
+
 #[cfg(test)]
 mod test {
 
@@ -202,7 +281,15 @@ mod test {
                 text: Some("Attending Physician".to_string()),
                 ..Default::default()
             }]),
-            speciality: None,
+            speciality: Some(vec![CodeableConcept {
+                coding: Some(vec![Coding {
+                    system: Some("http://snomed.info/sct".to_string()),
+                    code: Some("773568002".to_string()),
+                    display: Some("Emergency medicine".to_string()),
+                    ..Default::default()
+                }]),
+                ..Default::default()
+            }]),
             location: Some(vec![Reference::<Location> {
                 reference: Some("Location/location-1".to_string()),
                 display: Some("Emergency Department".to_string()),
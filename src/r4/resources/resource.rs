@@ -1,5 +1,12 @@
+use std::path::Path;
+
 use serde::{Deserialize, Serialize};
 
+use crate::{
+    FhirError,
+    r4::elements::{CodeableConcept, Extension, GetCodeableConceptsMut},
+};
+
 #[derive(Debug, Default, Serialize, Deserialize, PartialEq, Clone)]
 pub struct Resource {
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -21,10 +28,32 @@ pub struct DomainResource {
     pub text: Option<String>,
 
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub contained: Option<Vec<String>>, // To be resolved later
+    pub contained: Option<Vec<serde_json::Value>>,
 
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub extensions: Option<Vec<String>>, // to be resolved later
+    pub extension: Option<Vec<Extension>>,
+}
+
+impl DomainResource {
+    /// The extensions with `url`.
+    pub fn extensions<'a>(&'a self, url: &'a str) -> impl Iterator<Item = &'a Extension> {
+        self.extension
+            .iter()
+            .flatten()
+            .filter(move |extension| extension.url == url)
+    }
+}
+
+impl GetCodeableConceptsMut for DomainResource {
+    /// The codeable concepts of the extensions, `contained` resources are
+    /// not typed and are left out.
+    fn get_codeable_concepts_mut(&mut self) -> Vec<&mut CodeableConcept> {
+        self.extension
+            .iter_mut()
+            .flatten()
+            .flat_map(Extension::get_codeable_concepts_mut)
+            .collect()
+    }
 }
 
 pub trait ResourceType {
@@ -34,3 +63,49 @@ pub trait ResourceType {
         Self::TYPE.to_string()
     }
 }
+
+/// Passes the contents of every `.json` file in a directory, in file name
+/// order, to `add` and returns the number of files it accepted.
+pub(crate) fn load_json_dir(
+    path: &Path,
+    mut add: impl FnMut(&str) -> Result<bool, FhirError>,
+) -> Result<usize, FhirError> {
+    let mut paths = std::fs::read_dir(path)?
+        .map(|entry| entry.map(|e| e.path()))
+        .collect::<Result<Vec<_>, _>>()?;
+    paths.sort();
+
+    let mut loaded = 0;
+    for path in paths
+        .iter()
+        .filter(|p| p.extension().is_some_and(|e| e == "json"))
+    {
+        if add(&std::fs::read_to_string(path)?)? {
+            loaded += 1;
+        }
+    }
+    Ok(loaded)
+}
+
+/// A resource that can be the target of a `Reference`, see `Reference::to`.
+pub trait ReferenceTarget: ResourceType {
+    fn resource_id(&self) -> Option<&str>;
+
+    fn resource_id_mut(&mut self) -> &mut Option<String>;
+
+    /// A human readable name of the resource for `Reference.display`.
+    fn reference_display(&self) -> Option<String> {
+        None
+    }
+}
+
+/// The lifecycle status shared by the conformance and terminology resources.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum PublicationStatus {
+    #[default]
+    Draft,
+    Active,
+    Retired,
+    Unknown,
+}
@@ -0,0 +1,332 @@
+use crate::{
+    FhirError,
+    r4::{
+        elements::{CodeableConcept, Coding, ElementDefinition, Identifier},
+        resources::{
+            DomainResource, PublicationStatus, Resource, ResourceType, StructureDefinition,
+            StructureDefinitionContext, StructureDefinitionDifferential, StructureDefinitionKind,
+            StructureDefinitionMapping, StructureDefinitionSnapshot, TypeDerivationRule,
+        },
+    },
+};
+
+pub struct StructureDefinitionBuilder {
+    domain_resource: DomainResource,
+    url: String,
+    identifier: Option<Vec<Identifier>>,
+    version: Option<String>,
+    name: String,
+    title: Option<String>,
+    status: PublicationStatus,
+    experimental: Option<bool>,
+    date: Option<String>, // to be resolved later
+    publisher: Option<String>,
+    description: Option<String>,
+    jurisdiction: Option<Vec<CodeableConcept>>,
+    purpose: Option<String>,
+    copyright: Option<String>,
+    keyword: Option<Vec<Coding>>,
+    fhir_version: Option<String>,
+    mapping: Option<Vec<StructureDefinitionMapping>>,
+    kind: StructureDefinitionKind,
+    r#abstract: bool,
+    context: Option<Vec<StructureDefinitionContext>>,
+    context_invariant: Option<Vec<String>>,
+    r#type: String,
+    base_definition: Option<String>,
+    derivation: Option<TypeDerivationRule>,
+    snapshot: Option<StructureDefinitionSnapshot>,
+    differential: Option<StructureDefinitionDifferential>,
+    resource_type: String,
+}
+
+impl Default for StructureDefinitionBuilder {
+    fn default() -> Self {
+        StructureDefinitionBuilder {
+            domain_resource: DomainResource {
+                ..Default::default()
+            },
+            resource_type: StructureDefinition::get_resource_type(),
+            url: String::new(),
+            identifier: None,
+            version: None,
+            name: String::new(),
+            title: None,
+            status: PublicationStatus::Draft,
+            experimental: None,
+            date: None,
+            publisher: None,
+            description: None,
+            jurisdiction: None,
+            purpose: None,
+            copyright: None,
+            keyword: None,
+            fhir_version: None,
+            mapping: None,
+            kind: StructureDefinitionKind::Resource,
+            r#abstract: false,
+            context: None,
+            context_invariant: None,
+            r#type: String::new(),
+            base_definition: None,
+            derivation: None,
+            snapshot: None,
+            differential: None,
+        }
+    }
+}
+
+impl StructureDefinitionBuilder {
+    pub fn new(id: impl Into<String>) -> Self {
+        StructureDefinitionBuilder {
+            domain_resource: DomainResource {
+                resource: Resource {
+                    id: Some(id.into()),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    pub fn id(mut self, id: impl Into<String>) -> Self {
+        self.domain_resource.resource.id = Some(id.into());
+        self
+    }
+
+    pub fn url(mut self, url: impl Into<String>) -> Self {
+        self.url = url.into();
+        self
+    }
+
+    pub fn identifier(mut self, identifier: Vec<Identifier>) -> Self {
+        self.identifier = Some(identifier);
+        self
+    }
+
+    pub fn add_identifier(mut self, identifier: Identifier) -> Self {
+        match &mut self.identifier {
+            Some(ident) => ident.push(identifier),
+            None => self.identifier = Some(vec![identifier]),
+        }
+        self
+    }
+
+    pub fn version(mut self, version: impl Into<String>) -> Self {
+        self.version = Some(version.into());
+        self
+    }
+
+    pub fn name(mut self, name: impl Into<String>) -> Self {
+        self.name = name.into();
+        self
+    }
+
+    pub fn title(mut self, title: impl Into<String>) -> Self {
+        self.title = Some(title.into());
+        self
+    }
+
+    pub fn status(mut self, status: PublicationStatus) -> Self {
+        self.status = status;
+        self
+    }
+
+    pub fn experimental(mut self, experimental: bool) -> Self {
+        self.experimental = Some(experimental);
+        self
+    }
+
+    pub fn date(mut self, date: impl Into<String>) -> Self {
+        self.date = Some(date.into());
+        self
+    }
+
+    pub fn publisher(mut self, publisher: impl Into<String>) -> Self {
+        self.publisher = Some(publisher.into());
+        self
+    }
+
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    pub fn jurisdiction(mut self, jurisdiction: Vec<CodeableConcept>) -> Self {
+        self.jurisdiction = Some(jurisdiction);
+        self
+    }
+
+    pub fn purpose(mut self, purpose: impl Into<String>) -> Self {
+        self.purpose = Some(purpose.into());
+        self
+    }
+
+    pub fn copyright(mut self, copyright: impl Into<String>) -> Self {
+        self.copyright = Some(copyright.into());
+        self
+    }
+
+    pub fn keyword(mut self, keyword: Vec<Coding>) -> Self {
+        self.keyword = Some(keyword);
+        self
+    }
+
+    pub fn fhir_version(mut self, fhir_version: impl Into<String>) -> Self {
+        self.fhir_version = Some(fhir_version.into());
+        self
+    }
+
+    pub fn mapping(mut self, mapping: Vec<StructureDefinitionMapping>) -> Self {
+        self.mapping = Some(mapping);
+        self
+    }
+
+    pub fn kind(mut self, kind: StructureDefinitionKind) -> Self {
+        self.kind = kind;
+        self
+    }
+
+    pub fn r#abstract(mut self, r#abstract: bool) -> Self {
+        self.r#abstract = r#abstract;
+        self
+    }
+
+    pub fn context(mut self, context: Vec<StructureDefinitionContext>) -> Self {
+        self.context = Some(context);
+        self
+    }
+
+    pub fn context_invariant(mut self, context_invariant: Vec<String>) -> Self {
+        self.context_invariant = Some(context_invariant);
+        self
+    }
+
+    pub fn r#type(mut self, r#type: impl Into<String>) -> Self {
+        self.r#type = r#type.into();
+        self
+    }
+
+    pub fn base_definition(mut self, base_definition: impl Into<String>) -> Self {
+        self.base_definition = Some(base_definition.into());
+        self
+    }
+
+    pub fn derivation(mut self, derivation: TypeDerivationRule) -> Self {
+        self.derivation = Some(derivation);
+        self
+    }
+
+    pub fn snapshot(mut self, snapshot: StructureDefinitionSnapshot) -> Self {
+        self.snapshot = Some(snapshot);
+        self
+    }
+
+    pub fn add_snapshot_element(mut self, element: ElementDefinition) -> Self {
+        self.snapshot
+            .get_or_insert_with(StructureDefinitionSnapshot::default)
+            .element
+            .push(element);
+        self
+    }
+
+    pub fn differential(mut self, differential: StructureDefinitionDifferential) -> Self {
+        self.differential = Some(differential);
+        self
+    }
+
+    pub fn add_differential_element(mut self, element: ElementDefinition) -> Self {
+        self.differential
+            .get_or_insert_with(StructureDefinitionDifferential::default)
+            .element
+            .push(element);
+        self
+    }
+
+    pub fn build(self) -> StructureDefinition {
+        StructureDefinition {
+            domain_resource: self.domain_resource,
+            url: self.url,
+            identifier: self.identifier,
+            version: self.version,
+            name: self.name,
+            title: self.title,
+            status: self.status,
+            experimental: self.experimental,
+            date: self.date,
+            publisher: self.publisher,
+            description: self.description,
+            jurisdiction: self.jurisdiction,
+            purpose: self.purpose,
+            copyright: self.copyright,
+            keyword: self.keyword,
+            fhir_version: self.fhir_version,
+            mapping: self.mapping,
+            kind: self.kind,
+            r#abstract: self.r#abstract,
+            context: self.context,
+            context_invariant: self.context_invariant,
+            r#type: self.r#type,
+            base_definition: self.base_definition,
+            derivation: self.derivation,
+            snapshot: self.snapshot,
+            differential: self.differential,
+            resource_type: self.resource_type,
+        }
+    }
+
+    pub fn try_build(self) -> Result<StructureDefinition, FhirError> {
+        FhirError::require(
+            "StructureDefinition",
+            &[
+                ("url", !self.url.is_empty()),
+                ("name", !self.name.is_empty()),
+                ("type", !self.r#type.is_empty()),
+            ],
+        )?;
+        Ok(self.build())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::r4::elements::ElementDefinitionBuilder;
+
+    use super::*;
+
+    #[test]
+    fn test_build_structure_definition_should_succeed() {
+        let element = ElementDefinitionBuilder::new("Location")
+            .with_path("Location")
+            .build();
+        let expected = StructureDefinition {
+            domain_resource: DomainResource {
+                resource: Resource {
+                    id: Some("example-location".to_string()),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            url: "http://example.org/StructureDefinition/location".to_string(),
+            name: "ExampleLocation".to_string(),
+            status: PublicationStatus::Active,
+            r#type: "Location".to_string(),
+            snapshot: Some(StructureDefinitionSnapshot {
+                element: vec![element.clone()],
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+
+        let actual = StructureDefinitionBuilder::new("example-location")
+            .url("http://example.org/StructureDefinition/location")
+            .name("ExampleLocation")
+            .status(PublicationStatus::Active)
+            .r#type("Location")
+            .add_snapshot_element(element)
+            .build();
+
+        assert_eq!(expected, actual)
+    }
+}
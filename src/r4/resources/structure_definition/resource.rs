@@ -0,0 +1,372 @@
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    FhirError,
+    r4::{
+        elements::{
+            BackboneElement, CodeableConcept, Coding, ElementDefinition, GetCodeableConceptsMut,
+            GetIdentifiers, GetResourceReferences, Identifier, ReferenceTypes,
+        },
+        resources::{DomainResource, PublicationStatus, ReferenceTarget, ResourceType},
+    },
+};
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum StructureDefinitionKind {
+    PrimitiveType,
+    ComplexType,
+    #[default]
+    Resource,
+    Logical,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum TypeDerivationRule {
+    Specialization,
+    #[default]
+    Constraint,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Default)]
+pub struct StructureDefinitionMapping {
+    #[serde(flatten)]
+    pub backbone_element: BackboneElement,
+
+    pub identity: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub uri: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub comment: Option<String>,
+}
+
+impl ResourceType for StructureDefinitionMapping {
+    const TYPE: &'static str = "StructureDefinitionMapping";
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Default)]
+pub struct StructureDefinitionContext {
+    #[serde(flatten)]
+    pub backbone_element: BackboneElement,
+
+    pub r#type: String,
+
+    pub expression: String,
+}
+
+impl ResourceType for StructureDefinitionContext {
+    const TYPE: &'static str = "StructureDefinitionContext";
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Default)]
+pub struct StructureDefinitionSnapshot {
+    #[serde(flatten)]
+    pub backbone_element: BackboneElement,
+
+    pub element: Vec<ElementDefinition>,
+}
+
+impl ResourceType for StructureDefinitionSnapshot {
+    const TYPE: &'static str = "StructureDefinitionSnapshot";
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Default)]
+pub struct StructureDefinitionDifferential {
+    #[serde(flatten)]
+    pub backbone_element: BackboneElement,
+
+    pub element: Vec<ElementDefinition>,
+}
+
+impl ResourceType for StructureDefinitionDifferential {
+    const TYPE: &'static str = "StructureDefinitionDifferential";
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+#[serde(rename_all(serialize = "camelCase", deserialize = "camelCase"))]
+pub struct StructureDefinition {
+    #[serde(flatten)]
+    pub domain_resource: DomainResource,
+
+    pub url: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub identifier: Option<Vec<Identifier>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+
+    pub name: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+
+    pub status: PublicationStatus,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub experimental: Option<bool>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub date: Option<String>, // to be resolved later
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub publisher: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub jurisdiction: Option<Vec<CodeableConcept>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub purpose: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub copyright: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub keyword: Option<Vec<Coding>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fhir_version: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mapping: Option<Vec<StructureDefinitionMapping>>,
+
+    pub kind: StructureDefinitionKind,
+
+    pub r#abstract: bool,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub context: Option<Vec<StructureDefinitionContext>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub context_invariant: Option<Vec<String>>,
+
+    pub r#type: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub base_definition: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub derivation: Option<TypeDerivationRule>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub snapshot: Option<StructureDefinitionSnapshot>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub differential: Option<StructureDefinitionDifferential>,
+
+    #[serde(default = "StructureDefinition::get_resource_type")]
+    pub resource_type: String,
+}
+
+impl Default for StructureDefinition {
+    fn default() -> Self {
+        StructureDefinition {
+            domain_resource: DomainResource {
+                ..Default::default()
+            },
+            resource_type: Self::get_resource_type(),
+            url: String::new(),
+            identifier: None,
+            version: None,
+            name: String::new(),
+            title: None,
+            status: PublicationStatus::Draft,
+            experimental: None,
+            date: None,
+            publisher: None,
+            description: None,
+            jurisdiction: None,
+            purpose: None,
+            copyright: None,
+            keyword: None,
+            fhir_version: None,
+            mapping: None,
+            kind: StructureDefinitionKind::Resource,
+            r#abstract: false,
+            context: None,
+            context_invariant: None,
+            r#type: String::new(),
+            base_definition: None,
+            derivation: None,
+            snapshot: None,
+            differential: None,
+        }
+    }
+}
+
+impl ResourceType for StructureDefinition {
+    const TYPE: &'static str = "StructureDefinition";
+}
+
+impl ReferenceTarget for StructureDefinition {
+    fn resource_id(&self) -> Option<&str> {
+        self.domain_resource.resource.id.as_deref()
+    }
+
+    fn resource_id_mut(&mut self) -> &mut Option<String> {
+        &mut self.domain_resource.resource.id
+    }
+
+    fn reference_display(&self) -> Option<String> {
+        self.title.clone().or_else(|| Some(self.name.clone()))
+    }
+}
+
+impl GetIdentifiers for StructureDefinition {
+    fn get_identifiers(&self) -> Vec<&Identifier> {
+        self.identifier.iter().flatten().collect()
+    }
+}
+
+impl GetResourceReferences for StructureDefinition {
+    fn get_references(&self) -> Vec<ReferenceTypes<'_>> {
+        self.identifier
+            .iter()
+            .flatten()
+            .flat_map(GetResourceReferences::get_references)
+            .collect()
+    }
+}
+
+impl StructureDefinition {
+    pub fn from_json(data: &str) -> Result<Self, FhirError> {
+        Ok(serde_json::from_str(data)?)
+    }
+
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, FhirError> {
+        Self::from_json(&std::fs::read_to_string(path)?)
+    }
+
+    pub fn to_json_value(&self) -> Result<serde_json::Value, FhirError> {
+        Ok(serde_json::to_value(self)?)
+    }
+
+    pub fn to_json_string(&self) -> Result<String, FhirError> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    /// The snapshot elements, empty when no snapshot has been generated.
+    pub fn snapshot_elements(&self) -> &[ElementDefinition] {
+        self.snapshot
+            .as_ref()
+            .map(|s| s.element.as_slice())
+            .unwrap_or_default()
+    }
+
+    /// The snapshot element with the given element id.
+    pub fn find_element(&self, id: &str) -> Option<&ElementDefinition> {
+        self.snapshot_elements()
+            .iter()
+            .find(|e| e.element_id() == id)
+    }
+}
+
+impl GetCodeableConceptsMut for StructureDefinition {
+    fn get_codeable_concepts_mut(&mut self) -> Vec<&mut CodeableConcept> {
+        let mut concepts = self.domain_resource.get_codeable_concepts_mut();
+
+        concepts.extend(self.jurisdiction.iter_mut().flatten());
+
+        for identifier in self.identifier.iter_mut().flatten() {
+            concepts.extend(identifier.get_codeable_concepts_mut());
+        }
+
+        concepts
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::json;
+
+    use crate::r4::{elements::ElementDefinitionBuilder, resources::StructureDefinitionBuilder};
+
+    use super::*;
+
+    #[test]
+    fn test_from_file_should_succeed() {
+        let path = concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/fixtures/r4/profiles/organization.json"
+        );
+
+        let actual = StructureDefinition::from_file(path).unwrap();
+
+        assert_eq!("Organization", actual.r#type);
+        assert_eq!(StructureDefinitionKind::Resource, actual.kind);
+        assert_eq!(
+            Some(&TypeDerivationRule::Specialization),
+            actual.derivation.as_ref()
+        );
+        assert_eq!(
+            Some("org-1"),
+            actual
+                .find_element("Organization")
+                .and_then(|e| e.constraint.as_ref())
+                .and_then(|c| c.first())
+                .map(|c| c.key.as_str())
+        )
+    }
+
+    #[test]
+    fn test_from_json_should_round_trip() {
+        let data = include_str!("../../../../fixtures/r4/profiles/location.json");
+        let expected: serde_json::Value = serde_json::from_str(data).unwrap();
+
+        let actual = StructureDefinition::from_json(data)
+            .unwrap()
+            .to_json_value()
+            .unwrap();
+
+        assert_eq!(expected, actual)
+    }
+
+    #[test]
+    fn test_to_json_value_should_succeed() {
+        let expected = json!({
+            "resourceType": "StructureDefinition",
+            "url": "http://example.org/StructureDefinition/org",
+            "name": "ExampleOrganization",
+            "status": "draft",
+            "kind": "resource",
+            "abstract": false,
+            "type": "Organization",
+            "baseDefinition": "http://hl7.org/fhir/StructureDefinition/Organization",
+            "derivation": "constraint",
+            "differential": {
+                "element": [
+                    {"id": "Organization.name", "path": "Organization.name", "min": 1}
+                ]
+            }
+        });
+
+        let actual = StructureDefinitionBuilder::default()
+            .url("http://example.org/StructureDefinition/org")
+            .name("ExampleOrganization")
+            .r#type("Organization")
+            .base_definition("http://hl7.org/fhir/StructureDefinition/Organization")
+            .derivation(TypeDerivationRule::Constraint)
+            .add_differential_element(
+                ElementDefinitionBuilder::new("Organization.name")
+                    .with_path("Organization.name")
+                    .with_min(1)
+                    .build(),
+            )
+            .build()
+            .to_json_value()
+            .unwrap();
+
+        assert_eq!(expected, actual)
+    }
+}
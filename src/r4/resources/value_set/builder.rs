@@ -0,0 +1,379 @@
+use crate::{
+    FhirError,
+    r4::{
+        elements::{BackboneElement, CodeableConcept, Identifier},
+        resources::{
+            DomainResource, FilterOperator, PublicationStatus, Resource, ResourceType, ValueSet,
+            ValueSetCompose, ValueSetConceptReference, ValueSetConceptSet, ValueSetExpansion,
+            ValueSetFilter,
+        },
+    },
+};
+
+#[derive(Default)]
+pub struct ValueSetConceptSetBuilder {
+    backbone_element: BackboneElement,
+    system: Option<String>,
+    version: Option<String>,
+    concept: Option<Vec<ValueSetConceptReference>>,
+    filter: Option<Vec<ValueSetFilter>>,
+    value_set: Option<Vec<String>>,
+}
+
+impl ValueSetConceptSetBuilder {
+    pub fn new(id: impl Into<String>) -> Self {
+        let mut builder = Self::default();
+        builder.backbone_element.element.id = Some(id.into());
+        builder
+    }
+
+    pub fn id(mut self, id: impl Into<String>) -> Self {
+        self.backbone_element.element.id = Some(id.into());
+        self
+    }
+
+    pub fn system(mut self, system: impl Into<String>) -> Self {
+        self.system = Some(system.into());
+        self
+    }
+
+    pub fn version(mut self, version: impl Into<String>) -> Self {
+        self.version = Some(version.into());
+        self
+    }
+
+    pub fn concept(mut self, concept: Vec<ValueSetConceptReference>) -> Self {
+        self.concept = Some(concept);
+        self
+    }
+
+    pub fn add_concept(mut self, concept: ValueSetConceptReference) -> Self {
+        match &mut self.concept {
+            Some(c) => c.push(concept),
+            None => self.concept = Some(vec![concept]),
+        }
+        self
+    }
+
+    pub fn add_code(self, code: impl Into<String>) -> Self {
+        self.add_concept(ValueSetConceptReference {
+            code: code.into(),
+            ..Default::default()
+        })
+    }
+
+    pub fn filter(mut self, filter: Vec<ValueSetFilter>) -> Self {
+        self.filter = Some(filter);
+        self
+    }
+
+    pub fn add_filter(
+        mut self,
+        property: impl Into<String>,
+        op: FilterOperator,
+        value: impl Into<String>,
+    ) -> Self {
+        let filter = ValueSetFilter {
+            backbone_element: BackboneElement::default(),
+            property: property.into(),
+            op,
+            value: value.into(),
+        };
+        match &mut self.filter {
+            Some(f) => f.push(filter),
+            None => self.filter = Some(vec![filter]),
+        }
+        self
+    }
+
+    pub fn value_set(mut self, value_set: Vec<String>) -> Self {
+        self.value_set = Some(value_set);
+        self
+    }
+
+    pub fn add_value_set(mut self, value_set: impl Into<String>) -> Self {
+        match &mut self.value_set {
+            Some(vs) => vs.push(value_set.into()),
+            None => self.value_set = Some(vec![value_set.into()]),
+        }
+        self
+    }
+
+    pub fn build(self) -> ValueSetConceptSet {
+        ValueSetConceptSet {
+            backbone_element: self.backbone_element,
+            system: self.system,
+            version: self.version,
+            concept: self.concept,
+            filter: self.filter,
+            value_set: self.value_set,
+        }
+    }
+
+    pub fn try_build(self) -> Result<ValueSetConceptSet, FhirError> {
+        Ok(self.build())
+    }
+}
+
+pub struct ValueSetBuilder {
+    domain_resource: DomainResource,
+    url: Option<String>,
+    identifier: Option<Vec<Identifier>>,
+    version: Option<String>,
+    name: Option<String>,
+    title: Option<String>,
+    status: PublicationStatus,
+    experimental: Option<bool>,
+    date: Option<String>, // to be resolved later
+    publisher: Option<String>,
+    description: Option<String>,
+    jurisdiction: Option<Vec<CodeableConcept>>,
+    immutable: Option<bool>,
+    purpose: Option<String>,
+    copyright: Option<String>,
+    compose: Option<ValueSetCompose>,
+    expansion: Option<ValueSetExpansion>,
+    resource_type: String,
+}
+
+impl Default for ValueSetBuilder {
+    fn default() -> Self {
+        ValueSetBuilder {
+            domain_resource: DomainResource {
+                ..Default::default()
+            },
+            resource_type: ValueSet::get_resource_type(),
+            url: None,
+            identifier: None,
+            version: None,
+            name: None,
+            title: None,
+            status: PublicationStatus::Draft,
+            experimental: None,
+            date: None,
+            publisher: None,
+            description: None,
+            jurisdiction: None,
+            immutable: None,
+            purpose: None,
+            copyright: None,
+            compose: None,
+            expansion: None,
+        }
+    }
+}
+
+impl ValueSetBuilder {
+    pub fn new(id: impl Into<String>) -> Self {
+        ValueSetBuilder {
+            domain_resource: DomainResource {
+                resource: Resource {
+                    id: Some(id.into()),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    pub fn id(mut self, id: impl Into<String>) -> Self {
+        self.domain_resource.resource.id = Some(id.into());
+        self
+    }
+
+    pub fn url(mut self, url: impl Into<String>) -> Self {
+        self.url = Some(url.into());
+        self
+    }
+
+    pub fn identifier(mut self, identifier: Vec<Identifier>) -> Self {
+        self.identifier = Some(identifier);
+        self
+    }
+
+    pub fn add_identifier(mut self, identifier: Identifier) -> Self {
+        match &mut self.identifier {
+            Some(ident) => ident.push(identifier),
+            None => self.identifier = Some(vec![identifier]),
+        }
+        self
+    }
+
+    pub fn version(mut self, version: impl Into<String>) -> Self {
+        self.version = Some(version.into());
+        self
+    }
+
+    pub fn name(mut self, name: impl Into<String>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    pub fn title(mut self, title: impl Into<String>) -> Self {
+        self.title = Some(title.into());
+        self
+    }
+
+    pub fn status(mut self, status: PublicationStatus) -> Self {
+        self.status = status;
+        self
+    }
+
+    pub fn experimental(mut self, experimental: bool) -> Self {
+        self.experimental = Some(experimental);
+        self
+    }
+
+    pub fn date(mut self, date: impl Into<String>) -> Self {
+        self.date = Some(date.into());
+        self
+    }
+
+    pub fn publisher(mut self, publisher: impl Into<String>) -> Self {
+        self.publisher = Some(publisher.into());
+        self
+    }
+
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    pub fn jurisdiction(mut self, jurisdiction: Vec<CodeableConcept>) -> Self {
+        self.jurisdiction = Some(jurisdiction);
+        self
+    }
+
+    pub fn immutable(mut self, immutable: bool) -> Self {
+        self.immutable = Some(immutable);
+        self
+    }
+
+    pub fn purpose(mut self, purpose: impl Into<String>) -> Self {
+        self.purpose = Some(purpose.into());
+        self
+    }
+
+    pub fn copyright(mut self, copyright: impl Into<String>) -> Self {
+        self.copyright = Some(copyright.into());
+        self
+    }
+
+    pub fn compose(mut self, compose: ValueSetCompose) -> Self {
+        self.compose = Some(compose);
+        self
+    }
+
+    pub fn add_include(mut self, include: ValueSetConceptSet) -> Self {
+        self.compose
+            .get_or_insert_with(ValueSetCompose::default)
+            .include
+            .push(include);
+        self
+    }
+
+    pub fn add_exclude(mut self, exclude: ValueSetConceptSet) -> Self {
+        let compose = self.compose.get_or_insert_with(ValueSetCompose::default);
+        match &mut compose.exclude {
+            Some(e) => e.push(exclude),
+            None => compose.exclude = Some(vec![exclude]),
+        }
+        self
+    }
+
+    pub fn expansion(mut self, expansion: ValueSetExpansion) -> Self {
+        self.expansion = Some(expansion);
+        self
+    }
+
+    pub fn build(self) -> ValueSet {
+        ValueSet {
+            domain_resource: self.domain_resource,
+            url: self.url,
+            identifier: self.identifier,
+            version: self.version,
+            name: self.name,
+            title: self.title,
+            status: self.status,
+            experimental: self.experimental,
+            date: self.date,
+            publisher: self.publisher,
+            description: self.description,
+            jurisdiction: self.jurisdiction,
+            immutable: self.immutable,
+            purpose: self.purpose,
+            copyright: self.copyright,
+            compose: self.compose,
+            expansion: self.expansion,
+            resource_type: self.resource_type,
+        }
+    }
+
+    pub fn try_build(self) -> Result<ValueSet, FhirError> {
+        Ok(self.build())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_build_concept_set_should_succeed() {
+        let expected = ValueSetConceptSet {
+            system: Some("http://example.org/cs".to_string()),
+            version: Some("1".to_string()),
+            concept: Some(vec![ValueSetConceptReference {
+                code: "a".to_string(),
+                ..Default::default()
+            }]),
+            ..Default::default()
+        };
+
+        let actual = ValueSetConceptSetBuilder::default()
+            .system("http://example.org/cs")
+            .version("1")
+            .add_code("a")
+            .build();
+
+        assert_eq!(expected, actual)
+    }
+
+    #[test]
+    fn test_build_value_set_should_succeed() {
+        let include = ValueSetConceptSetBuilder::default()
+            .system("http://example.org/cs")
+            .build();
+        let exclude = ValueSetConceptSetBuilder::default()
+            .system("http://example.org/cs")
+            .add_code("b")
+            .build();
+        let expected = ValueSet {
+            domain_resource: DomainResource {
+                resource: Resource {
+                    id: Some("vs-1".to_string()),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            url: Some("http://example.org/vs".to_string()),
+            status: PublicationStatus::Active,
+            compose: Some(ValueSetCompose {
+                include: vec![include.clone()],
+                exclude: Some(vec![exclude.clone()]),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+
+        let actual = ValueSetBuilder::new("vs-1")
+            .url("http://example.org/vs")
+            .status(PublicationStatus::Active)
+            .add_include(include)
+            .add_exclude(exclude)
+            .build();
+
+        assert_eq!(expected, actual)
+    }
+}
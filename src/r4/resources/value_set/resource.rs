@@ -0,0 +1,505 @@
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    FhirError,
+    r4::{
+        elements::{
+            BackboneElement, CodeableConcept, Coding, GetCodeableConceptsMut, GetIdentifiers,
+            GetResourceReferences, Identifier, ReferenceTypes,
+        },
+        resources::{
+            ConceptDesignation, DomainResource, PublicationStatus, ReferenceTarget, ResourceType,
+        },
+    },
+};
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+pub enum FilterOperator {
+    #[serde(rename = "=")]
+    Equal,
+    #[serde(rename = "is-a")]
+    IsA,
+    #[serde(rename = "descendent-of")]
+    DescendentOf,
+    #[serde(rename = "is-not-a")]
+    IsNotA,
+    #[serde(rename = "regex")]
+    Regex,
+    #[serde(rename = "in")]
+    In,
+    #[serde(rename = "not-in")]
+    NotIn,
+    #[serde(rename = "generalizes")]
+    Generalizes,
+    #[serde(rename = "exists")]
+    Exists,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Default)]
+pub struct ValueSetConceptReference {
+    #[serde(flatten)]
+    pub backbone_element: BackboneElement,
+
+    pub code: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub display: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub designation: Option<Vec<ConceptDesignation>>,
+}
+
+impl ResourceType for ValueSetConceptReference {
+    const TYPE: &'static str = "ValueSetConceptReference";
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+pub struct ValueSetFilter {
+    #[serde(flatten)]
+    pub backbone_element: BackboneElement,
+
+    pub property: String,
+
+    pub op: FilterOperator,
+
+    pub value: String,
+}
+
+impl ResourceType for ValueSetFilter {
+    const TYPE: &'static str = "ValueSetFilter";
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Default)]
+#[serde(rename_all(serialize = "camelCase", deserialize = "camelCase"))]
+pub struct ValueSetConceptSet {
+    #[serde(flatten)]
+    pub backbone_element: BackboneElement,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub system: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub concept: Option<Vec<ValueSetConceptReference>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub filter: Option<Vec<ValueSetFilter>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value_set: Option<Vec<String>>,
+}
+
+impl ResourceType for ValueSetConceptSet {
+    const TYPE: &'static str = "ValueSetConceptSet";
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Default)]
+#[serde(rename_all(serialize = "camelCase", deserialize = "camelCase"))]
+pub struct ValueSetCompose {
+    #[serde(flatten)]
+    pub backbone_element: BackboneElement,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub locked_date: Option<String>, // to be resolved later
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub inactive: Option<bool>,
+
+    pub include: Vec<ValueSetConceptSet>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub exclude: Option<Vec<ValueSetConceptSet>>,
+}
+
+impl ResourceType for ValueSetCompose {
+    const TYPE: &'static str = "ValueSetCompose";
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Default)]
+#[serde(rename_all(serialize = "camelCase", deserialize = "camelCase"))]
+pub struct ValueSetExpansionParameter {
+    #[serde(flatten)]
+    pub backbone_element: BackboneElement,
+
+    pub name: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value_string: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value_boolean: Option<bool>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value_integer: Option<i64>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value_decimal: Option<f64>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value_uri: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value_code: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value_date_time: Option<String>,
+}
+
+impl ResourceType for ValueSetExpansionParameter {
+    const TYPE: &'static str = "ValueSetExpansionParameter";
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Default)]
+pub struct ValueSetExpansionContains {
+    #[serde(flatten)]
+    pub backbone_element: BackboneElement,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub system: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub r#abstract: Option<bool>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub inactive: Option<bool>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub code: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub display: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub designation: Option<Vec<ConceptDesignation>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub contains: Option<Vec<ValueSetExpansionContains>>,
+}
+
+impl ResourceType for ValueSetExpansionContains {
+    const TYPE: &'static str = "ValueSetExpansionContains";
+}
+
+impl ValueSetExpansionContains {
+    pub fn to_coding(&self) -> Coding {
+        Coding {
+            system: self.system.clone(),
+            version: self.version.clone(),
+            code: self.code.clone(),
+            display: self.display.clone(),
+            ..Default::default()
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Default)]
+pub struct ValueSetExpansion {
+    #[serde(flatten)]
+    pub backbone_element: BackboneElement,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub identifier: Option<String>,
+
+    pub timestamp: String, // to be resolved later
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total: Option<u32>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub offset: Option<u32>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parameter: Option<Vec<ValueSetExpansionParameter>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub contains: Option<Vec<ValueSetExpansionContains>>,
+}
+
+impl ResourceType for ValueSetExpansion {
+    const TYPE: &'static str = "ValueSetExpansion";
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+#[serde(rename_all(serialize = "camelCase", deserialize = "camelCase"))]
+pub struct ValueSet {
+    #[serde(flatten)]
+    pub domain_resource: DomainResource,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub identifier: Option<Vec<Identifier>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+
+    pub status: PublicationStatus,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub experimental: Option<bool>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub date: Option<String>, // to be resolved later
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub publisher: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub jurisdiction: Option<Vec<CodeableConcept>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub immutable: Option<bool>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub purpose: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub copyright: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub compose: Option<ValueSetCompose>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expansion: Option<ValueSetExpansion>,
+
+    #[serde(default = "ValueSet::get_resource_type")]
+    pub resource_type: String,
+}
+
+impl Default for ValueSet {
+    fn default() -> Self {
+        ValueSet {
+            domain_resource: DomainResource {
+                ..Default::default()
+            },
+            resource_type: Self::get_resource_type(),
+            url: None,
+            identifier: None,
+            version: None,
+            name: None,
+            title: None,
+            status: PublicationStatus::Draft,
+            experimental: None,
+            date: None,
+            publisher: None,
+            description: None,
+            jurisdiction: None,
+            immutable: None,
+            purpose: None,
+            copyright: None,
+            compose: None,
+            expansion: None,
+        }
+    }
+}
+
+impl ResourceType for ValueSet {
+    const TYPE: &'static str = "ValueSet";
+}
+
+impl ReferenceTarget for ValueSet {
+    fn resource_id(&self) -> Option<&str> {
+        self.domain_resource.resource.id.as_deref()
+    }
+
+    fn resource_id_mut(&mut self) -> &mut Option<String> {
+        &mut self.domain_resource.resource.id
+    }
+
+    fn reference_display(&self) -> Option<String> {
+        self.title.clone().or_else(|| self.name.clone())
+    }
+}
+
+impl GetIdentifiers for ValueSet {
+    fn get_identifiers(&self) -> Vec<&Identifier> {
+        self.identifier.iter().flatten().collect()
+    }
+}
+
+impl GetResourceReferences for ValueSet {
+    fn get_references(&self) -> Vec<ReferenceTypes<'_>> {
+        self.identifier
+            .iter()
+            .flatten()
+            .flat_map(GetResourceReferences::get_references)
+            .collect()
+    }
+}
+
+impl ValueSet {
+    pub fn from_json(data: &str) -> Result<Self, FhirError> {
+        Ok(serde_json::from_str(data)?)
+    }
+
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, FhirError> {
+        Self::from_json(&std::fs::read_to_string(path)?)
+    }
+
+    pub fn to_json_value(&self) -> Result<serde_json::Value, FhirError> {
+        Ok(serde_json::to_value(self)?)
+    }
+
+    pub fn to_json_string(&self) -> Result<String, FhirError> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+}
+
+impl GetCodeableConceptsMut for ValueSet {
+    fn get_codeable_concepts_mut(&mut self) -> Vec<&mut CodeableConcept> {
+        let mut concepts = self.domain_resource.get_codeable_concepts_mut();
+
+        concepts.extend(self.jurisdiction.iter_mut().flatten());
+
+        for identifier in self.identifier.iter_mut().flatten() {
+            concepts.extend(identifier.get_codeable_concepts_mut());
+        }
+
+        concepts
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::json;
+
+    use crate::r4::resources::{Resource, ValueSetBuilder, ValueSetConceptSetBuilder};
+
+    use super::*;
+
+    #[test]
+    fn test_from_json_should_succeed() {
+        let data = include_str!("../../../../fixtures/r4/resources/value_set.json");
+        let expected = ValueSet {
+            domain_resource: DomainResource {
+                resource: Resource {
+                    id: Some("provider-organization-type".to_string()),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            url: Some("http://example.org/ValueSet/provider-organization-type".to_string()),
+            version: Some("1.0.0".to_string()),
+            name: Some("ProviderOrganizationType".to_string()),
+            status: PublicationStatus::Active,
+            compose: Some(ValueSetCompose {
+                include: vec![
+                    ValueSetConceptSet {
+                        system: Some("http://example.org/CodeSystem/organization-type".to_string()),
+                        filter: Some(vec![ValueSetFilter {
+                            backbone_element: BackboneElement::default(),
+                            property: "concept".to_string(),
+                            op: FilterOperator::IsA,
+                            value: "prov".to_string(),
+                        }]),
+                        ..Default::default()
+                    },
+                    ValueSetConceptSet {
+                        system: Some(
+                            "http://terminology.hl7.org/CodeSystem/organization-type".to_string(),
+                        ),
+                        concept: Some(vec![ValueSetConceptReference {
+                            code: "prov".to_string(),
+                            display: Some("Healthcare Provider".to_string()),
+                            ..Default::default()
+                        }]),
+                        ..Default::default()
+                    },
+                ],
+                exclude: Some(vec![ValueSetConceptSet {
+                    system: Some("http://example.org/CodeSystem/organization-type".to_string()),
+                    concept: Some(vec![ValueSetConceptReference {
+                        code: "clinic".to_string(),
+                        ..Default::default()
+                    }]),
+                    ..Default::default()
+                }]),
+                ..Default::default()
+            }),
+            expansion: Some(ValueSetExpansion {
+                timestamp: "2025-01-01T00:00:00Z".to_string(),
+                total: Some(1),
+                contains: Some(vec![ValueSetExpansionContains {
+                    system: Some("http://example.org/CodeSystem/organization-type".to_string()),
+                    code: Some("hosp".to_string()),
+                    display: Some("Hospital".to_string()),
+                    ..Default::default()
+                }]),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+
+        let actual = ValueSet::from_json(data).unwrap();
+
+        assert_eq!(expected, actual)
+    }
+
+    #[test]
+    fn test_from_file_should_succeed() {
+        let path = concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/fixtures/r4/resources/value_set.json"
+        );
+
+        let actual = ValueSet::from_file(path).unwrap();
+
+        assert_eq!(Some("ProviderOrganizationType".to_string()), actual.name)
+    }
+
+    #[test]
+    fn test_to_json_value_should_succeed() {
+        let expected = json!({
+            "resourceType": "ValueSet",
+            "url": "http://example.org/vs",
+            "status": "draft",
+            "compose": {
+                "include": [
+                    {
+                        "system": "http://example.org/cs",
+                        "filter": [{"property": "concept", "op": "descendent-of", "value": "a"}]
+                    },
+                    {
+                        "valueSet": ["http://example.org/other-vs"]
+                    }
+                ]
+            }
+        });
+
+        let value_set = ValueSetBuilder::default()
+            .url("http://example.org/vs")
+            .add_include(
+                ValueSetConceptSetBuilder::default()
+                    .system("http://example.org/cs")
+                    .add_filter("concept", FilterOperator::DescendentOf, "a")
+                    .build(),
+            )
+            .add_include(
+                ValueSetConceptSetBuilder::default()
+                    .add_value_set("http://example.org/other-vs")
+                    .build(),
+            )
+            .build();
+
+        let actual = value_set.to_json_value().unwrap();
+
+        assert_eq!(expected, actual)
+    }
+}
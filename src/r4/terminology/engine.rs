@@ -0,0 +1,1168 @@
+use std::{
+    cmp::Ordering,
+    collections::{HashMap, HashSet},
+    path::Path,
+};
+
+use crate::{
+    FhirError,
+    r4::{
+        elements::{CodeableConcept, Coding, DateTimePrecision, FhirDateTime},
+        resources::{
+            CodeSystem, CodeSystemConcept, CodeSystemConceptProperty, CodeSystemContentMode,
+            ConceptDesignation, ConceptMap, FilterOperator, ResourceType, ValueSet,
+            ValueSetConceptSet, ValueSetExpansion, ValueSetExpansionContains,
+            ValueSetExpansionParameter, ValueSetFilter, load_json_dir,
+        },
+    },
+};
+
+/// The outcome of `$validate-code`.
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct ValidateCodeResult {
+    pub result: bool,
+    pub display: Option<String>,
+    pub message: Option<String>,
+}
+
+/// The outcome of `$lookup`, `parent` and `child` are reported as properties.
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct LookupResult {
+    pub name: Option<String>,
+    pub version: Option<String>,
+    pub display: Option<String>,
+    pub designation: Vec<ConceptDesignation>,
+    pub property: Vec<CodeSystemConceptProperty>,
+}
+
+#[derive(Debug, Clone)]
+struct ConceptEntry {
+    code: String,
+    display: Option<String>,
+    designation: Option<Vec<ConceptDesignation>>,
+    property: Vec<CodeSystemConceptProperty>,
+    parents: Vec<String>,
+    children: Vec<String>,
+    inactive: bool,
+    r#abstract: bool,
+}
+
+#[derive(Debug)]
+struct IndexedCodeSystem {
+    resource: CodeSystem,
+    concepts: HashMap<String, ConceptEntry>,
+    order: Vec<String>,
+    case_sensitive: bool,
+}
+
+impl IndexedCodeSystem {
+    fn new(resource: CodeSystem) -> Self {
+        let case_sensitive = resource.case_sensitive.unwrap_or(true);
+        let mut indexed = IndexedCodeSystem {
+            resource,
+            concepts: HashMap::new(),
+            order: Vec::new(),
+            case_sensitive,
+        };
+
+        let concepts = indexed.resource.concept.clone().unwrap_or_default();
+        indexed.index_concepts(&concepts, None);
+
+        let links: Vec<(String, String)> = indexed
+            .concepts
+            .iter()
+            .flat_map(|(key, entry)| entry.parents.iter().map(|p| (p.clone(), key.clone())))
+            .collect();
+        for (parent, child) in links {
+            if let Some(entry) = indexed.concepts.get_mut(&parent)
+                && !entry.children.contains(&child)
+            {
+                entry.children.push(child);
+            }
+        }
+
+        indexed
+    }
+
+    fn index_concepts(&mut self, concepts: &[CodeSystemConcept], parent: Option<&str>) {
+        for concept in concepts {
+            let key = self.key(&concept.code);
+            let property = concept.property.clone().unwrap_or_default();
+            let mut parents: Vec<String> = parent.map(|p| vec![p.to_string()]).unwrap_or_default();
+            for p in property
+                .iter()
+                .filter(|p| p.code == "parent" || p.code == "subsumedBy")
+            {
+                if let Some(code) = p.value_code.as_deref() {
+                    let parent_key = self.key(code);
+                    if !parents.contains(&parent_key) {
+                        parents.push(parent_key);
+                    }
+                }
+            }
+
+            let inactive = property.iter().any(|p| {
+                (p.code == "status" && p.value_code.as_deref() == Some("retired"))
+                    || (p.code == "inactive" && p.value_boolean == Some(true))
+            });
+            let r#abstract = property
+                .iter()
+                .any(|p| p.code == "notSelectable" && p.value_boolean == Some(true));
+
+            match self.concepts.get_mut(&key) {
+                Some(existing) => {
+                    for p in parents {
+                        if !existing.parents.contains(&p) {
+                            existing.parents.push(p);
+                        }
+                    }
+                }
+                None => {
+                    self.order.push(key.clone());
+                    self.concepts.insert(
+                        key.clone(),
+                        ConceptEntry {
+                            code: concept.code.clone(),
+                            display: concept.display.clone(),
+                            designation: concept.designation.clone(),
+                            property,
+                            parents,
+                            children: Vec::new(),
+                            inactive,
+                            r#abstract,
+                        },
+                    );
+                }
+            }
+
+            if let Some(children) = &concept.concept {
+                self.index_concepts(children, Some(&key));
+            }
+        }
+    }
+
+    fn key(&self, code: &str) -> String {
+        if self.case_sensitive {
+            code.to_string()
+        } else {
+            code.to_lowercase()
+        }
+    }
+
+    fn url(&self) -> &str {
+        self.resource.url.as_deref().unwrap_or_default()
+    }
+
+    fn get(&self, code: &str) -> Option<&ConceptEntry> {
+        self.concepts.get(&self.key(code))
+    }
+
+    fn walk(&self, code: &str, next: fn(&ConceptEntry) -> &Vec<String>) -> HashSet<String> {
+        let mut visited = HashSet::new();
+        let mut queue = vec![self.key(code)];
+        while let Some(key) = queue.pop() {
+            if let Some(entry) = self.concepts.get(&key) {
+                for n in next(entry) {
+                    if visited.insert(n.clone()) {
+                        queue.push(n.clone());
+                    }
+                }
+            }
+        }
+        visited
+    }
+
+    fn descendants(&self, code: &str) -> HashSet<String> {
+        self.walk(code, |e| &e.children)
+    }
+
+    fn ancestors(&self, code: &str) -> HashSet<String> {
+        self.walk(code, |e| &e.parents)
+    }
+
+    fn expanded(&self, entry: &ConceptEntry, display: Option<String>) -> ExpandedCode {
+        ExpandedCode {
+            system: self.url().to_string(),
+            version: self.resource.version.clone(),
+            code: entry.code.clone(),
+            display: display.or_else(|| entry.display.clone()),
+            designation: entry.designation.clone(),
+            inactive: entry.inactive,
+            r#abstract: entry.r#abstract,
+        }
+    }
+}
+
+fn property_value(property: &CodeSystemConceptProperty) -> Option<String> {
+    property
+        .value_code
+        .clone()
+        .or_else(|| property.value_string.clone())
+        .or_else(|| property.value_coding.as_ref().and_then(|c| c.code.clone()))
+        .or_else(|| property.value_boolean.map(|b| b.to_string()))
+        .or_else(|| property.value_integer.map(|i| i.to_string()))
+        .or_else(|| property.value_date_time.clone())
+        .or_else(|| property.value_decimal.map(|d| d.to_string()))
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct ExpandedCode {
+    system: String,
+    version: Option<String>,
+    code: String,
+    display: Option<String>,
+    designation: Option<Vec<ConceptDesignation>>,
+    inactive: bool,
+    r#abstract: bool,
+}
+
+impl ExpandedCode {
+    fn key(&self) -> (String, String) {
+        (self.system.clone(), self.code.clone())
+    }
+
+    fn to_contains(&self) -> ValueSetExpansionContains {
+        ValueSetExpansionContains {
+            system: Some(self.system.clone()),
+            version: self.version.clone(),
+            code: Some(self.code.clone()),
+            display: self.display.clone(),
+            designation: self.designation.clone(),
+            inactive: self.inactive.then_some(true),
+            r#abstract: self.r#abstract.then_some(true),
+            ..Default::default()
+        }
+    }
+}
+
+/// An in-memory terminology service over locally loaded `CodeSystem`,
+/// `ValueSet` and `ConceptMap` resources, implementing `$expand`,
+/// `$validate-code`, `$lookup` and `$translate`.
+#[derive(Debug, Default)]
+pub struct TerminologyEngine {
+    code_systems: HashMap<String, Vec<IndexedCodeSystem>>,
+    value_sets: HashMap<String, Vec<ValueSet>>,
+    concept_maps: HashMap<String, Vec<ConceptMap>>,
+}
+
+impl TerminologyEngine {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add_code_system(&mut self, code_system: CodeSystem) -> Result<(), FhirError> {
+        let Some(url) = code_system.url.clone() else {
+            return Err(terminology_error("CodeSystem must have a url"));
+        };
+        let versions = self.code_systems.entry(url).or_default();
+        versions.retain(|cs| cs.resource.version != code_system.version);
+        versions.push(IndexedCodeSystem::new(code_system));
+        Ok(())
+    }
+
+    pub fn add_value_set(&mut self, value_set: ValueSet) -> Result<(), FhirError> {
+        let Some(url) = value_set.url.clone() else {
+            return Err(terminology_error("ValueSet must have a url"));
+        };
+        let versions = self.value_sets.entry(url).or_default();
+        versions.retain(|vs| vs.version != value_set.version);
+        versions.push(value_set);
+        Ok(())
+    }
+
+    pub fn add_concept_map(&mut self, concept_map: ConceptMap) -> Result<(), FhirError> {
+        let Some(url) = concept_map.url.clone() else {
+            return Err(terminology_error("ConceptMap must have a url"));
+        };
+        let versions = self.concept_maps.entry(url).or_default();
+        versions.retain(|cm| cm.version != concept_map.version);
+        versions.push(concept_map);
+        Ok(())
+    }
+
+    /// Adds a `CodeSystem`, `ValueSet` or `ConceptMap` from its JSON representation, returns
+    /// `false` when the resource type is not a terminology resource.
+    pub fn add_json(&mut self, data: &str) -> Result<bool, FhirError> {
+        let value: serde_json::Value = serde_json::from_str(data)?;
+        match value.get("resourceType").and_then(|t| t.as_str()) {
+            Some(CodeSystem::TYPE) => self.add_code_system(serde_json::from_value(value)?)?,
+            Some(ValueSet::TYPE) => self.add_value_set(serde_json::from_value(value)?)?,
+            Some(ConceptMap::TYPE) => self.add_concept_map(serde_json::from_value(value)?)?,
+            _ => return Ok(false),
+        }
+        Ok(true)
+    }
+
+    /// Loads every terminology resource from the `.json` files in a directory,
+    /// returns the number of resources that were loaded.
+    pub fn load_dir(&mut self, path: impl AsRef<Path>) -> Result<usize, FhirError> {
+        load_json_dir(path.as_ref(), |data| self.add_json(data))
+    }
+
+    /// Returns the requested version of a code system, or the latest version
+    /// when no version is pinned.
+    pub fn code_system(&self, url: &str, version: Option<&str>) -> Option<&CodeSystem> {
+        self.indexed_code_system(url, version)
+            .map(|cs| &cs.resource)
+    }
+
+    /// Resolves a canonical (`url` or `url|version`) to a value set.
+    pub fn value_set(&self, canonical: &str) -> Option<&ValueSet> {
+        let (url, version) = split_canonical(canonical);
+        let versions = self.value_sets.get(url)?;
+        match version {
+            Some(v) => versions.iter().find(|vs| vs.version.as_deref() == Some(v)),
+            None => versions
+                .iter()
+                .max_by(|a, b| compare_versions(a.version.as_deref(), b.version.as_deref())),
+        }
+    }
+
+    /// Resolves a canonical (`url` or `url|version`) to a concept map.
+    pub fn concept_map(&self, canonical: &str) -> Option<&ConceptMap> {
+        let (url, version) = split_canonical(canonical);
+        let versions = self.concept_maps.get(url)?;
+        match version {
+            Some(v) => versions.iter().find(|cm| cm.version.as_deref() == Some(v)),
+            None => versions
+                .iter()
+                .max_by(|a, b| compare_versions(a.version.as_deref(), b.version.as_deref())),
+        }
+    }
+
+    /// Returns the latest version of every loaded concept map.
+    pub fn concept_maps(&self) -> impl Iterator<Item = &ConceptMap> {
+        self.concept_maps.values().filter_map(|versions| {
+            versions
+                .iter()
+                .max_by(|a, b| compare_versions(a.version.as_deref(), b.version.as_deref()))
+        })
+    }
+
+    fn indexed_code_system(&self, url: &str, version: Option<&str>) -> Option<&IndexedCodeSystem> {
+        let versions = self.code_systems.get(url)?;
+        match version {
+            Some(v) => versions
+                .iter()
+                .find(|cs| cs.resource.version.as_deref() == Some(v)),
+            None => versions.iter().max_by(|a, b| {
+                compare_versions(a.resource.version.as_deref(), b.resource.version.as_deref())
+            }),
+        }
+    }
+
+    /// `$expand`: returns a copy of the value set with its expansion populated.
+    pub fn expand(&self, canonical: &str) -> Result<ValueSet, FhirError> {
+        let value_set = self
+            .value_set(canonical)
+            .ok_or_else(|| terminology_error(format!("unknown value set '{canonical}'")))?;
+        let mut expanded = value_set.clone();
+        expanded.expansion = Some(self.expand_value_set(value_set)?);
+        Ok(expanded)
+    }
+
+    pub fn expand_value_set(&self, value_set: &ValueSet) -> Result<ValueSetExpansion, FhirError> {
+        let codes = self.expand_codes(value_set, &mut Vec::new())?;
+
+        let mut used: Vec<String> = Vec::new();
+        for code in &codes {
+            let canonical = match &code.version {
+                Some(v) => format!("{}|{}", code.system, v),
+                None => code.system.clone(),
+            };
+            if !used.contains(&canonical) {
+                used.push(canonical);
+            }
+        }
+
+        Ok(ValueSetExpansion {
+            timestamp: expansion_timestamp(),
+            total: Some(codes.len() as u32),
+            parameter: (!used.is_empty()).then(|| {
+                used.into_iter()
+                    .map(|uri| ValueSetExpansionParameter {
+                        name: "used-codesystem".to_string(),
+                        value_uri: Some(uri),
+                        ..Default::default()
+                    })
+                    .collect()
+            }),
+            contains: Some(codes.iter().map(ExpandedCode::to_contains).collect()),
+            ..Default::default()
+        })
+    }
+
+    fn expand_codes(
+        &self,
+        value_set: &ValueSet,
+        stack: &mut Vec<String>,
+    ) -> Result<Vec<ExpandedCode>, FhirError> {
+        let url = value_set.url.clone().unwrap_or_default();
+        if stack.contains(&url) {
+            return Err(terminology_error(format!(
+                "value set '{url}' includes itself via {}",
+                stack.join(" -> ")
+            )));
+        }
+
+        let Some(compose) = &value_set.compose else {
+            return match &value_set.expansion {
+                Some(expansion) => Ok(flatten_contains(
+                    expansion.contains.as_deref().unwrap_or_default(),
+                )),
+                None => Err(terminology_error(format!(
+                    "value set '{url}' has neither a compose nor an expansion"
+                ))),
+            };
+        };
+
+        stack.push(url);
+        let mut codes: Vec<ExpandedCode> = Vec::new();
+        let mut seen = HashSet::new();
+        for include in &compose.include {
+            for code in self.expand_concept_set(include, stack)? {
+                if seen.insert(code.key()) {
+                    codes.push(code);
+                }
+            }
+        }
+
+        for exclude in compose.exclude.as_deref().unwrap_or_default() {
+            let excluded: HashSet<_> = self
+                .expand_concept_set(exclude, stack)?
+                .iter()
+                .map(ExpandedCode::key)
+                .collect();
+            codes.retain(|c| !excluded.contains(&c.key()));
+        }
+        stack.pop();
+
+        if compose.inactive == Some(false) {
+            codes.retain(|c| !c.inactive);
+        }
+
+        Ok(codes)
+    }
+
+    fn expand_concept_set(
+        &self,
+        set: &ValueSetConceptSet,
+        stack: &mut Vec<String>,
+    ) -> Result<Vec<ExpandedCode>, FhirError> {
+        let mut result: Option<Vec<ExpandedCode>> = None;
+
+        if let Some(system) = &set.system {
+            let codes = match self.indexed_code_system(system, set.version.as_deref()) {
+                Some(cs) => self.codes_from_system(cs, set)?,
+                None if set.filter.is_none() && set.concept.is_some() => set
+                    .concept
+                    .iter()
+                    .flatten()
+                    .map(|c| ExpandedCode {
+                        system: system.clone(),
+                        version: set.version.clone(),
+                        code: c.code.clone(),
+                        display: c.display.clone(),
+                        designation: c.designation.clone(),
+                        inactive: false,
+                        r#abstract: false,
+                    })
+                    .collect(),
+                None => {
+                    return Err(terminology_error(match &set.version {
+                        Some(v) => format!("unknown code system '{system}' version '{v}'"),
+                        None => format!("unknown code system '{system}'"),
+                    }));
+                }
+            };
+            result = Some(codes);
+        }
+
+        for canonical in set.value_set.as_deref().unwrap_or_default() {
+            let value_set = self
+                .value_set(canonical)
+                .ok_or_else(|| terminology_error(format!("unknown value set '{canonical}'")))?;
+            let codes = self.expand_codes(value_set, stack)?;
+            result = Some(match result {
+                None => codes,
+                Some(previous) => {
+                    let keys: HashSet<_> = codes.iter().map(ExpandedCode::key).collect();
+                    previous
+                        .into_iter()
+                        .filter(|c| keys.contains(&c.key()))
+                        .collect()
+                }
+            });
+        }
+
+        Ok(result.unwrap_or_default())
+    }
+
+    fn codes_from_system(
+        &self,
+        cs: &IndexedCodeSystem,
+        set: &ValueSetConceptSet,
+    ) -> Result<Vec<ExpandedCode>, FhirError> {
+        let mut candidates: Vec<(String, Option<String>)> = match &set.concept {
+            Some(concepts) => {
+                let mut listed = Vec::new();
+                for c in concepts {
+                    match cs.get(&c.code) {
+                        Some(_) => listed.push((cs.key(&c.code), c.display.clone())),
+                        None if cs.resource.content == CodeSystemContentMode::Complete => {
+                            return Err(terminology_error(format!(
+                                "code '{}' is not defined in code system '{}'",
+                                c.code,
+                                cs.url()
+                            )));
+                        }
+                        None => {}
+                    }
+                }
+                listed
+            }
+            None => cs.order.iter().map(|k| (k.clone(), None)).collect(),
+        };
+
+        for filter in set.filter.as_deref().unwrap_or_default() {
+            let keep = apply_filter(cs, filter)?;
+            candidates.retain(|(key, _)| keep(key));
+        }
+
+        Ok(candidates
+            .into_iter()
+            .filter_map(|(key, display)| cs.concepts.get(&key).map(|e| cs.expanded(e, display)))
+            .collect())
+    }
+
+    /// `$validate-code` for a single coding against a value set canonical.
+    pub fn validate_code(
+        &self,
+        value_set: &str,
+        coding: &Coding,
+    ) -> Result<ValidateCodeResult, FhirError> {
+        let vs = self
+            .value_set(value_set)
+            .ok_or_else(|| terminology_error(format!("unknown value set '{value_set}'")))?;
+        let codes = self.expand_codes(vs, &mut Vec::new())?;
+        Ok(self.validate_against(value_set, &codes, coding))
+    }
+
+    /// `$validate-code` for a codeable concept, valid when any of its codings is.
+    pub fn validate_codeable_concept(
+        &self,
+        value_set: &str,
+        concept: &CodeableConcept,
+    ) -> Result<ValidateCodeResult, FhirError> {
+        let vs = self
+            .value_set(value_set)
+            .ok_or_else(|| terminology_error(format!("unknown value set '{value_set}'")))?;
+        let codes = self.expand_codes(vs, &mut Vec::new())?;
+
+        let mut messages = Vec::new();
+        for coding in concept.coding.as_deref().unwrap_or_default() {
+            let result = self.validate_against(value_set, &codes, coding);
+            if result.result {
+                return Ok(result);
+            }
+            messages.extend(result.message);
+        }
+
+        Ok(ValidateCodeResult {
+            result: false,
+            display: None,
+            message: Some(if messages.is_empty() {
+                "codeable concept has no codings".to_string()
+            } else {
+                messages.join("; ")
+            }),
+        })
+    }
+
+    fn validate_against(
+        &self,
+        value_set: &str,
+        codes: &[ExpandedCode],
+        coding: &Coding,
+    ) -> ValidateCodeResult {
+        let (Some(system), Some(code)) = (&coding.system, &coding.code) else {
+            return ValidateCodeResult {
+                result: false,
+                message: Some("coding must have a system and a code".to_string()),
+                ..Default::default()
+            };
+        };
+
+        let case_sensitive = self
+            .indexed_code_system(system, coding.version.as_deref())
+            .is_none_or(|cs| cs.case_sensitive);
+        let found = codes.iter().find(|c| {
+            &c.system == system
+                && if case_sensitive {
+                    &c.code == code
+                } else {
+                    c.code.eq_ignore_ascii_case(code)
+                }
+        });
+
+        let Some(found) = found else {
+            return ValidateCodeResult {
+                result: false,
+                message: Some(format!(
+                    "code '{code}' from system '{system}' is not in value set '{value_set}'"
+                )),
+                ..Default::default()
+            };
+        };
+
+        if let (Some(expected), Some(actual)) = (&found.version, &coding.version)
+            && expected != actual
+        {
+            return ValidateCodeResult {
+                result: false,
+                display: found.display.clone(),
+                message: Some(format!(
+                    "code '{code}' is in version '{expected}' of '{system}', not '{actual}'"
+                )),
+            };
+        }
+
+        let message = coding.display.as_ref().and_then(|display| {
+            let known = found
+                .display
+                .iter()
+                .chain(found.designation.iter().flatten().map(|d| &d.value));
+            let mut known = known.peekable();
+            if known.peek().is_none() || known.any(|d| d == display) {
+                None
+            } else {
+                Some(format!(
+                    "display '{display}' does not match '{}' for code '{code}'",
+                    found.display.clone().unwrap_or_default()
+                ))
+            }
+        });
+
+        ValidateCodeResult {
+            result: true,
+            display: found.display.clone(),
+            message,
+        }
+    }
+
+    /// `$lookup` of a code in a (optionally pinned) code system.
+    pub fn lookup(
+        &self,
+        system: &str,
+        version: Option<&str>,
+        code: &str,
+    ) -> Result<LookupResult, FhirError> {
+        let cs = self
+            .indexed_code_system(system, version)
+            .ok_or_else(|| terminology_error(format!("unknown code system '{system}'")))?;
+        let entry = cs.get(code).ok_or_else(|| {
+            terminology_error(format!("code '{code}' is not defined in '{system}'"))
+        })?;
+
+        let relation = |name: &str, key: &String| CodeSystemConceptProperty {
+            code: name.to_string(),
+            value_code: cs.concepts.get(key).map(|e| e.code.clone()),
+            ..Default::default()
+        };
+        let mut property: Vec<CodeSystemConceptProperty> = entry
+            .property
+            .iter()
+            .filter(|p| p.code != "parent" && p.code != "child")
+            .cloned()
+            .collect();
+        property.extend(entry.parents.iter().map(|p| relation("parent", p)));
+        property.extend(entry.children.iter().map(|c| relation("child", c)));
+
+        Ok(LookupResult {
+            name: cs.resource.name.clone(),
+            version: cs.resource.version.clone(),
+            display: entry.display.clone(),
+            designation: entry.designation.clone().unwrap_or_default(),
+            property,
+        })
+    }
+
+    /// Returns `true` when `code` is `ancestor` or one of its descendants.
+    pub fn subsumed_by(&self, system: &str, code: &str, ancestor: &str) -> bool {
+        self.indexed_code_system(system, None).is_some_and(|cs| {
+            cs.get(code).is_some()
+                && (cs.key(code) == cs.key(ancestor)
+                    || cs.ancestors(code).contains(&cs.key(ancestor)))
+        })
+    }
+}
+
+type ConceptPredicate<'a> = Box<dyn Fn(&String) -> bool + 'a>;
+
+fn apply_filter<'a>(
+    cs: &'a IndexedCodeSystem,
+    filter: &'a ValueSetFilter,
+) -> Result<ConceptPredicate<'a>, FhirError> {
+    let hierarchical = filter.property == "concept" || filter.property == "code";
+    let value = filter.value.as_str();
+    let list = || -> HashSet<String> { value.split(',').map(|v| cs.key(v.trim())).collect() };
+
+    Ok(match (&filter.op, hierarchical) {
+        (FilterOperator::IsA, true) => {
+            let mut set = cs.descendants(value);
+            set.insert(cs.key(value));
+            Box::new(move |k| set.contains(k))
+        }
+        (FilterOperator::DescendentOf, true) => {
+            let set = cs.descendants(value);
+            Box::new(move |k| set.contains(k))
+        }
+        (FilterOperator::IsNotA, true) => {
+            let mut set = cs.descendants(value);
+            set.insert(cs.key(value));
+            Box::new(move |k| !set.contains(k))
+        }
+        (FilterOperator::Generalizes, true) => {
+            let mut set = cs.ancestors(value);
+            set.insert(cs.key(value));
+            Box::new(move |k| set.contains(k))
+        }
+        (FilterOperator::Equal, true) => {
+            let key = cs.key(value);
+            Box::new(move |k| *k == key)
+        }
+        (FilterOperator::In, true) => {
+            let set = list();
+            Box::new(move |k| set.contains(k))
+        }
+        (FilterOperator::NotIn, true) => {
+            let set = list();
+            Box::new(move |k| !set.contains(k))
+        }
+        (FilterOperator::Equal, false) => {
+            Box::new(move |k| concept_property(cs, k, &filter.property).is_some_and(|v| v == value))
+        }
+        (FilterOperator::In, false) => {
+            let set: HashSet<String> = value.split(',').map(|v| v.trim().to_string()).collect();
+            Box::new(move |k| {
+                concept_property(cs, k, &filter.property).is_some_and(|v| set.contains(&v))
+            })
+        }
+        (FilterOperator::NotIn, false) => {
+            let set: HashSet<String> = value.split(',').map(|v| v.trim().to_string()).collect();
+            Box::new(move |k| {
+                concept_property(cs, k, &filter.property).is_none_or(|v| !set.contains(&v))
+            })
+        }
+        (FilterOperator::Exists, _) => {
+            let exists = value == "true";
+            Box::new(move |k| concept_property(cs, k, &filter.property).is_some() == exists)
+        }
+        (op, _) => {
+            return Err(terminology_error(format!(
+                "filter '{}' {op:?} '{}' is not supported",
+                filter.property, filter.value
+            )));
+        }
+    })
+}
+
+fn concept_property(cs: &IndexedCodeSystem, key: &String, property: &str) -> Option<String> {
+    let entry = cs.concepts.get(key)?;
+    if property == "display" {
+        return entry.display.clone();
+    }
+    entry
+        .property
+        .iter()
+        .find(|p| p.code == property)
+        .and_then(property_value)
+}
+
+fn flatten_contains(contains: &[ValueSetExpansionContains]) -> Vec<ExpandedCode> {
+    let mut codes = Vec::new();
+    for c in contains {
+        if let (Some(system), Some(code)) = (&c.system, &c.code) {
+            codes.push(ExpandedCode {
+                system: system.clone(),
+                version: c.version.clone(),
+                code: code.clone(),
+                display: c.display.clone(),
+                designation: c.designation.clone(),
+                inactive: c.inactive.unwrap_or(false),
+                r#abstract: c.r#abstract.unwrap_or(false),
+            });
+        }
+        codes.extend(flatten_contains(c.contains.as_deref().unwrap_or_default()));
+    }
+    codes
+}
+
+/// Splits a canonical reference into its url and optional version.
+pub fn split_canonical(canonical: &str) -> (&str, Option<&str>) {
+    match canonical.split_once('|') {
+        Some((url, version)) => (url, Some(version)),
+        None => (canonical, None),
+    }
+}
+
+/// Compares dotted version strings numerically where possible.
+fn compare_versions(a: Option<&str>, b: Option<&str>) -> Ordering {
+    match (a, b) {
+        (None, None) => Ordering::Equal,
+        (None, Some(_)) => Ordering::Less,
+        (Some(_), None) => Ordering::Greater,
+        (Some(a), Some(b)) => {
+            let mut left = a.split('.');
+            let mut right = b.split('.');
+            loop {
+                match (left.next(), right.next()) {
+                    (None, None) => return Ordering::Equal,
+                    (None, Some(_)) => return Ordering::Less,
+                    (Some(_), None) => return Ordering::Greater,
+                    (Some(l), Some(r)) => {
+                        let ordering = match (l.parse::<u64>(), r.parse::<u64>()) {
+                            (Ok(l), Ok(r)) => l.cmp(&r),
+                            _ => l.cmp(r),
+                        };
+                        if ordering != Ordering::Equal {
+                            return ordering;
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn expansion_timestamp() -> String {
+    FhirDateTime::now()
+        .truncate(DateTimePrecision::Second)
+        .to_string()
+}
+
+pub(super) fn terminology_error(message: impl Into<String>) -> FhirError {
+    FhirError::TerminologyError(message.into())
+}
+
+#[cfg(test)]
+mod test {
+    use crate::r4::{
+        elements::{CodeableConceptBuilder, CodingBuilder},
+        resources::{
+            CodeSystemBuilder, CodeSystemConceptBuilder, Organization, PublicationStatus,
+            ValueSetBuilder, ValueSetConceptSetBuilder,
+        },
+    };
+
+    use super::*;
+
+    const ORG_TYPE: &str = "http://example.org/CodeSystem/organization-type";
+    const HL7_ORG_TYPE: &str = "http://terminology.hl7.org/CodeSystem/organization-type";
+    const PROVIDER_VS: &str = "http://example.org/ValueSet/provider-organization-type";
+
+    fn engine() -> TerminologyEngine {
+        let mut engine = TerminologyEngine::new();
+        engine
+            .add_json(include_str!(
+                "../../../fixtures/r4/resources/code_system.json"
+            ))
+            .unwrap();
+        engine
+            .add_json(include_str!(
+                "../../../fixtures/r4/resources/value_set.json"
+            ))
+            .unwrap();
+        engine
+    }
+
+    fn codes(value_set: &ValueSet) -> Vec<String> {
+        value_set
+            .expansion
+            .as_ref()
+            .and_then(|e| e.contains.as_ref())
+            .map(|c| c.iter().filter_map(|c| c.code.clone()).collect())
+            .unwrap_or_default()
+    }
+
+    #[test]
+    fn test_expand_with_is_a_filter_and_exclude_should_succeed() {
+        let expanded = engine().expand(PROVIDER_VS).unwrap();
+
+        assert_eq!(vec!["prov", "hosp", "prov"], codes(&expanded));
+        let expansion = expanded.expansion.unwrap();
+        assert_eq!(Some(3), expansion.total);
+        assert_eq!(
+            Some(format!("{ORG_TYPE}|1.0.0")),
+            expansion.parameter.unwrap()[0].value_uri
+        );
+    }
+
+    #[test]
+    fn test_expand_descendent_of_should_exclude_the_code_itself() {
+        let mut engine = engine();
+        engine
+            .add_value_set(
+                ValueSetBuilder::default()
+                    .url("http://example.org/vs/descendants")
+                    .add_include(
+                        ValueSetConceptSetBuilder::default()
+                            .system(ORG_TYPE)
+                            .add_filter("concept", FilterOperator::DescendentOf, "prov")
+                            .build(),
+                    )
+                    .build(),
+            )
+            .unwrap();
+
+        let actual = engine.expand("http://example.org/vs/descendants").unwrap();
+
+        assert_eq!(vec!["hosp", "clinic"], codes(&actual))
+    }
+
+    #[test]
+    fn test_expand_nested_value_sets_should_intersect() {
+        let mut engine = engine();
+        engine
+            .add_value_set(
+                ValueSetBuilder::default()
+                    .url("http://example.org/vs/all")
+                    .add_include(
+                        ValueSetConceptSetBuilder::default()
+                            .system(ORG_TYPE)
+                            .build(),
+                    )
+                    .build(),
+            )
+            .unwrap();
+        engine
+            .add_value_set(
+                ValueSetBuilder::default()
+                    .url("http://example.org/vs/nested")
+                    .add_include(
+                        ValueSetConceptSetBuilder::default()
+                            .system(ORG_TYPE)
+                            .add_filter("concept", FilterOperator::IsNotA, "hosp")
+                            .add_value_set("http://example.org/vs/all")
+                            .build(),
+                    )
+                    .build(),
+            )
+            .unwrap();
+
+        let actual = engine.expand("http://example.org/vs/nested").unwrap();
+
+        assert_eq!(vec!["prov", "clinic", "govt"], codes(&actual))
+    }
+
+    #[test]
+    fn test_expand_recursive_value_set_should_fail() {
+        let mut engine = TerminologyEngine::new();
+        engine
+            .add_value_set(
+                ValueSetBuilder::default()
+                    .url("http://example.org/vs/a")
+                    .add_include(
+                        ValueSetConceptSetBuilder::default()
+                            .add_value_set("http://example.org/vs/a")
+                            .build(),
+                    )
+                    .build(),
+            )
+            .unwrap();
+
+        let actual = engine.expand("http://example.org/vs/a");
+
+        assert!(matches!(actual, Err(FhirError::TerminologyError(_))))
+    }
+
+    #[test]
+    fn test_expand_should_honour_version_pinning() {
+        let mut engine = TerminologyEngine::new();
+        for (version, code) in [("1", "old"), ("2", "new"), ("10", "newest")] {
+            engine
+                .add_code_system(
+                    CodeSystemBuilder::default()
+                        .url("http://example.org/cs")
+                        .version(version)
+                        .status(PublicationStatus::Active)
+                        .add_concept(CodeSystemConceptBuilder::default().code(code).build())
+                        .build(),
+                )
+                .unwrap();
+        }
+        engine
+            .add_value_set(
+                ValueSetBuilder::default()
+                    .url("http://example.org/vs/pinned")
+                    .version("1")
+                    .add_include(
+                        ValueSetConceptSetBuilder::default()
+                            .system("http://example.org/cs")
+                            .version("2")
+                            .build(),
+                    )
+                    .build(),
+            )
+            .unwrap();
+        engine
+            .add_value_set(
+                ValueSetBuilder::default()
+                    .url("http://example.org/vs/latest")
+                    .add_include(
+                        ValueSetConceptSetBuilder::default()
+                            .system("http://example.org/cs")
+                            .build(),
+                    )
+                    .build(),
+            )
+            .unwrap();
+
+        assert_eq!(
+            vec!["new"],
+            codes(&engine.expand("http://example.org/vs/pinned|1").unwrap())
+        );
+        assert_eq!(
+            vec!["newest"],
+            codes(&engine.expand("http://example.org/vs/latest").unwrap())
+        );
+        assert!(engine.expand("http://example.org/vs/pinned|2").is_err())
+    }
+
+    #[test]
+    fn test_validate_code_should_succeed() {
+        let engine = engine();
+        let coding = CodingBuilder::default()
+            .with_system(ORG_TYPE)
+            .with_code("hosp")
+            .with_display("Ziekenhuis")
+            .build();
+
+        let actual = engine.validate_code(PROVIDER_VS, &coding).unwrap();
+
+        assert_eq!(
+            ValidateCodeResult {
+                result: true,
+                display: Some("Hospital".to_string()),
+                message: None,
+            },
+            actual
+        )
+    }
+
+    #[test]
+    fn test_validate_code_with_wrong_display_should_warn() {
+        let engine = engine();
+        let coding = CodingBuilder::default()
+            .with_system(ORG_TYPE)
+            .with_code("hosp")
+            .with_display("Clinic")
+            .build();
+
+        let actual = engine.validate_code(PROVIDER_VS, &coding).unwrap();
+
+        assert!(actual.result);
+        assert!(actual.message.is_some())
+    }
+
+    #[test]
+    fn test_validate_code_outside_value_set_should_fail() {
+        let engine = engine();
+        let coding = CodingBuilder::default()
+            .with_system(ORG_TYPE)
+            .with_code("clinic")
+            .build();
+
+        let actual = engine.validate_code(PROVIDER_VS, &coding).unwrap();
+
+        assert!(!actual.result)
+    }
+
+    #[test]
+    fn test_validate_codeable_concept_on_organization_should_succeed() {
+        let engine = engine();
+        let organization = Organization::from_json(include_str!(
+            "../../../fixtures/r4/resources/organization.json"
+        ))
+        .unwrap();
+
+        let actual = organization
+            .r#type
+            .iter()
+            .flatten()
+            .map(|t| engine.validate_codeable_concept(PROVIDER_VS, t).unwrap())
+            .all(|r| r.result);
+
+        assert!(actual);
+
+        let unknown = CodeableConceptBuilder::default()
+            .add_coding(
+                CodingBuilder::default()
+                    .with_system(HL7_ORG_TYPE)
+                    .with_code("govt")
+                    .build(),
+            )
+            .build();
+        assert!(
+            !engine
+                .validate_codeable_concept(PROVIDER_VS, &unknown)
+                .unwrap()
+                .result
+        )
+    }
+
+    #[test]
+    fn test_lookup_should_succeed() {
+        let actual = engine().lookup(ORG_TYPE, None, "hosp").unwrap();
+
+        assert_eq!(Some("OrganizationType".to_string()), actual.name);
+        assert_eq!(Some("Hospital".to_string()), actual.display);
+        assert_eq!("Ziekenhuis", actual.designation[0].value);
+        assert_eq!(
+            vec![CodeSystemConceptProperty {
+                code: "parent".to_string(),
+                value_code: Some("prov".to_string()),
+                ..Default::default()
+            }],
+            actual.property
+        )
+    }
+
+    #[test]
+    fn test_lookup_unknown_code_should_fail() {
+        let actual = engine().lookup(ORG_TYPE, None, "nope");
+
+        assert!(matches!(actual, Err(FhirError::TerminologyError(_))))
+    }
+
+    #[test]
+    fn test_subsumed_by_should_succeed() {
+        let engine = engine();
+
+        assert!(engine.subsumed_by(ORG_TYPE, "clinic", "prov"));
+        assert!(!engine.subsumed_by(ORG_TYPE, "govt", "prov"))
+    }
+
+    #[test]
+    fn test_load_dir_should_succeed() {
+        let mut engine = TerminologyEngine::new();
+
+        let actual = engine
+            .load_dir(concat!(
+                env!("CARGO_MANIFEST_DIR"),
+                "/fixtures/r4/resources"
+            ))
+            .unwrap();
+
+        assert_eq!(3, actual);
+        assert!(engine.code_system(ORG_TYPE, Some("1.0.0")).is_some())
+    }
+
+    #[test]
+    fn test_expansion_timestamp_should_be_a_date_time() {
+        let actual = expansion_timestamp();
+
+        assert_eq!(20, actual.len());
+        assert!(actual.ends_with('Z'))
+    }
+}
@@ -0,0 +1,373 @@
+use std::{collections::HashMap, path::Path};
+
+use crate::{
+    FhirError,
+    r4::{
+        elements::{GetIdentifiers, Identifier},
+        resources::{NamingSystem, NamingSystemIdentifierType, ResourceType, load_json_dir},
+        terminology::terminology_error,
+    },
+};
+
+pub const OID_URN_PREFIX: &str = "urn:oid:";
+pub const UUID_URN_PREFIX: &str = "urn:uuid:";
+
+/// Returns `true` for a bare ISO OID such as `2.16.840.1.113883`.
+pub fn is_oid(value: &str) -> bool {
+    let mut arcs = value.split('.');
+    let first_arc_valid = arcs
+        .next()
+        .is_some_and(|arc| matches!(arc, "0" | "1" | "2"));
+    let rest: Vec<&str> = arcs.collect();
+    first_arc_valid
+        && !rest.is_empty()
+        && rest.iter().all(|arc| {
+            !arc.is_empty()
+                && arc.chars().all(|c| c.is_ascii_digit())
+                && (arc.len() == 1 || !arc.starts_with('0'))
+        })
+}
+
+/// Converts a bare OID into its `urn:oid:` URI form.
+pub fn oid_to_uri(oid: &str) -> Option<String> {
+    is_oid(oid).then(|| format!("{OID_URN_PREFIX}{oid}"))
+}
+
+/// Extracts the bare OID from a `urn:oid:` URI, the prefix is matched
+/// case-insensitively as URN namespaces are.
+pub fn uri_to_oid(uri: &str) -> Option<&str> {
+    let prefix = uri.get(..OID_URN_PREFIX.len())?;
+    let oid = &uri[OID_URN_PREFIX.len()..];
+    (prefix.eq_ignore_ascii_case(OID_URN_PREFIX) && is_oid(oid)).then_some(oid)
+}
+
+/// Normalizes the spelling of an identifier system without consulting a
+/// registry: bare OIDs become `urn:oid:` URIs and URN prefixes are lowercased.
+pub fn normalize_system(system: &str) -> String {
+    let system = system.trim();
+    if is_oid(system) {
+        return format!("{OID_URN_PREFIX}{system}");
+    }
+    if let Some(oid) = uri_to_oid(system) {
+        return format!("{OID_URN_PREFIX}{oid}");
+    }
+    match system.get(..UUID_URN_PREFIX.len()) {
+        Some(prefix) if prefix.eq_ignore_ascii_case(UUID_URN_PREFIX) => system.to_lowercase(),
+        _ => system.to_string(),
+    }
+}
+
+fn unique_id_key(r#type: &NamingSystemIdentifierType, value: &str) -> String {
+    match r#type {
+        NamingSystemIdentifierType::Uuid if !value.contains(':') => {
+            format!("{UUID_URN_PREFIX}{}", value.to_lowercase())
+        }
+        NamingSystemIdentifierType::Other => value.trim().to_string(),
+        _ => normalize_system(value),
+    }
+}
+
+/// An identifier whose system is missing or not known to the registry.
+#[derive(Debug, PartialEq, Clone)]
+pub struct UnknownIdentifierSystem {
+    pub path: String,
+    pub system: Option<String>,
+    pub value: Option<String>,
+}
+
+/// A registry of identifier systems backed by `NamingSystem` resources, used to
+/// recognise the URI, `urn:oid:` and bare OID spellings of the same system.
+#[derive(Debug, Default)]
+pub struct IdentifierSystemRegistry {
+    naming_systems: Vec<NamingSystem>,
+    index: HashMap<String, usize>,
+}
+
+impl IdentifierSystemRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add_naming_system(&mut self, naming_system: NamingSystem) -> Result<(), FhirError> {
+        if naming_system.unique_id.is_empty() {
+            return Err(terminology_error(format!(
+                "NamingSystem '{}' must have a unique id",
+                naming_system.name
+            )));
+        }
+
+        let position = self.naming_systems.len();
+        for unique_id in &naming_system.unique_id {
+            self.index
+                .insert(unique_id_key(&unique_id.r#type, &unique_id.value), position);
+        }
+        self.naming_systems.push(naming_system);
+        Ok(())
+    }
+
+    /// Adds a `NamingSystem` from its JSON representation, returns `false`
+    /// when the resource is of another type.
+    pub fn add_json(&mut self, data: &str) -> Result<bool, FhirError> {
+        let value: serde_json::Value = serde_json::from_str(data)?;
+        if value.get("resourceType").and_then(|t| t.as_str()) != Some(NamingSystem::TYPE) {
+            return Ok(false);
+        }
+        self.add_naming_system(serde_json::from_value(value)?)?;
+        Ok(true)
+    }
+
+    /// Loads every `NamingSystem` from the `.json` files in a directory,
+    /// returns the number of naming systems that were loaded.
+    pub fn load_dir(&mut self, path: impl AsRef<Path>) -> Result<usize, FhirError> {
+        load_json_dir(path.as_ref(), |data| self.add_json(data))
+    }
+
+    /// The naming system registered for any spelling of `system`.
+    pub fn naming_system(&self, system: &str) -> Option<&NamingSystem> {
+        self.index
+            .get(&normalize_system(system))
+            .and_then(|i| self.naming_systems.get(*i))
+    }
+
+    pub fn is_known(&self, system: &str) -> bool {
+        self.naming_system(system).is_some()
+    }
+
+    /// The canonical spelling of `system`: the preferred URI of its naming
+    /// system, or the normalized form when the system is unknown.
+    pub fn canonicalize(&self, system: &str) -> String {
+        self.to_uri(system)
+            .unwrap_or_else(|| normalize_system(system))
+    }
+
+    /// The bare OID of `system`, from its naming system or its `urn:oid:` form.
+    pub fn to_oid(&self, system: &str) -> Option<String> {
+        self.naming_system(system)
+            .and_then(|ns| ns.preferred_unique_id(NamingSystemIdentifierType::Oid))
+            .map(str::to_string)
+            .or_else(|| uri_to_oid(&normalize_system(system)).map(str::to_string))
+    }
+
+    /// The URI of `system`: the preferred `uri` unique id of its naming
+    /// system, falling back to the `urn:oid:` or `urn:uuid:` form.
+    pub fn to_uri(&self, system: &str) -> Option<String> {
+        let Some(naming_system) = self.naming_system(system) else {
+            let normalized = normalize_system(system);
+            return (normalized.starts_with(OID_URN_PREFIX)
+                || normalized.starts_with(UUID_URN_PREFIX))
+            .then_some(normalized);
+        };
+
+        naming_system
+            .preferred_unique_id(NamingSystemIdentifierType::Uri)
+            .map(str::to_string)
+            .or_else(|| {
+                naming_system
+                    .preferred_unique_id(NamingSystemIdentifierType::Oid)
+                    .map(normalize_system)
+            })
+            .or_else(|| {
+                naming_system
+                    .preferred_unique_id(NamingSystemIdentifierType::Uuid)
+                    .map(|uuid| unique_id_key(&NamingSystemIdentifierType::Uuid, uuid))
+            })
+    }
+
+    /// Rewrites the system of every identifier to its canonical spelling.
+    pub fn canonicalize_identifiers(&self, identifiers: &mut [Identifier]) {
+        for identifier in identifiers {
+            if let Some(system) = &identifier.system {
+                identifier.system = Some(self.canonicalize(system));
+            }
+        }
+    }
+
+    /// Flags identifiers on a resource whose system is missing or unknown.
+    pub fn unknown_systems<R>(&self, resource: &R) -> Vec<UnknownIdentifierSystem>
+    where
+        R: GetIdentifiers + ResourceType,
+    {
+        resource
+            .get_identifiers()
+            .into_iter()
+            .enumerate()
+            .filter(|(_, identifier)| {
+                identifier
+                    .system
+                    .as_deref()
+                    .is_none_or(|system| !self.is_known(system))
+            })
+            .map(|(i, identifier)| UnknownIdentifierSystem {
+                path: format!("{}.identifier[{i}].system", R::TYPE),
+                system: identifier.system.clone(),
+                value: identifier.value.clone(),
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::r4::{
+        elements::IdentifierBuilder,
+        resources::{
+            Location, NamingSystemBuilder, NamingSystemUniqueId, Organization, Practitioner,
+        },
+    };
+
+    use super::*;
+
+    const UZOVI_URI: &str = "http://fhir.nl/fhir/NamingSystem/uzovi";
+    const UZOVI_OID: &str = "2.16.840.1.113883.2.4.6.4";
+
+    fn registry() -> IdentifierSystemRegistry {
+        let mut registry = IdentifierSystemRegistry::new();
+        registry
+            .add_json(include_str!(
+                "../../../fixtures/r4/resources/naming_system.json"
+            ))
+            .unwrap();
+        registry
+    }
+
+    #[test]
+    fn test_is_oid_should_succeed() {
+        assert!(is_oid(UZOVI_OID));
+        assert!(is_oid("1.0"));
+        assert!(!is_oid("3.1"));
+        assert!(!is_oid("2"));
+        assert!(!is_oid("2.16.01"));
+        assert!(!is_oid("2..16"));
+        assert!(!is_oid("http://example.org"))
+    }
+
+    #[test]
+    fn test_oid_uri_conversion_should_succeed() {
+        assert_eq!(Some(format!("urn:oid:{UZOVI_OID}")), oid_to_uri(UZOVI_OID));
+        assert_eq!(
+            Some(UZOVI_OID),
+            uri_to_oid("URN:OID:2.16.840.1.113883.2.4.6.4")
+        );
+        assert_eq!(None, uri_to_oid("urn:uuid:2.16"));
+        assert_eq!(None, oid_to_uri("not-an-oid"))
+    }
+
+    #[test]
+    fn test_canonicalize_should_succeed() {
+        let registry = registry();
+
+        for spelling in [
+            UZOVI_URI,
+            UZOVI_OID,
+            "urn:oid:2.16.840.1.113883.2.4.6.4",
+            " URN:oid:2.16.840.1.113883.2.4.6.4 ",
+        ] {
+            assert_eq!(UZOVI_URI, registry.canonicalize(spelling))
+        }
+        assert_eq!("urn:oid:1.2.3", registry.canonicalize("1.2.3"));
+        assert_eq!(
+            "http://example.org/orgs",
+            registry.canonicalize("http://example.org/orgs")
+        )
+    }
+
+    #[test]
+    fn test_to_oid_and_to_uri_should_succeed() {
+        let registry = registry();
+
+        assert_eq!(Some(UZOVI_OID.to_string()), registry.to_oid(UZOVI_URI));
+        assert_eq!(Some(UZOVI_URI.to_string()), registry.to_uri(UZOVI_OID));
+        assert_eq!(Some("1.2.3".to_string()), registry.to_oid("urn:oid:1.2.3"));
+        assert_eq!(None, registry.to_oid("http://example.org/orgs"));
+        assert_eq!(None, registry.to_uri("http://example.org/orgs"))
+    }
+
+    #[test]
+    fn test_canonicalize_identifiers_should_succeed() {
+        let registry = registry();
+        let mut identifiers = vec![
+            IdentifierBuilder::default()
+                .with_system(UZOVI_OID)
+                .with_value("3311")
+                .build(),
+            IdentifierBuilder::default().with_value("no-system").build(),
+        ];
+
+        registry.canonicalize_identifiers(&mut identifiers);
+
+        assert_eq!(Some(UZOVI_URI.to_string()), identifiers[0].system);
+        assert_eq!(None, identifiers[1].system)
+    }
+
+    #[test]
+    fn test_unknown_systems_should_flag_identifiers() {
+        let registry = registry();
+        let mut organization = Organization::from_json(include_str!(
+            "../../../fixtures/r4/resources/organization.json"
+        ))
+        .unwrap();
+        organization.identifier.get_or_insert_with(Vec::new).push(
+            IdentifierBuilder::default()
+                .with_system(format!("urn:oid:{UZOVI_OID}"))
+                .with_value("3311")
+                .build(),
+        );
+
+        let actual = registry.unknown_systems(&organization);
+
+        assert_eq!(
+            vec![UnknownIdentifierSystem {
+                path: "Organization.identifier[0].system".to_string(),
+                system: Some("http://example.org/orgs".to_string()),
+                value: Some("ORG-001".to_string()),
+            }],
+            actual
+        )
+    }
+
+    #[test]
+    fn test_unknown_systems_on_practitioner_and_location_should_succeed() {
+        let mut registry = registry();
+        let practitioner = Practitioner::from_json(include_str!(
+            "../../../fixtures/r4/resources/practitioner.json"
+        ))
+        .unwrap();
+        let location =
+            Location::from_json(include_str!("../../../fixtures/r4/resources/location.json"))
+                .unwrap();
+
+        assert_eq!(1, registry.unknown_systems(&practitioner).len());
+        assert_eq!(
+            "Location.identifier[0].system",
+            registry.unknown_systems(&location)[0].path
+        );
+
+        registry
+            .add_naming_system(
+                NamingSystemBuilder::default()
+                    .name("Locations")
+                    .add_unique_id(NamingSystemUniqueId {
+                        value: "http://example.com/locations".to_string(),
+                        ..Default::default()
+                    })
+                    .build(),
+            )
+            .unwrap();
+        assert!(registry.unknown_systems(&location).is_empty())
+    }
+
+    #[test]
+    fn test_load_dir_should_succeed() {
+        let mut registry = IdentifierSystemRegistry::new();
+
+        let actual = registry
+            .load_dir(concat!(
+                env!("CARGO_MANIFEST_DIR"),
+                "/fixtures/r4/resources"
+            ))
+            .unwrap();
+
+        assert_eq!(1, actual);
+        assert!(registry.is_known(UZOVI_OID))
+    }
+}
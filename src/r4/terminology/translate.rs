@@ -0,0 +1,629 @@
+use crate::{
+    FhirError,
+    r4::{
+        elements::{CodeableConcept, Coding, GetCodeableConceptsMut},
+        resources::{
+            ConceptMap, ConceptMapDependsOn, ConceptMapEquivalence, ConceptMapGroup,
+            ConceptMapGroupUnmappedMode,
+        },
+        terminology::{TerminologyEngine, terminology_error},
+    },
+};
+
+/// A single `$translate` match.
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct TranslateMatch {
+    pub equivalence: ConceptMapEquivalence,
+    pub concept: Option<Coding>,
+    pub source: Option<String>,
+    pub comment: Option<String>,
+    pub product: Vec<ConceptMapDependsOn>,
+}
+
+/// The outcome of `$translate`, `result` is true when at least one match is
+/// not `unmatched` or `disjoint`.
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct TranslateResult {
+    pub result: bool,
+    pub message: Option<String>,
+    pub matches: Vec<TranslateMatch>,
+}
+
+impl TranslateResult {
+    /// The target codings of every valid match.
+    pub fn codings(&self) -> Vec<Coding> {
+        self.matches
+            .iter()
+            .filter(|m| m.equivalence.is_match())
+            .filter_map(|m| m.concept.clone())
+            .collect()
+    }
+}
+
+/// How translated codings are written back into a `CodeableConcept`.
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+pub enum CodingRewrite {
+    /// keep the source coding and append the translations
+    #[default]
+    Add,
+    /// replace the source coding with its translations
+    Replace,
+}
+
+/// Summary of a bulk rewrite of codeable concepts.
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct CodingTranslationReport {
+    pub translated: usize,
+    pub unmapped: Vec<Coding>,
+}
+
+impl TerminologyEngine {
+    /// `$translate` a coding with a specific concept map, optionally
+    /// restricted to groups targeting `target_system`.
+    ///
+    /// `dependsOn` conditions are not evaluated; they are reported through the
+    /// match's `product` and the map author is trusted to keep them unique.
+    pub fn translate(
+        &self,
+        map: &str,
+        coding: &Coding,
+        target_system: Option<&str>,
+    ) -> Result<TranslateResult, FhirError> {
+        let concept_map = self
+            .concept_map(map)
+            .ok_or_else(|| terminology_error(format!("unknown concept map '{map}'")))?;
+        let matches = self.translate_with(concept_map, coding, target_system, &mut Vec::new())?;
+        Ok(translate_result(map, coding, matches))
+    }
+
+    /// `$translate` a coding with every loaded concept map that has a group
+    /// for its system.
+    pub fn translate_all(
+        &self,
+        coding: &Coding,
+        target_system: Option<&str>,
+    ) -> Result<TranslateResult, FhirError> {
+        let mut matches = Vec::new();
+        for concept_map in self.concept_maps() {
+            matches.extend(self.translate_with(
+                concept_map,
+                coding,
+                target_system,
+                &mut Vec::new(),
+            )?);
+        }
+        Ok(translate_result("any concept map", coding, matches))
+    }
+
+    fn translate_with(
+        &self,
+        concept_map: &ConceptMap,
+        coding: &Coding,
+        target_system: Option<&str>,
+        stack: &mut Vec<String>,
+    ) -> Result<Vec<TranslateMatch>, FhirError> {
+        let url = concept_map.url.clone().unwrap_or_default();
+        if stack.contains(&url) {
+            return Err(terminology_error(format!(
+                "concept map '{url}' refers to itself via {}",
+                stack.join(" -> ")
+            )));
+        }
+        let Some(code) = &coding.code else {
+            return Ok(Vec::new());
+        };
+
+        stack.push(url.clone());
+        let mut matches = Vec::new();
+        for group in concept_map.group.iter().flatten() {
+            if !group_applies(group, coding, target_system) {
+                continue;
+            }
+
+            let element = group
+                .element
+                .iter()
+                .find(|e| e.code.as_deref() == Some(code.as_str()));
+            if let Some(element) = element {
+                for target in element.target.iter().flatten() {
+                    matches.push(TranslateMatch {
+                        equivalence: target.equivalence,
+                        concept: target.code.as_ref().map(|code| Coding {
+                            system: group.target.clone(),
+                            version: group.target_version.clone(),
+                            code: Some(code.clone()),
+                            display: target.display.clone(),
+                            ..Default::default()
+                        }),
+                        source: Some(url.clone()),
+                        comment: target.comment.clone(),
+                        product: target.product.clone().unwrap_or_default(),
+                    });
+                }
+                continue;
+            }
+
+            let Some(unmapped) = &group.unmapped else {
+                continue;
+            };
+            match unmapped.mode {
+                ConceptMapGroupUnmappedMode::Provided => matches.push(TranslateMatch {
+                    equivalence: ConceptMapEquivalence::Equal,
+                    concept: Some(Coding {
+                        system: group.target.clone().or_else(|| coding.system.clone()),
+                        version: group.target_version.clone(),
+                        code: Some(code.clone()),
+                        display: coding.display.clone(),
+                        ..Default::default()
+                    }),
+                    source: Some(url.clone()),
+                    ..Default::default()
+                }),
+                ConceptMapGroupUnmappedMode::Fixed => matches.push(TranslateMatch {
+                    equivalence: ConceptMapEquivalence::Inexact,
+                    concept: Some(Coding {
+                        system: group.target.clone(),
+                        version: group.target_version.clone(),
+                        code: unmapped.code.clone(),
+                        display: unmapped.display.clone(),
+                        ..Default::default()
+                    }),
+                    source: Some(url.clone()),
+                    ..Default::default()
+                }),
+                ConceptMapGroupUnmappedMode::OtherMap => {
+                    let other = unmapped.url.as_deref().unwrap_or_default();
+                    let other_map = self.concept_map(other).ok_or_else(|| {
+                        terminology_error(format!("unknown concept map '{other}'"))
+                    })?;
+                    matches.extend(self.translate_with(other_map, coding, target_system, stack)?);
+                }
+            }
+        }
+        stack.pop();
+
+        Ok(matches)
+    }
+
+    /// Translates every coding of a codeable concept, see [`CodingRewrite`].
+    pub fn translate_codeable_concept(
+        &self,
+        concept: &mut CodeableConcept,
+        map: &str,
+        mode: CodingRewrite,
+    ) -> Result<CodingTranslationReport, FhirError> {
+        let concept_map = self
+            .concept_map(map)
+            .ok_or_else(|| terminology_error(format!("unknown concept map '{map}'")))?;
+        let mut report = CodingTranslationReport::default();
+        let codings = concept.coding.take().unwrap_or_default();
+        concept.coding = Some(self.rewrite_codings(concept_map, codings, mode, &mut report)?);
+        Ok(report)
+    }
+
+    /// Rewrites or adds codings in every `CodeableConcept` of a resource
+    /// according to a concept map, nested backbone elements and extensions
+    /// included, see [`GetCodeableConceptsMut`]. Contained resources are left
+    /// alone.
+    pub fn apply_concept_map<R>(
+        &self,
+        resource: &mut R,
+        map: &str,
+        mode: CodingRewrite,
+    ) -> Result<CodingTranslationReport, FhirError>
+    where
+        R: GetCodeableConceptsMut + ?Sized,
+    {
+        let concept_map = self
+            .concept_map(map)
+            .ok_or_else(|| terminology_error(format!("unknown concept map '{map}'")))?;
+        let mut report = CodingTranslationReport::default();
+        for concept in resource.get_codeable_concepts_mut() {
+            if let Some(codings) = concept.coding.take() {
+                concept.coding =
+                    Some(self.rewrite_codings(concept_map, codings, mode, &mut report)?);
+            }
+        }
+        Ok(report)
+    }
+
+    fn rewrite_codings(
+        &self,
+        concept_map: &ConceptMap,
+        codings: Vec<Coding>,
+        mode: CodingRewrite,
+        report: &mut CodingTranslationReport,
+    ) -> Result<Vec<Coding>, FhirError> {
+        let mut rewritten: Vec<Coding> = Vec::new();
+        let push = |rewritten: &mut Vec<Coding>, coding: Coding| {
+            if !rewritten
+                .iter()
+                .any(|c| c.system == coding.system && c.code == coding.code)
+            {
+                rewritten.push(coding);
+            }
+        };
+
+        for coding in codings {
+            let mapped = concept_map
+                .group
+                .iter()
+                .flatten()
+                .any(|g| group_applies(g, &coding, None));
+            if !mapped {
+                push(&mut rewritten, coding);
+                continue;
+            }
+
+            let translations: Vec<Coding> = self
+                .translate_with(concept_map, &coding, None, &mut Vec::new())?
+                .into_iter()
+                .filter(|m| m.equivalence.is_match())
+                .filter_map(|m| m.concept)
+                .collect();
+
+            if translations.is_empty() {
+                report.unmapped.push(coding.clone());
+                push(&mut rewritten, coding);
+                continue;
+            }
+
+            report.translated += 1;
+            if mode == CodingRewrite::Add {
+                push(&mut rewritten, coding);
+            }
+            for translation in translations {
+                push(&mut rewritten, translation);
+            }
+        }
+
+        Ok(rewritten)
+    }
+}
+
+fn group_applies(group: &ConceptMapGroup, coding: &Coding, target_system: Option<&str>) -> bool {
+    let source = group.source.is_none() || group.source == coding.system;
+    let version = match (&group.source_version, &coding.version) {
+        (Some(expected), Some(actual)) => expected == actual,
+        _ => true,
+    };
+    let target = match (target_system, &group.target) {
+        (Some(expected), Some(actual)) => expected == actual,
+        _ => true,
+    };
+    source && version && target
+}
+
+fn translate_result(map: &str, coding: &Coding, matches: Vec<TranslateMatch>) -> TranslateResult {
+    let result = matches.iter().any(|m| m.equivalence.is_match());
+    TranslateResult {
+        result,
+        message: (!result).then(|| {
+            format!(
+                "no mapping for code '{}' from system '{}' in {map}",
+                coding.code.clone().unwrap_or_default(),
+                coding.system.clone().unwrap_or_default()
+            )
+        }),
+        matches,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::r4::{
+        elements::{CodeableConceptBuilder, CodingBuilder, ExtensionBuilder},
+        resources::{
+            ConceptMapBuilder, ConceptMapGroupBuilder, ConceptMapGroupUnmapped, EligibilityBuilder,
+            HealthcareServiceBuilder, Organization, PractitionerRole,
+        },
+    };
+
+    use super::*;
+
+    const ORG_TYPE: &str = "http://example.org/CodeSystem/organization-type";
+    const HL7_ORG_TYPE: &str = "http://terminology.hl7.org/CodeSystem/organization-type";
+    const ORG_TYPE_MAP: &str = "http://example.org/ConceptMap/organization-type-to-hl7";
+
+    fn engine() -> TerminologyEngine {
+        let mut engine = TerminologyEngine::new();
+        engine
+            .add_json(include_str!(
+                "../../../fixtures/r4/resources/concept_map.json"
+            ))
+            .unwrap();
+        engine
+    }
+
+    fn coding(system: &str, code: &str) -> Coding {
+        CodingBuilder::default()
+            .with_system(system)
+            .with_code(code)
+            .build()
+    }
+
+    #[test]
+    fn test_translate_should_succeed() {
+        let expected = TranslateResult {
+            result: true,
+            message: None,
+            matches: vec![TranslateMatch {
+                equivalence: ConceptMapEquivalence::Wider,
+                concept: Some(Coding {
+                    system: Some(HL7_ORG_TYPE.to_string()),
+                    code: Some("prov".to_string()),
+                    display: Some("Healthcare Provider".to_string()),
+                    ..Default::default()
+                }),
+                source: Some(ORG_TYPE_MAP.to_string()),
+                ..Default::default()
+            }],
+        };
+
+        let actual = engine()
+            .translate(ORG_TYPE_MAP, &coding(ORG_TYPE, "hosp"), None)
+            .unwrap();
+
+        assert_eq!(expected, actual)
+    }
+
+    #[test]
+    fn test_translate_unmatched_should_fail() {
+        let actual = engine()
+            .translate(ORG_TYPE_MAP, &coding(ORG_TYPE, "clinic"), None)
+            .unwrap();
+
+        assert!(!actual.result);
+        assert_eq!(
+            ConceptMapEquivalence::Unmatched,
+            actual.matches[0].equivalence
+        );
+        assert!(actual.message.is_some())
+    }
+
+    #[test]
+    fn test_translate_unmapped_fixed_should_succeed() {
+        let actual = engine()
+            .translate(ORG_TYPE_MAP, &coding(ORG_TYPE, "lab"), None)
+            .unwrap();
+
+        assert_eq!(
+            vec![Coding {
+                system: Some(HL7_ORG_TYPE.to_string()),
+                code: Some("other".to_string()),
+                display: Some("Other".to_string()),
+                ..Default::default()
+            }],
+            actual.codings()
+        )
+    }
+
+    #[test]
+    fn test_translate_with_other_target_system_should_be_empty() {
+        let actual = engine()
+            .translate(
+                ORG_TYPE_MAP,
+                &coding(ORG_TYPE, "hosp"),
+                Some("http://example.org/national"),
+            )
+            .unwrap();
+
+        assert!(!actual.result);
+        assert!(actual.matches.is_empty())
+    }
+
+    #[test]
+    fn test_translate_other_map_should_follow_and_detect_cycles() {
+        let mut engine = TerminologyEngine::new();
+        for (url, other) in [
+            ("http://example.org/cm/a", "http://example.org/cm/b"),
+            ("http://example.org/cm/b", "http://example.org/cm/a"),
+        ] {
+            engine
+                .add_concept_map(
+                    ConceptMapBuilder::default()
+                        .url(url)
+                        .add_group(
+                            ConceptMapGroupBuilder::default()
+                                .source("http://example.org/local")
+                                .target("http://example.org/national")
+                                .add_mapping(
+                                    if url.ends_with('b') { "b" } else { "a" },
+                                    "x",
+                                    ConceptMapEquivalence::Equivalent,
+                                )
+                                .unmapped(ConceptMapGroupUnmapped {
+                                    mode: ConceptMapGroupUnmappedMode::OtherMap,
+                                    url: Some(other.to_string()),
+                                    ..Default::default()
+                                })
+                                .build(),
+                        )
+                        .build(),
+                )
+                .unwrap();
+        }
+
+        let followed = engine
+            .translate(
+                "http://example.org/cm/a",
+                &coding("http://example.org/local", "b"),
+                None,
+            )
+            .unwrap();
+        let cyclic = engine.translate(
+            "http://example.org/cm/a",
+            &coding("http://example.org/local", "c"),
+            None,
+        );
+
+        assert!(followed.result);
+        assert!(matches!(cyclic, Err(FhirError::TerminologyError(_))))
+    }
+
+    #[test]
+    fn test_translate_all_should_succeed() {
+        let actual = engine()
+            .translate_all(&coding(ORG_TYPE, "govt"), Some(HL7_ORG_TYPE))
+            .unwrap();
+
+        assert_eq!(Some("govt".to_string()), actual.codings()[0].code)
+    }
+
+    #[test]
+    fn test_translate_codeable_concept_replace_should_succeed() {
+        let mut concept = CodeableConceptBuilder::default()
+            .add_coding(coding(ORG_TYPE, "hosp"))
+            .add_coding(coding(ORG_TYPE, "prov"))
+            .add_coding(coding("http://snomed.info/sct", "22232009"))
+            .build();
+
+        let report = engine()
+            .translate_codeable_concept(&mut concept, ORG_TYPE_MAP, CodingRewrite::Replace)
+            .unwrap();
+
+        assert_eq!(2, report.translated);
+        assert_eq!(
+            vec![
+                Some(HL7_ORG_TYPE.to_string()),
+                Some("http://snomed.info/sct".to_string())
+            ],
+            concept
+                .coding
+                .unwrap()
+                .into_iter()
+                .map(|c| c.system)
+                .collect::<Vec<_>>()
+        )
+    }
+
+    #[test]
+    fn test_apply_concept_map_should_add_codings() {
+        let mut organization = Organization::from_json(include_str!(
+            "../../../fixtures/r4/resources/organization.json"
+        ))
+        .unwrap();
+        organization.r#type = Some(vec![
+            CodeableConceptBuilder::default()
+                .add_coding(coding(ORG_TYPE, "hosp"))
+                .build(),
+            CodeableConceptBuilder::default()
+                .add_coding(coding(ORG_TYPE, "clinic"))
+                .build(),
+        ]);
+
+        let report = engine()
+            .apply_concept_map(&mut organization, ORG_TYPE_MAP, CodingRewrite::Add)
+            .unwrap();
+
+        let types = organization.r#type.unwrap();
+        assert_eq!(1, report.translated);
+        assert_eq!(vec![coding(ORG_TYPE, "clinic")], report.unmapped);
+        assert_eq!(
+            vec![Some("hosp".to_string()), Some("prov".to_string())],
+            types[0]
+                .coding
+                .iter()
+                .flatten()
+                .map(|c| c.code.clone())
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(1, types[1].coding.as_ref().unwrap().len())
+    }
+
+    #[test]
+    fn test_apply_concept_map_should_leave_unrelated_codings() {
+        let mut role = PractitionerRole::from_json(include_str!(
+            "../../../fixtures/r4/resources/practitioner_role.json"
+        ))
+        .unwrap();
+        let expected = role.clone();
+
+        let report = engine()
+            .apply_concept_map(&mut role, ORG_TYPE_MAP, CodingRewrite::Replace)
+            .unwrap();
+
+        assert_eq!(CodingTranslationReport::default(), report);
+        assert_eq!(expected, role)
+    }
+
+    #[test]
+    fn test_apply_concept_map_should_keep_address() {
+        let mut organization = Organization::from_json(include_str!(
+            "../../../fixtures/r4/resources/organization.json"
+        ))
+        .unwrap();
+        organization.address.as_mut().unwrap()[0].postal_code = Some("3511 AB".to_string());
+        organization.r#type = Some(vec![
+            CodeableConceptBuilder::default()
+                .add_coding(coding(ORG_TYPE, "hosp"))
+                .build(),
+        ]);
+        let mut expected = organization.clone();
+        expected.r#type = Some(vec![
+            CodeableConceptBuilder::default()
+                .add_coding(
+                    CodingBuilder::default()
+                        .with_system(HL7_ORG_TYPE)
+                        .with_code("prov")
+                        .with_display("Healthcare Provider")
+                        .build(),
+                )
+                .build(),
+        ]);
+
+        engine()
+            .apply_concept_map(&mut organization, ORG_TYPE_MAP, CodingRewrite::Replace)
+            .unwrap();
+
+        assert_eq!(
+            Some("3511 AB"),
+            organization.address.as_ref().unwrap()[0]
+                .postal_code
+                .as_deref()
+        );
+        assert_eq!(expected, organization)
+    }
+
+    #[test]
+    fn test_apply_concept_map_should_rewrite_nested_concepts() {
+        let hosp = || {
+            CodeableConceptBuilder::default()
+                .add_coding(coding(ORG_TYPE, "hosp"))
+                .build()
+        };
+        let mut service = HealthcareServiceBuilder::new("service-1")
+            .add_eligibility(EligibilityBuilder::default().with_code(hosp()).build())
+            .build();
+        service.domain_resource.extension = Some(vec![
+            ExtensionBuilder::default()
+                .with_url("http://example.org/outer")
+                .add_extension(
+                    ExtensionBuilder::default()
+                        .with_url("http://example.org/inner")
+                        .with_value_codeable_concept(hosp())
+                        .build(),
+                )
+                .build(),
+        ]);
+
+        let report = engine()
+            .apply_concept_map(&mut service, ORG_TYPE_MAP, CodingRewrite::Replace)
+            .unwrap();
+
+        let codes = |concept: &Option<CodeableConcept>| -> Vec<String> {
+            concept
+                .iter()
+                .flat_map(|concept| concept.coding.iter().flatten())
+                .filter_map(|coding| coding.code.clone())
+                .collect()
+        };
+        assert_eq!(2, report.translated);
+        assert_eq!(vec!["prov"], codes(&service.eligibility.unwrap()[0].code));
+        let outer = &service.domain_resource.extension.unwrap()[0];
+        assert_eq!(
+            vec!["prov"],
+            codes(&outer.extension.as_ref().unwrap()[0].value_codeable_concept)
+        );
+    }
+}